@@ -2,12 +2,16 @@ fn main() {
     // PyBullet does not enable `BT_THREADSAFE`. I assume this is because of the GIL - PyBullet does
     // nothing without the GIL locked. We'll make the same guarantee by using Rust's ownership
     // model.
+    let gui = cfg!(feature = "gui");
     let config = &mut cmake::Config::new("bullet3");
+    config.define("MISFIRE_GUI", if gui { "ON" } else { "OFF" });
     let dst = config.build();
 
     println!("cargo:rustc-link-search=native={}/lib", dst.display());
-    println!("cargo:rustc-link-lib=static=BulletExampleBrowserLib");
-    println!("cargo:rustc-link-lib=static=BulletRoboticsGUI");
+    if gui {
+        println!("cargo:rustc-link-lib=static=BulletExampleBrowserLib");
+        println!("cargo:rustc-link-lib=static=BulletRoboticsGUI");
+    }
     println!("cargo:rustc-link-lib=static=BulletRobotics");
     println!("cargo:rustc-link-lib=static=BulletFileLoader");
     println!("cargo:rustc-link-lib=static=BulletWorldImporter");
@@ -17,8 +21,10 @@ fn main() {
     println!("cargo:rustc-link-lib=static=BulletInverseDynamicsUtils");
     println!("cargo:rustc-link-lib=static=BulletInverseDynamics");
     println!("cargo:rustc-link-lib=static=LinearMath");
-    println!("cargo:rustc-link-lib=static=OpenGLWindow");
-    println!("cargo:rustc-link-lib=static=gwen");
+    if gui {
+        println!("cargo:rustc-link-lib=static=OpenGLWindow");
+        println!("cargo:rustc-link-lib=static=gwen");
+    }
     println!("cargo:rustc-link-lib=static=BussIK");
     println!("cargo:rustc-link-lib=static=Bullet3Common");
     println!("cargo:rustc-link-lib=static=cbullet");
@@ -27,7 +33,9 @@ fn main() {
 
     if cfg!(target_os = "macos") {
         println!("cargo:rustc-link-lib=c++");
-        println!("cargo:rustc-link-lib=framework=Cocoa");
-        println!("cargo:rustc-link-lib=framework=OpenGL");
+        if gui {
+            println!("cargo:rustc-link-lib=framework=Cocoa");
+            println!("cargo:rustc-link-lib=framework=OpenGL");
+        }
     }
 }
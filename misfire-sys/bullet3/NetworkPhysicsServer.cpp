@@ -0,0 +1,268 @@
+// Headless physics servers which accept TCP and UDP clients.
+//
+// Bullet only ships these servers as standalone applications
+// (examples/SharedMemory/tcp/main.cpp and examples/SharedMemory/udp/main.cpp, built with
+// NO_SHARED_MEMORY). This file runs the same message loops on a background thread, so that they can
+// be started from Rust. Commands are executed by a PhysicsServerCommandProcessor without any GUI.
+
+#include <atomic>
+#include <cstring>
+#include <thread>
+
+#include "NetworkPhysicsServer.h"
+
+#include "Bullet3Common/b3AlignedObjectArray.h"
+#include "CommonInterfaces/CommonGUIHelperInterface.h"
+#include "SharedMemory/PhysicsServerCommandProcessor.h"
+#include "SharedMemory/SharedMemoryCommands.h"
+#include "Utils/b3Clock.h"
+
+#include "ActiveSocket.h"
+#include "PassiveSocket.h"
+#include "enet/enet.h"
+
+namespace
+{
+const double STATUS_TIME_OUT_SECONDS = 10.;
+
+enum NetworkProtocol
+{
+	NETWORK_PROTOCOL_TCP,
+	NETWORK_PROTOCOL_UDP,
+};
+
+void serializeInt(int value, unsigned char* out)
+{
+	out[0] = (unsigned char)(value & 0xff);
+	out[1] = (unsigned char)((value >> 8) & 0xff);
+	out[2] = (unsigned char)((value >> 16) & 0xff);
+	out[3] = (unsigned char)((value >> 24) & 0xff);
+}
+}  // namespace
+
+struct b3NetworkPhysicsServer
+{
+	DummyGUIHelper m_guiHelper;
+	PhysicsServerCommandProcessor m_processor;
+	std::atomic<bool> m_exitRequested;
+	std::atomic<bool> m_running;
+	std::thread m_thread;
+	b3AlignedObjectArray<char> m_buffer;
+
+	b3NetworkPhysicsServer()
+		: m_exitRequested(false),
+		  m_running(false)
+	{
+		m_processor.setGuiHelper(&m_guiHelper);
+		m_buffer.resize(SHARED_MEMORY_MAX_STREAM_CHUNK_SIZE);
+	}
+
+	// Executes a command and waits for its status. Returns false if the command produced no status.
+	bool processCommand(const SharedMemoryCommand& command, SharedMemoryStatus& status)
+	{
+		b3Clock clock;
+		bool hasStatus = m_processor.processCommand(command, status, &m_buffer[0], m_buffer.size());
+		double startTime = clock.getTimeInSeconds();
+		while (!hasStatus && clock.getTimeInSeconds() - startTime < STATUS_TIME_OUT_SECONDS)
+		{
+			hasStatus = m_processor.receiveStatus(status, &m_buffer[0], m_buffer.size());
+		}
+		return hasStatus;
+	}
+
+	// Serializes the status in the same way as the TCP and UDP servers of Bullet. The packet
+	// starts with its size. Simulation steps only send the status type to save bandwidth.
+	void statusPacket(const SharedMemoryCommand& command, const SharedMemoryStatus& status,
+				   b3AlignedObjectArray<unsigned char>& packet)
+	{
+		const unsigned char* statusBytes = (const unsigned char*)&status;
+		if (command.m_type == CMD_STEP_FORWARD_SIMULATION)
+		{
+			packet.resize(4 + sizeof(int));
+			serializeInt(packet.size(), &packet[0]);
+			memcpy(&packet[4], statusBytes, sizeof(int));
+			return;
+		}
+		packet.resize(4 + sizeof(SharedMemoryStatus) + status.m_numDataStreamBytes);
+		serializeInt(packet.size(), &packet[0]);
+		memcpy(&packet[4], statusBytes, sizeof(SharedMemoryStatus));
+		if (status.m_numDataStreamBytes > 0)
+		{
+			memcpy(&packet[4 + sizeof(SharedMemoryStatus)], &m_buffer[0], status.m_numDataStreamBytes);
+		}
+	}
+
+	void runTcp(CPassiveSocket* socket)
+	{
+		while (!m_exitRequested)
+		{
+			CActiveSocket* client = socket->Accept();
+			if (client == NULL)
+			{
+				b3Clock::usleep(1000);
+				continue;
+			}
+			client->SetBlocking();
+			client->SetReceiveTimeout(0, 100000);
+			b3AlignedObjectArray<char> bytesReceived;
+			bool connected = true;
+			while (connected && !m_exitRequested)
+			{
+				int numBytes = client->Receive(sizeof(SharedMemoryCommand) - bytesReceived.size());
+				if (numBytes == 0)
+				{
+					// the client closed the connection
+					break;
+				}
+				if (numBytes < 0)
+				{
+					connected = client->GetSocketError() == CSimpleSocket::SocketEwouldblock ||
+								client->GetSocketError() == CSimpleSocket::SocketTimedout;
+					continue;
+				}
+				const char* data = (const char*)client->GetData();
+				for (int i = 0; i < numBytes; i++)
+				{
+					bytesReceived.push_back(data[i]);
+				}
+				if (bytesReceived.size() == 10 && strncmp(&bytesReceived[0], "disconnect", 10) == 0)
+				{
+					break;
+				}
+				if (bytesReceived.size() == 15 && strncmp(&bytesReceived[0], "terminateserver", 15) == 0)
+				{
+					m_exitRequested = true;
+					break;
+				}
+				if (bytesReceived.size() != sizeof(SharedMemoryCommand))
+				{
+					continue;
+				}
+				SharedMemoryCommand command;
+				memcpy(&command, &bytesReceived[0], sizeof(SharedMemoryCommand));
+				bytesReceived.clear();
+				SharedMemoryStatus status;
+				if (processCommand(command, status))
+				{
+					b3AlignedObjectArray<unsigned char> packet;
+					statusPacket(command, status, packet);
+					client->Send(&packet[0], packet.size());
+				}
+			}
+			client->Close();
+			delete client;
+		}
+		socket->Close();
+	}
+
+	void runUdp(ENetHost* host)
+	{
+		while (!m_exitRequested)
+		{
+			ENetEvent event;
+			if (enet_host_service(host, &event, 100) <= 0)
+			{
+				continue;
+			}
+			if (event.type != ENET_EVENT_TYPE_RECEIVE)
+			{
+				continue;
+			}
+			if (event.packet->dataLength == sizeof(SharedMemoryCommand))
+			{
+				SharedMemoryCommand command;
+				memcpy(&command, event.packet->data, sizeof(SharedMemoryCommand));
+				SharedMemoryStatus status;
+				if (processCommand(command, status))
+				{
+					b3AlignedObjectArray<unsigned char> data;
+					statusPacket(command, status, data);
+					ENetPacket* packet = enet_packet_create(&data[0], data.size(), ENET_PACKET_FLAG_RELIABLE);
+					enet_peer_send(event.peer, 0, packet);
+				}
+			}
+			enet_packet_destroy(event.packet);
+		}
+		enet_host_destroy(host);
+		enet_deinitialize();
+	}
+};
+
+static b3NetworkPhysicsServerHandle createServer(NetworkProtocol protocol, int port)
+{
+	b3NetworkPhysicsServer* server = new b3NetworkPhysicsServer();
+	if (!server->m_processor.connect())
+	{
+		delete server;
+		return NULL;
+	}
+	// The sockets are opened before the thread starts, so that errors can be reported right away.
+	if (protocol == NETWORK_PROTOCOL_TCP)
+	{
+		CPassiveSocket* socket = new CPassiveSocket();
+		if (!socket->Initialize() || !socket->Listen(NULL, port))
+		{
+			delete socket;
+			delete server;
+			return NULL;
+		}
+		socket->SetNonblocking();
+		server->m_running = true;
+		server->m_thread = std::thread([server, socket]() {
+			server->runTcp(socket);
+			delete socket;
+			server->m_running = false;
+		});
+	}
+	else
+	{
+		if (enet_initialize() != 0)
+		{
+			delete server;
+			return NULL;
+		}
+		ENetAddress address;
+		address.host = ENET_HOST_ANY;
+		address.port = port;
+		ENetHost* host = enet_host_create(&address, 32, 2, 0, 0);
+		if (host == NULL)
+		{
+			enet_deinitialize();
+			delete server;
+			return NULL;
+		}
+		server->m_running = true;
+		server->m_thread = std::thread([server, host]() {
+			server->runUdp(host);
+			server->m_running = false;
+		});
+	}
+	return (b3NetworkPhysicsServerHandle)server;
+}
+
+B3_SHARED_API b3NetworkPhysicsServerHandle b3CreateNetworkPhysicsServerTCP(int port)
+{
+	return createServer(NETWORK_PROTOCOL_TCP, port);
+}
+
+B3_SHARED_API b3NetworkPhysicsServerHandle b3CreateNetworkPhysicsServerUDP(int port)
+{
+	return createServer(NETWORK_PROTOCOL_UDP, port);
+}
+
+B3_SHARED_API int b3NetworkPhysicsServerIsRunning(b3NetworkPhysicsServerHandle serverHandle)
+{
+	b3NetworkPhysicsServer* server = (b3NetworkPhysicsServer*)serverHandle;
+	return server->m_running ? 1 : 0;
+}
+
+B3_SHARED_API void b3DestroyNetworkPhysicsServer(b3NetworkPhysicsServerHandle serverHandle)
+{
+	b3NetworkPhysicsServer* server = (b3NetworkPhysicsServer*)serverHandle;
+	server->m_exitRequested = true;
+	if (server->m_thread.joinable())
+	{
+		server->m_thread.join();
+	}
+	delete server;
+}
@@ -0,0 +1,31 @@
+#ifndef NETWORK_PHYSICS_SERVER_H
+#define NETWORK_PHYSICS_SERVER_H
+
+#include "SharedMemory/PhysicsClientC_API.h"
+
+B3_DECLARE_HANDLE(b3NetworkPhysicsServerHandle);
+
+#ifdef __cplusplus
+extern "C"
+{
+#endif
+
+	/// Starts a headless physics server which accepts TCP clients on the given port.
+	/// Returns NULL if the port can not be opened.
+	B3_SHARED_API b3NetworkPhysicsServerHandle b3CreateNetworkPhysicsServerTCP(int port);
+
+	/// Starts a headless physics server which accepts UDP (enet) clients on the given port.
+	/// Returns NULL if the port can not be opened.
+	B3_SHARED_API b3NetworkPhysicsServerHandle b3CreateNetworkPhysicsServerUDP(int port);
+
+	/// Returns 1 as long as the server accepts clients.
+	B3_SHARED_API int b3NetworkPhysicsServerIsRunning(b3NetworkPhysicsServerHandle serverHandle);
+
+	/// Stops the server and frees it.
+	B3_SHARED_API void b3DestroyNetworkPhysicsServer(b3NetworkPhysicsServerHandle serverHandle);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif  //NETWORK_PHYSICS_SERVER_H
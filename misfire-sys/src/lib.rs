@@ -11,6 +11,12 @@ pub struct b3PhysicsClientHandle__ {
 }
 pub type b3PhysicsClientHandle = NonNull<b3PhysicsClientHandle__>;
 
+#[repr(C)]
+pub struct b3NetworkPhysicsServerHandle__ {
+    _unused: c_int,
+}
+pub type b3NetworkPhysicsServerHandle = NonNull<b3NetworkPhysicsServerHandle__>;
+
 #[repr(C)]
 pub struct b3SharedMemoryCommandHandle__ {
     _unused: c_int,
@@ -23,43 +29,61 @@ pub struct b3SharedMemoryStatusHandle__ {
 }
 pub type b3SharedMemoryStatusHandle = *mut b3SharedMemoryStatusHandle__;
 pub const MAX_SDF_BODIES: u32 = 512;
+pub const B3_MAX_PLUGIN_ARG_SIZE: usize = 128;
+pub const B3_MAX_PLUGIN_ARG_TEXT_LEN: usize = 1024;
 extern "C" {
     pub fn b3ConnectPhysicsDirect() -> Option<b3PhysicsClientHandle>;
+    // The in-process servers are part of the example browser and only exist with the `gui`
+    // feature.
+    #[cfg(feature = "gui")]
     pub fn b3CreateInProcessPhysicsServerAndConnect(
         argc: c_int,
         argv: *mut *mut c_char,
     ) -> Option<b3PhysicsClientHandle>;
+    #[cfg(feature = "gui")]
     pub fn b3CreateInProcessPhysicsServerAndConnectMainThread(
         argc: c_int,
         argv: *mut *mut c_char,
     ) -> Option<b3PhysicsClientHandle>;
+    #[cfg(feature = "gui")]
     pub fn b3CreateInProcessGraphicsServerAndConnectMainThreadSharedMemory(
         port: c_int,
     ) -> Option<b3PhysicsClientHandle>;
+    #[cfg(feature = "gui")]
     pub fn b3CreateInProcessGraphicsServerAndConnectSharedMemory(
         port: c_int,
     ) -> Option<b3PhysicsClientHandle>;
+    #[cfg(feature = "gui")]
     pub fn b3CreateInProcessPhysicsServerFromExistingExampleBrowserAndConnectTCP(
         host_name: *const c_char,
         port: c_int,
     ) -> Option<b3PhysicsClientHandle>;
+    #[cfg(feature = "gui")]
     pub fn b3CreateInProcessPhysicsServerAndConnectSharedMemory(
         argc: c_int,
         argv: *mut *mut c_char,
     ) -> Option<b3PhysicsClientHandle>;
+    #[cfg(feature = "gui")]
     pub fn b3CreateInProcessPhysicsServerAndConnectMainThreadSharedMemory(
         argc: c_int,
         argv: *mut *mut c_char,
     ) -> Option<b3PhysicsClientHandle>;
+    #[cfg(feature = "gui")]
     pub fn b3CreateInProcessPhysicsServerFromExistingExampleBrowserAndConnect3(
         guiHelperPtr: *mut c_void,
         sharedMemoryKey: c_int,
     ) -> Option<b3PhysicsClientHandle>;
+    #[cfg(feature = "gui")]
     pub fn b3CreateInProcessPhysicsServerFromExistingExampleBrowserAndConnect4(
         guiHelperPtr: *mut c_void,
         sharedMemoryKey: c_int,
     ) -> Option<b3PhysicsClientHandle>;
 
+    pub fn b3CreateNetworkPhysicsServerTCP(port: c_int) -> Option<b3NetworkPhysicsServerHandle>;
+    pub fn b3CreateNetworkPhysicsServerUDP(port: c_int) -> Option<b3NetworkPhysicsServerHandle>;
+    pub fn b3NetworkPhysicsServerIsRunning(serverHandle: b3NetworkPhysicsServerHandle) -> c_int;
+    pub fn b3DestroyNetworkPhysicsServer(serverHandle: b3NetworkPhysicsServerHandle);
+
     pub fn b3ConnectPhysicsUDP(
         host_name: *const c_char,
         port: c_int,
@@ -86,6 +110,12 @@ extern "C" {
         commandHandle: b3SharedMemoryCommandHandle,
     ) -> b3SharedMemoryStatusHandle;
 
+    pub fn b3SubmitClientCommand(
+        physClient: b3PhysicsClientHandle,
+        commandHandle: b3SharedMemoryCommandHandle,
+    ) -> c_int;
+    pub fn b3ProcessServerStatus(physClient: b3PhysicsClientHandle) -> b3SharedMemoryStatusHandle;
+
     pub fn b3GetStatusType(statusHandle: b3SharedMemoryStatusHandle) -> c_int;
 
     pub fn b3GetStatusActualState(
@@ -125,6 +155,75 @@ extern "C" {
     pub fn b3InitSyncUserDataCommand(
         physClient: b3PhysicsClientHandle,
     ) -> b3SharedMemoryCommandHandle;
+    pub fn b3AddBodyToSyncUserDataRequest(
+        commandHandle: b3SharedMemoryCommandHandle,
+        bodyUniqueId: c_int,
+    );
+    pub fn b3InitAddUserDataCommand(
+        physClient: b3PhysicsClientHandle,
+        bodyUniqueId: c_int,
+        linkIndex: c_int,
+        visualShapeIndex: c_int,
+        key: *const c_char,
+        valueType: UserDataValueType,
+        valueLength: c_int,
+        valueData: *const c_void,
+    ) -> b3SharedMemoryCommandHandle;
+    pub fn b3InitRemoveUserDataCommand(
+        physClient: b3PhysicsClientHandle,
+        userDataId: c_int,
+    ) -> b3SharedMemoryCommandHandle;
+    pub fn b3GetUserData(
+        physClient: b3PhysicsClientHandle,
+        userDataId: c_int,
+        valueOut: *mut b3UserDataValue,
+    ) -> c_int;
+    pub fn b3GetUserDataId(
+        physClient: b3PhysicsClientHandle,
+        bodyUniqueId: c_int,
+        linkIndex: c_int,
+        visualShapeIndex: c_int,
+        key: *const c_char,
+    ) -> c_int;
+    pub fn b3GetUserDataIdFromStatus(statusHandle: b3SharedMemoryStatusHandle) -> c_int;
+    pub fn b3GetNumUserData(physClient: b3PhysicsClientHandle, bodyUniqueId: c_int) -> c_int;
+    pub fn b3CreateCustomCommand(physClient: b3PhysicsClientHandle) -> b3SharedMemoryCommandHandle;
+    pub fn b3CustomCommandLoadPlugin(
+        commandHandle: b3SharedMemoryCommandHandle,
+        pluginPath: *const c_char,
+    );
+    pub fn b3CustomCommandLoadPluginSetPostFix(
+        commandHandle: b3SharedMemoryCommandHandle,
+        postFix: *const c_char,
+    );
+    pub fn b3GetStatusPluginUniqueId(statusHandle: b3SharedMemoryStatusHandle) -> c_int;
+    pub fn b3GetStatusPluginCommandResult(statusHandle: b3SharedMemoryStatusHandle) -> c_int;
+    pub fn b3CustomCommandUnloadPlugin(
+        commandHandle: b3SharedMemoryCommandHandle,
+        pluginUniqueId: c_int,
+    );
+    pub fn b3CustomCommandExecutePluginCommand(
+        commandHandle: b3SharedMemoryCommandHandle,
+        pluginUniqueId: c_int,
+        textArguments: *const c_char,
+    );
+    pub fn b3CustomCommandExecuteAddIntArgument(
+        commandHandle: b3SharedMemoryCommandHandle,
+        intVal: c_int,
+    );
+    pub fn b3CustomCommandExecuteAddFloatArgument(
+        commandHandle: b3SharedMemoryCommandHandle,
+        floatVal: f32,
+    );
+    pub fn b3GetUserDataInfo(
+        physClient: b3PhysicsClientHandle,
+        bodyUniqueId: c_int,
+        userDataIndex: c_int,
+        keyOut: *mut *const c_char,
+        userDataIdOut: *mut c_int,
+        linkIndexOut: *mut c_int,
+        visualShapeIndexOut: *mut c_int,
+    );
 
     pub fn b3InitStepSimulationCommand(
         physClient: b3PhysicsClientHandle,
@@ -427,6 +526,20 @@ extern "C" {
         jointIndex: c_int,
         jointVelocity: f64,
     ) -> c_int;
+    pub fn b3CreatePoseCommandSetJointPositionMultiDof(
+        physClient: b3PhysicsClientHandle,
+        commandHandle: b3SharedMemoryCommandHandle,
+        jointIndex: c_int,
+        jointPosition: *const f64,
+        posSize: c_int,
+    ) -> c_int;
+    pub fn b3CreatePoseCommandSetJointVelocityMultiDof(
+        physClient: b3PhysicsClientHandle,
+        commandHandle: b3SharedMemoryCommandHandle,
+        jointIndex: c_int,
+        jointVelocity: *const f64,
+        velSize: c_int,
+    ) -> c_int;
 
     pub fn b3ComputeDofCount(physClient: b3PhysicsClientHandle, bodyUniqueId: c_int) -> c_int;
     pub fn b3SaveStateCommandInit(physClient: b3PhysicsClientHandle)
@@ -661,6 +774,36 @@ extern "C" {
         dofIndex: c_int,
         value: f64,
     ) -> c_int;
+    pub fn b3JointControlSetDesiredPositionMultiDof(
+        commandHandle: b3SharedMemoryCommandHandle,
+        qIndex: c_int,
+        position: *const f64,
+        dofCount: c_int,
+    ) -> c_int;
+    pub fn b3JointControlSetKpMultiDof(
+        commandHandle: b3SharedMemoryCommandHandle,
+        dofIndex: c_int,
+        kps: *const f64,
+        dofCount: c_int,
+    ) -> c_int;
+    pub fn b3JointControlSetKdMultiDof(
+        commandHandle: b3SharedMemoryCommandHandle,
+        dofIndex: c_int,
+        kds: *const f64,
+        dofCount: c_int,
+    ) -> c_int;
+    pub fn b3JointControlSetDesiredVelocityMultiDof(
+        commandHandle: b3SharedMemoryCommandHandle,
+        dofIndex: c_int,
+        velocity: *const f64,
+        dofCount: c_int,
+    ) -> c_int;
+    pub fn b3JointControlSetDesiredForceTorqueMultiDof(
+        commandHandle: b3SharedMemoryCommandHandle,
+        dofIndex: c_int,
+        forces: *const f64,
+        dofCount: c_int,
+    ) -> c_int;
     #[doc = "request an image from a simulated camera, using a software renderer."]
     pub fn b3InitRequestCameraImage(
         physClient: b3PhysicsClientHandle,
@@ -1836,6 +1979,15 @@ pub enum eURDF_Flags {
     URDF_ENABLE_WAKEUP = 262144,
 }
 
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserDataValueType {
+    /// Data represents generic binary data (e.g. used for storing data that is not a string).
+    USER_DATA_VALUE_TYPE_BYTES = 0,
+    /// Data represents a string (null-terminated).
+    USER_DATA_VALUE_TYPE_STRING = 1,
+}
+
 #[repr(C)]
 pub enum EnumSharedMemoryServerStatus {
     CMD_SHARED_MEMORY_NOT_INITIALIZED = 0,
@@ -2363,3 +2515,20 @@ pub struct b3RayHitInfo {
     pub m_hitNormalWorld: [f64; 3usize],
 }
 pub const MAX_RAY_INTERSECTION_BATCH_SIZE_STREAMING: usize = 16384;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct b3UserDataValue {
+    pub m_type: c_int,
+    pub m_length: c_int,
+    pub m_data1: *const c_char,
+}
+impl Default for b3UserDataValue {
+    fn default() -> Self {
+        b3UserDataValue {
+            m_type: 0,
+            m_length: 0,
+            m_data1: std::ptr::null(),
+        }
+    }
+}
@@ -39,12 +39,12 @@ fn get_ray_from_to(
 fn main() -> Result<()> {
     let mut physics_client = PhysicsClient::connect(Mode::Gui)?;
     physics_client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
-    physics_client.configure_debug_visualizer(DebugVisualizerFlag::CovEnableGui, false);
+    physics_client.configure_debug_visualizer(DebugVisualizerFlag::CovEnableGui, false)?;
     physics_client.set_physics_engine_parameter(SetPhysicsEngineParameterOptions {
         num_solver_iterations: Some(10),
         ..Default::default()
-    });
-    physics_client.set_time_step(Duration::from_secs_f64(1. / 120.));
+    })?;
+    physics_client.set_time_step(Duration::from_secs_f64(1. / 120.))?;
     let log_id = physics_client.start_state_logging(
         LoggingType::ProfileTimings,
         "visualShapeBench.json",
@@ -58,10 +58,11 @@ fn main() -> Result<()> {
             ..Default::default()
         },
     )?;
-    physics_client.configure_debug_visualizer(DebugVisualizerFlag::CovEnableRendering, false);
-    physics_client.configure_debug_visualizer(DebugVisualizerFlag::CovEnablePlanarReflection, true);
-    physics_client.configure_debug_visualizer(DebugVisualizerFlag::CovEnableGui, false);
-    physics_client.configure_debug_visualizer(DebugVisualizerFlag::CovEnableTinyRenderer, false);
+    physics_client.configure_debug_visualizer(DebugVisualizerFlag::CovEnableRendering, false)?;
+    physics_client
+        .configure_debug_visualizer(DebugVisualizerFlag::CovEnablePlanarReflection, true)?;
+    physics_client.configure_debug_visualizer(DebugVisualizerFlag::CovEnableGui, false)?;
+    physics_client.configure_debug_visualizer(DebugVisualizerFlag::CovEnableTinyRenderer, false)?;
 
     let shift = Isometry3::translation(0., -0.02, 0.);
     let mesh_scale = Vector3::new(0.1, 0.1, 0.1);
@@ -110,10 +111,10 @@ fn main() -> Result<()> {
         }
     }
 
-    physics_client.configure_debug_visualizer(DebugVisualizerFlag::CovEnableRendering, true);
-    physics_client.stop_state_logging(log_id);
-    physics_client.set_gravity([0., 0., -10.]);
-    physics_client.set_real_time_simulation(true);
+    physics_client.configure_debug_visualizer(DebugVisualizerFlag::CovEnableRendering, true)?;
+    physics_client.stop_state_logging(log_id)?;
+    physics_client.set_gravity([0., 0., -10.])?;
+    physics_client.set_real_time_simulation(true)?;
 
     let colors = [
         [1., 0., 0., 1.],
@@ -124,7 +125,7 @@ fn main() -> Result<()> {
     let mut current_color = 0;
 
     loop {
-        let mouse_events = physics_client.get_mouse_events();
+        let mouse_events = physics_client.get_mouse_events()?;
         for event in mouse_events {
             match event {
                 MouseEvent::Move { .. } => {}
@@ -136,7 +137,7 @@ fn main() -> Result<()> {
                 } => {
                     if button_state.was_triggered() && button_index == 0 {
                         let (ray_from, ray_to) = get_ray_from_to(
-                            physics_client.get_debug_visualizer_camera(),
+                            physics_client.get_debug_visualizer_camera()?,
                             mouse_pos_x,
                             mouse_pos_y,
                         );
@@ -6,7 +6,7 @@ use std::f64::consts::PI;
 fn main() -> Result<()> {
     let mut physics_client = PhysicsClient::connect(Mode::Gui)?;
     physics_client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
-    physics_client.configure_debug_visualizer(DebugVisualizerFlag::CovEnableGui, false);
+    physics_client.configure_debug_visualizer(DebugVisualizerFlag::CovEnableGui, false)?;
 
     // physics_client.load_urdf("r2d2.urdf", None)?;
     let _r2d2 = physics_client.load_urdf(
@@ -66,7 +66,7 @@ fn main() -> Result<()> {
             },
         )?;
         if !replace_lines {
-            physics_client.remove_all_user_debug_items();
+            physics_client.remove_all_user_debug_items()?;
         }
         for i in 0..num_rays {
             if let Some(result) = results[i] {
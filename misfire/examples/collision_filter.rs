@@ -30,15 +30,15 @@ fn main() -> Result<()> {
         None,
         collision_filter_group,
         collision_filter_mask,
-    );
+    )?;
 
     let enable_collision = true;
 
-    physics_client.set_collision_filter_pair(plane_id, cube_id, None, None, enable_collision);
-    physics_client.set_real_time_simulation(true);
-    physics_client.set_gravity([0., 0., -10.]);
+    physics_client.set_collision_filter_pair(plane_id, cube_id, None, None, enable_collision)?;
+    physics_client.set_real_time_simulation(true)?;
+    physics_client.set_gravity([0., 0., -10.])?;
     loop {
         std::thread::sleep(Duration::from_secs_f64(1. / 240.));
-        physics_client.set_gravity([0., 0., -10.]);
+        physics_client.set_gravity([0., 0., -10.])?;
     }
 }
@@ -15,8 +15,8 @@ fn main() -> Result<()> {
             ..Default::default()
         },
     )?;
-    physics_client.set_gravity([0., 0., -10.]);
-    physics_client.set_real_time_simulation(true);
+    physics_client.set_gravity([0., 0., -10.])?;
+    physics_client.set_real_time_simulation(true)?;
 
     let cid = physics_client.create_constraint(
         cube_id,
@@ -43,6 +43,6 @@ fn main() -> Result<()> {
             max_force: Some(50.),
             ..Default::default()
         };
-        physics_client.change_constraint(cid, change_constraint_options);
+        physics_client.change_constraint(cid, change_constraint_options)?;
     }
 }
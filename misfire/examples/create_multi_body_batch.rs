@@ -10,12 +10,12 @@ fn main() -> Result<()> {
     let mut physics_client = PhysicsClient::connect(Mode::Gui)?;
 
     physics_client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
-    physics_client.set_time_step(Duration::from_secs_f64(1. / 120.));
+    physics_client.set_time_step(Duration::from_secs_f64(1. / 120.))?;
     let _plane_id = physics_client.load_urdf("plane100.urdf", None)?;
 
-    physics_client.configure_debug_visualizer(CovEnableRendering, false);
-    physics_client.configure_debug_visualizer(CovEnableGui, false);
-    physics_client.configure_debug_visualizer(CovEnableTinyRenderer, false);
+    physics_client.configure_debug_visualizer(CovEnableRendering, false)?;
+    physics_client.configure_debug_visualizer(CovEnableGui, false)?;
+    physics_client.configure_debug_visualizer(CovEnableTinyRenderer, false)?;
 
     let shift = Isometry3::translation(0.0, -0.02, 0.0);
     let mesh_scaling = Vector3::from_element(0.1);
@@ -160,8 +160,8 @@ fn main() -> Result<()> {
             ..Default::default()
         },
     )?;
-    physics_client.configure_debug_visualizer(CovEnableRendering, true);
-    physics_client.set_gravity(Vector3::new(0.0, 0.0, -10.0));
+    physics_client.configure_debug_visualizer(CovEnableRendering, true)?;
+    physics_client.set_gravity(Vector3::new(0.0, 0.0, -10.0))?;
 
     loop {
         physics_client.step_simulation()?;
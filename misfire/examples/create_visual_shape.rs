@@ -10,8 +10,8 @@ fn main() -> Result<()> {
     let mut physics_client = PhysicsClient::connect(Mode::Gui)?;
 
     physics_client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
-    physics_client.set_gravity(Vector3::new(0.0, 0.0, -10.0));
-    physics_client.set_time_step(Duration::from_secs_f64(1. / 120.));
+    physics_client.set_gravity(Vector3::new(0.0, 0.0, -10.0))?;
+    physics_client.set_time_step(Duration::from_secs_f64(1. / 120.))?;
     // physics_client.configure_debug_visualizer(DebugVisualizerFlag::)
     let _plane_id = physics_client.load_urdf("plane100.urdf", None)?;
     let shift = Isometry3::translation(0.0, -0.02, 0.0);
@@ -55,7 +55,7 @@ fn main() -> Result<()> {
         }
     }
 
-    physics_client.set_real_time_simulation(true);
+    physics_client.set_real_time_simulation(true)?;
     for _ in 0..10000 {
         thread::sleep(Duration::from_secs_f64(1. / 240.));
     }
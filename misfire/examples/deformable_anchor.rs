@@ -6,9 +6,9 @@ use std::time::Duration;
 fn main() -> Result<()> {
     let mut physics_client = PhysicsClient::connect(Mode::Gui)?;
     physics_client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
-    physics_client.reset_simulation_with_flags(ResetFlags::DEFORMABLE_WORLD);
+    physics_client.reset_simulation_with_flags(ResetFlags::DEFORMABLE_WORLD)?;
 
-    physics_client.set_gravity([0., 0., -10.]);
+    physics_client.set_gravity([0., 0., -10.])?;
 
     let cube_id = physics_client.load_urdf(
         "cube.urdf",
@@ -65,9 +65,9 @@ fn main() -> Result<()> {
     physics_client.set_physics_engine_parameter(SetPhysicsEngineParameterOptions {
         sparse_sdf_voxel_size: Some(0.25),
         ..Default::default()
-    });
+    })?;
 
-    physics_client.set_gravity([0., 0., -10.]);
+    physics_client.set_gravity([0., 0., -10.])?;
     loop {
         std::thread::sleep(Duration::from_secs_f64(1. / 240.));
         physics_client.step_simulation()?;
@@ -149,7 +149,7 @@ fn main() -> Result<()> {
     let aabb = physics_client.get_aabb(r2d2, None)?;
     println!("{:?}", aabb);
     draw_aabb(aabb, &mut physics_client);
-    for i in 0..physics_client.get_num_joints(r2d2) {
+    for i in 0..physics_client.get_num_joints(r2d2)? {
         let aabb = physics_client.get_aabb(r2d2, i)?;
         println!("{:?}", aabb);
         draw_aabb(aabb, &mut physics_client);
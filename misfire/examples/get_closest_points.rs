@@ -11,7 +11,7 @@ pub enum ClosestPointsMethod {
 const METHOD: ClosestPointsMethod = ClosestPointsMethod::Shape;
 fn main() -> Result<()> {
     let mut physics_client = PhysicsClient::connect(Mode::Gui)?;
-    physics_client.configure_debug_visualizer(DebugVisualizerFlag::CovEnableGui, false);
+    physics_client.configure_debug_visualizer(DebugVisualizerFlag::CovEnableGui, false)?;
     let geom = physics_client
         .create_collision_shape(GeometricCollisionShape::Sphere { radius: 0.1 }, None)?;
     let geom_box = physics_client.create_collision_shape(
@@ -69,7 +69,7 @@ fn main() -> Result<()> {
             yaw = 0.;
         }
         base_pose_b.rotation = UnitQuaternion::from_euler_angles(0., pitch, yaw);
-        physics_client.reset_base_transform(ob_b, base_pose_b);
+        physics_client.reset_base_transform(ob_b, base_pose_b)?;
 
         let pts = match METHOD {
             ClosestPointsMethod::Body => {
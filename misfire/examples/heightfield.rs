@@ -8,7 +8,7 @@ use std::time::Duration;
 
 fn main() -> Result<()> {
     let mut physics_client = PhysicsClient::connect(Mode::Gui)?;
-    physics_client.configure_debug_visualizer(CovEnableRendering, false);
+    physics_client.configure_debug_visualizer(CovEnableRendering, false)?;
     let height_pertubation_range = 0.05;
     let mut rng = rand::rng();
     let num_heightfield_rows = 256;
@@ -112,20 +112,20 @@ fn main() -> Result<()> {
                     }
                 };
 
-                for joint in 0..physics_client.get_num_joints(sphere_uid) {
+                for joint in 0..physics_client.get_num_joints(sphere_uid)? {
                     physics_client.set_joint_motor_control(
                         sphere_uid,
                         joint,
                         ControlCommand::Velocity(1.),
                         Some(10.),
-                    );
+                    )?;
                 }
             }
         }
     }
-    physics_client.configure_debug_visualizer(CovEnableRendering, true);
-    physics_client.set_gravity(Vector3::new(0.0, 0.0, -10.0));
-    physics_client.set_real_time_simulation(true);
+    physics_client.configure_debug_visualizer(CovEnableRendering, true)?;
+    physics_client.set_gravity(Vector3::new(0.0, 0.0, -10.0))?;
+    physics_client.set_real_time_simulation(true)?;
 
     loop {
         std::thread::sleep(Duration::from_secs_f64(0.01));
@@ -9,7 +9,7 @@ fn main() -> Result<()> {
     let mut physics_client = PhysicsClient::connect(Mode::Gui)?;
 
     physics_client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
-    physics_client.set_gravity(Vector3::new(0.0, 0.0, -10.0));
+    physics_client.set_gravity(Vector3::new(0.0, 0.0, -10.0))?;
 
     let _plane_id = physics_client.load_urdf("plane.urdf", None)?;
 
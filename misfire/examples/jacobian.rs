@@ -6,7 +6,7 @@ use std::time::Duration;
 use anyhow::Result;
 
 pub fn set_joint_positions(client: &mut PhysicsClient, robot: BodyId, position: &[f64]) {
-    let num_joints = client.get_num_joints(robot);
+    let num_joints = client.get_num_joints(robot).unwrap();
     assert_eq!(num_joints, position.len());
     let indices = (0..num_joints).collect::<Vec<usize>>();
     let zero_vec = vec![0.; num_joints];
@@ -31,7 +31,7 @@ pub fn get_joint_states(
     client: &mut PhysicsClient,
     robot: BodyId,
 ) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
-    let num_joints = client.get_num_joints(robot);
+    let num_joints = client.get_num_joints(robot).unwrap();
     let indices = (0..num_joints).collect::<Vec<usize>>();
     let joint_states = client.get_joint_states(robot, indices.as_slice()).unwrap();
     let pos = joint_states
@@ -53,11 +53,11 @@ pub fn get_motor_joint_states(
     client: &mut PhysicsClient,
     robot: BodyId,
 ) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
-    let num_joints = client.get_num_joints(robot);
+    let num_joints = client.get_num_joints(robot).unwrap();
     let indices = (0..num_joints).collect::<Vec<usize>>();
     let joint_states = client.get_joint_states(robot, indices.as_slice()).unwrap();
     let joint_infos: Vec<JointInfo> = (0..num_joints)
-        .map(|y| client.get_joint_info(robot, y))
+        .map(|y| client.get_joint_info(robot, y).unwrap())
         .collect::<Vec<JointInfo>>();
     let joint_states = joint_states
         .iter()
@@ -89,7 +89,7 @@ pub fn multiply_jacobian(
     let mut result = Vector3::new(0., 0., 0.);
     let mut i = 0;
     for c in 0..vector.len() {
-        if client.get_joint_info(robot, c).q_index > -1 {
+        if client.get_joint_info(robot, c).unwrap().q_index > -1 {
             for r in 0..3 {
                 result[r] += jacobian[(r, i)] * vector[c];
             }
@@ -104,8 +104,8 @@ fn main() -> Result<()> {
     let mut p = PhysicsClient::connect(Direct).unwrap();
     p.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
     let gravity_constant = -9.81;
-    p.set_time_step(delta_t);
-    p.set_gravity(Vector3::new(0., 0., gravity_constant));
+    p.set_time_step(delta_t)?;
+    p.set_gravity(Vector3::new(0., 0., gravity_constant))?;
     p.load_urdf(
         "plane.urdf",
         UrdfOptions {
@@ -123,7 +123,7 @@ fn main() -> Result<()> {
     )?;
     // let kuka_id = p.load_urdf("kuka_iiwa/model.urdf", UrdfOptions::default())?;
     // let kuka_id = p.load_urdf("kuka_lwr/kuka.urdf", UrdfOptions::default())?;
-    let num_joints = p.get_num_joints(kuka_id);
+    let num_joints = p.get_num_joints(kuka_id)?;
     let kuka_end_effector_index = num_joints - 1;
 
     set_joint_positions(&mut p, kuka_id, vec![0.1; num_joints].as_slice());
@@ -23,7 +23,7 @@ fn main() -> Result<()> {
         },
     )?;
     let kuka_end_effector_index = 6;
-    let num_joints = physics_client.get_num_joints(kuka_id);
+    let num_joints = physics_client.get_num_joints(kuka_id)?;
     assert_eq!(num_joints, 7);
     let cube_id = physics_client.load_urdf(
         "cube.urdf",
@@ -52,8 +52,8 @@ fn main() -> Result<()> {
     for i in 0..num_joints {
         physics_client.reset_joint_state(kuka_id, i, rp[i], None)?;
     }
-    physics_client.set_gravity([0., 0., -10.]);
-    physics_client.set_real_time_simulation(true);
+    physics_client.set_gravity([0., 0., -10.])?;
+    physics_client.set_real_time_simulation(true)?;
     let _log_id_1 = physics_client.start_state_logging(
         LoggingType::GenericRobot,
         "LOG0001.txt",
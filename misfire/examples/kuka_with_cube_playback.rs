@@ -1,8 +1,7 @@
 use anyhow::Result;
-use nalgebra::Isometry3;
-use misfire::logging_utils::read_generic_robot_log;
+use misfire::logging_utils::LogPlayer;
 use misfire::*;
-use std::time::Duration;
+use nalgebra::Isometry3;
 
 fn main() -> Result<()> {
     let mut physics_client = PhysicsClient::connect(Mode::Gui)?;
@@ -43,24 +42,8 @@ fn main() -> Result<()> {
             ..Default::default()
         },
     )?;
-    let logs = read_generic_robot_log("LOG0001.txt")?;
-
-    let time_step = {
-        let last_log = logs.last().unwrap();
-        Duration::from_secs_f64(last_log.time_stamp.as_secs_f64() / last_log.step_count as f64)
-    };
-    for log in logs {
-        physics_client.reset_base_transform(log.body, log.base_pose);
-        for i in 0..log.num_joints {
-            physics_client.reset_joint_state(
-                log.body,
-                i,
-                log.joint_positions[i],
-                log.joint_velocities[i],
-            )?;
-        }
-        std::thread::sleep(time_step);
-    }
+    let mut player = LogPlayer::open("LOG0001.txt")?;
+    player.play(&mut physics_client)?;
 
     Ok(())
 }
@@ -20,6 +20,6 @@ fn main() -> Result<()> {
     physics_client.step_simulation()?;
     physics_client.step_simulation()?;
     physics_client.step_simulation()?;
-    physics_client.stop_state_logging(log_id);
+    physics_client.stop_state_logging(log_id)?;
     Ok(())
 }
@@ -11,9 +11,9 @@ fn main() -> Result<()> {
     physics_client.set_additional_search_path(
         "../misfire-sys/bullet3/libbullet3/examples/pybullet/gym/pybullet_data",
     )?;
-    physics_client.configure_debug_visualizer(DebugVisualizerFlag::CovEnableYAxisUp, true);
-    physics_client.set_time_step(Duration::from_secs_f64(1. / 60.));
-    physics_client.set_gravity(Vector3::new(0.0, -9.8, 0.));
+    physics_client.configure_debug_visualizer(DebugVisualizerFlag::CovEnableYAxisUp, true)?;
+    physics_client.set_time_step(Duration::from_secs_f64(1. / 60.))?;
+    physics_client.set_gravity(Vector3::new(0.0, -9.8, 0.))?;
 
     let time_step = Duration::from_secs_f64(1. / 60.);
     let mut panda = PandaSim::new(&mut physics_client, Vector3::zeros())?;
@@ -126,10 +126,10 @@ impl PandaSim {
                 angular_damping: Some(0.),
                 ..Default::default()
             },
-        );
+        )?;
         let mut index = 0;
-        for i in 0..client.get_num_joints(panda_id) {
-            let info = client.get_joint_info(panda_id, i);
+        for i in 0..client.get_num_joints(panda_id)? {
+            let info = client.get_joint_info(panda_id, i)?;
             if info.joint_type == JointType::Revolute || info.joint_type == JointType::Prismatic {
                 client.reset_joint_state(
                     panda_id,
@@ -168,12 +168,14 @@ impl PandaSim {
             .calculate_inverse_kinematics(self.id, inverse_kinematics_parameters)
             .unwrap();
         for i in 0..PandaSim::PANDA_NUM_DOFS {
-            client.set_joint_motor_control(
-                self.id,
-                i,
-                ControlCommand::Position(joint_poses[i]),
-                Some(240. * 5.),
-            );
+            client
+                .set_joint_motor_control(
+                    self.id,
+                    i,
+                    ControlCommand::Position(joint_poses[i]),
+                    Some(240. * 5.),
+                )
+                .unwrap();
         }
     }
 }
@@ -23,7 +23,7 @@ fn main() -> Result<()> {
         },
     )?;
 
-    let num_joints = physics_client.get_num_joints(kuka_id);
+    let num_joints = physics_client.get_num_joints(kuka_id)?;
     assert_eq!(num_joints, 7);
     let _cube_id = physics_client.load_urdf(
         "cube.urdf",
@@ -51,21 +51,21 @@ fn main() -> Result<()> {
     for i in 0..num_joints {
         physics_client.reset_joint_state(kuka_id, i, rp[i], None)?;
     }
-    physics_client.set_gravity([0., 0., -10.]);
+    physics_client.set_gravity([0., 0., -10.])?;
 
     std::thread::sleep(Duration::from_secs(2));
     physics_client.save_world("kuka_world.py")?;
     physics_client.save_bullet("state1.bullet")?;
     let state_1 = physics_client.save_state()?;
 
-    physics_client.reset_base_transform(kuka_id, Isometry3::translation(2., 0., 0.));
+    physics_client.reset_base_transform(kuka_id, Isometry3::translation(2., 0., 0.))?;
     physics_client.save_bullet("state2.bullet")?;
     std::thread::sleep(Duration::from_secs(2));
 
     physics_client.restore_state(state_1)?;
     std::thread::sleep(Duration::from_secs(2));
 
-    physics_client.reset_simulation();
+    physics_client.reset_simulation()?;
     std::thread::sleep(Duration::from_secs(2));
 
     let _bodies = physics_client.load_bullet("state1.bullet")?;
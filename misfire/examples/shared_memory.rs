@@ -1,6 +1,6 @@
 use anyhow::Result;
-use nalgebra::Isometry3;
 use misfire::*;
+use nalgebra::Isometry3;
 use std::time::Duration;
 
 fn main() -> Result<()> {
@@ -14,29 +14,34 @@ fn main() -> Result<()> {
         },
     )?;
     std::thread::spawn(move || {
-        let mut shared_memory_client = PhysicsClient::connect(Mode::SharedMemory).unwrap();
+        let mut shared_memory_client =
+            PhysicsClient::connect(Mode::SharedMemory { key: None }).unwrap();
         let mut i: usize = 0;
         loop {
             std::thread::sleep(Duration::from_secs_f64(1. / 240.));
-            shared_memory_client.reset_base_transform(
-                cube,
-                Isometry3::translation(f64::sin(i as f64 * 0.02), 0., 0.),
-            );
+            shared_memory_client
+                .reset_base_transform(
+                    cube,
+                    Isometry3::translation(f64::sin(i as f64 * 0.02), 0., 0.),
+                )
+                .unwrap();
             i += 1;
         }
     });
 
-    // this thread will fail as there can only be one client using the SharedMemory Mode
+    // this thread will fail as there can only be one client per shared memory key
     std::thread::spawn(move || {
         std::thread::sleep(Duration::from_secs_f64(1.));
-        let mut client_fail = PhysicsClient::connect(Mode::SharedMemory).unwrap();
+        let mut client_fail = PhysicsClient::connect(Mode::SharedMemory { key: None }).unwrap();
         let mut i: usize = 0;
         loop {
             std::thread::sleep(Duration::from_secs_f64(1. / 240.));
-            client_fail.reset_base_transform(
-                cube,
-                Isometry3::translation(f64::sin(i as f64 * 0.02), 0., 0.),
-            );
+            client_fail
+                .reset_base_transform(
+                    cube,
+                    Isometry3::translation(f64::sin(i as f64 * 0.02), 0., 0.),
+                )
+                .unwrap();
             i += 1;
         }
     });
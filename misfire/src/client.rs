@@ -2,9 +2,15 @@
 //!
 //! This is largely modeled after the PyBullet API but utilizes Rust's more expressive type system
 //! where available.
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::os::unix::ffi::OsStrExt;
-use std::{ffi::CString, os::raw::c_int, path::Path, ptr};
+use std::{
+    ffi::{CStr, CString},
+    os::raw::{c_char, c_int},
+    path::Path,
+    ptr,
+};
 
 use nalgebra::{
     DMatrix, DVector, Isometry3, Matrix4, Matrix6xX, Quaternion, Translation3, UnitQuaternion,
@@ -13,46 +19,56 @@ use nalgebra::{
 
 use self::marker::GuiMarker;
 use crate::client::marker::SharedMemoryMarker;
+use crate::owned::{Generations, Owned, Resource};
+use crate::pending::Backoff;
+use crate::scene::{LoadedAssets, Scene};
 use crate::types::{
     Aabb, AddDebugLineOptions, AddDebugTextOptions, BodyId, ChangeVisualShapeOptions, CollisionId,
-    ConstraintInfo, ControlCommandArray, ExternalForceFrame, GeometricCollisionShape,
-    GeometricVisualShape, Images, InverseKinematicsParameters, ItemId, Jacobian, JointInfo,
-    JointState, JointType, KeyboardEvent, LinkState, LoadModelFlags, MouseButtonState, MouseEvent,
-    MultiBodyOptions, OverlappingObject, SdfOptions, TextureId, Velocity, VisualId,
-    VisualShapeOptions,
+    ConstraintInfo, ControlCommandArray, ControlCommandMultiDof, ExternalForceFrame,
+    GeometricCollisionShape, GeometricVisualShape, ImageChannels, Images,
+    InverseKinematicsParameters, ItemId, Jacobian, JointInfo, JointState, JointStateMultiDof,
+    JointType, KeyboardEvent, LinkState, LoadModelFlags, MouseButtonState, MouseEvent,
+    MultiBodyOptions, OverlappingObject, PluginId, SdfOptions, TextureId, UserDataId, UserDataInfo,
+    UserDataValue, Velocity, VisualId, VisualShapeOptions,
 };
 use crate::{
-    BodyInfo, CameraImageOptions, ChangeConstraintOptions, ChangeDynamicsOptions, ConstraintId,
-    ContactPoint, ControlCommand, DebugVisualizerCameraInfo, DebugVisualizerFlag, DynamicsInfo,
-    Error, LogId, LoggingType, Mode, PhysicsEngineParameters, RayHitInfo, RayTestBatchOptions,
-    RayTestOptions, ResetFlags, SetPhysicsEngineParameterOptions, SoftBodyOptions, StateId,
-    StateLoggingOptions, UrdfOptions, VisualShapeData,
+    BodyInfo, CameraImageOptions, ChangeConstraintOptions, ChangeDynamicsOptions, CommandBatch,
+    ConstraintId, ContactPoint, ControlCommand, DebugVisualizerCameraInfo, DebugVisualizerFlag,
+    DynamicsInfo, Error, ErrorKind, LogId, LoggingType, Mode, PendingCommand,
+    PhysicsEngineParameters, RayHitInfo, RayTestBatchOptions, RayTestOptions, RendererAuxFlags,
+    ResetFlags, SetPhysicsEngineParameterOptions, SoftBodyOptions, StateId, StateLoggingOptions,
+    UrdfOptions, VisualShapeData,
 };
-use image::{ImageBuffer, Luma, RgbaImage};
+use image::{ImageBuffer, Pixel};
 use misfire_sys as ffi;
 use misfire_sys::EnumSharedMemoryServerStatus::{
-    CMD_ACTUAL_STATE_UPDATE_COMPLETED, CMD_BULLET_LOADING_COMPLETED, CMD_BULLET_SAVING_COMPLETED,
-    CMD_CALCULATED_INVERSE_DYNAMICS_COMPLETED, CMD_CALCULATED_JACOBIAN_COMPLETED,
-    CMD_CALCULATED_MASS_MATRIX_COMPLETED, CMD_CAMERA_IMAGE_COMPLETED, CMD_CLIENT_COMMAND_COMPLETED,
+    CMD_ACTUAL_STATE_UPDATE_COMPLETED, CMD_ADD_USER_DATA_COMPLETED, CMD_BULLET_LOADING_COMPLETED,
+    CMD_BULLET_SAVING_COMPLETED, CMD_CALCULATED_INVERSE_DYNAMICS_COMPLETED,
+    CMD_CALCULATED_JACOBIAN_COMPLETED, CMD_CALCULATED_MASS_MATRIX_COMPLETED,
+    CMD_CAMERA_IMAGE_COMPLETED, CMD_CHANGE_USER_CONSTRAINT_COMPLETED, CMD_CLIENT_COMMAND_COMPLETED,
     CMD_CONTACT_POINT_INFORMATION_COMPLETED, CMD_CREATE_COLLISION_SHAPE_COMPLETED,
     CMD_CREATE_MULTI_BODY_COMPLETED, CMD_CREATE_VISUAL_SHAPE_COMPLETED,
-    CMD_GET_DYNAMICS_INFO_COMPLETED, CMD_LOAD_SOFT_BODY_COMPLETED, CMD_LOAD_TEXTURE_COMPLETED,
-    CMD_REQUEST_COLLISION_INFO_COMPLETED, CMD_REQUEST_PHYSICS_SIMULATION_PARAMETERS_COMPLETED,
-    CMD_REQUEST_RAY_CAST_INTERSECTIONS_COMPLETED, CMD_RESTORE_STATE_COMPLETED,
-    CMD_SAVE_STATE_COMPLETED, CMD_SAVE_WORLD_COMPLETED, CMD_STATE_LOGGING_START_COMPLETED,
-    CMD_SYNC_BODY_INFO_COMPLETED, CMD_USER_CONSTRAINT_COMPLETED, CMD_USER_DEBUG_DRAW_COMPLETED,
+    CMD_CUSTOM_COMMAND_COMPLETED, CMD_GET_DYNAMICS_INFO_COMPLETED, CMD_LOAD_SOFT_BODY_COMPLETED,
+    CMD_LOAD_TEXTURE_COMPLETED, CMD_REMOVE_BODY_COMPLETED, CMD_REMOVE_STATE_COMPLETED,
+    CMD_REMOVE_USER_DATA_COMPLETED, CMD_REQUEST_AABB_OVERLAP_COMPLETED,
+    CMD_REQUEST_COLLISION_INFO_COMPLETED, CMD_REQUEST_KEYBOARD_EVENTS_DATA_COMPLETED,
+    CMD_REQUEST_MOUSE_EVENTS_DATA_COMPLETED, CMD_REQUEST_PHYSICS_SIMULATION_PARAMETERS_COMPLETED,
+    CMD_REQUEST_RAY_CAST_INTERSECTIONS_COMPLETED, CMD_RESET_SIMULATION_COMPLETED,
+    CMD_RESTORE_STATE_COMPLETED, CMD_SAVE_STATE_COMPLETED, CMD_SAVE_WORLD_COMPLETED,
+    CMD_STATE_LOGGING_START_COMPLETED, CMD_SYNC_BODY_INFO_COMPLETED, CMD_SYNC_USER_DATA_COMPLETED,
+    CMD_USER_CONSTRAINT_COMPLETED, CMD_USER_DEBUG_DRAW_COMPLETED,
     CMD_USER_DEBUG_DRAW_PARAMETER_COMPLETED, CMD_VISUAL_SHAPE_INFO_COMPLETED,
     CMD_VISUAL_SHAPE_UPDATE_COMPLETED,
 };
 use misfire_sys::{
     b3AABBOverlapData, b3CameraImageData, b3ContactInformation, b3DynamicsInfo, b3JointInfo,
-    b3JointSensorState, b3KeyboardEventsData, b3LinkState, b3MouseEventsData,
+    b3JointSensorState, b3JointSensorState2, b3KeyboardEventsData, b3LinkState, b3MouseEventsData,
     b3OpenGLVisualizerCameraInfo, b3PhysicsSimulationParameters, b3RaycastInformation,
-    b3SharedMemoryCommandHandle, b3SharedMemoryStatusHandle, b3SubmitClientCommandAndWaitStatus,
-    B3_MAX_NUM_INDICES, B3_MAX_NUM_VERTICES, MAX_RAY_INTERSECTION_BATCH_SIZE_STREAMING,
-    MAX_SDF_BODIES, SHARED_MEMORY_KEY,
+    b3SharedMemoryCommandHandle, b3SharedMemoryStatusHandle, UserDataValueType, B3_MAX_NUM_INDICES,
+    B3_MAX_NUM_VERTICES, B3_MAX_PLUGIN_ARG_SIZE, B3_MAX_PLUGIN_ARG_TEXT_LEN,
+    MAX_RAY_INTERSECTION_BATCH_SIZE_STREAMING, MAX_SDF_BODIES, SHARED_MEMORY_KEY,
 };
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// The "handle" to the physics client.
 ///
@@ -64,6 +80,16 @@ type Handle = ffi::b3PhysicsClientHandle;
 /// Connection to a physics server.
 ///
 /// This serves as an abstraction over the possible physics servers, providing a unified interface.
+///
+/// # Threads
+/// A `PhysicsClient` is neither [`Send`] nor [`Sync`]. Clients which were created with
+/// [`Mode::GuiMainThread`](`crate::Mode::GuiMainThread`), or with [`Mode::Gui`](`crate::Mode::Gui`)
+/// on macOS, render the GUI on the thread which created them and have to stay on that thread.
+/// Clients in [`Direct`](`crate::Mode::Direct`) and [`Tcp`](`crate::Mode::Tcp`) mode can be
+/// moved to another thread with [`SendablePhysicsClient`](`crate::SendablePhysicsClient`), e.g.
+/// to run the simulation in the background. Use
+/// [`SharedPhysicsClient`](`crate::SharedPhysicsClient`) if several threads need to access the
+/// same simulation.
 pub struct PhysicsClient {
     /// The underlying `b3PhysicsClientHandle` that is guaranteed to not be null.
     pub(crate) handle: Handle,
@@ -73,6 +99,19 @@ pub struct PhysicsClient {
 
     /// A marker indicating whether or not SharedMemory is in use by this client.
     pub(crate) _shared_memory_marker: Option<SharedMemoryMarker>,
+
+    /// The generations of the ids which were created by this client.
+    pub(crate) generations: Generations,
+
+    /// The files from which the bodies of this client were loaded.
+    pub(crate) assets: LoadedAssets,
+
+    /// Whether the client may be moved to another thread.
+    pub(crate) sendable: bool,
+
+    /// Whether the server still owes the answer to a command whose [`PendingCommand`] was dropped
+    /// or timed out.
+    pub(crate) awaiting_status: bool,
 }
 
 impl PhysicsClient {
@@ -90,7 +129,9 @@ impl PhysicsClient {
     /// There are also other modes for more advanced use cases. However, these were not heavily tested,
     /// so be careful when you use them.
     pub fn connect(mode: Mode) -> Result<PhysicsClient, Error> {
+        let sendable = matches!(mode, Mode::Direct | Mode::Tcp { .. });
         let (raw_handle, _gui_marker, _shared_memory_marker) = match mode {
+            #[cfg(feature = "gui")]
             Mode::GuiMainThread => {
                 // Only one GUI is allowed per process. Try to get the marker and fail if there is
                 // another.
@@ -102,13 +143,16 @@ impl PhysicsClient {
                     (raw_handle, Some(gui_marker), None)
                 }
             }
+            #[cfg(feature = "gui")]
             Mode::GraphicsServerTcp { hostname, port } => {
                 let gui_marker = GuiMarker::acquire()?;
 
                 let raw_handle = {
                     unsafe {
                         let port = port.unwrap_or(6667);
-                        let hostname = CString::new(hostname.as_bytes()).unwrap();
+                        let hostname = CString::new(hostname.as_bytes()).map_err(|_| {
+                            Error::of_kind(ErrorKind::InvalidArgument, "Invalid hostname")
+                        })?;
                         ffi::b3CreateInProcessPhysicsServerFromExistingExampleBrowserAndConnectTCP(
                             hostname.as_ptr(),
                             port as i32,
@@ -125,7 +169,9 @@ impl PhysicsClient {
                 let raw_handle = {
                     unsafe {
                         let port = port.unwrap_or(1234);
-                        let hostname = CString::new(hostname.as_bytes()).unwrap();
+                        let hostname = CString::new(hostname.as_bytes()).map_err(|_| {
+                            Error::of_kind(ErrorKind::InvalidArgument, "Invalid hostname")
+                        })?;
                         ffi::b3ConnectPhysicsUDP(hostname.as_ptr(), port as i32)
                     }
                 };
@@ -139,21 +185,25 @@ impl PhysicsClient {
                 let raw_handle = {
                     unsafe {
                         let port = port.unwrap_or(6667);
-                        let hostname = CString::new(hostname.as_bytes()).unwrap();
+                        let hostname = CString::new(hostname.as_bytes()).map_err(|_| {
+                            Error::of_kind(ErrorKind::InvalidArgument, "Invalid hostname")
+                        })?;
                         ffi::b3ConnectPhysicsTCP(hostname.as_ptr(), port as i32)
                     }
                 };
 
                 (raw_handle, None, None)
             }
-            Mode::SharedMemoryServer => unsafe {
+            #[cfg(feature = "gui")]
+            Mode::SharedMemoryServer { key } => unsafe {
                 let raw_handle =
                     ffi::b3CreateInProcessPhysicsServerFromExistingExampleBrowserAndConnect3(
                         ptr::null_mut(),
-                        SHARED_MEMORY_KEY,
+                        key.unwrap_or(SHARED_MEMORY_KEY),
                     );
                 (raw_handle, None, None)
             },
+            #[cfg(feature = "gui")]
             Mode::GuiServer => {
                 // Only one GUI is allowed per process. Try to get the marker and fail if there is
                 // another.
@@ -178,6 +228,7 @@ impl PhysicsClient {
                 (raw_handle, Some(gui_marker), None)
             }
             Mode::Direct => unsafe { (ffi::b3ConnectPhysicsDirect(), None, None) },
+            #[cfg(feature = "gui")]
             Mode::Gui => {
                 // Only one GUI is allowed per process. Try to get the marker and fail if there is
                 // another.
@@ -196,22 +247,27 @@ impl PhysicsClient {
 
                 (raw_handle, Some(gui_marker), None)
             }
-            Mode::SharedMemoryGui => unsafe {
-                let shared_memory_marker = SharedMemoryMarker::acquire()?;
+            #[cfg(feature = "gui")]
+            Mode::SharedMemoryGui { key } => unsafe {
+                let key = key.unwrap_or(SHARED_MEMORY_KEY);
+                let shared_memory_marker = SharedMemoryMarker::acquire(key)?;
                 let raw_handle =
                     ffi::b3CreateInProcessPhysicsServerFromExistingExampleBrowserAndConnect4(
                         ptr::null_mut(),
-                        SHARED_MEMORY_KEY,
+                        key,
                     );
                 (raw_handle, None, Some(shared_memory_marker))
             },
-            Mode::SharedMemory => unsafe {
-                let shared_memory_marker = SharedMemoryMarker::acquire()?;
-                let raw_handle = ffi::b3ConnectSharedMemory(SHARED_MEMORY_KEY);
+            Mode::SharedMemory { key } => unsafe {
+                let key = key.unwrap_or(SHARED_MEMORY_KEY);
+                let shared_memory_marker = SharedMemoryMarker::acquire(key)?;
+                let raw_handle = ffi::b3ConnectSharedMemory(key);
                 (raw_handle, None, Some(shared_memory_marker))
             },
         };
-        let handle = raw_handle.expect("Bullet returned a null pointer");
+        let handle = raw_handle.ok_or_else(|| {
+            Error::of_kind(ErrorKind::Disconnected, "Bullet returned a null pointer")
+        })?;
 
         // At this point, we need to disconnect the physics client at any error. So we create the
         // Rust struct and allow the `Drop` implementation to take care of that.
@@ -219,11 +275,18 @@ impl PhysicsClient {
             handle,
             _gui_marker,
             _shared_memory_marker,
+            generations: Generations::default(),
+            assets: LoadedAssets::default(),
+            sendable,
+            awaiting_status: false,
         };
 
         // Make sure it is up and running.
         if !client.can_submit_command() {
-            return Err(Error::new("Physics server is not running"));
+            return Err(Error::of_kind(
+                ErrorKind::Disconnected,
+                "Physics server is not running",
+            ));
         }
 
         // Now perform a series of commands to finish starting up the server. I don't know what they
@@ -231,19 +294,27 @@ impl PhysicsClient {
         // am assuming that they either can't be null or the consumer does the check.
         unsafe {
             let command = ffi::b3InitSyncBodyInfoCommand(client.handle);
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(client.handle, command);
+            let status_handle = client.submit_command_and_wait_status(command);
             let status_type = ffi::b3GetStatusType(status_handle);
 
             if status_type != ffi::EnumSharedMemoryServerStatus::CMD_SYNC_BODY_INFO_COMPLETED as _ {
-                return Err(Error::new("Connection terminated, couldn't get body info"));
+                return Err(Error::command_failed(
+                    "connect",
+                    status_type,
+                    "Connection terminated, couldn't get body info",
+                ));
             }
 
             let command = ffi::b3InitSyncUserDataCommand(client.handle);
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(client.handle, command);
+            let status_handle = client.submit_command_and_wait_status(command);
             let status_type = ffi::b3GetStatusType(status_handle);
 
             if status_type != ffi::EnumSharedMemoryServerStatus::CMD_SYNC_USER_DATA_COMPLETED as _ {
-                return Err(Error::new("Connection terminated, couldn't get user data"));
+                return Err(Error::command_failed(
+                    "connect",
+                    status_type,
+                    "Connection terminated, couldn't get user data",
+                ));
             }
         }
 
@@ -251,13 +322,8 @@ impl PhysicsClient {
         Ok(client)
     }
     /// reset_simulation will remove all objects from the world and reset the world to initial conditions.
-    pub fn reset_simulation(&mut self) {
-        unsafe {
-            let command_handle = ffi::b3InitResetSimulationCommand(self.handle);
-            ffi::b3InitResetSimulationSetFlags(command_handle, 0);
-            let _status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
-        }
+    pub fn reset_simulation(&mut self) -> Result<(), Error> {
+        self.reset_simulation_with_flags(ResetFlags::empty())
     }
     /// Warning: in many cases it is best to leave the timeStep to default, which is 240Hz.
     /// Several parameters are tuned with this value in mind. For example the number of solver
@@ -269,11 +335,14 @@ impl PhysicsClient {
     /// [`step_simulation`](`Self::step_simulation()`).
     /// It is best to only call this method at the start of a simulation.
     /// Don't change this time step regularly.
-    pub fn set_time_step(&mut self, time_step: Duration) {
+    pub fn set_time_step(&mut self, time_step: Duration) -> Result<(), Error> {
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
         unsafe {
             let command = ffi::b3InitPhysicsParamCommand(self.handle);
             let _ret = ffi::b3PhysicsParamSetTimeStep(command, time_step.as_secs_f64());
-            let _status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
+            self.submit_simple_command(command)
         }
     }
     /// By default, the physics server will not step the simulation, unless you explicitly send a
@@ -295,31 +364,36 @@ impl PhysicsClient {
     ///
     /// # Arguments
     /// * `enable_real_time_simulation` - activates or deactivates real-time simulation
-    pub fn set_real_time_simulation(&mut self, enable_real_time_simulation: bool) {
+    pub fn set_real_time_simulation(
+        &mut self,
+        enable_real_time_simulation: bool,
+    ) -> Result<(), Error> {
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
         unsafe {
             let command = ffi::b3InitPhysicsParamCommand(self.handle);
             let _ret = ffi::b3PhysicsParamSetRealTimeSimulation(
                 command,
                 enable_real_time_simulation as i32,
             );
-            let _status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
+            self.submit_simple_command(command)
         }
     }
     /// Sets an additional search path for loading assets.
     pub fn set_additional_search_path<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
         if !self.can_submit_command() {
-            return Err(Error::new("Not connected to physics server"));
+            return Err(Error::disconnected());
         }
 
         let path = CString::new(path.as_ref().as_os_str().as_bytes())
-            .map_err(|_| Error::new("Invalid path"))?;
+            .map_err(|_| Error::of_kind(ErrorKind::InvalidArgument, "Invalid path"))?;
 
         unsafe {
             // Based on PyBullet, it appears that this path is copied and it does not need to live
             // after calling the function.
             let command_handle = ffi::b3SetAdditionalSearchPath(self.handle, path.as_ptr());
-            let _status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let _status_handle = self.submit_command_and_wait_status(command_handle);
         }
 
         Ok(())
@@ -332,18 +406,25 @@ impl PhysicsClient {
     /// # Arguments
     /// * `gravity` - a gravity vector. Can be a Vector3, a \[f64;3\]-array or anything else that can be
     /// converted into a Vector3.
-    pub fn set_gravity<GravityVector: Into<Vector3<f64>>>(&mut self, gravity: GravityVector) {
+    pub fn set_gravity<GravityVector: Into<Vector3<f64>>>(
+        &mut self,
+        gravity: GravityVector,
+    ) -> Result<(), Error> {
         let gravity = gravity.into();
 
-        assert!(self.can_submit_command(), "Not connected to physics server");
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
 
         unsafe {
             // PyBullet error checks none of these. Looking through the code, it looks like there is
             // no possible way to return an error on them.
             let command = ffi::b3InitPhysicsParamCommand(self.handle);
             let _ret = ffi::b3PhysicsParamSetGravity(command, gravity.x, gravity.y, gravity.z);
-            let _status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
+            let _status_handle = self.submit_command_and_wait_status(command);
         }
+
+        Ok(())
     }
 
     /// Sends a command to the physics server to load a physics model from a Unified Robot
@@ -371,7 +452,7 @@ impl PhysicsClient {
     ///             },
     ///         )?;
     ///     }
-    ///     assert_eq!(11, physics_client.get_num_bodies());
+    ///     assert_eq!(11, physics_client.get_num_bodies()?);
     ///     Ok(())
     /// }
     /// ```
@@ -381,11 +462,12 @@ impl PhysicsClient {
         options: Options,
     ) -> Result<BodyId, Error> {
         if !self.can_submit_command() {
-            return Err(Error::new("Not connected to physics server"));
+            return Err(Error::disconnected());
         }
 
+        let path = file.as_ref().to_path_buf();
         let file = CString::new(file.as_ref().as_os_str().as_bytes())
-            .map_err(|_| Error::new("Invalid path"))?;
+            .map_err(|_| Error::of_kind(ErrorKind::InvalidArgument, "Invalid path"))?;
 
         let options = options.into().unwrap_or_default();
         unsafe {
@@ -417,18 +499,25 @@ impl PhysicsClient {
             }
 
             if options.global_scaling > 0.0 {
-                let _ret =
-                    ffi::b3LoadUrdfCommandSetGlobalScaling(command, options.global_scaling);
+                let _ret = ffi::b3LoadUrdfCommandSetGlobalScaling(command, options.global_scaling);
             }
 
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
+            let status_handle = self.submit_command_and_wait_status(command);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type != ffi::EnumSharedMemoryServerStatus::CMD_URDF_LOADING_COMPLETED as c_int
             {
-                return Err(Error::new("Cannot load URDF file"));
+                return Err(Error::command_failed(
+                    "load_urdf",
+                    status_type,
+                    "Cannot load URDF file",
+                ));
             }
 
-            Ok(BodyId(ffi::b3GetStatusBodyIndex(status_handle)))
+            let body = self
+                .generations
+                .create(ffi::b3GetStatusBodyIndex(status_handle));
+            self.assets.insert_urdf(path, &options, body);
+            Ok(body)
         }
     }
 
@@ -460,15 +549,17 @@ impl PhysicsClient {
         options: Options,
     ) -> Result<Vec<BodyId>, Error> {
         if !self.can_submit_command() {
-            return Err(Error::new("Not connected to physics server"));
+            return Err(Error::disconnected());
         }
 
+        let path = file.as_ref().to_path_buf();
         let file = CString::new(file.as_ref().as_os_str().as_bytes())
-            .map_err(|_| Error::new("Invalid path"))?;
+            .map_err(|_| Error::of_kind(ErrorKind::InvalidArgument, "Invalid path"))?;
 
+        let options = options.into();
         unsafe {
             let command = ffi::b3LoadSdfCommandInit(self.handle, file.as_ptr());
-            if let Some(options) = options.into() {
+            if let Some(options) = options.as_ref() {
                 if options.use_maximal_coordinates {
                     ffi::b3LoadSdfCommandSetUseMultiBody(command, 0);
                 }
@@ -477,11 +568,15 @@ impl PhysicsClient {
                 }
             }
 
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
+            let status_handle = self.submit_command_and_wait_status(command);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type != ffi::EnumSharedMemoryServerStatus::CMD_SDF_LOADING_COMPLETED as c_int
             {
-                return Err(Error::new("Cannot load SDF file"));
+                return Err(Error::command_failed(
+                    "load_sdf",
+                    status_type,
+                    "Cannot load SDF file",
+                ));
             }
             let mut body_indices_out = [0; MAX_SDF_BODIES as usize];
             let num_bodies = ffi::b3GetStatusBodyIndices(
@@ -498,9 +593,10 @@ impl PhysicsClient {
             let mut bodies = Vec::<BodyId>::with_capacity(num_bodies as usize);
             if num_bodies > 0 && num_bodies <= MAX_SDF_BODIES as i32 {
                 for i in 0..num_bodies {
-                    bodies.push(BodyId(body_indices_out[i as usize]));
+                    bodies.push(self.generations.create(body_indices_out[i as usize]));
                 }
             }
+            self.assets.insert_sdf(path, options.as_ref(), &bodies);
             Ok(bodies)
         }
     }
@@ -534,22 +630,28 @@ impl PhysicsClient {
         flags: Flags,
     ) -> Result<Vec<BodyId>, Error> {
         if !self.can_submit_command() {
-            return Err(Error::new("Not connected to physics server"));
+            return Err(Error::disconnected());
         }
 
+        let path = file.as_ref().to_path_buf();
         let file = CString::new(file.as_ref().as_os_str().as_bytes())
-            .map_err(|_| Error::new("Invalid path"))?;
+            .map_err(|_| Error::of_kind(ErrorKind::InvalidArgument, "Invalid path"))?;
 
+        let flags = flags.into();
         unsafe {
             let command = ffi::b3LoadMJCFCommandInit(self.handle, file.as_ptr());
-            if let Some(flags) = flags.into() {
+            if let Some(flags) = flags {
                 ffi::b3LoadMJCFCommandSetFlags(command, flags.bits());
             }
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
+            let status_handle = self.submit_command_and_wait_status(command);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type != ffi::EnumSharedMemoryServerStatus::CMD_MJCF_LOADING_COMPLETED as c_int
             {
-                return Err(Error::new("Cannot load .mjcf file"));
+                return Err(Error::command_failed(
+                    "load_mjcf",
+                    status_type,
+                    "Cannot load .mjcf file",
+                ));
             }
             let mut body_indices_out = [0; MAX_SDF_BODIES as usize];
             let num_bodies = ffi::b3GetStatusBodyIndices(
@@ -566,9 +668,10 @@ impl PhysicsClient {
             let mut bodies = Vec::<BodyId>::with_capacity(num_bodies as usize);
             if num_bodies > 0 && num_bodies <= MAX_SDF_BODIES as i32 {
                 for i in 0..num_bodies {
-                    bodies.push(BodyId(body_indices_out[i as usize]));
+                    bodies.push(self.generations.create(body_indices_out[i as usize]));
                 }
             }
+            self.assets.insert_mjcf(path, flags, &bodies);
             Ok(bodies)
         }
     }
@@ -579,73 +682,69 @@ impl PhysicsClient {
     // TODO: Return analytics data?
     pub fn step_simulation(&mut self) -> Result<(), Error> {
         if !self.can_submit_command() {
-            return Err(Error::new("Not connected to physics server"));
+            return Err(Error::disconnected());
         }
 
         unsafe {
             let command = ffi::b3InitStepSimulationCommand(self.handle);
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
-            let status_type = ffi::b3GetStatusType(status_handle);
-            if status_type
-                != ffi::EnumSharedMemoryServerStatus::CMD_STEP_FORWARD_SIMULATION_COMPLETED as i32
-            {
-                return Err(Error::new("Failed to perform forward step"));
-            }
+            let status_handle = self.submit_command_and_wait_status(command);
+            check_step_simulation_status(status_handle)
+        }
+    }
+    /// Non-blocking version of [`step_simulation`](`Self::step_simulation`). It returns as soon
+    /// as the command was sent. See [`PendingCommand`](`crate::PendingCommand`) for how to get
+    /// the result.
+    pub fn step_simulation_async(&mut self) -> Result<PendingCommand<'_, ()>, Error> {
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
         }
 
-        Ok(())
+        unsafe {
+            let command = ffi::b3InitStepSimulationCommand(self.handle);
+            PendingCommand::submit(self, command, |_, status_handle| {
+                check_step_simulation_status(status_handle)
+            })
+        }
+    }
+    /// Starts a [`CommandBatch`](`crate::CommandBatch`), which queues several commands, e.g.
+    /// motor commands, a simulation step and state queries, and sends each one as soon as the
+    /// server answered the previous one. The results of all of them are collected together.
+    pub fn batch(&mut self) -> CommandBatch<'_> {
+        CommandBatch::new(self)
     }
 
     /// Reports the current transform of the base.
     /// # Arguments
     /// * `body` - the [`BodyId`](`crate::types::BodyId`), as returned by [`load_urdf`](`Self::load_urdf()`) etc.
     pub fn get_base_transform(&mut self, body: BodyId) -> Result<Isometry3<f64>, Error> {
+        self.generations.check(body)?;
         if !self.can_submit_command() {
-            return Err(Error::new("Not connected to physics server"));
+            return Err(Error::disconnected());
         }
 
         unsafe {
             let cmd_handle = ffi::b3RequestActualStateCommandInit(self.handle, body.0);
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, cmd_handle);
-            let status_type = ffi::b3GetStatusType(status_handle);
-
-            if status_type
-                != ffi::EnumSharedMemoryServerStatus::CMD_ACTUAL_STATE_UPDATE_COMPLETED as c_int
-            {
-                return Err(Error::new("Failed to get base transform"));
-            }
-
-            // To be totally honest, I'm not sure this part is correct.
-            let mut actual_state_q: *const f64 = ptr::null();
-            ffi::b3GetStatusActualState(
-                status_handle,
-                ptr::null_mut(),
-                ptr::null_mut(),
-                ptr::null_mut(),
-                ptr::null_mut(),
-                &mut actual_state_q,
-                ptr::null_mut(),
-                ptr::null_mut(),
-            );
-
-            assert!(!actual_state_q.is_null());
-
-            let tx = *actual_state_q;
-            let ty = *(actual_state_q.offset(1));
-            let tz = *(actual_state_q.offset(2));
-
-            let rx = *(actual_state_q.offset(3));
-            let ry = *(actual_state_q.offset(4));
-            let rz = *(actual_state_q.offset(5));
-            let rw = *(actual_state_q.offset(6));
-
-            let tra = Translation3::new(tx, ty, tz);
-            let rot = Quaternion::new(rw, rx, ry, rz);
+            let status_handle = self.submit_command_and_wait_status(cmd_handle);
+            base_transform_from_status(status_handle)
+        }
+    }
+    /// Non-blocking version of [`get_base_transform`](`Self::get_base_transform`). It returns
+    /// as soon as the command was sent. See [`PendingCommand`](`crate::PendingCommand`) for how
+    /// to get the result.
+    pub fn get_base_transform_async(
+        &mut self,
+        body: BodyId,
+    ) -> Result<PendingCommand<'_, Isometry3<f64>>, Error> {
+        self.generations.check(body)?;
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
 
-            Ok(Isometry3::from_parts(
-                tra,
-                UnitQuaternion::from_quaternion(rot),
-            ))
+        unsafe {
+            let cmd_handle = ffi::b3RequestActualStateCommandInit(self.handle, body.0);
+            PendingCommand::submit(self, cmd_handle, |_, status_handle| {
+                base_transform_from_status(status_handle)
+            })
         }
     }
     /// You can reset the position and orientation of the base (root) of each object.
@@ -657,7 +756,15 @@ impl PhysicsClient {
     /// # Arguments
     /// * `body` - the [`BodyId`](`crate::types::BodyId`), as returned by [`load_urdf`](`Self::load_urdf()`) etc.
     /// * `pose` - reset the base of the object to the specified pose in world space coordinates
-    pub fn reset_base_transform(&mut self, body: BodyId, pose: Isometry3<f64>) {
+    pub fn reset_base_transform(
+        &mut self,
+        body: BodyId,
+        pose: Isometry3<f64>,
+    ) -> Result<(), Error> {
+        self.generations.check(body)?;
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
         unsafe {
             let command_handle = ffi::b3CreatePoseCommandInit(self.handle, body.0);
             ffi::b3CreatePoseCommandSetBasePosition(
@@ -673,8 +780,7 @@ impl PhysicsClient {
                 pose.rotation.k,
                 pose.rotation.w,
             );
-            let _status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            self.submit_simple_command(command_handle)
         }
     }
     /// You get access to the linear and angular velocity of the base of a body.
@@ -686,14 +792,19 @@ impl PhysicsClient {
     /// # See also
     /// [reset_base_velocity](`Self::reset_base_velocity()`) to reset a base velocity and for examples
     pub fn get_base_velocity(&mut self, body: BodyId) -> Result<Velocity, Error> {
+        self.generations.check(body)?;
         let mut base_velocity = [0.; 6];
         unsafe {
             let cmd_handle = ffi::b3RequestActualStateCommandInit(self.handle, body.0);
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, cmd_handle);
+            let status_handle = self.submit_command_and_wait_status(cmd_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             let mut actual_state_qdot: *const f64 = ptr::null();
             if status_type != CMD_ACTUAL_STATE_UPDATE_COMPLETED as i32 {
-                return Err(Error::new("get_base_velocity_failed."));
+                return Err(Error::command_failed(
+                    "get_base_velocity",
+                    status_type,
+                    "get_base_velocity failed.",
+                ));
             }
             ffi::b3GetStatusActualState(
                 status_handle,
@@ -705,6 +816,12 @@ impl PhysicsClient {
                 &mut actual_state_qdot,
                 ptr::null_mut(),
             );
+            if actual_state_qdot.is_null() {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidBodyId,
+                    "get_base_velocity failed.",
+                ));
+            }
             let base_velocity_slice = std::slice::from_raw_parts(actual_state_qdot, 6);
             base_velocity[..6].clone_from_slice(&base_velocity_slice[..6]);
         }
@@ -739,17 +856,17 @@ impl PhysicsClient {
     ///    )?;
     ///
     ///    physics_client
-    ///        .reset_base_velocity(box_id, [1., 2., 3.], [4., 5., 6.]);
+    ///        .reset_base_velocity(box_id, [1., 2., 3.], [4., 5., 6.])?;
     ///    let velocity = physics_client.get_base_velocity(box_id)?;
     ///    assert_eq!(velocity.to_vector().as_slice(), &[1., 2., 3., 4., 5., 6.]);
     ///
     ///    physics_client
-    ///        .reset_base_velocity(box_id, Vector3::zeros(), None);
+    ///        .reset_base_velocity(box_id, Vector3::zeros(), None)?;
     ///    let velocity = physics_client.get_base_velocity(box_id)?;
     ///    assert_eq!(velocity.to_vector().as_slice(), &[0., 0., 0., 4., 5., 6.]);
     ///
     ///    physics_client
-    ///        .reset_base_velocity(box_id, None, [0., 0., 0.]);
+    ///        .reset_base_velocity(box_id, None, [0., 0., 0.])?;
     ///    let velocity = physics_client.get_base_velocity(box_id)?;
     ///    assert_eq!(velocity.to_vector().as_slice(), & [0.; 6]);
     ///
@@ -765,7 +882,11 @@ impl PhysicsClient {
         body: BodyId,
         linear_velocity: Linear,
         angular_velocity: Angular,
-    ) {
+    ) -> Result<(), Error> {
+        self.generations.check(body)?;
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
         let maybe_lin = linear_velocity.into();
         let maybe_angular = angular_velocity.into();
         unsafe {
@@ -793,8 +914,7 @@ impl PhysicsClient {
                     );
                 }
             }
-            let _status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            self.submit_simple_command(command_handle)
         }
     }
     /// Queries the Cartesian world pose for the center of mass for a link.
@@ -821,8 +941,14 @@ impl PhysicsClient {
         compute_link_velocity: bool,
         compute_forward_kinematics: bool,
     ) -> Result<LinkState, Error> {
+        self.generations.check(body)?;
         unsafe {
-            assert!(body.0 >= 0, "get_link_state failed; invalid BodyId");
+            if body.0 < 0 {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidBodyId,
+                    "get_link_state failed; invalid BodyId",
+                ));
+            }
 
             let cmd_handle = ffi::b3RequestActualStateCommandInit(self.handle, body.0);
             if compute_link_velocity {
@@ -831,10 +957,14 @@ impl PhysicsClient {
             if compute_forward_kinematics {
                 ffi::b3RequestActualStateCommandComputeForwardKinematics(cmd_handle, 1);
             }
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, cmd_handle);
+            let status_handle = self.submit_command_and_wait_status(cmd_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type != CMD_ACTUAL_STATE_UPDATE_COMPLETED as i32 {
-                return Err(Error::new("getLinkState failed."));
+                return Err(Error::command_failed(
+                    "get_link_state",
+                    status_type,
+                    "getLinkState failed.",
+                ));
             }
             let mut link_state = b3LinkState::default();
             if ffi::b3GetLinkState(
@@ -847,7 +977,10 @@ impl PhysicsClient {
                 return Ok((link_state, compute_link_velocity).into());
             }
         }
-        Err(Error::new("getLinkState failed."))
+        Err(Error::of_kind(
+            ErrorKind::InvalidLinkIndex,
+            "getLinkState failed.",
+        ))
     }
     /// getLinkStates will return the information for multiple links.
     /// Instead of link_index it will accept link_indices as an array of i32.
@@ -873,8 +1006,14 @@ impl PhysicsClient {
         compute_link_velocity: bool,
         compute_forward_kinematics: bool,
     ) -> Result<Vec<LinkState>, Error> {
+        self.generations.check(body)?;
         unsafe {
-            assert!(body.0 >= 0, "get_link_states failed; invalid BodyId");
+            if body.0 < 0 {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidBodyId,
+                    "get_link_states failed; invalid BodyId",
+                ));
+            }
 
             let cmd_handle = ffi::b3RequestActualStateCommandInit(self.handle, body.0);
             if compute_link_velocity {
@@ -883,10 +1022,14 @@ impl PhysicsClient {
             if compute_forward_kinematics {
                 ffi::b3RequestActualStateCommandComputeForwardKinematics(cmd_handle, 1);
             }
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, cmd_handle);
+            let status_handle = self.submit_command_and_wait_status(cmd_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type != CMD_ACTUAL_STATE_UPDATE_COMPLETED as i32 {
-                return Err(Error::new("getLinkState failed."));
+                return Err(Error::command_failed(
+                    "get_link_states",
+                    status_type,
+                    "getLinkState failed.",
+                ));
             }
             let mut link_states = Vec::<LinkState>::with_capacity(link_indices.len());
             for &link_index in link_indices.iter() {
@@ -900,7 +1043,10 @@ impl PhysicsClient {
                 {
                     link_states.push((link_state, compute_link_velocity).into());
                 } else {
-                    return Err(Error::new("getLinkStates failed."));
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidLinkIndex,
+                        "getLinkStates failed.",
+                    ));
                 }
             }
             Ok(link_states)
@@ -911,6 +1057,53 @@ impl PhysicsClient {
     pub(crate) fn can_submit_command(&mut self) -> bool {
         unsafe { ffi::b3CanSubmitCommand(self.handle) != 0 }
     }
+    /// Bullet only keeps one command in flight per client. If the answer to an abandoned command
+    /// is still outstanding, this waits for it and throws it away, so that it is not mistaken for
+    /// the answer to the next command. Returns false if the server did not answer in time.
+    pub(crate) fn drain_status(&mut self) -> bool {
+        if !self.awaiting_status {
+            return true;
+        }
+        unsafe {
+            let deadline =
+                Instant::now() + Duration::from_secs_f64(ffi::b3GetTimeOut(self.handle).max(0.));
+            let mut backoff = Backoff::new();
+            while ffi::b3ProcessServerStatus(self.handle).is_null() {
+                if Instant::now() >= deadline {
+                    return false;
+                }
+                backoff.wait();
+            }
+        }
+        self.awaiting_status = false;
+        true
+    }
+    /// Submits a command and waits for its status, like `b3SubmitClientCommandAndWaitStatus`,
+    /// after draining an outstanding answer. Returns a null status handle if the server does not
+    /// answer in time, in which case the command is not sent at all.
+    pub(crate) unsafe fn submit_command_and_wait_status(
+        &mut self,
+        command: b3SharedMemoryCommandHandle,
+    ) -> b3SharedMemoryStatusHandle {
+        if !self.drain_status() {
+            return ptr::null_mut();
+        }
+        ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command)
+    }
+    /// Submits a command whose answer carries no data. Returns a
+    /// [`Disconnected`](`ErrorKind::Disconnected`) error if the server did not answer.
+    pub(crate) unsafe fn submit_simple_command(
+        &mut self,
+        command: b3SharedMemoryCommandHandle,
+    ) -> Result<(), Error> {
+        if self.submit_command_and_wait_status(command).is_null() {
+            return Err(Error::of_kind(
+                ErrorKind::Disconnected,
+                "the physics server did not answer",
+            ));
+        }
+        Ok(())
+    }
     /// You can change the properties such as mass, friction and restitution coefficients using this
     /// method.
     /// # Arguments
@@ -944,7 +1137,7 @@ impl PhysicsClient {
     ///             lateral_friction: Some(0.1),
     ///             ..Default::default()
     ///         },
-    ///     );
+    ///     )?;
     ///     let dynamics_info = physics_client.get_dynamics_info(cube_id, None)?;
     ///     println!("{:?}", dynamics_info);
     ///     assert!((dynamics_info.mass - 38.).abs() < 1e-7);
@@ -957,7 +1150,11 @@ impl PhysicsClient {
         body: BodyId,
         link_index: Link,
         options: ChangeDynamicsOptions,
-    ) {
+    ) -> Result<(), Error> {
+        self.generations.check(body)?;
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
         let link_index = match link_index.into() {
             None => -1,
             Some(index) => index as i32,
@@ -965,7 +1162,12 @@ impl PhysicsClient {
         unsafe {
             let command = ffi::b3InitChangeDynamicsInfo(self.handle);
             if let Some(joint_limit_force) = options.joint_limit_force {
-                assert!(joint_limit_force >= 0.);
+                if joint_limit_force < 0. {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "joint_limit_force must not be negative",
+                    ));
+                }
                 ffi::b3ChangeDynamicsInfoSetJointLimitForce(
                     command,
                     body.0,
@@ -974,7 +1176,12 @@ impl PhysicsClient {
                 );
             }
             if let Some(joint_limits) = options.joint_limits {
-                assert!(joint_limits.0 <= joint_limits.1);
+                if joint_limits.0 > joint_limits.1 {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "lower joint limit must not exceed the upper joint limit",
+                    ));
+                }
                 ffi::b3ChangeDynamicsInfoSetJointLimit(
                     command,
                     body.0,
@@ -984,7 +1191,12 @@ impl PhysicsClient {
                 );
             }
             if let Some(mass) = options.mass {
-                assert!(mass >= 0.);
+                if mass < 0. {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "mass must not be negative",
+                    ));
+                }
                 ffi::b3ChangeDynamicsInfoSetMass(command, body.0, link_index, mass);
             }
             if let Some(anisotropic_friction) = options.anisotropic_friction {
@@ -997,7 +1209,12 @@ impl PhysicsClient {
                 );
             }
             if let Some(lateral_friction) = options.lateral_friction {
-                assert!(lateral_friction >= 0.);
+                if lateral_friction < 0. {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "lateral_friction must not be negative",
+                    ));
+                }
                 ffi::b3ChangeDynamicsInfoSetLateralFriction(
                     command,
                     body.0,
@@ -1006,7 +1223,12 @@ impl PhysicsClient {
                 );
             }
             if let Some(spinning_friction) = options.spinning_friction {
-                assert!(spinning_friction >= 0.);
+                if spinning_friction < 0. {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "spinning_friction must not be negative",
+                    ));
+                }
                 ffi::b3ChangeDynamicsInfoSetSpinningFriction(
                     command,
                     body.0,
@@ -1015,7 +1237,12 @@ impl PhysicsClient {
                 );
             }
             if let Some(rolling_friction) = options.rolling_friction {
-                assert!(rolling_friction >= 0.);
+                if rolling_friction < 0. {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "rolling_friction must not be negative",
+                    ));
+                }
                 ffi::b3ChangeDynamicsInfoSetRollingFriction(
                     command,
                     body.0,
@@ -1024,15 +1251,30 @@ impl PhysicsClient {
                 );
             }
             if let Some(linear_damping) = options.linear_damping {
-                assert!(linear_damping >= 0.);
+                if linear_damping < 0. {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "linear_damping must not be negative",
+                    ));
+                }
                 ffi::b3ChangeDynamicsInfoSetLinearDamping(command, body.0, linear_damping);
             }
             if let Some(angular_damping) = options.angular_damping {
-                assert!(angular_damping >= 0.);
+                if angular_damping < 0. {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "angular_damping must not be negative",
+                    ));
+                }
                 ffi::b3ChangeDynamicsInfoSetAngularDamping(command, body.0, angular_damping);
             }
             if let Some(joint_damping) = options.joint_damping {
-                assert!(joint_damping >= 0.);
+                if joint_damping < 0. {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "joint_damping must not be negative",
+                    ));
+                }
                 ffi::b3ChangeDynamicsInfoSetJointDamping(
                     command,
                     body.0,
@@ -1041,12 +1283,27 @@ impl PhysicsClient {
                 );
             }
             if let Some(restitution) = options.restitution {
-                assert!(restitution >= 0.);
+                if restitution < 0. {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "restitution must not be negative",
+                    ));
+                }
                 ffi::b3ChangeDynamicsInfoSetRestitution(command, body.0, link_index, restitution);
             }
             if let Some(contact_stiffness_and_damping) = options.contact_stiffness_and_damping {
-                assert!(contact_stiffness_and_damping.0 >= 0.);
-                assert!(contact_stiffness_and_damping.1 >= 0.);
+                if contact_stiffness_and_damping.0 < 0. {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "contact stiffness must not be negative",
+                    ));
+                }
+                if contact_stiffness_and_damping.1 < 0. {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "contact damping must not be negative",
+                    ));
+                }
                 ffi::b3ChangeDynamicsInfoSetContactStiffnessAndDamping(
                     command,
                     body.0,
@@ -1066,7 +1323,12 @@ impl PhysicsClient {
                 }
             }
             if let Some(ccd_swept_sphere_radius) = options.ccd_swept_sphere_radius {
-                assert!(ccd_swept_sphere_radius >= 0.);
+                if ccd_swept_sphere_radius < 0. {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "ccd_swept_sphere_radius must not be negative",
+                    ));
+                }
                 ffi::b3ChangeDynamicsInfoSetCcdSweptSphereRadius(
                     command,
                     body.0,
@@ -1082,7 +1344,12 @@ impl PhysicsClient {
                 );
             }
             if let Some(contact_processing_threshold) = options.contact_processing_threshold {
-                assert!(contact_processing_threshold >= 0.);
+                if contact_processing_threshold < 0. {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "contact_processing_threshold must not be negative",
+                    ));
+                }
                 ffi::b3ChangeDynamicsInfoSetContactProcessingThreshold(
                     command,
                     body.0,
@@ -1091,15 +1358,26 @@ impl PhysicsClient {
                 );
             }
             if let Some(max_joint_velocity) = options.max_joint_velocity {
-                assert!(max_joint_velocity >= 0.);
+                if max_joint_velocity < 0. {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "max_joint_velocity must not be negative",
+                    ));
+                }
                 ffi::b3ChangeDynamicsInfoSetMaxJointVelocity(command, body.0, max_joint_velocity);
             }
             if let Some(collision_margin) = options.collision_margin {
-                assert!(collision_margin >= 0.);
+                if collision_margin < 0. {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "collision_margin must not be negative",
+                    ));
+                }
                 ffi::b3ChangeDynamicsInfoSetCollisionMargin(command, body.0, collision_margin);
             }
-            ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
+            self.submit_command_and_wait_status(command);
         }
+        Ok(())
     }
     /// With this method you can get information about the mass, center of mass,
     /// friction and other properties of the base and links.
@@ -1114,17 +1392,22 @@ impl PhysicsClient {
         body: BodyId,
         link_index: Link,
     ) -> Result<DynamicsInfo, Error> {
-        assert!(body.0 >= 0);
+        self.generations.check(body)?;
+        if body.0 < 0 {
+            return Err(Error::of_kind(ErrorKind::InvalidBodyId, "Invalid BodyId"));
+        }
         let link_index = match link_index.into() {
             None => -1,
             Some(index) => index as i32,
         };
         unsafe {
             let cmd_handle = ffi::b3GetDynamicsInfoCommandInit(self.handle, body.0, link_index);
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, cmd_handle);
+            let status_handle = self.submit_command_and_wait_status(cmd_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type != CMD_GET_DYNAMICS_INFO_COMPLETED as i32 {
-                return Err(Error::new(
+                return Err(Error::command_failed(
+                    "get_dynamics_info",
+                    status_type,
                     "get_dynamics_info failed; invalid return status",
                 ));
             }
@@ -1149,7 +1432,7 @@ impl PhysicsClient {
                 m_dynamicType: 0,
             };
             if ffi::b3GetDynamicsInfo(status_handle, &mut dynamics_info) != 0 {
-                Ok(dynamics_info.into())
+                DynamicsInfo::try_from(dynamics_info)
             } else {
                 Err(Error::new("Couldn't get dynamics info"))
             }
@@ -1158,8 +1441,20 @@ impl PhysicsClient {
     /// returns the number of joints of a body
     /// # Arguments
     /// * `body` - the [`BodyId`](`crate::types::BodyId`), as returned by [`load_urdf`](`Self::load_urdf()`) etc.
-    pub fn get_num_joints(&mut self, body: BodyId) -> usize {
-        unsafe { ffi::b3GetNumJoints(self.handle, body.0) as usize }
+    pub fn get_num_joints(&mut self, body: BodyId) -> Result<usize, Error> {
+        self.generations.check(body)?;
+        // b3GetNumJoints reports 0 joints for bodies it does not know, so we have to check that
+        // the body exists first.
+        let mut body_info = ffi::b3BodyInfo {
+            m_baseName: [0; 1024],
+            m_bodyName: [0; 1024],
+        };
+        unsafe {
+            if ffi::b3GetBodyInfo(self.handle, body.0, &mut body_info) == 0 {
+                return Err(Error::of_kind(ErrorKind::InvalidBodyId, "Invalid BodyId"));
+            }
+            Ok(ffi::b3GetNumJoints(self.handle, body.0) as usize)
+        }
     }
     /// Query info about a joint like its name and type
     /// # Arguments
@@ -1167,23 +1462,31 @@ impl PhysicsClient {
     /// * `joint_index` - an index in the range \[0..[`get_num_joints(body)`](`Self::get_num_joints()`)\]
     ///
     /// See [JointInfo](`crate::types::JointInfo`) for an example use
-    pub fn get_joint_info(&mut self, body: BodyId, joint_index: usize) -> JointInfo {
-        self.get_joint_info_intern(body, joint_index).into()
+    pub fn get_joint_info(&mut self, body: BodyId, joint_index: usize) -> Result<JointInfo, Error> {
+        self.generations.check(body)?;
+        JointInfo::try_from(self.get_joint_info_intern(body, joint_index)?)
     }
 
-    fn get_joint_info_intern(&mut self, body: BodyId, joint_index: usize) -> b3JointInfo {
+    fn get_joint_info_intern(
+        &mut self,
+        body: BodyId,
+        joint_index: usize,
+    ) -> Result<b3JointInfo, Error> {
         unsafe {
             let mut joint_info = b3JointInfo::default();
             if ffi::b3GetJointInfo(self.handle, body.0, joint_index as i32, &mut joint_info) == 0 {
-                assert!(
-                    joint_index < self.get_num_joints(body),
-                    "Joint index out-of-range."
-                );
-                // This should never happen as 'b3GetJointInfo' can only fail if the joint index
+                // 'b3GetJointInfo' can only fail if the body does not exist or if the joint index
                 // is out of range.
-                panic!("internal error: get_joint_info_intern failed")
+                let num_joints = self.get_num_joints(body)?;
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidLinkIndex,
+                    format!(
+                        "Joint index {} out-of-range. The body only has {} joints",
+                        joint_index, num_joints
+                    ),
+                ));
             }
-            joint_info
+            Ok(joint_info)
         }
     }
     /// You can reset the state of the joint. It is best only to do this at the start,
@@ -1202,10 +1505,16 @@ impl PhysicsClient {
         value: f64,
         velocity: Velocity,
     ) -> Result<(), Error> {
+        self.generations.check(body)?;
         unsafe {
             let joint_index = joint_index as i32;
             let num_joints = ffi::b3GetNumJoints(self.handle, body.0);
-            assert!(joint_index < num_joints, "Joint index out-of-range.");
+            if joint_index >= num_joints {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidLinkIndex,
+                    "Joint index out-of-range.",
+                ));
+            }
             let command_handle = ffi::b3CreatePoseCommandInit(self.handle, body.0);
 
             ffi::b3CreatePoseCommandSetJointPosition(
@@ -1220,7 +1529,7 @@ impl PhysicsClient {
                 joint_index,
                 velocity.into().unwrap_or(0.),
             );
-            let _handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let _handle = self.submit_command_and_wait_status(command_handle);
             Ok(())
         }
     }
@@ -1234,13 +1543,23 @@ impl PhysicsClient {
         body: BodyId,
         joint_index: usize,
     ) -> Result<JointState, Error> {
+        self.generations.check(body)?;
         unsafe {
-            assert!(body.0 >= 0, "get_joint_state failed; invalid BodyId");
+            if body.0 < 0 {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidBodyId,
+                    "get_joint_state failed; invalid BodyId",
+                ));
+            }
             let cmd_handle = ffi::b3RequestActualStateCommandInit(self.handle, body.0);
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, cmd_handle);
+            let status_handle = self.submit_command_and_wait_status(cmd_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type != CMD_ACTUAL_STATE_UPDATE_COMPLETED as i32 {
-                return Err(Error::new("getJointState failed."));
+                return Err(Error::command_failed(
+                    "get_joint_state",
+                    status_type,
+                    "getJointState failed.",
+                ));
             }
             let mut sensor_state = b3JointSensorState::default();
             if 0 != ffi::b3GetJointState(
@@ -1252,7 +1571,10 @@ impl PhysicsClient {
                 return Ok(sensor_state.into());
             }
         }
-        Err(Error::new("getJointState failed (2)."))
+        Err(Error::of_kind(
+            ErrorKind::InvalidLinkIndex,
+            "getJointState failed (2).",
+        ))
     }
     /// get_joint_states is the array version of [get_joint_state](`Self::get_joint_state()`).
     /// Instead of passing in a single joint_index, you pass in a list of joint_indices.
@@ -1264,39 +1586,300 @@ impl PhysicsClient {
         body: BodyId,
         joint_indices: &[usize],
     ) -> Result<Vec<JointState>, Error> {
+        self.generations.check(body)?;
+        let num_joints = self.check_joint_states_request(body, joint_indices)?;
+        unsafe {
+            let cmd_handle = ffi::b3RequestActualStateCommandInit(self.handle, body.0);
+            let status_handle = self.submit_command_and_wait_status(cmd_handle);
+            self.joint_states_from_status(status_handle, joint_indices, num_joints)
+        }
+    }
+    /// Non-blocking version of [`get_joint_states`](`Self::get_joint_states`). It returns as soon
+    /// as the request was sent. See [`PendingCommand`](`crate::PendingCommand`) for how to get
+    /// the joint states.
+    pub fn get_joint_states_async(
+        &mut self,
+        body: BodyId,
+        joint_indices: &[usize],
+    ) -> Result<PendingCommand<'_, Vec<JointState>>, Error> {
+        self.generations.check(body)?;
+        let num_joints = self.check_joint_states_request(body, joint_indices)?;
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
+        let joint_indices = joint_indices.to_vec();
+        unsafe {
+            let cmd_handle = ffi::b3RequestActualStateCommandInit(self.handle, body.0);
+            PendingCommand::submit(self, cmd_handle, move |client, status_handle| {
+                client.joint_states_from_status(status_handle, &joint_indices, num_joints)
+            })
+        }
+    }
+    /// validates the arguments of [`get_joint_states`](`Self::get_joint_states`) and returns the
+    /// number of joints of the body.
+    pub(crate) fn check_joint_states_request(
+        &mut self,
+        body: BodyId,
+        joint_indices: &[usize],
+    ) -> Result<usize, Error> {
+        if body.0 < 0 {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidBodyId,
+                "get_joint_states failed; invalid BodyId",
+            ));
+        }
+        let num_joints = self.get_num_joints(body)?;
+        if joint_indices.is_empty() {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                "expected a sequence of joint indices",
+            ));
+        }
+        Ok(num_joints)
+    }
+    /// reads the joint states from the answer to a `b3RequestActualStateCommandInit` command.
+    pub(crate) unsafe fn joint_states_from_status(
+        &mut self,
+        status_handle: b3SharedMemoryStatusHandle,
+        joint_indices: &[usize],
+        num_joints: usize,
+    ) -> Result<Vec<JointState>, Error> {
+        let status_type = ffi::b3GetStatusType(status_handle);
+        if status_type != CMD_ACTUAL_STATE_UPDATE_COMPLETED as i32 {
+            return Err(Error::command_failed(
+                "get_joint_states",
+                status_type,
+                "getJointState failed.",
+            ));
+        }
+        let mut result_list_joint_states = Vec::<JointState>::with_capacity(num_joints);
+        for &joint_index in joint_indices.iter() {
+            if joint_index >= num_joints {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidLinkIndex,
+                    format!(
+                        "get_joint_states failed; invalid joint_index ({}). The robot only has {} joints",
+                        joint_index, num_joints,
+                    ),
+                ));
+            }
+            let mut sensor_state = b3JointSensorState::default();
+            if 0 != ffi::b3GetJointState(
+                self.handle,
+                status_handle,
+                joint_index as i32,
+                &mut sensor_state,
+            ) {
+                result_list_joint_states.push(sensor_state.into());
+            } else {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidLinkIndex,
+                    "getJointState failed (2).",
+                ));
+            }
+        }
+        Ok(result_list_joint_states)
+    }
+    /// Resets the state of a joint with multiple degrees of freedom, such as a spherical or a
+    /// planar joint. Just like [`reset_joint_state`](`Self::reset_joint_state`), this overrides
+    /// all physics simulation and should only be done while not running the simulation.
+    /// # Arguments
+    /// * `body` - the [`BodyId`](`crate::types::BodyId`), as returned by [`load_urdf`](`Self::load_urdf()`) etc.
+    /// * `joint_index` - a joint index in the range \[0..[`get_num_joints(body)`](`Self::get_num_joints()`)\]
+    /// * `position` - the joint position. For spherical joints this is a quaternion \[x,y,z,w\].
+    ///   See [`JointStateMultiDof`](`crate::types::JointStateMultiDof`) for the number of values per joint type.
+    /// * `velocity` - optional joint velocity. For spherical joints this is the angular velocity.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use misfire::*;
+    /// use nalgebra::UnitQuaternion;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut client = PhysicsClient::connect(Mode::Direct)?;
+    ///     client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/examples/pybullet/gym/pybullet_data")?;
+    ///     let humanoid = client.load_urdf("humanoid/humanoid.urdf", None)?;
+    ///     let neck = 2;
+    ///     let orientation = UnitQuaternion::from_euler_angles(0.2, 0., 0.);
+    ///     client.reset_joint_state_multi_dof(humanoid, neck, orientation.coords.as_slice(), None)?;
+    ///     let state = client.get_joint_state_multi_dof(humanoid, neck)?;
+    ///     assert!(state.orientation().unwrap().angle_to(&orientation) < 1e-6);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn reset_joint_state_multi_dof(
+        &mut self,
+        body: BodyId,
+        joint_index: usize,
+        position: &[f64],
+        velocity: Option<&[f64]>,
+    ) -> Result<(), Error> {
+        self.generations.check(body)?;
+        self.reset_joint_states_multi_dof(
+            body,
+            &[joint_index],
+            &[position],
+            velocity.as_ref().map(std::slice::from_ref),
+        )
+    }
+    /// reset_joint_states_multi_dof is the array version of
+    /// [reset_joint_state_multi_dof](`Self::reset_joint_state_multi_dof()`).
+    /// # Arguments
+    /// * `body` - the [`BodyId`](`crate::types::BodyId`), as returned by [`load_urdf`](`Self::load_urdf()`) etc.
+    /// * `joint_indices` - a list of joint indices which each index  in the range \[0..[`get_num_joints(body)`](`Self::get_num_joints()`)\]
+    /// * `positions` - the position of each joint in `joint_indices`
+    /// * `velocities` - optional velocity of each joint in `joint_indices`
+    pub fn reset_joint_states_multi_dof(
+        &mut self,
+        body: BodyId,
+        joint_indices: &[usize],
+        positions: &[&[f64]],
+        velocities: Option<&[&[f64]]>,
+    ) -> Result<(), Error> {
+        self.generations.check(body)?;
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
+        if positions.len() != joint_indices.len() {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "number of positions ({}) should match the number of joint indices ({})",
+                    positions.len(),
+                    joint_indices.len(),
+                ),
+            ));
+        }
+        if let Some(velocities) = velocities {
+            if velocities.len() != joint_indices.len() {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "number of velocities ({}) should match the number of joint indices ({})",
+                        velocities.len(),
+                        joint_indices.len(),
+                    ),
+                ));
+            }
+        }
+        let mut infos = Vec::with_capacity(joint_indices.len());
+        for (i, &joint_index) in joint_indices.iter().enumerate() {
+            let info = self.get_joint_info_intern(body, joint_index)?;
+            check_dof_count("position", joint_index, positions[i], info.m_q_size)?;
+            if let Some(velocities) = velocities {
+                check_dof_count("velocity", joint_index, velocities[i], info.m_u_size)?;
+            }
+            infos.push(info);
+        }
         unsafe {
-            assert!(body.0 >= 0, "get_joint_states failed; invalid BodyId");
-            let num_joints = self.get_num_joints(body);
-            if joint_indices.is_empty() {
-                return Err(Error::new("expected a sequence of joint indices"));
+            let command_handle = ffi::b3CreatePoseCommandInit(self.handle, body.0);
+            for (i, info) in infos.iter().enumerate() {
+                let zero_velocity = vec![0.; info.m_u_size as usize];
+                let velocity = match velocities {
+                    Some(velocities) => velocities[i],
+                    None => zero_velocity.as_slice(),
+                };
+                ffi::b3CreatePoseCommandSetJointPositionMultiDof(
+                    self.handle,
+                    command_handle,
+                    info.m_joint_index,
+                    positions[i].as_ptr(),
+                    info.m_q_size,
+                );
+                ffi::b3CreatePoseCommandSetJointVelocityMultiDof(
+                    self.handle,
+                    command_handle,
+                    info.m_joint_index,
+                    velocity.as_ptr(),
+                    info.m_u_size,
+                );
             }
+            let _status_handle = self.submit_command_and_wait_status(command_handle);
+        }
+        Ok(())
+    }
+    /// get information about the [joint state](`crate::types::JointStateMultiDof`) of a joint with
+    /// multiple degrees of freedom, such as a spherical or a planar joint.
+    /// This also works for joints with a single degree of freedom.
+    /// # Arguments
+    /// * `body` - the [`BodyId`](`crate::types::BodyId`), as returned by [`load_urdf`](`Self::load_urdf()`) etc.
+    /// * `joint_index` - a joint index in the range \[0..[`get_num_joints(body)`](`Self::get_num_joints()`)\]
+    ///
+    /// See [`reset_joint_state_multi_dof`](`Self::reset_joint_state_multi_dof()`) for an example.
+    pub fn get_joint_state_multi_dof(
+        &mut self,
+        body: BodyId,
+        joint_index: usize,
+    ) -> Result<JointStateMultiDof, Error> {
+        self.generations.check(body)?;
+        let mut states = self.get_joint_states_multi_dof(body, &[joint_index])?;
+        Ok(states.remove(0))
+    }
+    /// get_joint_states_multi_dof is the array version of
+    /// [get_joint_state_multi_dof](`Self::get_joint_state_multi_dof()`).
+    /// # Arguments
+    /// * `body` - the [`BodyId`](`crate::types::BodyId`), as returned by [`load_urdf`](`Self::load_urdf()`) etc.
+    /// * `joint_indices` - a list of joint indices which each index  in the range \[0..[`get_num_joints(body)`](`Self::get_num_joints()`)\]
+    pub fn get_joint_states_multi_dof(
+        &mut self,
+        body: BodyId,
+        joint_indices: &[usize],
+    ) -> Result<Vec<JointStateMultiDof>, Error> {
+        self.generations.check(body)?;
+        if body.0 < 0 {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidBodyId,
+                "get_joint_states_multi_dof failed; invalid BodyId",
+            ));
+        }
+        if joint_indices.is_empty() {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                "expected a sequence of joint indices",
+            ));
+        }
+        let num_joints = self.get_num_joints(body)?;
+        if let Some(&joint_index) = joint_indices.iter().find(|&&index| index >= num_joints) {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidLinkIndex,
+                format!(
+                    "get_joint_states_multi_dof failed; invalid joint_index ({}). The robot only has {} joints",
+                    joint_index, num_joints,
+                ),
+            ));
+        }
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
+        unsafe {
             let cmd_handle = ffi::b3RequestActualStateCommandInit(self.handle, body.0);
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, cmd_handle);
+            let status_handle = self.submit_command_and_wait_status(cmd_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type != CMD_ACTUAL_STATE_UPDATE_COMPLETED as i32 {
-                return Err(Error::new("getJointState failed."));
+                return Err(Error::command_failed(
+                    "get_joint_states_multi_dof",
+                    status_type,
+                    "getJointStateMultiDof failed.",
+                ));
             }
-            let mut result_list_joint_states = Vec::<JointState>::with_capacity(num_joints);
+            let mut states = Vec::with_capacity(joint_indices.len());
             for &joint_index in joint_indices.iter() {
-                assert!(
-                    joint_index < num_joints,
-                    "get_joint_states failed; invalid joint_index ({}). The robot only has {} joints",
-                    joint_index,
-                    num_joints,
-                );
-                let mut sensor_state = b3JointSensorState::default();
-                if 0 != ffi::b3GetJointState(
+                let mut sensor_state = b3JointSensorState2::default();
+                if 0 == ffi::b3GetJointStateMultiDof(
                     self.handle,
                     status_handle,
                     joint_index as i32,
                     &mut sensor_state,
                 ) {
-                    result_list_joint_states.push(sensor_state.into());
-                } else {
-                    return Err(Error::new("getJointState failed (2)."));
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidLinkIndex,
+                        "getJointStateMultiDof failed (2).",
+                    ));
                 }
+                states.push(JointStateMultiDof::try_from(sensor_state)?);
             }
-            Ok(result_list_joint_states)
+            Ok(states)
         }
     }
     /// calculate_mass_matrix will compute the system inertia for an articulated body given
@@ -1315,6 +1898,7 @@ impl PhysicsClient {
         body: BodyId,
         object_positions: &[f64],
     ) -> Result<DMatrix<f64>, Error> {
+        self.generations.check(body)?;
         if !object_positions.is_empty() {
             let joint_positions = object_positions;
             let flags = 0; // TODO add flags
@@ -1326,8 +1910,7 @@ impl PhysicsClient {
                     joint_positions.len() as i32,
                 );
                 ffi::b3CalculateMassMatrixSetFlags(command_handle, flags);
-                let status_handle =
-                    ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+                let status_handle = self.submit_command_and_wait_status(command_handle);
                 let status_type = ffi::b3GetStatusType(status_handle);
                 if status_type == CMD_CALCULATED_MASS_MATRIX_COMPLETED as i32 {
                     let mut dof_count = 0;
@@ -1350,11 +1933,18 @@ impl PhysicsClient {
                         return Ok(mass_mat);
                     }
                 } else {
-                    return Err(Error::new("Internal error in calculateMassMatrix"));
+                    return Err(Error::command_failed(
+                        "calculate_mass_matrix",
+                        status_type,
+                        "Internal error in calculateMassMatrix",
+                    ));
                 }
             }
         }
-        Err(Error::new("error in calculate_mass_matrix"))
+        Err(Error::of_kind(
+            ErrorKind::InvalidArgument,
+            "error in calculate_mass_matrix",
+        ))
     }
     /// You can compute the joint angles that makes the end-effector reach a given target position
     /// in Cartesian world space. Internally, Bullet uses an improved version of
@@ -1376,6 +1966,7 @@ impl PhysicsClient {
         body: BodyId,
         params: InverseKinematicsParameters,
     ) -> Result<Vec<f64>, Error> {
+        self.generations.check(body)?;
         let solver = params.solver.into();
         let end_effector_link_index = params.end_effector_link_index;
 
@@ -1410,28 +2001,38 @@ impl PhysicsClient {
         {
             has_null_space = true;
         } else if params.limits.is_some() {
-            panic!(
-                "Null space parameter lengths do not match the number DoF! Robot has {} DoF",
-                dof_count
-            );
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Null space parameter lengths do not match the number DoF! Robot has {} DoF",
+                    dof_count
+                ),
+            ));
         }
         if let Some(positions) = current_positions {
-            assert_ne!(
-                positions.len(),
-                dof_count,
-                "number of current_positions ({}) is not equal to the number of DoF's ({})",
-                positions.len(),
-                dof_count
-            );
+            if positions.len() != dof_count {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "number of current_positions ({}) is not equal to the number of DoF's ({})",
+                        positions.len(),
+                        dof_count
+                    ),
+                ));
+            }
             has_current_positions = true;
         }
         if let Some(damping) = joint_damping {
-            assert_eq!(damping.len(),
-                       dof_count,
-                       "calculateInverseKinematics: the size of input joint damping values ({}) should be equal to the number of degrees of freedom ({})",
-                       damping.len(),
-                       dof_count,
-            );
+            if damping.len() != dof_count {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "calculateInverseKinematics: the size of input joint damping values ({}) should be equal to the number of degrees of freedom ({})",
+                        damping.len(),
+                        dof_count,
+                    ),
+                ));
+            }
 
             has_joint_damping = true;
         }
@@ -1507,7 +2108,7 @@ impl PhysicsClient {
                     joint_damping.unwrap().as_ptr(),
                 )
             }
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
+            let status_handle = self.submit_command_and_wait_status(command);
             let mut result_body_index: c_int = 0;
             let result = ffi::b3GetStatusInverseKinematicsJointPositions(
                 status_handle,
@@ -1525,8 +2126,12 @@ impl PhysicsClient {
                 );
                 return Ok(ik_output_joint_pos);
             }
+            Err(Error::command_failed(
+                "calculate_inverse_kinematics",
+                ffi::b3GetStatusType(status_handle),
+                "Error in calculateInverseKinematics",
+            ))
         }
-        Err(Error::new("Error in calculateInverseKinematics"))
     }
     /// calculate_inverse_dynamics will compute the forces needed to reach the given
     /// joint accelerations, starting from specified joint positions and velocities.
@@ -1564,13 +2169,18 @@ impl PhysicsClient {
         object_velocities: &[f64],
         object_accelerations: &[f64],
     ) -> Result<Vec<f64>, Error> {
+        self.generations.check(body)?;
         let flags = 0; // TODO find out what those flags are and let the user set them
-        assert_eq!(object_velocities.len(),
-                   object_accelerations.len(),
-                   "number of object velocities ({}) should be equal to the number of object accelerations ({})",
-                   object_velocities.len(),
-                   object_accelerations.len(),
-        );
+        if object_velocities.len() != object_accelerations.len() {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "number of object velocities ({}) should be equal to the number of object accelerations ({})",
+                    object_velocities.len(),
+                    object_accelerations.len(),
+                ),
+            ));
+        }
         unsafe {
             let command_handle = ffi::b3CalculateInverseDynamicsCommandInit2(
                 self.handle,
@@ -1582,8 +2192,7 @@ impl PhysicsClient {
                 object_velocities.len() as i32,
             );
             ffi::b3CalculateInverseDynamicsSetFlags(command_handle, flags);
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type == CMD_CALCULATED_INVERSE_DYNAMICS_COMPLETED as i32 {
                 let mut body_unique_id = 0;
@@ -1605,10 +2214,12 @@ impl PhysicsClient {
                     return Ok(joint_forces_output);
                 }
             }
+            Err(Error::command_failed(
+                "calculate_inverse_dynamics",
+                status_type,
+                "Error in calculateInverseDynamics, please check arguments.",
+            ))
         }
-        Err(Error::new(
-            "Error in calculateInverseDynamics, please check arguments.",
-        ))
     }
     /// calculate_jacobian will compute the translational and rotational jacobians for a point on a
     /// link, e.g. x_dot = J * q_dot. The returned jacobians are slightly different depending on
@@ -1638,37 +2249,46 @@ impl PhysicsClient {
         object_velocities: &[f64],
         object_accelerations: &[f64],
     ) -> Result<Jacobian, Error> {
-        assert_eq!(
-            object_velocities.len(),
-            object_positions.len(),
-            "object_velocities (size: {})  has not the same size as object_positions (size: {})",
-            object_velocities.len(),
-            object_positions.len(),
-        );
-        assert_eq!(
-            object_accelerations.len(),
-            object_positions.len(),
-            "object_accelerations (size: {})  has not the same size as object_positions (size: {})",
-            object_accelerations.len(),
-            object_positions.len(),
-        );
-
-        let num_joints = self.get_num_joints(body);
-        let mut dof_count_org = 0;
+        self.generations.check(body)?;
+        if object_velocities.len() != object_positions.len() {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "object_velocities (size: {})  has not the same size as object_positions (size: {})",
+                    object_velocities.len(),
+                    object_positions.len(),
+                ),
+            ));
+        }
+        if object_accelerations.len() != object_positions.len() {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "object_accelerations (size: {})  has not the same size as object_positions (size: {})",
+                    object_accelerations.len(),
+                    object_positions.len(),
+                ),
+            ));
+        }
+
+        let num_joints = self.get_num_joints(body)?;
+        let mut dof_count_org = 0;
         for j in 0..num_joints {
             let joint_type =
-                JointType::try_from(self.get_joint_info_intern(body, j).m_joint_type).unwrap();
+                JointType::try_from(self.get_joint_info_intern(body, j)?.m_joint_type)?;
             match joint_type {
                 JointType::Revolute | JointType::Prismatic => {
                     dof_count_org += 1;
                 }
                 JointType::Spherical => {
-                    return Err(Error::new(
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
                         "Spherical joints are not supported in the rubullet binding",
                     ))
                 }
                 JointType::Planar => {
-                    return Err(Error::new(
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
                         "Planar joints are not supported in the rubullet binding",
                     ))
                 }
@@ -1690,8 +2310,7 @@ impl PhysicsClient {
                     joint_velocities.as_ptr(),
                     joint_accelerations.as_ptr(),
                 );
-                let status_handle =
-                    ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+                let status_handle = self.submit_command_and_wait_status(command_handle);
                 let status_type = ffi::b3GetStatusType(status_handle);
                 if status_type == CMD_CALCULATED_JACOBIAN_COMPLETED as i32 {
                     let mut dof_count = 0;
@@ -1718,9 +2337,17 @@ impl PhysicsClient {
                         });
                     }
                 }
+                return Err(Error::command_failed(
+                    "calculate_jacobian",
+                    status_type,
+                    "Error in calculateJacobian",
+                ));
             }
         }
-        Err(Error::new("Error in calculateJacobian"))
+        Err(Error::of_kind(
+            ErrorKind::InvalidArgument,
+            "Error in calculateJacobian",
+        ))
     }
 
     /// sets joint motor commands. This function is the rust version of `setJointMotorControl2` from PyBullet.
@@ -1745,13 +2372,13 @@ impl PhysicsClient {
     ///#     client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/examples/pybullet/gym/pybullet_data")?;
     ///#     let panda_id = client.load_urdf("franka_panda/panda.urdf", None)?;
     ///#     let joint_index = 1;
-    ///     client.set_joint_motor_control(panda_id, joint_index, ControlCommand::Velocity(0.), Some(0.));
+    ///     client.set_joint_motor_control(panda_id, joint_index, ControlCommand::Velocity(0.), Some(0.))?;
     ///# Ok(())
     ///# }
     /// ```
     /// # Arguments
     /// * `body` - the [`BodyId`](`crate::types::BodyId`), as returned by [`load_urdf`](`Self::load_urdf()`) etc.
-    /// * `joint_index` - link index in range [0..get_num_joints(bodyUniqueId)] (note that link index == joint index)
+    /// * `joint_index` - link index in range [0..get_num_joints(bodyUniqueId)?] (note that link index == joint index)
     /// * `control_command` - Specifies how to control the robot (Position, Torque, etc.) inlcuding the respective values.
     /// * `maximum_force` - this is the maximum motor force used to reach the target value. It has no effect in Torque mode.
     /// # Example
@@ -1763,8 +2390,8 @@ impl PhysicsClient {
     ///     client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/examples/pybullet/gym/pybullet_data")?;
     ///     let panda_id = client.load_urdf("franka_panda/panda.urdf", None)?;
     ///     let joint_index = 1;
-    ///     client.set_joint_motor_control(panda_id, joint_index, ControlCommand::Torque(100.), None);
-    ///     client.set_joint_motor_control(panda_id, joint_index, ControlCommand::Position(0.4), Some(1000.));
+    ///     client.set_joint_motor_control(panda_id, joint_index, ControlCommand::Torque(100.), None)?;
+    ///     client.set_joint_motor_control(panda_id, joint_index, ControlCommand::Position(0.4), Some(1000.))?;
     /// Ok(())
     /// }
     /// ```
@@ -1776,15 +2403,16 @@ impl PhysicsClient {
         joint_index: usize,
         control_command: ControlCommand,
         maximum_force: Option<f64>,
-    ) {
+    ) -> Result<(), Error> {
+        self.generations.check(body)?;
         let force = maximum_force.unwrap_or(100000.);
         let kp = 0.1;
         let kd = 1.0;
         let target_velocity = 0.;
+        let info = self.get_joint_info_intern(body, joint_index)?;
         unsafe {
             let command_handle =
                 ffi::b3JointControlCommandInit2(self.handle, body.0, control_command.get_int());
-            let info = self.get_joint_info_intern(body, joint_index);
 
             match control_command {
                 ControlCommand::Position(target_position) => {
@@ -1842,16 +2470,16 @@ impl PhysicsClient {
                     ffi::b3JointControlSetDesiredForceTorque(command_handle, info.m_u_index, f);
                 }
             }
-            let _status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let _status_handle = self.submit_command_and_wait_status(command_handle);
         }
+        Ok(())
     }
     /// The array version of [`set_joint_motor_control()`](`crate::client::PhysicsClient::set_joint_motor_control()`).
     /// This reduces the calling overhead and should therefore be faster. See [`set_joint_motor_control()`](`crate::client::PhysicsClient::set_joint_motor_control()`)
     /// for more details.
     /// # Arguments
     /// * `body` - the [`BodyId`](`crate::types::BodyId`), as returned by [`load_urdf`](`Self::load_urdf()`) etc.
-    /// * `joint_indices` - list of link indices in range [0..get_num_joints(bodyUniqueId)] (note that link index == joint index)
+    /// * `joint_indices` - list of link indices in range [0..get_num_joints(bodyUniqueId)?] (note that link index == joint index)
     /// * `control_command` - Specifies how to control the robot (Position, Torque, etc.)
     /// * `maximum_force` - this is the maximum motor force used to reach the target value for each joint. It has no effect in Torque mode.
     pub fn set_joint_motor_control_array(
@@ -1861,6 +2489,49 @@ impl PhysicsClient {
         control_command: ControlCommandArray,
         maximum_force: Option<&[f64]>,
     ) -> Result<(), Error> {
+        self.generations.check(body)?;
+        let command_handle = self.joint_motor_control_array_command(
+            body,
+            joint_indices,
+            control_command,
+            maximum_force,
+        )?;
+        unsafe {
+            let _status_handle = self.submit_command_and_wait_status(command_handle);
+        }
+        Ok(())
+    }
+    /// Non-blocking version of [`set_joint_motor_control_array`](`Self::set_joint_motor_control_array`).
+    /// It returns as soon as the command was sent. See [`PendingCommand`](`crate::PendingCommand`)
+    /// for how to wait for the server.
+    pub fn set_joint_motor_control_array_async(
+        &mut self,
+        body: BodyId,
+        joint_indices: &[usize],
+        control_command: ControlCommandArray,
+        maximum_force: Option<&[f64]>,
+    ) -> Result<PendingCommand<'_, ()>, Error> {
+        self.generations.check(body)?;
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
+        let command_handle = self.joint_motor_control_array_command(
+            body,
+            joint_indices,
+            control_command,
+            maximum_force,
+        )?;
+        unsafe { PendingCommand::submit(self, command_handle, |_, _| Ok(())) }
+    }
+    /// builds the command for [`set_joint_motor_control_array`](`Self::set_joint_motor_control_array`)
+    /// without submitting it.
+    pub(crate) fn joint_motor_control_array_command(
+        &mut self,
+        body: BodyId,
+        joint_indices: &[usize],
+        control_command: ControlCommandArray,
+        maximum_force: Option<&[f64]>,
+    ) -> Result<b3SharedMemoryCommandHandle, Error> {
         let alloc_vec;
         let forces;
         match maximum_force {
@@ -1872,37 +2543,48 @@ impl PhysicsClient {
                 forces = max_forces;
             }
         }
-        assert_eq!(forces.len(),
-                   joint_indices.len(),
-                   "number of maximum forces (size: {}) should match the number of joint indices (size: {})",
-                   forces.len(),
-                   joint_indices.len(),
-        );
+        if forces.len() != joint_indices.len() {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "number of maximum forces (size: {}) should match the number of joint indices (size: {})",
+                    forces.len(),
+                    joint_indices.len(),
+                ),
+            ));
+        }
         let kp = 0.1;
         let kd = 1.0;
-        let num_joints = self.get_num_joints(body);
+        let num_joints = self.get_num_joints(body)?;
+        for &joint_index in joint_indices.iter() {
+            if joint_index >= num_joints {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidLinkIndex,
+                    format!(
+                        "Joint index ({}) out-of-range. Robot has a total number of {} joints",
+                        joint_index, num_joints,
+                    ),
+                ));
+            }
+        }
         unsafe {
             let command_handle =
                 ffi::b3JointControlCommandInit2(self.handle, body.0, control_command.get_int());
 
-            for &joint_index in joint_indices.iter() {
-                assert!(
-                    joint_index < num_joints,
-                    "Joint index ({}) out-of-range. Robot has a total number of {} joints",
-                    joint_index,
-                    num_joints,
-                );
-            }
             match control_command {
                 ControlCommandArray::Positions(target_positions) => {
-                    assert_eq!(target_positions.len(),
-                               joint_indices.len(),
-                               "number of target positions ({}) should match the number of joint indices ({})",
-                               target_positions.len(),
-                               joint_indices.len(),
-                    );
+                    if target_positions.len() != joint_indices.len() {
+                        return Err(Error::of_kind(
+                            ErrorKind::InvalidArgument,
+                            format!(
+                                "number of target positions ({}) should match the number of joint indices ({})",
+                                target_positions.len(),
+                                joint_indices.len(),
+                            ),
+                        ));
+                    }
                     for i in 0..target_positions.len() {
-                        let info = self.get_joint_info_intern(body, joint_indices[i]);
+                        let info = self.get_joint_info_intern(body, joint_indices[i])?;
                         ffi::b3JointControlSetDesiredPosition(
                             command_handle,
                             info.m_q_index,
@@ -1931,33 +2613,49 @@ impl PhysicsClient {
                     position_gains: pg,
                     velocity_gains: vg,
                 } => {
-                    assert_eq!(pos.len(),
-                               joint_indices.len(),
-                               "number of target positions ({}) should match the number of joint indices ({})",
-                               pos.len(),
-                               joint_indices.len(),
-                    );
-                    assert_eq!(vel.len(),
-                               joint_indices.len(),
-                               "number of target velocities ({}) should match the number of joint indices ({})",
-                               vel.len(),
-                               joint_indices.len(),
-                    );
-                    assert_eq!(pg.len(),
-                               joint_indices.len(),
-                               "number of position gains ({}) should match the number of joint indices ({})",
-                               pg.len(),
-                               joint_indices.len(),
-                    );
-                    assert_eq!(vg.len(),
-                               joint_indices.len(),
-                               "number of velocity gains ({}) should match the number of joint indices ({})",
-                               vg.len(),
-                               joint_indices.len(),
-                    );
+                    if pos.len() != joint_indices.len() {
+                        return Err(Error::of_kind(
+                            ErrorKind::InvalidArgument,
+                            format!(
+                                "number of target positions ({}) should match the number of joint indices ({})",
+                                pos.len(),
+                                joint_indices.len(),
+                            ),
+                        ));
+                    }
+                    if vel.len() != joint_indices.len() {
+                        return Err(Error::of_kind(
+                            ErrorKind::InvalidArgument,
+                            format!(
+                                "number of target velocities ({}) should match the number of joint indices ({})",
+                                vel.len(),
+                                joint_indices.len(),
+                            ),
+                        ));
+                    }
+                    if pg.len() != joint_indices.len() {
+                        return Err(Error::of_kind(
+                            ErrorKind::InvalidArgument,
+                            format!(
+                                "number of position gains ({}) should match the number of joint indices ({})",
+                                pg.len(),
+                                joint_indices.len(),
+                            ),
+                        ));
+                    }
+                    if vg.len() != joint_indices.len() {
+                        return Err(Error::of_kind(
+                            ErrorKind::InvalidArgument,
+                            format!(
+                                "number of velocity gains ({}) should match the number of joint indices ({})",
+                                vg.len(),
+                                joint_indices.len(),
+                            ),
+                        ));
+                    }
 
                     for i in 0..pos.len() {
-                        let info = self.get_joint_info_intern(body, joint_indices[i]);
+                        let info = self.get_joint_info_intern(body, joint_indices[i])?;
                         ffi::b3JointControlSetDesiredPosition(
                             command_handle,
                             info.m_q_index,
@@ -1980,14 +2678,18 @@ impl PhysicsClient {
                     }
                 }
                 ControlCommandArray::Velocities(vel) => {
-                    assert_eq!(vel.len(),
-                               joint_indices.len(),
-                               "number of target velocities ({}) should match the number of joint indices ({})",
-                               vel.len(),
-                               joint_indices.len(),
-                    );
+                    if vel.len() != joint_indices.len() {
+                        return Err(Error::of_kind(
+                            ErrorKind::InvalidArgument,
+                            format!(
+                                "number of target velocities ({}) should match the number of joint indices ({})",
+                                vel.len(),
+                                joint_indices.len(),
+                            ),
+                        ));
+                    }
                     for i in 0..vel.len() {
-                        let info = self.get_joint_info_intern(body, joint_indices[i]);
+                        let info = self.get_joint_info_intern(body, joint_indices[i])?;
                         ffi::b3JointControlSetDesiredVelocity(
                             command_handle,
                             info.m_u_index,
@@ -2002,14 +2704,18 @@ impl PhysicsClient {
                     }
                 }
                 ControlCommandArray::Torques(f) => {
-                    assert_eq!(f.len(),
-                               joint_indices.len(),
-                               "number of target torques ({}) should match the number of joint indices ({})",
-                               f.len(),
-                               joint_indices.len(),
-                    );
+                    if f.len() != joint_indices.len() {
+                        return Err(Error::of_kind(
+                            ErrorKind::InvalidArgument,
+                            format!(
+                                "number of target torques ({}) should match the number of joint indices ({})",
+                                f.len(),
+                                joint_indices.len(),
+                            ),
+                        ));
+                    }
                     for i in 0..f.len() {
-                        let info = self.get_joint_info_intern(body, joint_indices[i]);
+                        let info = self.get_joint_info_intern(body, joint_indices[i])?;
                         ffi::b3JointControlSetDesiredForceTorque(
                             command_handle,
                             info.m_u_index,
@@ -2018,8 +2724,280 @@ impl PhysicsClient {
                     }
                 }
             }
-            let _status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            Ok(command_handle)
+        }
+    }
+    /// Controls a joint with multiple degrees of freedom, such as a spherical or a planar joint.
+    /// It works just like [`set_joint_motor_control()`](`Self::set_joint_motor_control()`), but
+    /// all targets and gains are given for each degree of freedom.
+    /// # Arguments
+    /// * `body` - the [`BodyId`](`crate::types::BodyId`), as returned by [`load_urdf`](`Self::load_urdf()`) etc.
+    /// * `joint_index` - link index in range [0..get_num_joints(bodyUniqueId)] (note that link index == joint index)
+    /// * `control_command` - Specifies how to control the joint (Position, Torque, etc.) including the respective values.
+    /// * `maximum_force` - the maximum motor force for each degree of freedom. It has no effect in Torque mode.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use misfire::*;
+    /// use nalgebra::UnitQuaternion;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut client = PhysicsClient::connect(Mode::Direct)?;
+    ///     client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/examples/pybullet/gym/pybullet_data")?;
+    ///     let humanoid = client.load_urdf("humanoid/humanoid.urdf", None)?;
+    ///     let neck = 2;
+    ///     let target = UnitQuaternion::from_euler_angles(0.2, 0., 0.);
+    ///     client.set_joint_motor_control_multi_dof(
+    ///         humanoid,
+    ///         neck,
+    ///         ControlCommandMultiDof::PositionWithPd {
+    ///             target_position: target.coords.as_slice(),
+    ///             target_velocity: &[0.; 3],
+    ///             position_gain: &[0.2; 3],
+    ///             velocity_gain: &[1.; 3],
+    ///             maximum_velocity: None,
+    ///         },
+    ///         Some(&[50.; 3]),
+    ///     )?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[doc(alias = "setJointMotorControlMultiDof")]
+    pub fn set_joint_motor_control_multi_dof(
+        &mut self,
+        body: BodyId,
+        joint_index: usize,
+        control_command: ControlCommandMultiDof,
+        maximum_force: Option<&[f64]>,
+    ) -> Result<(), Error> {
+        self.generations.check(body)?;
+        self.set_joint_motor_control_multi_dof_array(
+            body,
+            &[joint_index],
+            &[control_command],
+            maximum_force.as_ref().map(std::slice::from_ref),
+        )
+    }
+    /// The array version of [`set_joint_motor_control_multi_dof()`](`Self::set_joint_motor_control_multi_dof()`).
+    /// All control commands need to use the same control mode, e.g. they all have to be
+    /// [`ControlCommandMultiDof::Position`](`crate::types::ControlCommandMultiDof::Position`).
+    /// # Arguments
+    /// * `body` - the [`BodyId`](`crate::types::BodyId`), as returned by [`load_urdf`](`Self::load_urdf()`) etc.
+    /// * `joint_indices` - list of link indices in range [0..get_num_joints(bodyUniqueId)] (note that link index == joint index)
+    /// * `control_commands` - one control command for each joint in `joint_indices`
+    /// * `maximum_forces` - the maximum motor forces for each joint in `joint_indices`. It has no effect in Torque mode.
+    #[doc(alias = "setJointMotorControlMultiDofArray")]
+    pub fn set_joint_motor_control_multi_dof_array(
+        &mut self,
+        body: BodyId,
+        joint_indices: &[usize],
+        control_commands: &[ControlCommandMultiDof],
+        maximum_forces: Option<&[&[f64]]>,
+    ) -> Result<(), Error> {
+        self.generations.check(body)?;
+        if control_commands.len() != joint_indices.len() {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "number of control commands ({}) should match the number of joint indices ({})",
+                    control_commands.len(),
+                    joint_indices.len(),
+                ),
+            ));
+        }
+        if let Some(maximum_forces) = maximum_forces {
+            if maximum_forces.len() != joint_indices.len() {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "number of maximum forces ({}) should match the number of joint indices ({})",
+                        maximum_forces.len(),
+                        joint_indices.len(),
+                    ),
+                ));
+            }
+        }
+        let control_mode = match control_commands.first() {
+            Some(command) => command.get_int(),
+            None => {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidArgument,
+                    "expected a sequence of control commands",
+                ))
+            }
+        };
+        if control_commands
+            .iter()
+            .any(|command| command.get_int() != control_mode)
+        {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                "all control commands need to use the same control mode",
+            ));
+        }
+        let mut infos = Vec::with_capacity(joint_indices.len());
+        for (i, &joint_index) in joint_indices.iter().enumerate() {
+            let info = self.get_joint_info_intern(body, joint_index)?;
+            let (q_size, u_size) = (info.m_q_size, info.m_u_size);
+            match control_commands[i] {
+                ControlCommandMultiDof::Position(pos) => {
+                    check_dof_count("target position", joint_index, pos, q_size)?;
+                }
+                ControlCommandMultiDof::Pd {
+                    target_position: pos,
+                    target_velocity: vel,
+                    position_gain: pg,
+                    velocity_gain: vg,
+                    ..
+                }
+                | ControlCommandMultiDof::PositionWithPd {
+                    target_position: pos,
+                    target_velocity: vel,
+                    position_gain: pg,
+                    velocity_gain: vg,
+                    ..
+                } => {
+                    check_dof_count("target position", joint_index, pos, q_size)?;
+                    check_dof_count("target velocity", joint_index, vel, u_size)?;
+                    check_dof_count("position gain", joint_index, pg, u_size)?;
+                    check_dof_count("velocity gain", joint_index, vg, u_size)?;
+                }
+                ControlCommandMultiDof::Velocity(vel) => {
+                    check_dof_count("target velocity", joint_index, vel, u_size)?;
+                }
+                ControlCommandMultiDof::Torque(f) => {
+                    check_dof_count("target torque", joint_index, f, u_size)?;
+                }
+            }
+            if let Some(maximum_forces) = maximum_forces {
+                check_dof_count("maximum force", joint_index, maximum_forces[i], u_size)?;
+            }
+            infos.push(info);
+        }
+        let kp = 0.1;
+        let kd = 1.0;
+        unsafe {
+            let command_handle = ffi::b3JointControlCommandInit2(self.handle, body.0, control_mode);
+            for (i, info) in infos.iter().enumerate() {
+                let u_size = info.m_u_size;
+                let default_forces = vec![100000.; u_size as usize];
+                let forces = match maximum_forces {
+                    Some(maximum_forces) => maximum_forces[i],
+                    None => default_forces.as_slice(),
+                };
+                match control_commands[i] {
+                    ControlCommandMultiDof::Position(pos) => {
+                        ffi::b3JointControlSetDesiredPositionMultiDof(
+                            command_handle,
+                            info.m_q_index,
+                            pos.as_ptr(),
+                            info.m_q_size,
+                        );
+                        let kps = vec![kp; u_size as usize];
+                        ffi::b3JointControlSetKpMultiDof(
+                            command_handle,
+                            info.m_u_index,
+                            kps.as_ptr(),
+                            u_size,
+                        );
+                        let velocities = vec![0.; u_size as usize];
+                        ffi::b3JointControlSetDesiredVelocityMultiDof(
+                            command_handle,
+                            info.m_u_index,
+                            velocities.as_ptr(),
+                            u_size,
+                        );
+                        let kds = vec![kd; u_size as usize];
+                        ffi::b3JointControlSetKdMultiDof(
+                            command_handle,
+                            info.m_u_index,
+                            kds.as_ptr(),
+                            u_size,
+                        );
+                    }
+                    ControlCommandMultiDof::Pd {
+                        target_position: pos,
+                        target_velocity: vel,
+                        position_gain: pg,
+                        velocity_gain: vg,
+                        maximum_velocity: max_vel,
+                    }
+                    | ControlCommandMultiDof::PositionWithPd {
+                        target_position: pos,
+                        target_velocity: vel,
+                        position_gain: pg,
+                        velocity_gain: vg,
+                        maximum_velocity: max_vel,
+                    } => {
+                        if let Some(max_vel) = max_vel {
+                            for dof in 0..u_size {
+                                ffi::b3JointControlSetMaximumVelocity(
+                                    command_handle,
+                                    info.m_u_index + dof,
+                                    max_vel,
+                                );
+                            }
+                        }
+                        ffi::b3JointControlSetDesiredPositionMultiDof(
+                            command_handle,
+                            info.m_q_index,
+                            pos.as_ptr(),
+                            info.m_q_size,
+                        );
+                        ffi::b3JointControlSetKpMultiDof(
+                            command_handle,
+                            info.m_u_index,
+                            pg.as_ptr(),
+                            u_size,
+                        );
+                        ffi::b3JointControlSetDesiredVelocityMultiDof(
+                            command_handle,
+                            info.m_u_index,
+                            vel.as_ptr(),
+                            u_size,
+                        );
+                        ffi::b3JointControlSetKdMultiDof(
+                            command_handle,
+                            info.m_u_index,
+                            vg.as_ptr(),
+                            u_size,
+                        );
+                    }
+                    ControlCommandMultiDof::Velocity(vel) => {
+                        ffi::b3JointControlSetDesiredVelocityMultiDof(
+                            command_handle,
+                            info.m_u_index,
+                            vel.as_ptr(),
+                            u_size,
+                        );
+                        let kds = vec![kd; u_size as usize];
+                        ffi::b3JointControlSetKdMultiDof(
+                            command_handle,
+                            info.m_u_index,
+                            kds.as_ptr(),
+                            u_size,
+                        );
+                    }
+                    ControlCommandMultiDof::Torque(f) => {
+                        ffi::b3JointControlSetDesiredForceTorqueMultiDof(
+                            command_handle,
+                            info.m_u_index,
+                            f.as_ptr(),
+                            u_size,
+                        );
+                        continue;
+                    }
+                }
+                for (dof, &force) in forces.iter().enumerate() {
+                    ffi::b3JointControlSetMaximumForce(
+                        command_handle,
+                        info.m_u_index + dof as i32,
+                        force,
+                    );
+                }
+            }
+            let _status_handle = self.submit_command_and_wait_status(command_handle);
         }
         Ok(())
     }
@@ -2215,14 +3193,15 @@ impl PhysicsClient {
     /// properly support these types yet.
     ///
     /// # Arguments
-    /// * `width` - eye position in Cartesian world coordinates
-    /// * `height` - position of the target (focus) point, in Cartesian world coordinates
+    /// * `width` - horizontal image resolution in pixels
+    /// * `height` - vertical image resolution in pixels
     /// * `options` - additional options to set view and projection matrix etc.
     /// # See also
     /// * [compute_view_matrix](`Self::compute_view_matrix`)
     /// * [compute_view_matrix_from_yaw_pitch_roll](`Self::compute_view_matrix_from_yaw_pitch_roll`)
     /// * [compute_projection_matrix](`Self::compute_projection_matrix`)
     /// * [compute_projection_matrix_fov](`Self::compute_projection_matrix_fov`)
+    /// * [get_camera_image_into](`Self::get_camera_image_into`)
     /// * panda_camera_demo.rs for an example
     pub fn get_camera_image<Options: Into<Option<CameraImageOptions>>>(
         &mut self,
@@ -2230,6 +3209,52 @@ impl PhysicsClient {
         height: usize,
         options: Options,
     ) -> Result<Images, Error> {
+        let mut images = Images::default();
+        self.get_camera_image_into(&mut images, width, height, ImageChannels::all(), options)?;
+        Ok(images)
+    }
+    /// renders camera images like [`get_camera_image`](`Self::get_camera_image`), but writes them
+    /// into existing [`Images`] and reuses their memory, so that rendering at a high rate does
+    /// not allocate new buffers for every frame.
+    ///
+    /// Only the selected channels are copied. The other images are cleared to 0x0 pixels, but
+    /// keep their memory. If the segmentation image is not selected, the renderer does not
+    /// compute it.
+    ///
+    /// # Arguments
+    /// * `images` - the images which are overwritten, e.g. `Images::default()` or the result of
+    ///   a previous call.
+    /// * `width` - horizontal image resolution in pixels
+    /// * `height` - vertical image resolution in pixels
+    /// * `channels` - the images which are copied from the renderer
+    /// * `options` - additional options to set view and projection matrix etc.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use misfire::*;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut client = PhysicsClient::connect(Mode::Direct)?;
+    ///     client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
+    ///     client.load_urdf("r2d2.urdf", None)?;
+    ///     let mut images = Images::default();
+    ///     for _ in 0..100 {
+    ///         client.step_simulation()?;
+    ///         client.get_camera_image_into(&mut images, 320, 240, ImageChannels::RGBA, None)?;
+    ///         assert_eq!(images.rgba.width(), 320);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn get_camera_image_into<Options: Into<Option<CameraImageOptions>>>(
+        &mut self,
+        images: &mut Images,
+        width: usize,
+        height: usize,
+        channels: ImageChannels,
+        options: Options,
+    ) -> Result<(), Error> {
         unsafe {
             let options = options.into().unwrap_or_default();
             let command = ffi::b3InitRequestCameraImage(self.handle);
@@ -2264,7 +3289,14 @@ impl PhysicsClient {
             if let Some(light_specular_coeff) = options.light_specular_coeff {
                 ffi::b3RequestCameraImageSetLightSpecularCoeff(command, light_specular_coeff);
             }
-            if let Some(flags) = options.flags {
+            let mut flags = options.flags;
+            if !channels.contains(ImageChannels::SEGMENTATION) {
+                flags = Some(
+                    flags.unwrap_or_else(RendererAuxFlags::empty)
+                        | RendererAuxFlags::NO_SEGMENTATION_MASK,
+                );
+            }
+            if let Some(flags) = flags {
                 ffi::b3RequestCameraImageSetFlags(command, flags.bits());
             }
             if let Some(renderer) = options.renderer {
@@ -2282,44 +3314,61 @@ impl PhysicsClient {
             }
 
             if self.can_submit_command() {
-                let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
+                let status_handle = self.submit_command_and_wait_status(command);
                 let status_type = ffi::b3GetStatusType(status_handle);
                 if status_type == CMD_CAMERA_IMAGE_COMPLETED as i32 {
                     let mut image_data = b3CameraImageData::default();
                     ffi::b3GetCameraImageData(self.handle, &mut image_data);
-                    let buffer =
-                        std::slice::from_raw_parts(image_data.m_rgb_color_data, width * height * 4);
-                    let depth_buffer =
-                        std::slice::from_raw_parts(image_data.m_depth_values, width * height);
-                    let segmentation_buffer = std::slice::from_raw_parts(
-                        image_data.m_segmentation_mask_values,
-                        width * height,
-                    );
-                    let rgba: RgbaImage =
-                        ImageBuffer::from_vec(width as u32, height as u32, buffer.into()).unwrap();
-
-                    let depth = ImageBuffer::<Luma<f32>, Vec<f32>>::from_vec(
-                        width as u32,
-                        height as u32,
-                        depth_buffer.into(),
-                    )
-                    .unwrap();
-                    let segmentation = ImageBuffer::<Luma<i32>, Vec<i32>>::from_vec(
-                        width as u32,
-                        height as u32,
-                        segmentation_buffer.into(),
-                    )
-                    .unwrap();
-                    return Ok(Images {
-                        width: image_data.m_pixel_width as usize,
-                        height: image_data.m_pixel_height as usize,
-                        rgba,
-                        depth,
-                        segmentation,
-                    });
+                    if (channels.contains(ImageChannels::RGBA)
+                        && image_data.m_rgb_color_data.is_null())
+                        || (channels.contains(ImageChannels::DEPTH)
+                            && image_data.m_depth_values.is_null())
+                        || (channels.contains(ImageChannels::SEGMENTATION)
+                            && image_data.m_segmentation_mask_values.is_null())
+                    {
+                        return Err(Error::of_kind(
+                            ErrorKind::ParseError,
+                            "get_camera_image returned no image data",
+                        ));
+                    }
+                    let width = image_data.m_pixel_width as usize;
+                    let height = image_data.m_pixel_height as usize;
+                    if channels.contains(ImageChannels::RGBA) {
+                        let buffer = std::slice::from_raw_parts(
+                            image_data.m_rgb_color_data,
+                            width * height * 4,
+                        );
+                        refill_image(&mut images.rgba, width, height, buffer)?;
+                    } else {
+                        refill_image(&mut images.rgba, 0, 0, &[])?;
+                    }
+                    if channels.contains(ImageChannels::DEPTH) {
+                        let depth_buffer =
+                            std::slice::from_raw_parts(image_data.m_depth_values, width * height);
+                        refill_image(&mut images.depth, width, height, depth_buffer)?;
+                    } else {
+                        refill_image(&mut images.depth, 0, 0, &[])?;
+                    }
+                    if channels.contains(ImageChannels::SEGMENTATION) {
+                        let segmentation_buffer = std::slice::from_raw_parts(
+                            image_data.m_segmentation_mask_values,
+                            width * height,
+                        );
+                        refill_image(&mut images.segmentation, width, height, segmentation_buffer)?;
+                    } else {
+                        refill_image(&mut images.segmentation, 0, 0, &[])?;
+                    }
+                    images.width = width;
+                    images.height = height;
+                    return Ok(());
                 }
+                return Err(Error::command_failed(
+                    "get_camera_image",
+                    status_type,
+                    "get_camera_image failed",
+                ));
             }
-            Err(Error::new("get_camera_image failed"))
+            Err(Error::disconnected())
         }
     }
     /// This method can configure some settings of the built-in OpenGL visualizer,
@@ -2330,7 +3379,14 @@ impl PhysicsClient {
     /// * `flag` - Feature to enable or disable
     /// * `enable` - enables or disables the feature
     // TODO implement the other options
-    pub fn configure_debug_visualizer(&mut self, flag: DebugVisualizerFlag, enable: bool) {
+    pub fn configure_debug_visualizer(
+        &mut self,
+        flag: DebugVisualizerFlag,
+        enable: bool,
+    ) -> Result<(), Error> {
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
         unsafe {
             let command_handle = ffi::b3InitConfigureOpenGLVisualizer(self.handle);
             ffi::b3ConfigureOpenGLVisualizerSetVisualizationFlags(
@@ -2338,7 +3394,7 @@ impl PhysicsClient {
                 flag as i32,
                 enable as i32,
             );
-            ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            self.submit_simple_command(command_handle)
         }
     }
 
@@ -2391,6 +3447,7 @@ impl PhysicsClient {
     ) -> Result<ItemId, Error> {
         unsafe {
             let options = options.into().unwrap_or_default();
+            self.check_debug_item_options(options.parent_object_id, options.replace_item_id)?;
             let command_handle = ffi::b3InitUserDebugDrawAddLine3D(
                 self.handle,
                 line_from_xyz.into().as_ptr(),
@@ -2409,14 +3466,19 @@ impl PhysicsClient {
             if let Some(replacement) = options.replace_item_id {
                 ffi::b3UserDebugItemSetReplaceItemUniqueId(command_handle, replacement.0);
             }
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type == CMD_USER_DEBUG_DRAW_COMPLETED as i32 {
-                let debug_item = ItemId(ffi::b3GetDebugItemUniqueId(status_handle));
+                let debug_item = self
+                    .generations
+                    .create(ffi::b3GetDebugItemUniqueId(status_handle));
                 return Ok(debug_item);
             }
-            Err(Error::new("Error in addUserDebugLine."))
+            Err(Error::command_failed(
+                "add_user_debug_line",
+                status_type,
+                "Error in addUserDebugLine.",
+            ))
         }
     }
     /// Lets you add custom sliders and buttons to tune parameters.
@@ -2452,7 +3514,9 @@ impl PhysicsClient {
         start_value: f64,
     ) -> Result<ItemId, Error> {
         unsafe {
-            let param_name = CString::new(param_name.into().as_bytes()).unwrap();
+            let param_name = CString::new(param_name.into().as_bytes()).map_err(|_| {
+                Error::of_kind(ErrorKind::InvalidArgument, "Invalid parameter name")
+            })?;
             let command_handle = ffi::b3InitUserDebugAddParameter(
                 self.handle,
                 param_name.as_ptr(),
@@ -2460,14 +3524,17 @@ impl PhysicsClient {
                 range_max,
                 start_value,
             );
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type == CMD_USER_DEBUG_DRAW_COMPLETED as i32 {
                 let debug_item_unique_id = ffi::b3GetDebugItemUniqueId(status_handle);
-                return Ok(ItemId(debug_item_unique_id));
+                return Ok(self.generations.create(debug_item_unique_id));
             }
-            Err(Error::new("Error in addUserDebugParameter."))
+            Err(Error::command_failed(
+                "add_user_debug_parameter",
+                status_type,
+                "Error in addUserDebugParameter.",
+            ))
         }
     }
     /// Reads the current value of a debug parameter. For a button the value will increase by 1 every
@@ -2477,10 +3544,10 @@ impl PhysicsClient {
     /// `item` - the unique item generated by [`add_user_debug_parameter()`)[`Self::add_user_debug_parameter()`]
     /// See [`add_user_debug_parameter()`)[`Self::add_user_debug_parameter()`] for an example.
     pub fn read_user_debug_parameter(&mut self, item: ItemId) -> Result<f64, Error> {
+        self.generations.check(item)?;
         unsafe {
             let command_handle = ffi::b3InitUserDebugReadParameter(self.handle, item.0);
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type == CMD_USER_DEBUG_DRAW_PARAMETER_COMPLETED as i32 {
                 let mut param_value = 0.;
@@ -2489,7 +3556,11 @@ impl PhysicsClient {
                     return Ok(param_value);
                 }
             }
-            Err(Error::new("Failed to read parameter."))
+            Err(Error::command_failed(
+                "read_user_debug_parameter",
+                status_type,
+                "Failed to read parameter.",
+            ))
         }
     }
     /// You can add some 3d text at a specific location using a color and size.
@@ -2542,7 +3613,9 @@ impl PhysicsClient {
     ) -> Result<ItemId, Error> {
         unsafe {
             let options = options.into().unwrap_or_default();
-            let text = CString::new(text.into().as_bytes()).unwrap();
+            self.check_debug_item_options(options.parent_object_id, options.replace_item_id)?;
+            let text = CString::new(text.into().as_bytes())
+                .map_err(|_| Error::of_kind(ErrorKind::InvalidArgument, "Invalid text"))?;
             let command_handle = ffi::b3InitUserDebugDrawAddText3D(
                 self.handle,
                 text.as_ptr(),
@@ -2571,15 +3644,20 @@ impl PhysicsClient {
             if let Some(replacement_id) = options.replace_item_id {
                 ffi::b3UserDebugItemSetReplaceItemUniqueId(command_handle, replacement_id.0);
             }
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type == CMD_USER_DEBUG_DRAW_COMPLETED as i32 {
-                let debug_item_id = ItemId(ffi::b3GetDebugItemUniqueId(status_handle));
+                let debug_item_id = self
+                    .generations
+                    .create(ffi::b3GetDebugItemUniqueId(status_handle));
                 return Ok(debug_item_id);
             }
+            Err(Error::command_failed(
+                "add_user_debug_text",
+                status_type,
+                "Error in add_user_debug_text",
+            ))
         }
-        Err(Error::new("Error in add_user_debug_text"))
     }
     /// Removes debug items which were created with [`add_user_debug_line`](`crate::PhysicsClient::add_user_debug_line()`),
     /// [`add_user_debug_parameter`](`crate::PhysicsClient::add_user_debug_parameter()`) or
@@ -2598,17 +3676,35 @@ impl PhysicsClient {
     ///# pub fn main() -> Result<()> {
     ///#     let mut client = PhysicsClient::connect(Gui)?;
     ///     let text = client.add_user_debug_text("My text", [0., 0., 1.], None)?;
-    ///     client.remove_user_debug_item(text);
+    ///     client.remove_user_debug_item(text)?;
     ///#     Ok(())
     ///# }
     /// ```
-    pub fn remove_user_debug_item(&mut self, item: ItemId) {
+    pub fn remove_user_debug_item(&mut self, item: ItemId) -> Result<(), Error> {
+        self.generations.check(item)?;
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
+        self.generations.remove(item);
         unsafe {
             let command_handle = ffi::b3InitUserDebugDrawRemove(self.handle, item.0);
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
-            let _status_type = ffi::b3GetStatusType(status_handle);
+            self.submit_simple_command(command_handle)
+        }
+    }
+    /// checks the ids in the options of [`add_user_debug_line`](`Self::add_user_debug_line`) and
+    /// [`add_user_debug_text`](`Self::add_user_debug_text`).
+    fn check_debug_item_options(
+        &mut self,
+        parent_object_id: Option<BodyId>,
+        replace_item_id: Option<ItemId>,
+    ) -> Result<(), Error> {
+        if let Some(parent) = parent_object_id {
+            self.generations.check(parent)?;
         }
+        if let Some(item) = replace_item_id {
+            self.generations.check(item)?;
+        }
+        Ok(())
     }
     /// will remove all debug items (text, lines etc).
     /// # Example
@@ -2623,17 +3719,18 @@ impl PhysicsClient {
     /// let mut client = PhysicsClient::connect(Gui)?;
     ///     let text = client.add_user_debug_text("My text", Vector3::new(0., 0., 1.), None)?;
     ///     let text_2 = client.add_user_debug_text("My text2", [0., 0., 2.], None)?;
-    ///     client.remove_all_user_debug_items();
+    ///     client.remove_all_user_debug_items()?;
     ///#     std::thread::sleep(Duration::from_secs(10));
     ///#     Ok(())
     ///# }
     /// ```
-    pub fn remove_all_user_debug_items(&mut self) {
+    pub fn remove_all_user_debug_items(&mut self) -> Result<(), Error> {
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
         unsafe {
             let command_handle = ffi::b3InitUserDebugDrawRemoveAll(self.handle);
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
-            let _status_type = ffi::b3GetStatusType(status_handle);
+            self.submit_simple_command(command_handle)
         }
     }
     /// The built-in OpenGL visualizers have a wireframe debug rendering feature: press 'w' to toggle.
@@ -2648,7 +3745,11 @@ impl PhysicsClient {
         body: BodyId,
         link_index: Link,
         object_debug_color: Color,
-    ) {
+    ) -> Result<(), Error> {
+        self.generations.check(body)?;
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
         unsafe {
             let link_index = match link_index.into() {
                 None => -1,
@@ -2661,7 +3762,7 @@ impl PhysicsClient {
             } else {
                 ffi::b3RemoveDebugObjectColor(command_handle, body.0, link_index);
             }
-            ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            self.submit_simple_command(command_handle)
         }
     }
     /// You can receive all keyboard events that happened since the last time you called
@@ -2677,7 +3778,7 @@ impl PhysicsClient {
     /// fn main() -> Result<()> {
     ///     let mut physics_client = PhysicsClient::connect(Mode::Gui)?;
     ///     loop {
-    ///         let events = physics_client.get_keyboard_events();
+    ///         let events = physics_client.get_keyboard_events()?;
     ///         for event in events.iter() {
     ///             if event.key == 'i' && event.was_triggered() {
     ///                 println!("i-key was pressed");
@@ -2688,12 +3789,28 @@ impl PhysicsClient {
     ///     Ok(())
     /// }
     /// ```
-    pub fn get_keyboard_events(&mut self) -> Vec<KeyboardEvent> {
+    pub fn get_keyboard_events(&mut self) -> Result<Vec<KeyboardEvent>, Error> {
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
         unsafe {
             let mut keyboard_events = b3KeyboardEventsData::default();
             let command_handle = ffi::b3RequestKeyboardEventsCommandInit(self.handle);
-            ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
+            let status_type = ffi::b3GetStatusType(status_handle);
+            if status_type != CMD_REQUEST_KEYBOARD_EVENTS_DATA_COMPLETED as i32 {
+                return Err(Error::command_failed(
+                    "get_keyboard_events",
+                    status_type,
+                    "Could not get keyboard events",
+                ));
+            }
             ffi::b3GetKeyboardEventsData(self.handle, &mut keyboard_events);
+            if keyboard_events.m_numKeyboardEvents <= 0
+                || keyboard_events.m_keyboardEvents.is_null()
+            {
+                return Ok(Vec::new());
+            }
             let mut events =
                 Vec::<KeyboardEvent>::with_capacity(keyboard_events.m_numKeyboardEvents as usize);
             let data = std::slice::from_raw_parts_mut(
@@ -2701,12 +3818,14 @@ impl PhysicsClient {
                 keyboard_events.m_numKeyboardEvents as usize,
             );
             for &event in data.iter() {
+                let key = std::char::from_u32(event.m_keyCode as u32)
+                    .ok_or_else(|| Error::of_kind(ErrorKind::ParseError, "Got invalid key code"))?;
                 events.push(KeyboardEvent {
-                    key: std::char::from_u32(event.m_keyCode as u32).expect("Got invalid key code"),
+                    key,
                     key_state: event.m_keyState,
                 });
             }
-            events
+            Ok(events)
         }
     }
 
@@ -2724,7 +3843,7 @@ impl PhysicsClient {
     /// fn main() -> Result<()> {
     ///     let mut physics_client = PhysicsClient::connect(Mode::Gui)?;
     ///     loop {
-    ///         let events = physics_client.get_mouse_events();
+    ///         let events = physics_client.get_mouse_events()?;
     ///         for event in events.iter() {
     ///             match event {
     ///                 MouseEvent::Move {
@@ -2757,12 +3876,26 @@ impl PhysicsClient {
     ///     Ok(())
     /// }
     /// ```
-    pub fn get_mouse_events(&mut self) -> Vec<MouseEvent> {
+    pub fn get_mouse_events(&mut self) -> Result<Vec<MouseEvent>, Error> {
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
         unsafe {
             let mut mouse_events = b3MouseEventsData::default();
             let command_handle = ffi::b3RequestMouseEventsCommandInit(self.handle);
-            ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
+            let status_type = ffi::b3GetStatusType(status_handle);
+            if status_type != CMD_REQUEST_MOUSE_EVENTS_DATA_COMPLETED as i32 {
+                return Err(Error::command_failed(
+                    "get_mouse_events",
+                    status_type,
+                    "Could not get mouse events",
+                ));
+            }
             ffi::b3GetMouseEventsData(self.handle, &mut mouse_events);
+            if mouse_events.m_numMouseEvents <= 0 || mouse_events.m_mouseEvents.is_null() {
+                return Ok(Vec::new());
+            }
             let mut events =
                 Vec::<MouseEvent>::with_capacity(mouse_events.m_numMouseEvents as usize);
             let data = std::slice::from_raw_parts_mut(
@@ -2786,7 +3919,7 @@ impl PhysicsClient {
                     });
                 }
             }
-            events
+            Ok(events)
         }
     }
     /// Applies a force to a body.
@@ -2817,7 +3950,11 @@ impl PhysicsClient {
         force_object: Force,
         position_object: Position,
         flags: ExternalForceFrame,
-    ) {
+    ) -> Result<(), Error> {
+        self.generations.check(body)?;
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
         let link_index = match link_index.into() {
             None => -1,
             Some(index) => index as i32,
@@ -2832,7 +3969,7 @@ impl PhysicsClient {
                 position_object.into().as_ptr(),
                 flags as i32,
             );
-            let _status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
+            self.submit_simple_command(command)
         }
     }
     /// Applies a torque to a body.
@@ -2855,7 +3992,11 @@ impl PhysicsClient {
         link_index: Link,
         torque_object: Torque,
         flags: ExternalForceFrame,
-    ) {
+    ) -> Result<(), Error> {
+        self.generations.check(body)?;
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
         let link_index = match link_index.into() {
             None => -1,
             Some(index) => index as i32,
@@ -2869,7 +4010,7 @@ impl PhysicsClient {
                 torque_object.into().as_ptr(),
                 flags as i32,
             );
-            let _status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
+            self.submit_simple_command(command)
         }
     }
     /// You can enable or disable a joint force/torque sensor in each joint.
@@ -2887,6 +4028,7 @@ impl PhysicsClient {
         joint_index: usize,
         enable_sensor: bool,
     ) -> Result<(), Error> {
+        self.generations.check(body)?;
         unsafe {
             let command_handle = ffi::b3CreateSensorCommandInit(self.handle, body.0);
             ffi::b3CreateSensorEnable6DofJointForceTorqueSensor(
@@ -2894,13 +4036,16 @@ impl PhysicsClient {
                 joint_index as i32,
                 enable_sensor as i32,
             );
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type == CMD_CLIENT_COMMAND_COMPLETED as i32 {
                 return Ok(());
             }
-            Err(Error::new("Error creating sensor."))
+            Err(Error::command_failed(
+                "enable_joint_torque_sensor",
+                status_type,
+                "Error creating sensor.",
+            ))
         }
     }
     /// You can create a collision shape in a similar way to creating a visual shape. If you have
@@ -2949,7 +4094,8 @@ impl PhysicsClient {
                     mesh_scaling,
                     texture_scaling,
                 } => {
-                    let file = CString::new(filename.into_os_string().as_bytes()).unwrap();
+                    let file = CString::new(filename.into_os_string().as_bytes())
+                        .map_err(|_| Error::of_kind(ErrorKind::InvalidArgument, "Invalid path"))?;
                     shape_index = ffi::b3CreateCollisionShapeAddHeightfield(
                         command_handle,
                         file.as_ptr(),
@@ -2969,12 +4115,17 @@ impl PhysicsClient {
                 } => {
                     if num_heightfield_columns > 0 && num_heightfield_rows > 0 {
                         let num_height_field_points = heightfield_data.len();
-                        assert_eq!(num_heightfield_rows * num_heightfield_columns,
-                                   num_height_field_points,
-                                   "Size of heightfield_data ({}) doesn't match num_heightfield_columns * num_heightfield_rows = {}",
-                                   num_height_field_points,
-                                   num_heightfield_rows * num_heightfield_columns,
-                        );
+                        if num_heightfield_rows * num_heightfield_columns != num_height_field_points
+                        {
+                            return Err(Error::of_kind(
+                                ErrorKind::InvalidArgument,
+                                format!(
+                                    "Size of heightfield_data ({}) doesn't match num_heightfield_columns * num_heightfield_rows = {}",
+                                    num_height_field_points,
+                                    num_heightfield_rows * num_heightfield_columns,
+                                ),
+                            ));
+                        }
                         shape_index = ffi::b3CreateCollisionShapeAddHeightfield2(
                             self.handle,
                             command_handle,
@@ -2994,7 +4145,8 @@ impl PhysicsClient {
                     mesh_scaling,
                     flags,
                 } => {
-                    let file = CString::new(filename.into_os_string().as_bytes()).unwrap();
+                    let file = CString::new(filename.into_os_string().as_bytes())
+                        .map_err(|_| Error::of_kind(ErrorKind::InvalidArgument, "Invalid path"))?;
                     shape_index = ffi::b3CreateCollisionShapeAddMesh(
                         command_handle,
                         file.as_ptr(),
@@ -3014,7 +4166,10 @@ impl PhysicsClient {
                     mesh_scaling,
                 } => {
                     if vertices.len() > B3_MAX_NUM_VERTICES {
-                        return Err(Error::new("Number of vertices exceeds the maximum."));
+                        return Err(Error::of_kind(
+                            ErrorKind::InvalidArgument,
+                            "Number of vertices exceeds the maximum.",
+                        ));
                     }
 
                     let mut new_vertices = Vec::<f64>::with_capacity(vertices.len() * 3);
@@ -3023,7 +4178,10 @@ impl PhysicsClient {
                     }
                     if let Some(indices) = indices {
                         if indices.len() > B3_MAX_NUM_INDICES {
-                            return Err(Error::new("Number of indices exceeds the maximum."));
+                            return Err(Error::of_kind(
+                                ErrorKind::InvalidArgument,
+                                "Number of indices exceeds the maximum.",
+                            ));
                         }
                         shape_index = ffi::b3CreateCollisionShapeAddConcaveMesh(
                             self.handle,
@@ -3070,14 +4228,17 @@ impl PhysicsClient {
                     rotation_array.as_ptr(),
                 );
             }
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type == CMD_CREATE_COLLISION_SHAPE_COMPLETED as i32 {
                 let uid = ffi::b3GetStatusCollisionShapeUniqueId(status_handle);
                 return Ok(CollisionId(uid));
             }
-            Err(Error::new("create_collision_shape failed."))
+            Err(Error::command_failed(
+                "create_collision_shape",
+                status_type,
+                "create_collision_shape failed.",
+            ))
         }
     }
     /// You can create a visual shape in a similar way to creating a collision shape, with some
@@ -3128,7 +4289,8 @@ impl PhysicsClient {
                     filename,
                     mesh_scaling,
                 } => {
-                    let file = CString::new(filename.into_os_string().as_bytes()).unwrap();
+                    let file = CString::new(filename.into_os_string().as_bytes())
+                        .map_err(|_| Error::of_kind(ErrorKind::InvalidArgument, "Invalid path"))?;
                     shape_index = ffi::b3CreateVisualShapeAddMesh(
                         command_handle,
                         file.as_ptr(),
@@ -3149,20 +4311,29 @@ impl PhysicsClient {
                     let mut new_uvs = Vec::<f64>::with_capacity(vertices.len() * 2);
 
                     if vertices.len() > B3_MAX_NUM_VERTICES {
-                        return Err(Error::new("Number of vertices exceeds the maximum."));
+                        return Err(Error::of_kind(
+                            ErrorKind::InvalidArgument,
+                            "Number of vertices exceeds the maximum.",
+                        ));
                     }
                     for vertex in vertices.iter() {
                         new_vertices.extend_from_slice(vertex);
                     }
 
                     if indices.len() > B3_MAX_NUM_INDICES {
-                        return Err(Error::new("Number of indices exceeds the maximum."));
+                        return Err(Error::of_kind(
+                            ErrorKind::InvalidArgument,
+                            "Number of indices exceeds the maximum.",
+                        ));
                     }
                     let new_indices = indices;
 
                     if let Some(uvs) = uvs {
                         if uvs.len() > B3_MAX_NUM_VERTICES {
-                            return Err(Error::new("Number of uvs exceeds the maximum."));
+                            return Err(Error::of_kind(
+                                ErrorKind::InvalidArgument,
+                                "Number of uvs exceeds the maximum.",
+                            ));
                         }
                         for uv in uvs.iter() {
                             new_uvs.extend_from_slice(uv);
@@ -3170,7 +4341,10 @@ impl PhysicsClient {
                     }
                     if let Some(normals) = normals {
                         if normals.len() > B3_MAX_NUM_VERTICES {
-                            return Err(Error::new("Number of normals exceeds the maximum."));
+                            return Err(Error::of_kind(
+                                ErrorKind::InvalidArgument,
+                                "Number of normals exceeds the maximum.",
+                            ));
                         }
                         for normal in normals.iter() {
                             new_normals.extend_from_slice(normal);
@@ -3228,8 +4402,7 @@ impl PhysicsClient {
                     rotation_array.as_ptr(),
                 );
             }
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type == CMD_CREATE_VISUAL_SHAPE_COMPLETED as i32 {
                 let uid = ffi::b3GetStatusVisualShapeUniqueId(status_handle);
@@ -3238,7 +4411,11 @@ impl PhysicsClient {
                 }
                 return Ok(VisualId(uid));
             }
-            Err(Error::new("create visual Shape failed."))
+            Err(Error::command_failed(
+                "create_visual_shape",
+                status_type,
+                "create visual Shape failed.",
+            ))
         }
     }
     /// You can create a multi body with only a single base without joints/child links or
@@ -3294,15 +4471,19 @@ impl PhysicsClient {
         let options = options.into().unwrap_or_default();
         unsafe {
             let command_handle =
-                self.create_multi_body_base(base_collision_shape, base_visual_shape, &options);
+                self.create_multi_body_base(base_collision_shape, base_visual_shape, &options)?;
             let status_handle = self.submit_multi_body_command(&options, command_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type == CMD_CREATE_MULTI_BODY_COMPLETED as i32 {
                 let uid = ffi::b3GetStatusBodyIndex(status_handle);
-                return Ok(BodyId(uid));
+                return Ok(self.generations.create(uid));
             }
+            Err(Error::command_failed(
+                "create_multi_body",
+                status_type,
+                "create_multi_body failed.",
+            ))
         }
-        Err(Error::new("create_multi_body failed."))
     }
 
     /// like [`create_multi_body`](`Self::create_multi_body`) but creates multiple instances of this
@@ -3332,7 +4513,7 @@ impl PhysicsClient {
         let options = options.into().unwrap_or_default();
         unsafe {
             let command_handle =
-                self.create_multi_body_base(base_collision_shape, base_visual_shape, &options);
+                self.create_multi_body_base(base_collision_shape, base_visual_shape, &options)?;
 
             let mut new_batch_positions = Vec::<f64>::with_capacity(batch_positions.len() * 3);
             for pos in batch_positions.iter() {
@@ -3351,12 +4532,16 @@ impl PhysicsClient {
                 let uid = ffi::b3GetStatusBodyIndex(status_handle);
                 let num_batch_positions = batch_positions.len() as i32;
                 let out = (0..num_batch_positions)
-                    .map(|x| BodyId(uid - num_batch_positions + x + 1))
+                    .map(|x| self.generations.create(uid - num_batch_positions + x + 1))
                     .collect();
                 return Ok(out);
             }
+            Err(Error::command_failed(
+                "create_multi_body_batch",
+                status_type,
+                "create_multi_body_batch failed.",
+            ))
         }
-        Err(Error::new("create_multi_body_batch failed."))
     }
     // internal method to split create_multi_body and create_multi_body_batch
     fn create_multi_body_base(
@@ -3364,18 +4549,21 @@ impl PhysicsClient {
         base_collision_shape: CollisionId,
         base_visual_shape: VisualId,
         options: &MultiBodyOptions,
-    ) -> b3SharedMemoryCommandHandle {
+    ) -> Result<b3SharedMemoryCommandHandle, Error> {
         unsafe {
-            assert!(
-                options.link_masses.len() == options.link_collision_shapes.len()
-                    && options.link_masses.len() == options.link_visual_shapes.len()
-                    && options.link_masses.len() == options.link_poses.len()
-                    && options.link_masses.len() == options.link_joint_types.len()
-                    && options.link_masses.len() == options.link_joint_axis.len()
-                    && options.link_masses.len() == options.link_inertial_frame_poses.len()
-                    && options.link_masses.len() == options.link_collision_shapes.len(),
-                "All link arrays need to be same size."
-            );
+            if !(options.link_masses.len() == options.link_collision_shapes.len()
+                && options.link_masses.len() == options.link_visual_shapes.len()
+                && options.link_masses.len() == options.link_poses.len()
+                && options.link_masses.len() == options.link_joint_types.len()
+                && options.link_masses.len() == options.link_joint_axis.len()
+                && options.link_masses.len() == options.link_inertial_frame_poses.len()
+                && options.link_masses.len() == options.link_parent_indices.len())
+            {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidArgument,
+                    "All link arrays need to be same size.",
+                ));
+            }
 
             let command_handle = ffi::b3CreateMultiBodyCommandInit(self.handle);
             let position_vector = &options.base_pose.translation.vector;
@@ -3398,7 +4586,7 @@ impl PhysicsClient {
                 base_inertial_position_array.as_ptr(),
                 base_inertial_rotation_array.as_ptr(),
             );
-            command_handle
+            Ok(command_handle)
         }
     }
     // internal method to split create_multi_body and create_multi_body_batch
@@ -3449,7 +4637,7 @@ impl PhysicsClient {
             if let Some(flags) = &options.flags {
                 ffi::b3CreateMultiBodySetFlags(command_handle, flags.bits());
             }
-            b3SubmitClientCommandAndWaitStatus(self.handle, command_handle)
+            self.submit_command_and_wait_status(command_handle)
         }
     }
     /// Use this function to change the texture of a shape,
@@ -3509,6 +4697,7 @@ impl PhysicsClient {
         link_index: Link,
         options: ChangeVisualShapeOptions,
     ) -> Result<(), Error> {
+        self.generations.check(body)?;
         let link_index = match link_index.into() {
             None => -1,
             Some(index) => index as i32,
@@ -3528,12 +4717,15 @@ impl PhysicsClient {
             if let Some(flags) = options.flags {
                 ffi::b3UpdateVisualShapeFlags(command_handle, flags.bits());
             }
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
 
             if status_type != CMD_VISUAL_SHAPE_UPDATE_COMPLETED as i32 {
-                return Err(Error::new("Error resetting visual shape info"));
+                return Err(Error::command_failed(
+                    "change_visual_shape",
+                    status_type,
+                    "Error resetting visual shape info",
+                ));
             }
         }
         Ok(())
@@ -3545,10 +4737,10 @@ impl PhysicsClient {
         body: BodyId,
         request_texture_id: bool,
     ) -> Result<Vec<VisualShapeData>, Error> {
+        self.generations.check(body)?;
         unsafe {
             let command_handle = ffi::b3InitRequestVisualShapeInformation(self.handle, body.0);
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type == CMD_VISUAL_SHAPE_INFO_COMPLETED as i32 {
                 let mut visual_shape_info = ffi::b3VisualShapeInformation::default();
@@ -3568,8 +4760,12 @@ impl PhysicsClient {
                 }
                 return Ok(visual_shapes);
             }
+            Err(Error::command_failed(
+                "get_visual_shape_data",
+                status_type,
+                "Error receiving visual shape info",
+            ))
         }
-        Err(Error::new("Error receiving visual shape info"))
     }
     /// Load a texture from file and return a non-negative texture unique id if the loading succeeds.
     /// This unique id can be used with [change_visual_shape](`Self::change_visual_shape`).
@@ -3578,35 +4774,53 @@ impl PhysicsClient {
     /// See create_multi_body_batch.rs for an example
     pub fn load_texture<File: AsRef<Path>>(&mut self, file: File) -> Result<TextureId, Error> {
         unsafe {
-            let cfilename = CString::new(file.as_ref().as_os_str().as_bytes()).unwrap();
+            let cfilename = CString::new(file.as_ref().as_os_str().as_bytes())
+                .map_err(|_| Error::of_kind(ErrorKind::InvalidArgument, "Invalid path"))?;
             let command_handle = ffi::b3InitLoadTexture(self.handle, cfilename.as_ptr());
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type == CMD_LOAD_TEXTURE_COMPLETED as i32 {
                 let texture_id = TextureId(ffi::b3GetStatusTextureUniqueId(status_handle));
                 return Ok(texture_id);
             }
+            Err(Error::command_failed(
+                "load_texture",
+                status_type,
+                "Error loading texture",
+            ))
         }
-        Err(Error::new("Error loading texture"))
     }
-    /// will remove a body by its body unique id
-    pub fn remove_body(&mut self, body: BodyId) {
+    /// will remove a body by its body unique id.
+    ///
+    /// Returns an error with kind [`InvalidBodyId`](`crate::ErrorKind::InvalidBodyId`) if the body
+    /// was already removed, even if the physics server has reused its id for a new body.
+    pub fn remove_body(&mut self, body: BodyId) -> Result<(), Error> {
         unsafe {
-            assert!(body.0 >= 0, "Invalid BodyId");
-            assert!(
-                self.can_submit_command(),
-                "Internal Error: Can not submit command!",
-            );
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(
-                self.handle,
-                ffi::b3InitRemoveBodyCommand(self.handle, body.0),
-            );
-            let _status_type = ffi::b3GetStatusType(status_handle);
+            if body.0 < 0 {
+                return Err(Error::of_kind(ErrorKind::InvalidBodyId, "Invalid BodyId"));
+            }
+            self.generations.check(body)?;
+            if !self.can_submit_command() {
+                return Err(Error::disconnected());
+            }
+            let status_handle = self
+                .submit_command_and_wait_status(ffi::b3InitRemoveBodyCommand(self.handle, body.0));
+            let status_type = ffi::b3GetStatusType(status_handle);
+            if status_type != CMD_REMOVE_BODY_COMPLETED as i32 {
+                return Err(Error::command_failed(
+                    "remove_body",
+                    status_type,
+                    "Could not remove body",
+                ));
+            }
         }
+        self.generations.remove(body);
+        self.assets.remove(body);
+        Ok(())
     }
     /// gets the BodyInfo (base name and body name) of a body
     pub fn get_body_info(&mut self, body: BodyId) -> Result<BodyInfo, Error> {
+        self.generations.check(body)?;
         let mut body_info_c = ffi::b3BodyInfo {
             m_baseName: [0; 1024],
             m_bodyName: [0; 1024],
@@ -3616,11 +4830,17 @@ impl PhysicsClient {
                 return Ok(body_info_c.into());
             }
         }
-        Err(Error::new("Couldn't get body info"))
+        Err(Error::of_kind(
+            ErrorKind::InvalidBodyId,
+            "Couldn't get body info",
+        ))
     }
     /// returns the total number of bodies in the physics server
-    pub fn get_num_bodies(&mut self) -> usize {
-        unsafe { ffi::b3GetNumBodies(self.handle) as usize }
+    pub fn get_num_bodies(&mut self) -> Result<usize, Error> {
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
+        unsafe { Ok(ffi::b3GetNumBodies(self.handle) as usize) }
     }
     /// URDF, SDF and MJCF specify articulated bodies as a tree-structures without loops.
     /// Thhis method allows you to connect specific links of bodies to close those loops.
@@ -3655,7 +4875,7 @@ impl PhysicsClient {
     ///# use std::time::Duration;
     ///# fn main() -> Result<()> {
     ///#     let mut physics_client = PhysicsClient::connect(Mode::Direct)?;
-    ///#     assert_eq!(0, physics_client.get_num_constraints());
+    ///#     assert_eq!(0, physics_client.get_num_constraints()?);
     ///#     physics_client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
     ///#     physics_client.load_urdf("plane.urdf", None)?;
     ///     let cube_id = physics_client.load_urdf(
@@ -3665,8 +4885,8 @@ impl PhysicsClient {
     ///             ..Default::default()
     ///         },
     ///     )?;
-    ///     physics_client.set_gravity([0., 0., -10.]);
-    ///     physics_client.set_real_time_simulation(true);
+    ///     physics_client.set_gravity([0., 0., -10.])?;
+    ///     physics_client.set_real_time_simulation(true)?;
     ///     let cid = physics_client.create_constraint(
     ///         cube_id,
     ///         None,
@@ -3679,7 +4899,7 @@ impl PhysicsClient {
     ///     )?;
     ///     println!("{:?}", cid);
     ///     println!("{:?}", physics_client.get_constraint(0)?);
-    ///     assert_eq!(1, physics_client.get_num_constraints());
+    ///     assert_eq!(1, physics_client.get_num_constraints()?);
     ///     let constraint_info = physics_client.get_constraint_info(cid)?;
     ///     println!("{:?}", constraint_info);
     ///     let mut a = -PI;
@@ -3695,7 +4915,7 @@ impl PhysicsClient {
     ///             max_force: Some(50.),
     ///             ..Default::default()
     ///         };
-    ///         physics_client.change_constraint(cid, change_constraint_options);
+    ///         physics_client.change_constraint(cid, change_constraint_options)?;
     ///         let constraint_info = physics_client.get_constraint_info(cid)?;
     ///         assert!((constraint_info.joint_child_frame_pose.translation.x - a).abs() < 1e-7);
     ///         assert!(
@@ -3711,8 +4931,8 @@ impl PhysicsClient {
     ///     }
     ///     let constraint_state = physics_client.get_constraint_state(cid)?;
     ///     println!("{}", constraint_state);
-    ///     physics_client.remove_constraint(cid);
-    ///     assert_eq!(0, physics_client.get_num_constraints());
+    ///     physics_client.remove_constraint(cid)?;
+    ///     assert_eq!(0, physics_client.get_num_constraints()?);
     ///#     Ok(())
     ///# }
     /// ```
@@ -3733,9 +4953,13 @@ impl PhysicsClient {
         parent_frame_pose: Isometry3<f64>,
         child_frame_pose: Isometry3<f64>,
     ) -> Result<ConstraintId, Error> {
+        self.generations.check(parent_body)?;
         let child_body = match child_body.into() {
             None => -1,
-            Some(body) => body.0,
+            Some(body) => {
+                self.generations.check(body)?;
+                body.0
+            }
         };
         let parent_link_index: i32 = match parent_link_index.into() {
             None => -1,
@@ -3777,16 +5001,19 @@ impl PhysicsClient {
                 child_link_index,
                 &mut joint_info,
             );
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type == CMD_USER_CONSTRAINT_COMPLETED as i32 {
-                return Ok(ConstraintId(ffi::b3GetStatusUserConstraintUniqueId(
-                    status_handle,
-                )));
-            }
+                return Ok(self
+                    .generations
+                    .create(ffi::b3GetStatusUserConstraintUniqueId(status_handle)));
+            }
+            Err(Error::command_failed(
+                "create_constraint",
+                status_type,
+                "create_constraint failed",
+            ))
         }
-        Err(Error::new("create_constraint failed"))
     }
     /// Allows you to change parameters of an existing constraint.
     /// See [`create_constraint`](`Self::create_constraint`) for an example.
@@ -3794,7 +5021,8 @@ impl PhysicsClient {
         &mut self,
         constraint: ConstraintId,
         options: ChangeConstraintOptions,
-    ) {
+    ) -> Result<(), Error> {
+        self.generations.check(constraint)?;
         unsafe {
             let command_handle = ffi::b3InitChangeUserConstraintCommand(self.handle, constraint.0);
             if let Some(pivot) = options.joint_child_pivot {
@@ -3807,21 +5035,33 @@ impl PhysicsClient {
                 );
             }
             if let Some(relative_position_target) = options.relative_position_target {
-                assert!(
-                    relative_position_target < 1e10,
-                    "relative position target must not exceed 1e10"
-                );
+                if relative_position_target >= 1e10 {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "relative position target must not exceed 1e10",
+                    ));
+                }
                 ffi::b3InitChangeUserConstraintSetRelativePositionTarget(
                     command_handle,
                     relative_position_target,
                 );
             }
             if let Some(erp) = options.erp {
-                assert!(erp.is_sign_positive(), "erp must be positive");
+                if !erp.is_sign_positive() {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "erp must be positive",
+                    ));
+                }
                 ffi::b3InitChangeUserConstraintSetERP(command_handle, erp);
             }
             if let Some(max_force) = options.max_force {
-                assert!(max_force.is_sign_positive(), "max_force must be positive");
+                if !max_force.is_sign_positive() {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "max_force must be positive",
+                    ));
+                }
                 ffi::b3InitChangeUserConstraintSetMaxForce(command_handle, max_force);
             }
             if let Some(gear_ratio) = options.gear_ratio {
@@ -3830,26 +5070,39 @@ impl PhysicsClient {
             if let Some(aux_link) = options.gear_aux_link {
                 ffi::b3InitChangeUserConstraintSetGearAuxLink(command_handle, aux_link as i32);
             }
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
-            let _status_type = ffi::b3GetStatusType(status_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
+            let status_type = ffi::b3GetStatusType(status_handle);
+            if status_type != CMD_CHANGE_USER_CONSTRAINT_COMPLETED as i32 {
+                return Err(Error::command_failed(
+                    "change_constraint",
+                    status_type,
+                    "change_constraint failed",
+                ));
+            }
         }
+        Ok(())
     }
-    /// removes a constraint
+    /// removes a constraint. Returns an error if the constraint was already removed.
     /// See [`create_constraint`](`Self::create_constraint`) for an example.
-    pub fn remove_constraint(&mut self, constraint: ConstraintId) {
+    pub fn remove_constraint(&mut self, constraint: ConstraintId) -> Result<(), Error> {
+        self.generations.check(constraint)?;
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
+        self.generations.remove(constraint);
         unsafe {
             let command_handle = ffi::b3InitRemoveUserConstraintCommand(self.handle, constraint.0);
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
-            let _status_type = ffi::b3GetStatusType(status_handle);
+            self.submit_simple_command(command_handle)
         }
     }
     /// You can query for the total number of constraints, created using
     /// [`create_constraint`](`Self::create_constraint`)
     /// See [`create_constraint`](`Self::create_constraint`) for an example.
-    pub fn get_num_constraints(&mut self) -> usize {
-        unsafe { ffi::b3GetNumUserConstraints(self.handle) as usize }
+    pub fn get_num_constraints(&mut self) -> Result<usize, Error> {
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
+        unsafe { Ok(ffi::b3GetNumUserConstraints(self.handle) as usize) }
     }
     /// will take a serial index in range 0..[`get_num_constraints`](`Self::get_num_constraints`),
     /// and reports the constraint unique id.
@@ -3859,10 +5112,13 @@ impl PhysicsClient {
         unsafe {
             let constraint_id = ffi::b3GetUserConstraintId(self.handle, serial_index as i32);
             if constraint_id >= 0 {
-                return Ok(ConstraintId(constraint_id));
+                return Ok(self.generations.existing(constraint_id));
             }
         }
-        Err(Error::new("no constraint with this serial index"))
+        Err(Error::of_kind(
+            ErrorKind::InvalidArgument,
+            "no constraint with this serial index",
+        ))
     }
     /// Get the user-created constraint info, given a ConstraintId.
     /// See [`create_constraint`](`Self::create_constraint`) for an example.
@@ -3870,14 +5126,18 @@ impl PhysicsClient {
         &mut self,
         constraint: ConstraintId,
     ) -> Result<ConstraintInfo, Error> {
+        self.generations.check(constraint)?;
         let mut b3_constraint_info = ffi::b3UserConstraint::default();
         unsafe {
             if ffi::b3GetUserConstraintInfo(self.handle, constraint.0, &mut b3_constraint_info) != 0
             {
-                return Ok(b3_constraint_info.into());
+                return ConstraintInfo::try_from(b3_constraint_info);
             }
         }
-        Err(Error::new("Couldn't get user constraint info"))
+        Err(Error::of_kind(
+            ErrorKind::InvalidArgument,
+            "Couldn't get user constraint info",
+        ))
     }
     /// Give a constraint unique id, you can query for the applied constraint forces in the most
     /// recent simulation step. The input is a constraint unique id and the output is a vector of
@@ -3889,14 +5149,14 @@ impl PhysicsClient {
         &mut self,
         constraint: ConstraintId,
     ) -> Result<DVector<f64>, Error> {
+        self.generations.check(constraint)?;
         let mut constraint_state = ffi::b3UserConstraintState::default();
         unsafe {
             if self.can_submit_command() {
                 let cmd_handle =
                     ffi::b3InitGetUserConstraintStateCommand(self.handle, constraint.0);
-                let status_handle =
-                    ffi::b3SubmitClientCommandAndWaitStatus(self.handle, cmd_handle);
-                let _status_type = ffi::b3GetStatusType(status_handle);
+                let status_handle = self.submit_command_and_wait_status(cmd_handle);
+                let status_type = ffi::b3GetStatusType(status_handle);
                 if ffi::b3GetStatusUserConstraintState(status_handle, &mut constraint_state) != 0 {
                     if constraint_state.m_numDofs != 0 {
                         return Ok(DVector::from_column_slice(
@@ -3905,8 +5165,13 @@ impl PhysicsClient {
                         ));
                     }
                 }
+                return Err(Error::command_failed(
+                    "get_constraint_state",
+                    status_type,
+                    "Could not get constraint state",
+                ));
             }
-            Err(Error::new("Could not get constraint state"))
+            Err(Error::disconnected())
         }
     }
     /// queriers the axis aligned bounding box (in world space) given an object unique id,
@@ -3936,17 +5201,24 @@ impl PhysicsClient {
         body: BodyId,
         link_index: Link,
     ) -> Result<Aabb, Error> {
+        self.generations.check(body)?;
         let link_index = match link_index.into() {
             None => -1,
             Some(index) => index as i32,
         };
-        assert!(body.0 >= 0);
+        if body.0 < 0 {
+            return Err(Error::of_kind(ErrorKind::InvalidBodyId, "Invalid BodyId"));
+        }
         unsafe {
             let cmd_handle = ffi::b3RequestCollisionInfoCommandInit(self.handle, body.0);
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, cmd_handle);
+            let status_handle = self.submit_command_and_wait_status(cmd_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type != CMD_REQUEST_COLLISION_INFO_COMPLETED as i32 {
-                return Err(Error::new("get_aabb failed"));
+                return Err(Error::command_failed(
+                    "get_aabb",
+                    status_type,
+                    "get_aabb failed",
+                ));
             }
             let mut aabb_min = [0.; 3];
             let mut aabb_max = [0.; 3];
@@ -3962,7 +5234,11 @@ impl PhysicsClient {
                     max: aabb_max.into(),
                 });
             }
-            Err(Error::new("get_aabb failed"))
+            Err(Error::command_failed(
+                "get_aabb",
+                status_type,
+                "get_aabb failed",
+            ))
         }
     }
     /// This query will return all the unique ids of objects that have axis aligned bounding
@@ -3992,44 +5268,61 @@ impl PhysicsClient {
     ///     let overlapping_object = physics_client.get_overlapping_objects(Aabb{
     ///         min: [-1.;3].into(),
     ///         max:[1.;3].into(),
-    ///     });
+    ///     })?;
     ///     assert_eq!(2,overlapping_object.len());
     ///#     Ok(())
     ///# }
     /// ```
-    pub fn get_overlapping_objects(&mut self, aabb: Aabb) -> Vec<OverlappingObject> {
+    pub fn get_overlapping_objects(&mut self, aabb: Aabb) -> Result<Vec<OverlappingObject>, Error> {
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
         unsafe {
             let Aabb { min, max } = aabb;
             let command_handle =
                 ffi::b3InitAABBOverlapQuery(self.handle, min.as_ptr(), max.as_ptr());
-            let _status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
+            let status_type = ffi::b3GetStatusType(status_handle);
+            if status_type != CMD_REQUEST_AABB_OVERLAP_COMPLETED as i32 {
+                return Err(Error::command_failed(
+                    "get_overlapping_objects",
+                    status_type,
+                    "get_overlapping_objects failed",
+                ));
+            }
             let mut overlap_data = b3AABBOverlapData {
                 m_numOverlappingObjects: 0,
                 m_overlappingObjects: [].as_mut_ptr(),
             };
             ffi::b3GetAABBOverlapResults(self.handle, &mut overlap_data);
+            if overlap_data.m_numOverlappingObjects <= 0
+                || overlap_data.m_overlappingObjects.is_null()
+            {
+                return Ok(Vec::new());
+            }
             let mut objects = Vec::with_capacity(overlap_data.m_numOverlappingObjects as usize);
             let data = std::slice::from_raw_parts_mut(
                 overlap_data.m_overlappingObjects,
                 overlap_data.m_numOverlappingObjects as usize,
             );
             for object in data.iter() {
-                let link_index = {
-                    assert!(object.m_linkIndex >= -1);
-                    if object.m_linkIndex == -1 {
-                        None
-                    } else {
-                        Some(object.m_linkIndex as usize)
+                let link_index = match object.m_linkIndex {
+                    -1 => None,
+                    index if index >= 0 => Some(index as usize),
+                    _ => {
+                        return Err(Error::of_kind(
+                            ErrorKind::ParseError,
+                            "Server returned an invalid link index",
+                        ))
                     }
                 };
                 let object = OverlappingObject {
-                    body: BodyId(object.m_objectUniqueId),
+                    body: self.generations.existing(object.m_objectUniqueId),
                     link_index,
                 };
                 objects.push(object);
             }
-            objects
+            Ok(objects)
         }
     }
     /// The getContactPoints API returns the contact points computed during the most recent call to
@@ -4091,6 +5384,9 @@ impl PhysicsClient {
     ) -> Result<Vec<ContactPoint>, Error> {
         let body_a = body_a.into();
         let body_b = body_b.into();
+        for &body in body_a.iter().chain(&body_b) {
+            self.generations.check(body)?;
+        }
         unsafe {
             let command_handle = ffi::b3InitRequestContactPointInformation(self.handle);
             if let Some(body_1) = body_a {
@@ -4120,8 +5416,7 @@ impl PhysicsClient {
                     }
                 }
             }
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type == CMD_CONTACT_POINT_INFORMATION_COMPLETED as i32 {
                 let mut contact_information = b3ContactInformation {
@@ -4140,8 +5435,12 @@ impl PhysicsClient {
                 }
                 return Ok(objects);
             }
+            Err(Error::command_failed(
+                "get_contact_points",
+                status_type,
+                "could not get contact points",
+            ))
         }
-        Err(Error::new("could not get contact points"))
     }
 
     /// Computes contact points independent from [`step_simulation`](`Self::step_simulation`).
@@ -4204,6 +5503,8 @@ impl PhysicsClient {
         link_index_b: LinkB,
         distance: f64,
     ) -> Result<Vec<ContactPoint>, Error> {
+        self.generations.check(body_a)?;
+        self.generations.check(body_b)?;
         let link_index_a = match link_index_a.into() {
             None => -1,
             Some(index) => index as i32,
@@ -4214,8 +5515,12 @@ impl PhysicsClient {
         };
         unsafe {
             let command_handle = ffi::b3InitClosestDistanceQuery(self.handle);
-            assert!(body_a.0 >= 0);
-            assert!(body_b.0 >= 0);
+            if body_a.0 < 0 {
+                return Err(Error::of_kind(ErrorKind::InvalidBodyId, "Invalid BodyId"));
+            }
+            if body_b.0 < 0 {
+                return Err(Error::of_kind(ErrorKind::InvalidBodyId, "Invalid BodyId"));
+            }
             ffi::b3SetClosestDistanceFilterBodyA(command_handle, body_a.0);
             ffi::b3SetClosestDistanceFilterBodyB(command_handle, body_b.0);
             ffi::b3SetClosestDistanceThreshold(command_handle, distance);
@@ -4253,14 +5558,22 @@ impl PhysicsClient {
         shape_pose: Isometry3<f64>,
         distance: f64,
     ) -> Result<Vec<ContactPoint>, Error> {
+        self.generations.check(body)?;
         let link_index = match link_index.into() {
             None => -1,
             Some(index) => index as i32,
         };
         unsafe {
             let command_handle = ffi::b3InitClosestDistanceQuery(self.handle);
-            assert!(body.0 >= 0);
-            assert!(collision_shape.0 >= 0);
+            if body.0 < 0 {
+                return Err(Error::of_kind(ErrorKind::InvalidBodyId, "Invalid BodyId"));
+            }
+            if collision_shape.0 < 0 {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidArgument,
+                    "Invalid CollisionId",
+                ));
+            }
             ffi::b3SetClosestDistanceFilterBodyA(command_handle, body.0);
             ffi::b3SetClosestDistanceFilterLinkA(command_handle, link_index);
             ffi::b3SetClosestDistanceThreshold(command_handle, distance);
@@ -4307,8 +5620,18 @@ impl PhysicsClient {
     ) -> Result<Vec<ContactPoint>, Error> {
         unsafe {
             let command_handle = ffi::b3InitClosestDistanceQuery(self.handle);
-            assert!(collision_shape_a.0 >= 0);
-            assert!(collision_shape_b.0 >= 0);
+            if collision_shape_a.0 < 0 {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidArgument,
+                    "Invalid CollisionId",
+                ));
+            }
+            if collision_shape_b.0 < 0 {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidArgument,
+                    "Invalid CollisionId",
+                ));
+            }
 
             ffi::b3SetClosestDistanceThreshold(command_handle, distance);
             ffi::b3SetClosestDistanceFilterCollisionShapeA(command_handle, collision_shape_a.0);
@@ -4341,7 +5664,7 @@ impl PhysicsClient {
             m_numContactPoints: 0,
             m_contactPointData: [].as_mut_ptr(),
         };
-        let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+        let status_handle = self.submit_command_and_wait_status(command_handle);
         let status_type = ffi::b3GetStatusType(status_handle);
         if status_type == CMD_CONTACT_POINT_INFORMATION_COMPLETED as i32 {
             ffi::b3GetContactPointInformation(self.handle, &mut contact_information);
@@ -4357,7 +5680,11 @@ impl PhysicsClient {
             }
             return Ok(objects);
         }
-        Err(Error::new("get_closes_points failed"))
+        Err(Error::command_failed(
+            "get_closest_points",
+            status_type,
+            "get_closes_points failed",
+        ))
     }
     /// State logging lets you log the state of the simulation, such as the state of one or more
     /// objects after each simulation step (after each call to stepSimulation or automatically after
@@ -4402,10 +5729,18 @@ impl PhysicsClient {
         options: Options,
     ) -> Result<LogId, Error> {
         let options = options.into().unwrap_or_default();
+        for &body in options
+            .object_ids
+            .iter()
+            .chain(&options.body_a)
+            .chain(&options.body_b)
+        {
+            self.generations.check(body)?;
+        }
         unsafe {
             let command_handle = ffi::b3StateLoggingCommandInit(self.handle);
             let file = CString::new(file.as_ref().as_os_str().as_bytes())
-                .map_err(|_| Error::new("Invalid path"))?;
+                .map_err(|_| Error::of_kind(ErrorKind::InvalidArgument, "Invalid path"))?;
             ffi::b3StateLoggingStart(command_handle, logging_type as i32, file.as_ptr());
             for body in options.object_ids.iter() {
                 ffi::b3StateLoggingAddLoggingObjectUniqueId(command_handle, body.0);
@@ -4439,13 +5774,18 @@ impl PhysicsClient {
             if let Some(flags) = options.log_flags {
                 ffi::b3StateLoggingSetLogFlags(command_handle, flags.bits());
             }
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type == CMD_STATE_LOGGING_START_COMPLETED as i32 {
-                return Ok(LogId(ffi::b3GetStatusLoggingUniqueId(status_handle)));
-            }
-            Err(Error::new("could not start logging"))
+                return Ok(self
+                    .generations
+                    .create(ffi::b3GetStatusLoggingUniqueId(status_handle)));
+            }
+            Err(Error::command_failed(
+                "start_state_logging",
+                status_type,
+                "could not start logging",
+            ))
         }
     }
     /// Stops a logger. If you use a  [`ProfileTimings`](`crate::types::LoggingType::ProfileTimings`)
@@ -4453,15 +5793,19 @@ impl PhysicsClient {
     /// file.
     /// # Arguments
     /// * `log` - [`LogId`](`crate::types::LogId`) as returned by [`start_state_logging`](`Self::start_state_logging`)
-    pub fn stop_state_logging(&mut self, log: LogId) {
-        assert!(log.0 >= 0);
+    pub fn stop_state_logging(&mut self, log: LogId) -> Result<(), Error> {
+        if log.0 < 0 {
+            return Err(Error::of_kind(ErrorKind::InvalidArgument, "Invalid LogId"));
+        }
+        self.generations.check(log)?;
+        self.generations.remove(log);
         unsafe {
             let command_handle = ffi::b3StateLoggingCommandInit(self.handle);
             ffi::b3StateLoggingStop(command_handle, log.0);
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
             let _status_type = ffi::b3GetStatusType(status_handle);
         }
+        Ok(())
     }
     /// submit_profile_timing allows to insert start and stop timings to profile Rust code.
     /// RuBullet and Bullet have instrumented many functions so you can see where the time is spend.
@@ -4477,10 +5821,10 @@ impl PhysicsClient {
     /// with [`ProfileTimings`](`crate::types::LoggingType::ProfileTimings`) as [`LoggingType`](`crate::types::LoggingType`)
     ///
     /// You can start a timing by calling
-    /// `client.submit_profile_timing("my_timing");`
+    /// `client.submit_profile_timing("my_timing")?;`
     /// This will start a timing called `my_timing0`. The "0" is a running index which get s increased for every new timing.
-    /// calling `client.submit_profile_timing("my_timing");` again will start a timing called `my_timing1`.
-    /// The timing are put onto stacked and are being stopped by calling `client.submit_profile_timing(None);`.
+    /// calling `client.submit_profile_timing("my_timing")?;` again will start a timing called `my_timing1`.
+    /// The timing are put onto stacked and are being stopped by calling `client.submit_profile_timing(None)?;`.
     /// Calling it the first time will stop `my_timing1`. And the second calling it a second time will
     /// stop `my_timing0`.
     ///
@@ -4507,44 +5851,44 @@ impl PhysicsClient {
     ///     while Instant::now() < t {
     ///         physics_client.step_simulation()?;
     ///
-    ///         physics_client.submit_profile_timing("rusttest");
+    ///         physics_client.submit_profile_timing("rusttest")?;
     ///         std::thread::sleep(Duration::from_secs_f64(1. / 240.));
     ///
-    ///         physics_client.submit_profile_timing("nested");
+    ///         physics_client.submit_profile_timing("nested")?;
     ///         for _ in 0..100 {
-    ///             physics_client.submit_profile_timing("deep_nested");
-    ///             physics_client.submit_profile_timing(None);
+    ///             physics_client.submit_profile_timing("deep_nested")?;
+    ///             physics_client.submit_profile_timing(None)?;
     ///         }
     ///         std::thread::sleep(Duration::from_millis(1));
-    ///         physics_client.submit_profile_timing(None);
-    ///         physics_client.submit_profile_timing(None);
+    ///         physics_client.submit_profile_timing(None)?;
+    ///         physics_client.submit_profile_timing(None)?;
     ///     }
-    ///     physics_client.stop_state_logging(log_id);
+    ///     physics_client.stop_state_logging(log_id)?;
     ///     Ok(())
     /// }
     /// ```
     pub fn submit_profile_timing<'a, EventName: Into<Option<&'a str>>>(
         &mut self,
         event_name: EventName,
-    ) {
+    ) -> Result<(), Error> {
         unsafe {
             match event_name.into() {
                 None => {
                     let command_handle = ffi::b3ProfileTimingCommandInit(self.handle, [0].as_ptr());
                     ffi::b3SetProfileTimingType(command_handle, 1);
-                    let _status_handle =
-                        ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+                    let _status_handle = self.submit_command_and_wait_status(command_handle);
                 }
                 Some(event_name) => {
-                    let event_name = CString::new(event_name.as_bytes()).expect("Invalid name");
+                    let event_name = CString::new(event_name.as_bytes())
+                        .map_err(|_| Error::of_kind(ErrorKind::InvalidArgument, "Invalid name"))?;
                     let command_handle =
                         ffi::b3ProfileTimingCommandInit(self.handle, event_name.as_ptr());
                     ffi::b3SetProfileTimingType(command_handle, 0);
-                    let _status_handle =
-                        ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+                    let _status_handle = self.submit_command_and_wait_status(command_handle);
                 }
             };
         }
+        Ok(())
     }
     /// You can create an approximate snapshot of the current world as a PyBullet Python file
     /// (Yes, a Python file and not a Rust file),
@@ -4586,13 +5930,16 @@ impl PhysicsClient {
     pub fn save_world<P: AsRef<Path>>(&mut self, filename: P) -> Result<(), Error> {
         unsafe {
             let file = CString::new(filename.as_ref().as_os_str().as_bytes())
-                .map_err(|_| Error::new("Invalid path"))?;
+                .map_err(|_| Error::of_kind(ErrorKind::InvalidArgument, "Invalid path"))?;
             let command_handle = ffi::b3SaveWorldCommandInit(self.handle, file.as_ptr());
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type != CMD_SAVE_WORLD_COMPLETED as i32 {
-                return Err(Error::new("save_world command execution failed"));
+                return Err(Error::command_failed(
+                    "save_world",
+                    status_type,
+                    "save_world command execution failed",
+                ));
             }
             Ok(())
         }
@@ -4624,12 +5971,16 @@ impl PhysicsClient {
     ) -> Result<Vec<BodyId>, Error> {
         unsafe {
             let file = CString::new(bullet_filename.as_ref().as_os_str().as_bytes())
-                .map_err(|_| Error::new("Invalid path"))?;
+                .map_err(|_| Error::of_kind(ErrorKind::InvalidArgument, "Invalid path"))?;
             let command = ffi::b3LoadBulletCommandInit(self.handle, file.as_ptr());
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
+            let status_handle = self.submit_command_and_wait_status(command);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type != CMD_BULLET_LOADING_COMPLETED as i32 {
-                return Err(Error::new("Couldn't load .bullet file."));
+                return Err(Error::command_failed(
+                    "load_bullet",
+                    status_type,
+                    "Couldn't load .bullet file.",
+                ));
             }
             let mut body_indices_out = [0; MAX_SDF_BODIES as usize];
             let num_bodies = ffi::b3GetStatusBodyIndices(
@@ -4640,11 +5991,21 @@ impl PhysicsClient {
             if num_bodies > MAX_SDF_BODIES as i32 {
                 return Err(Error::new("load_bullet exceeds body capacity"));
             }
-            assert!(num_bodies >= 0);
+            if num_bodies < 0 {
+                return Err(Error::of_kind(
+                    ErrorKind::ParseError,
+                    "load_bullet returned a negative number of bodies",
+                ));
+            }
             let mut bodies = Vec::with_capacity(num_bodies as usize);
             for &body in body_indices_out.iter().take(num_bodies as usize) {
-                assert!(body >= 0);
-                bodies.push(BodyId(body));
+                if body < 0 {
+                    return Err(Error::of_kind(
+                        ErrorKind::ParseError,
+                        "load_bullet returned an invalid BodyId",
+                    ));
+                }
+                bodies.push(self.generations.create(body));
             }
             Ok(bodies)
         }
@@ -4680,12 +6041,16 @@ impl PhysicsClient {
     pub fn save_bullet<P: AsRef<Path>>(&mut self, bullet_filename: P) -> Result<(), Error> {
         unsafe {
             let file = CString::new(bullet_filename.as_ref().as_os_str().as_bytes())
-                .map_err(|_| Error::new("Invalid path"))?;
+                .map_err(|_| Error::of_kind(ErrorKind::InvalidArgument, "Invalid path"))?;
             let command = ffi::b3SaveBulletCommandInit(self.handle, file.as_ptr());
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
+            let status_handle = self.submit_command_and_wait_status(command);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type != CMD_BULLET_SAVING_COMPLETED as i32 {
-                return Err(Error::new("Couldn't save .bullet file."));
+                return Err(Error::command_failed(
+                    "save_bullet",
+                    status_type,
+                    "Couldn't save .bullet file.",
+                ));
             }
             Ok(())
         }
@@ -4693,17 +6058,28 @@ impl PhysicsClient {
     /// restores a state from memory using a state id which was created with [`save_state`](`Self::save_state`).
     /// See `save_and_restore.rs` example.
     pub fn restore_state(&mut self, state: StateId) -> Result<(), Error> {
+        self.generations.check(state)?;
         unsafe {
             let command = ffi::b3LoadStateCommandInit(self.handle);
-            assert!(state.0 >= 0);
+            if state.0 < 0 {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidArgument,
+                    "Invalid StateId",
+                ));
+            }
             ffi::b3LoadStateSetStateId(command, state.0);
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
+            let status_handle = self.submit_command_and_wait_status(command);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type != CMD_RESTORE_STATE_COMPLETED as i32 {
-                return Err(Error::new("Couldn't restore state."));
+                return Err(Error::command_failed(
+                    "restore_state",
+                    status_type,
+                    "Couldn't restore state.",
+                ));
             }
-            Ok(())
         }
+        self.generations.restore_state(state);
+        Ok(())
     }
     /// restores a state from a `.bullet` file. It is necessary that the correct bodies are already
     /// loaded. If this is not the case use [`load_bullet`](`Self::load_bullet`) instead.
@@ -4711,13 +6087,17 @@ impl PhysicsClient {
     pub fn restore_state_from_file<P: AsRef<Path>>(&mut self, filename: P) -> Result<(), Error> {
         unsafe {
             let file = CString::new(filename.as_ref().as_os_str().as_bytes())
-                .map_err(|_| Error::new("Invalid path"))?;
+                .map_err(|_| Error::of_kind(ErrorKind::InvalidArgument, "Invalid path"))?;
             let command = ffi::b3LoadStateCommandInit(self.handle);
             ffi::b3LoadStateSetFileName(command, file.as_ptr());
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
+            let status_handle = self.submit_command_and_wait_status(command);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type != CMD_RESTORE_STATE_COMPLETED as i32 {
-                return Err(Error::new("Couldn't restore state."));
+                return Err(Error::command_failed(
+                    "restore_state_from_file",
+                    status_type,
+                    "Couldn't restore state.",
+                ));
             }
             Ok(())
         }
@@ -4729,29 +6109,59 @@ impl PhysicsClient {
     pub fn save_state(&mut self) -> Result<StateId, Error> {
         unsafe {
             let command = ffi::b3SaveStateCommandInit(self.handle);
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
+            let status_handle = self.submit_command_and_wait_status(command);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type != CMD_SAVE_STATE_COMPLETED as i32 {
-                return Err(Error::new("Couldn't save state."));
+                return Err(Error::command_failed(
+                    "save_state",
+                    status_type,
+                    "Couldn't save state.",
+                ));
             }
             let state_id = ffi::b3GetStatusGetStateId(status_handle);
-            assert!(state_id >= 0);
-            Ok(StateId(state_id))
+            if state_id < 0 {
+                return Err(Error::of_kind(
+                    ErrorKind::ParseError,
+                    "save_state returned an invalid StateId",
+                ));
+            }
+            let state = self.generations.create(state_id);
+            self.generations.save_state(state);
+            Ok(state)
         }
     }
     /// Removes a state from memory.
-    pub fn remove_state(&mut self, state: StateId) {
+    pub fn remove_state(&mut self, state: StateId) -> Result<(), Error> {
+        if state.0 < 0 {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                "Invalid StateId",
+            ));
+        }
+        self.generations.check(state)?;
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
         unsafe {
-            assert!(state.0 >= 0);
-            if self.can_submit_command() {
-                let command = ffi::b3InitRemoveStateCommand(self.handle, state.0);
-                let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
-                let _status_type = ffi::b3GetStatusType(status_handle);
+            let command = ffi::b3InitRemoveStateCommand(self.handle, state.0);
+            let status_handle = self.submit_command_and_wait_status(command);
+            let status_type = ffi::b3GetStatusType(status_handle);
+            if status_type != CMD_REMOVE_STATE_COMPLETED as i32 {
+                return Err(Error::command_failed(
+                    "remove_state",
+                    status_type,
+                    "Couldn't remove state.",
+                ));
             }
         }
+        self.generations.remove_state(state);
+        Ok(())
     }
     /// Set some internal physics engine parameter, such as cfm or erp etc.
-    pub fn set_physics_engine_parameter(&mut self, options: SetPhysicsEngineParameterOptions) {
+    pub fn set_physics_engine_parameter(
+        &mut self,
+        options: SetPhysicsEngineParameterOptions,
+    ) -> Result<(), Error> {
         unsafe {
             let command = ffi::b3InitPhysicsParamCommand(self.handle);
             if let Some(num_solver_iterations) = options.num_solver_iterations {
@@ -4764,7 +6174,12 @@ impl PhysicsClient {
                 );
             }
             if let Some(solver_residual_threshold) = options.solver_residual_threshold {
-                assert!(solver_residual_threshold.is_sign_positive());
+                if !solver_residual_threshold.is_sign_positive() {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "solver_residual_threshold must be positive",
+                    ));
+                }
                 ffi::b3PhysicsParamSetSolverResidualThreshold(command, solver_residual_threshold);
             }
             if let Some(collision_filter_mode) = options.collision_filter_mode {
@@ -4789,26 +6204,51 @@ impl PhysicsClient {
             if let Some(split_impulse_penetration_threshold) =
                 options.split_impulse_penetration_threshold
             {
-                assert!(split_impulse_penetration_threshold.is_sign_positive());
+                if !split_impulse_penetration_threshold.is_sign_positive() {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "split_impulse_penetration_threshold must be positive",
+                    ));
+                }
                 ffi::b3PhysicsParamSetSplitImpulsePenetrationThreshold(
                     command,
                     split_impulse_penetration_threshold,
                 );
             }
             if let Some(contact_breaking_threshold) = options.contact_breaking_threshold {
-                assert!(contact_breaking_threshold.is_sign_positive());
+                if !contact_breaking_threshold.is_sign_positive() {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "contact_breaking_threshold must be positive",
+                    ));
+                }
                 ffi::b3PhysicsParamSetContactBreakingThreshold(command, contact_breaking_threshold);
             }
             if let Some(contact_slop) = options.contact_slop {
-                assert!(contact_slop.is_sign_positive());
+                if !contact_slop.is_sign_positive() {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "contact_slop must be positive",
+                    ));
+                }
                 ffi::b3PhysicsParamSetContactSlop(command, contact_slop);
             }
             if let Some(max_num_cmd_per_1_ms) = options.max_num_cmd_per_1_ms {
-                assert!(max_num_cmd_per_1_ms >= -1);
+                if max_num_cmd_per_1_ms < -1 {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "max_num_cmd_per_1_ms must be at least -1",
+                    ));
+                }
                 ffi::b3PhysicsParamSetMaxNumCommandsPer1ms(command, max_num_cmd_per_1_ms);
             }
             if let Some(restitution_velocity_threshold) = options.restitution_velocity_threshold {
-                assert!(restitution_velocity_threshold.is_sign_positive());
+                if !restitution_velocity_threshold.is_sign_positive() {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "restitution_velocity_threshold must be positive",
+                    ));
+                }
                 ffi::b3PhysicsParamSetRestitutionVelocityThreshold(
                     command,
                     restitution_velocity_threshold,
@@ -4825,15 +6265,30 @@ impl PhysicsClient {
                 }
             }
             if let Some(erp) = options.erp {
-                assert!(erp.is_sign_positive());
+                if !erp.is_sign_positive() {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "erp must be positive",
+                    ));
+                }
                 ffi::b3PhysicsParamSetDefaultNonContactERP(command, erp);
             }
             if let Some(contact_erp) = options.contact_erp {
-                assert!(contact_erp.is_sign_positive());
+                if !contact_erp.is_sign_positive() {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "contact_erp must be positive",
+                    ));
+                }
                 ffi::b3PhysicsParamSetDefaultContactERP(command, contact_erp);
             }
             if let Some(friction_erp) = options.friction_erp {
-                assert!(friction_erp.is_sign_positive());
+                if !friction_erp.is_sign_positive() {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "friction_erp must be positive",
+                    ));
+                }
                 ffi::b3PhysicsParamSetDefaultFrictionERP(command, friction_erp);
             }
             if let Some(enable_cone_friction) = options.enable_cone_friction {
@@ -4857,7 +6312,12 @@ impl PhysicsClient {
                 }
             }
             if let Some(allowed_ccd_penetration) = options.allowed_ccd_penetration {
-                assert!(allowed_ccd_penetration.is_sign_positive());
+                if !allowed_ccd_penetration.is_sign_positive() {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "allowed_ccd_penetration must be positive",
+                    ));
+                }
                 ffi::b3PhysicsParameterSetAllowedCcdPenetration(command, allowed_ccd_penetration);
             }
             if let Some(joint_feedback_mode) = options.joint_feedback_mode {
@@ -4874,12 +6334,18 @@ impl PhysicsClient {
                 }
             }
             if let Some(constraint_solver_type) = options.constraint_solver_type {
-                let val = constraint_solver_type as i32;
-                println!("{:?}", val);
-                ffi::b3PhysicsParameterSetConstraintSolverType(command, val);
+                ffi::b3PhysicsParameterSetConstraintSolverType(
+                    command,
+                    constraint_solver_type as i32,
+                );
             }
             if let Some(global_cfm) = options.global_cfm {
-                assert!(global_cfm.is_sign_positive());
+                if !global_cfm.is_sign_positive() {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "global_cfm must be positive",
+                    ));
+                }
                 ffi::b3PhysicsParamSetDefaultGlobalCFM(command, global_cfm);
             }
             if let Some(report_solver_analytics) = options.report_solver_analytics {
@@ -4893,23 +6359,39 @@ impl PhysicsClient {
                 }
             }
             if let Some(warm_starting_factor) = options.warm_starting_factor {
-                assert!(warm_starting_factor.is_sign_positive());
+                if !warm_starting_factor.is_sign_positive() {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "warm_starting_factor must be positive",
+                    ));
+                }
                 ffi::b3PhysicsParamSetWarmStartingFactor(command, warm_starting_factor);
             }
             if let Some(sparse_sdf_voxel_size) = options.sparse_sdf_voxel_size {
-                assert!(sparse_sdf_voxel_size.is_sign_positive());
+                if !sparse_sdf_voxel_size.is_sign_positive() {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "sparse_sdf_voxel_size must be positive",
+                    ));
+                }
                 ffi::b3PhysicsParameterSetSparseSdfVoxelSize(command, sparse_sdf_voxel_size);
             }
             if let Some(num_non_contact_inner_iterations) = options.num_non_contact_inner_iterations
             {
-                assert!(num_non_contact_inner_iterations >= 1);
+                if num_non_contact_inner_iterations < 1 {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "num_non_contact_inner_iterations must be at least 1",
+                    ));
+                }
                 ffi::b3PhysicsParamSetNumNonContactInnerIterations(
                     command,
                     num_non_contact_inner_iterations as i32,
                 );
             }
-            let _status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
+            let _status_handle = self.submit_command_and_wait_status(command);
         }
+        Ok(())
     }
     /// Get the current values of internal physics engine parameter.
     /// # Warning
@@ -4924,26 +6406,39 @@ impl PhysicsClient {
     pub fn get_physics_engine_parameters(&mut self) -> Result<PhysicsEngineParameters, Error> {
         unsafe {
             let command = ffi::b3InitRequestPhysicsParamCommand(self.handle);
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
+            let status_handle = self.submit_command_and_wait_status(command);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type != CMD_REQUEST_PHYSICS_SIMULATION_PARAMETERS_COMPLETED as i32 {
-                return Err(Error::new("Couldn't get physics simulation parameters."));
+                return Err(Error::command_failed(
+                    "get_physics_engine_parameters",
+                    status_type,
+                    "Couldn't get physics simulation parameters.",
+                ));
             }
             let mut params = b3PhysicsSimulationParameters::default();
             ffi::b3GetStatusPhysicsSimulationParameters(status_handle, &mut params);
-            Ok(params.into())
+            PhysicsEngineParameters::try_from(params)
         }
     }
     /// You can get the width and height (in pixels) of the camera, its view and projection matrix
     /// and more information using this command.
     /// Can be useful to calculate rays. See `add_planar_reflection.rs` example.
-    pub fn get_debug_visualizer_camera(&mut self) -> DebugVisualizerCameraInfo {
+    pub fn get_debug_visualizer_camera(&mut self) -> Result<DebugVisualizerCameraInfo, Error> {
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
         unsafe {
             let mut camera = b3OpenGLVisualizerCameraInfo::default();
             let command = ffi::b3InitRequestOpenGLVisualizerCameraCommand(self.handle);
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
-            let _has_cam_info = ffi::b3GetStatusOpenGLVisualizerCamera(status_handle, &mut camera);
-            camera.into()
+            let status_handle = self.submit_command_and_wait_status(command);
+            if ffi::b3GetStatusOpenGLVisualizerCamera(status_handle, &mut camera) == 0 {
+                return Err(Error::command_failed(
+                    "get_debug_visualizer_camera",
+                    ffi::b3GetStatusType(status_handle),
+                    "Could not get the debug visualizer camera",
+                ));
+            }
+            Ok(camera.into())
         }
     }
     /// You can reset the 3D OpenGL debug visualizer camera distance
@@ -4959,14 +6454,16 @@ impl PhysicsClient {
         camera_yaw: f32,
         camera_pitch: f32,
         camera_target_position: Vector,
-    ) {
+    ) -> Result<(), Error> {
+        if !camera_distance.is_sign_positive() {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                "camera_distance cannot be negative!",
+            ));
+        }
         let camera_target_position = camera_target_position.into();
         unsafe {
             let command_handle = ffi::b3InitConfigureOpenGLVisualizer(self.handle);
-            assert!(
-                camera_distance.is_sign_positive(),
-                "camera_distance cannot be negative!"
-            );
             ffi::b3ConfigureOpenGLVisualizerSetViewMatrix(
                 command_handle,
                 camera_distance,
@@ -4974,8 +6471,9 @@ impl PhysicsClient {
                 camera_yaw,
                 camera_target_position.as_ptr(),
             );
-            ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            self.submit_command_and_wait_status(command_handle);
         }
+        Ok(())
     }
     /// You can perform a single raycast to find the intersection information of the first object hit.
     /// # Arguments
@@ -5031,8 +6529,7 @@ impl PhysicsClient {
             if let Some(report_hit_number) = options.report_hit_number {
                 ffi::b3RaycastBatchSetReportHitNumber(command_handle, report_hit_number as i32);
             }
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type == CMD_REQUEST_RAY_CAST_INTERSECTIONS_COMPLETED as i32 {
                 let mut raycast_info = b3RaycastInformation::default();
@@ -5042,11 +6539,20 @@ impl PhysicsClient {
                     m_numRayHits,
                     m_rayHits,
                 } = raycast_info;
-                assert_eq!(m_numRayHits, 1);
+                if m_numRayHits != 1 || m_rayHits.is_null() {
+                    return Err(Error::of_kind(
+                        ErrorKind::ParseError,
+                        format!("ray_test expected exactly one hit but got {}", m_numRayHits),
+                    ));
+                }
                 let array = std::slice::from_raw_parts(m_rayHits, m_numRayHits as usize);
                 return Ok(RayHitInfo::new(array[0]));
             }
-            Err(Error::new("could not get ray info"))
+            Err(Error::command_failed(
+                "ray_test",
+                status_type,
+                "could not get ray info",
+            ))
         }
     }
     /// This is similar to the [`ray_test`](`Self::ray_test`), but allows you to provide an array
@@ -5088,25 +6594,35 @@ impl PhysicsClient {
         ray_to_positions: &[Vector3<f64>],
         options: Options,
     ) -> Result<Vec<Option<RayHitInfo>>, Error> {
-        assert_eq!(
-            ray_from_positions.len(),
-            ray_to_positions.len(),
-            "ray_from_positions and ray_to_positions must have the same length!"
-        );
-        assert!(
-            !ray_to_positions.is_empty(),
-            "ray_positions cannot be empty!"
-        );
-        assert!(
-            ray_to_positions.len() <= MAX_RAY_INTERSECTION_BATCH_SIZE_STREAMING,
-            "Number of rays exceed the maximum batch size of {}.",
-            MAX_RAY_INTERSECTION_BATCH_SIZE_STREAMING
-        );
+        if ray_from_positions.len() != ray_to_positions.len() {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                "ray_from_positions and ray_to_positions must have the same length!",
+            ));
+        }
+        if ray_to_positions.is_empty() {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                "ray_positions cannot be empty!",
+            ));
+        }
+        if ray_to_positions.len() > MAX_RAY_INTERSECTION_BATCH_SIZE_STREAMING {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Number of rays exceed the maximum batch size of {}.",
+                    MAX_RAY_INTERSECTION_BATCH_SIZE_STREAMING
+                ),
+            ));
+        }
 
         let options = options.into().unwrap_or_default();
+        if let Some(body) = options.parent_object_id {
+            self.generations.check(body)?;
+        }
         unsafe {
             let command_handle = ffi::b3CreateRaycastBatchCommandInit(self.handle);
-            let num_threads = match options.report_hit_number {
+            let num_threads = match options.num_threads {
                 None => -1,
                 Some(threads) => threads as i32,
             };
@@ -5132,11 +6648,15 @@ impl PhysicsClient {
             let collision_mask = options.collision_filter_mask.unwrap_or(-1);
             ffi::b3RaycastBatchSetCollisionFilterMask(command_handle, collision_mask);
             if let Some(fraction_epsilon) = options.fraction_epsilon {
-                assert!(fraction_epsilon >= 0., "fraction epsilon must be positive");
+                if fraction_epsilon < 0. {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "fraction epsilon must be positive",
+                    ));
+                }
                 ffi::b3RaycastBatchSetFractionEpsilon(command_handle, fraction_epsilon);
             }
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type == CMD_REQUEST_RAY_CAST_INTERSECTIONS_COMPLETED as i32 {
                 let mut ray_cast_info = b3RaycastInformation::default();
@@ -5146,6 +6666,9 @@ impl PhysicsClient {
                     m_numRayHits,
                     m_rayHits,
                 } = ray_cast_info;
+                if m_numRayHits <= 0 || m_rayHits.is_null() {
+                    return Ok(Vec::new());
+                }
                 let mut vec = Vec::<Option<RayHitInfo>>::with_capacity(m_numRayHits as usize);
 
                 let array = std::slice::from_raw_parts(m_rayHits, m_numRayHits as usize);
@@ -5155,7 +6678,11 @@ impl PhysicsClient {
 
                 return Ok(vec);
             }
-            Err(Error::new("could not get ray info"))
+            Err(Error::command_failed(
+                "ray_test_batch",
+                status_type,
+                "could not get ray info",
+            ))
         }
     }
     /// Each body is part of a group. It collides with other bodies if their group matches the mask, and vise versa.
@@ -5174,7 +6701,11 @@ impl PhysicsClient {
         link_index: Link,
         collision_filter_group: i32,
         collision_filter_mask: i32,
-    ) {
+    ) -> Result<(), Error> {
+        self.generations.check(body)?;
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
         let link_index = match link_index.into() {
             None => -1,
             Some(index) => index as i32,
@@ -5188,9 +6719,7 @@ impl PhysicsClient {
                 collision_filter_group,
                 collision_filter_mask,
             );
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
-            let _status_type = ffi::b3GetStatusType(status_handle);
+            self.submit_simple_command(command_handle)
         }
     }
     /// You can have more fine-grain control over collision detection between specific pairs of links.
@@ -5212,7 +6741,12 @@ impl PhysicsClient {
         link_index_a: LinkA,
         link_index_b: LinkB,
         enable_collision: bool,
-    ) {
+    ) -> Result<(), Error> {
+        self.generations.check(body_a)?;
+        self.generations.check(body_b)?;
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
         let link_index_a = match link_index_a.into() {
             None => -1,
             Some(index) => index as i32,
@@ -5231,9 +6765,7 @@ impl PhysicsClient {
                 link_index_b,
                 enable_collision as i32,
             );
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
-            let _status_type = ffi::b3GetStatusType(status_handle);
+            self.submit_simple_command(command_handle)
         }
     }
     /// lets you load a deformable object from a VTK or OBJ file.
@@ -5259,7 +6791,7 @@ impl PhysicsClient {
     ) -> Result<BodyId, Error> {
         unsafe {
             let file = CString::new(filename.as_ref().as_os_str().as_bytes())
-                .map_err(|_| Error::new("Invalid path"))?;
+                .map_err(|_| Error::of_kind(ErrorKind::InvalidArgument, "Invalid path"))?;
             let command = ffi::b3LoadSoftBodyCommandInit(self.handle, file.as_ptr());
             let options = options.into().unwrap_or_default();
             let pose = options.base_pose;
@@ -5278,19 +6810,34 @@ impl PhysicsClient {
             );
             if let Some(sim_filename) = options.sim_filename {
                 let sim_file = CString::new(sim_filename.as_os_str().as_bytes())
-                    .map_err(|_| Error::new("Invalid path"))?;
+                    .map_err(|_| Error::of_kind(ErrorKind::InvalidArgument, "Invalid path"))?;
                 ffi::b3LoadSoftBodyUpdateSimMesh(command, sim_file.as_ptr());
             }
             if let Some(scale) = options.scale {
-                assert!(scale > 0.);
+                if scale <= 0. {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "scale must be positive",
+                    ));
+                }
                 ffi::b3LoadSoftBodySetScale(command, scale);
             }
             if let Some(mass) = options.mass {
-                assert!(mass > 0.);
+                if mass <= 0. {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "mass must be positive",
+                    ));
+                }
                 ffi::b3LoadSoftBodySetMass(command, mass);
             }
             if let Some(collision_margin) = options.collision_margin {
-                assert!(collision_margin > 0.);
+                if collision_margin <= 0. {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "collision_margin must be positive",
+                    ));
+                }
                 ffi::b3LoadSoftBodySetCollisionMargin(command, collision_margin);
             }
             if options.use_mass_spring {
@@ -5321,19 +6868,33 @@ impl PhysicsClient {
                 ffi::b3LoadSoftBodySetSelfCollision(command, 1);
             }
             if let Some(repulsion_stiffness) = options.repulsion_stiffness {
-                assert!(repulsion_stiffness > 0.);
+                if repulsion_stiffness <= 0. {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        "repulsion_stiffness must be positive",
+                    ));
+                }
                 ffi::b3LoadSoftBodySetRepulsionStiffness(command, repulsion_stiffness);
             }
             ffi::b3LoadSoftBodySetFrictionCoefficient(command, options.friction_coeff);
 
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
+            let status_handle = self.submit_command_and_wait_status(command);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type != CMD_LOAD_SOFT_BODY_COMPLETED as i32 {
-                Err(Error::new("Cannot load soft body."))
+                Err(Error::command_failed(
+                    "load_soft_body",
+                    status_type,
+                    "Cannot load soft body.",
+                ))
             } else {
                 let id = ffi::b3GetStatusBodyIndex(status_handle);
-                assert!(id >= 0);
-                Ok(BodyId(id))
+                if id < 0 {
+                    return Err(Error::of_kind(
+                        ErrorKind::ParseError,
+                        "load_soft_body returned an invalid BodyId",
+                    ));
+                }
+                Ok(self.generations.create(id))
             }
         }
     }
@@ -5355,8 +6916,10 @@ impl PhysicsClient {
         link_index: Link,
         body_frame_position: Vector,
     ) -> Result<ConstraintId, Error> {
+        self.generations.check(soft_body_id)?;
         let body_frame_position = body_frame_position.into().unwrap_or_else(Vector3::zeros);
-        let body_id = body.into().unwrap_or(BodyId(-1));
+        let body_id = body.into().unwrap_or(BodyId::unchecked(-1));
+        self.generations.check(body_id)?;
         let link_index = match link_index.into() {
             None => -1,
             Some(index) => index as i32,
@@ -5370,27 +6933,49 @@ impl PhysicsClient {
                 link_index,
                 body_frame_position.as_ptr(),
             );
-            let status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type == CMD_USER_CONSTRAINT_COMPLETED as i32 {
-                return Ok(ConstraintId(ffi::b3GetStatusUserConstraintUniqueId(
-                    status_handle,
-                )));
-            }
-            Err(Error::new("Cannot load soft body."))
+                return Ok(self
+                    .generations
+                    .create(ffi::b3GetStatusUserConstraintUniqueId(status_handle)));
+            }
+            Err(Error::command_failed(
+                "create_soft_body_anchor",
+                status_type,
+                "Cannot load soft body.",
+            ))
         }
     }
     /// reset_simulation_with_flags does the same as [`reset_simulation`](`Self::reset_simulation`),
     /// but also lets you add some experimental flags. It can be useful if you want to create a world
     /// with soft body objects.
-    pub fn reset_simulation_with_flags(&mut self, flags: ResetFlags) {
+    pub fn reset_simulation_with_flags(&mut self, flags: ResetFlags) -> Result<(), Error> {
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
         unsafe {
             let command_handle = ffi::b3InitResetSimulationCommand(self.handle);
             ffi::b3InitResetSimulationSetFlags(command_handle, flags.bits());
-            let _status_handle =
-                ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command_handle);
+            let status_handle = self.submit_command_and_wait_status(command_handle);
+            let status_type = ffi::b3GetStatusType(status_handle);
+            if status_type != CMD_RESET_SIMULATION_COMPLETED as i32 {
+                return Err(Error::command_failed(
+                    "reset_simulation",
+                    status_type,
+                    "could not reset the simulation",
+                ));
+            }
         }
+        self.generations.reset_simulation();
+        self.assets.clear();
+        Ok(())
+    }
+    /// returns `true` if the client can be moved to another thread with
+    /// [`SendablePhysicsClient`](`crate::SendablePhysicsClient`). This is only the case for
+    /// clients in [`Direct`](`crate::Mode::Direct`) and [`Tcp`](`crate::Mode::Tcp`) mode.
+    pub fn is_sendable(&self) -> bool {
+        self.sendable
     }
     /// check whether the client is still connected. Most of the time the call blocks instead of returning false, though
     pub fn is_connected(&mut self) -> bool {
@@ -5402,14 +6987,532 @@ impl PhysicsClient {
     pub fn sync_body_info(&mut self) -> Result<(), Error> {
         unsafe {
             let command = ffi::b3InitSyncBodyInfoCommand(self.handle);
-            let status_handle = ffi::b3SubmitClientCommandAndWaitStatus(self.handle, command);
+            let status_handle = self.submit_command_and_wait_status(command);
             let status_type = ffi::b3GetStatusType(status_handle);
             if status_type != CMD_SYNC_BODY_INFO_COMPLETED as i32 {
-                return Err(Error::new("Error in sync_body_info command"));
+                return Err(Error::command_failed(
+                    "sync_body_info",
+                    status_type,
+                    "Error in sync_body_info command",
+                ));
+            }
+        }
+        Ok(())
+    }
+    /// sync_user_data will synchronize the user data ([`get_user_data`](`Self::get_user_data`))
+    /// in case of multiple clients connected to one physics server changing the user data
+    /// ([`add_user_data`](`Self::add_user_data`), [`remove_user_data`](`Self::remove_user_data`) etc).
+    /// # Arguments
+    /// * `bodies` - the bodies for which the user data should be synchronized.
+    ///   If it is empty, the user data of all bodies is synchronized.
+    pub fn sync_user_data(&mut self, bodies: &[BodyId]) -> Result<(), Error> {
+        for &body in bodies {
+            self.generations.check(body)?;
+        }
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
+        unsafe {
+            let command = ffi::b3InitSyncUserDataCommand(self.handle);
+            for body in bodies {
+                ffi::b3AddBodyToSyncUserDataRequest(command, body.0);
+            }
+            let status_handle = self.submit_command_and_wait_status(command);
+            let status_type = ffi::b3GetStatusType(status_handle);
+            if status_type != CMD_SYNC_USER_DATA_COMPLETED as i32 {
+                return Err(Error::command_failed(
+                    "sync_user_data",
+                    status_type,
+                    "Error in syncUserData command.",
+                ));
+            }
+        }
+        Ok(())
+    }
+    /// Adds or updates a user data entry of a body, link or visual shape. The user data is a
+    /// key-value pair which is stored on the physics server, so that it is also visible to other
+    /// clients connected to the same server. User data can also be specified in a URDF within a
+    /// `<user-data>` block.
+    ///
+    /// Returns the [`UserDataId`](`crate::types::UserDataId`) of the entry.
+    /// # Arguments
+    /// * `body` - the body to which the user data is attached
+    /// * `key` - the key which identifies the user data
+    /// * `value` - the value of the user data. Can be a string or binary data.
+    /// * `link_index` - the link to which the user data is attached or `None` for the base.
+    /// * `visual_shape_index` - the visual shape of the link to which the user data is attached or
+    ///   `None` if the user data is attached to the link itself.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use misfire::*;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut client = PhysicsClient::connect(Mode::Direct)?;
+    ///     client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
+    ///     let r2d2 = client.load_urdf("r2d2.urdf", None)?;
+    ///     let id = client.add_user_data(r2d2, "object_class", "robot", None, None)?;
+    ///     assert_eq!(client.get_user_data(id)?.as_str(), Some("robot"));
+    ///     assert_eq!(client.get_user_data_id(r2d2, "object_class", None, None)?, Some(id));
+    ///     assert_eq!(client.get_num_user_data(r2d2)?, 1);
+    ///     let info = client.get_user_data_info(r2d2, 0)?;
+    ///     assert_eq!(info.key, "object_class");
+    ///     client.remove_user_data(id)?;
+    ///     assert_eq!(client.get_num_user_data(r2d2)?, 0);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn add_user_data<
+        Value: Into<UserDataValue>,
+        Link: Into<Option<usize>>,
+        Visual: Into<Option<usize>>,
+    >(
+        &mut self,
+        body: BodyId,
+        key: &str,
+        value: Value,
+        link_index: Link,
+        visual_shape_index: Visual,
+    ) -> Result<UserDataId, Error> {
+        self.generations.check(body)?;
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
+        let link_index = link_index.into().map_or(-1, |index| index as i32);
+        let visual_shape_index = visual_shape_index.into().map_or(-1, |index| index as i32);
+        let key = CString::new(key).map_err(|_| {
+            Error::of_kind(
+                ErrorKind::InvalidArgument,
+                "key must not contain a null byte",
+            )
+        })?;
+        let (value_type, data) = match value.into() {
+            UserDataValue::String(value) => (
+                UserDataValueType::USER_DATA_VALUE_TYPE_STRING,
+                CString::new(value)
+                    .map_err(|_| {
+                        Error::of_kind(
+                            ErrorKind::InvalidArgument,
+                            "string value must not contain a null byte",
+                        )
+                    })?
+                    .into_bytes_with_nul(),
+            ),
+            UserDataValue::Bytes(value) => (UserDataValueType::USER_DATA_VALUE_TYPE_BYTES, value),
+        };
+        unsafe {
+            let command = ffi::b3InitAddUserDataCommand(
+                self.handle,
+                body.0,
+                link_index,
+                visual_shape_index,
+                key.as_ptr(),
+                value_type,
+                data.len() as i32,
+                data.as_ptr().cast(),
+            );
+            let status_handle = self.submit_command_and_wait_status(command);
+            let status_type = ffi::b3GetStatusType(status_handle);
+            if status_type != CMD_ADD_USER_DATA_COMPLETED as i32 {
+                return Err(Error::command_failed(
+                    "add_user_data",
+                    status_type,
+                    "Error in addUserData command.",
+                ));
+            }
+            Ok(UserDataId(ffi::b3GetUserDataIdFromStatus(status_handle)))
+        }
+    }
+    /// Removes a user data entry which was previously added with
+    /// [`add_user_data`](`Self::add_user_data`) or loaded from a URDF.
+    /// See [`add_user_data`](`Self::add_user_data`) for an example.
+    pub fn remove_user_data(&mut self, user_data_id: UserDataId) -> Result<(), Error> {
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
+        unsafe {
+            let command = ffi::b3InitRemoveUserDataCommand(self.handle, user_data_id.0);
+            let status_handle = self.submit_command_and_wait_status(command);
+            let status_type = ffi::b3GetStatusType(status_handle);
+            if status_type != CMD_REMOVE_USER_DATA_COMPLETED as i32 {
+                return Err(Error::command_failed(
+                    "remove_user_data",
+                    status_type,
+                    "Error in removeUserData command.",
+                ));
+            }
+        }
+        Ok(())
+    }
+    /// Returns the value of a user data entry.
+    /// See [`add_user_data`](`Self::add_user_data`) for an example.
+    pub fn get_user_data(&mut self, user_data_id: UserDataId) -> Result<UserDataValue, Error> {
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
+        unsafe {
+            let mut value = ffi::b3UserDataValue::default();
+            if ffi::b3GetUserData(self.handle, user_data_id.0, &mut value) == 0
+                || value.m_data1.is_null()
+                || value.m_length < 0
+            {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidArgument,
+                    "Cannot get user data",
+                ));
+            }
+            let data =
+                std::slice::from_raw_parts(value.m_data1.cast::<u8>(), value.m_length as usize);
+            if value.m_type == UserDataValueType::USER_DATA_VALUE_TYPE_STRING as i32 {
+                let data = data.strip_suffix(&[0]).unwrap_or(data);
+                Ok(UserDataValue::String(
+                    String::from_utf8_lossy(data).into_owned(),
+                ))
+            } else {
+                Ok(UserDataValue::Bytes(data.to_vec()))
+            }
+        }
+    }
+    /// Looks up the [`UserDataId`](`crate::types::UserDataId`) of a user data entry.
+    /// Returns `None` if there is no user data with the given key.
+    /// # Arguments
+    /// * `body` - the body to which the user data is attached
+    /// * `key` - the key which identifies the user data
+    /// * `link_index` - the link to which the user data is attached or `None` for the base.
+    /// * `visual_shape_index` - the visual shape of the link to which the user data is attached or
+    ///   `None` if the user data is attached to the link itself.
+    ///
+    /// See [`add_user_data`](`Self::add_user_data`) for an example.
+    pub fn get_user_data_id<Link: Into<Option<usize>>, Visual: Into<Option<usize>>>(
+        &mut self,
+        body: BodyId,
+        key: &str,
+        link_index: Link,
+        visual_shape_index: Visual,
+    ) -> Result<Option<UserDataId>, Error> {
+        self.generations.check(body)?;
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
+        let link_index = link_index.into().map_or(-1, |index| index as i32);
+        let visual_shape_index = visual_shape_index.into().map_or(-1, |index| index as i32);
+        let key = CString::new(key).map_err(|_| {
+            Error::of_kind(
+                ErrorKind::InvalidArgument,
+                "key must not contain a null byte",
+            )
+        })?;
+        unsafe {
+            let id = ffi::b3GetUserDataId(
+                self.handle,
+                body.0,
+                link_index,
+                visual_shape_index,
+                key.as_ptr(),
+            );
+            if id < 0 {
+                return Ok(None);
+            }
+            Ok(Some(UserDataId(id)))
+        }
+    }
+    /// Returns the number of user data entries of a body, including the entries of its links
+    /// and visual shapes.
+    /// See [`add_user_data`](`Self::add_user_data`) for an example.
+    pub fn get_num_user_data(&mut self, body: BodyId) -> Result<usize, Error> {
+        self.generations.check(body)?;
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
+        unsafe {
+            let num_user_data = ffi::b3GetNumUserData(self.handle, body.0);
+            if num_user_data < 0 {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidBodyId,
+                    "Cannot get number of user data",
+                ));
+            }
+            Ok(num_user_data as usize)
+        }
+    }
+    /// Returns information about where a user data entry is attached to.
+    /// # Arguments
+    /// * `body` - the body to which the user data is attached
+    /// * `user_data_index` - an index in the range \[0..[`get_num_user_data(body)`](`Self::get_num_user_data()`)\]
+    ///
+    /// See [`add_user_data`](`Self::add_user_data`) for an example.
+    pub fn get_user_data_info(
+        &mut self,
+        body: BodyId,
+        user_data_index: usize,
+    ) -> Result<UserDataInfo, Error> {
+        self.generations.check(body)?;
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
+        let mut key: *const c_char = ptr::null();
+        let mut user_data_id = -1;
+        let mut link_index = -1;
+        let mut visual_shape_index = -1;
+        unsafe {
+            ffi::b3GetUserDataInfo(
+                self.handle,
+                body.0,
+                user_data_index as i32,
+                &mut key,
+                &mut user_data_id,
+                &mut link_index,
+                &mut visual_shape_index,
+            );
+            if key.is_null() || user_data_id == -1 {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidArgument,
+                    "Could not get user data info.",
+                ));
+            }
+            Ok(UserDataInfo {
+                user_data_id: UserDataId(user_data_id),
+                key: CStr::from_ptr(key).to_string_lossy().into_owned(),
+                body,
+                link_index: usize::try_from(link_index).ok(),
+                visual_shape_index: usize::try_from(visual_shape_index).ok(),
+            })
+        }
+    }
+    /// Loads a plugin into the physics server and returns its [`PluginId`](`crate::types::PluginId`).
+    /// The plugins `pdControlPlugin`, `collisionFilterPlugin` and `tinyRendererPlugin` are
+    /// statically linked into the physics server and can be loaded by their name. Other plugins
+    /// are loaded from a shared library at the given path.
+    /// See the [`plugins`](`crate::plugins`) module for typed helpers for the built-in plugins.
+    /// # Arguments
+    /// * `plugin_path` - name of a built-in plugin or the path to a shared library
+    /// * `postfix` - the postfix of the plugin functions within the shared library. Plugins
+    ///   like `"eglRendererPlugin"` are compiled with the postfix `"_eglRendererPlugin"`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use misfire::*;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut client = PhysicsClient::connect(Mode::Direct)?;
+    ///     let plugin = client.load_plugin("pdControlPlugin", None)?;
+    ///     client.unload_plugin(plugin)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn load_plugin<P: AsRef<Path>>(
+        &mut self,
+        plugin_path: P,
+        postfix: Option<&str>,
+    ) -> Result<PluginId, Error> {
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
+        let plugin_path = CString::new(plugin_path.as_ref().as_os_str().as_bytes())
+            .map_err(|_| Error::of_kind(ErrorKind::InvalidArgument, "Invalid path"))?;
+        let postfix = postfix.map(CString::new).transpose().map_err(|_| {
+            Error::of_kind(
+                ErrorKind::InvalidArgument,
+                "postfix must not contain a null byte",
+            )
+        })?;
+        unsafe {
+            let command = ffi::b3CreateCustomCommand(self.handle);
+            ffi::b3CustomCommandLoadPlugin(command, plugin_path.as_ptr());
+            if let Some(postfix) = postfix {
+                ffi::b3CustomCommandLoadPluginSetPostFix(command, postfix.as_ptr());
+            }
+            let status_handle = self.submit_command_and_wait_status(command);
+            let status_type = ffi::b3GetStatusType(status_handle);
+            if status_type != CMD_CUSTOM_COMMAND_COMPLETED as i32 {
+                return Err(Error::command_failed(
+                    "load_plugin",
+                    status_type,
+                    "Cannot load plugin.",
+                ));
+            }
+            let plugin_id = ffi::b3GetStatusPluginUniqueId(status_handle);
+            if plugin_id < 0 {
+                return Err(Error::of_kind(
+                    ErrorKind::FileNotFound,
+                    "Cannot load plugin.",
+                ));
             }
+            Ok(PluginId(plugin_id))
+        }
+    }
+    /// Unloads a plugin which was loaded with [`load_plugin`](`Self::load_plugin`).
+    pub fn unload_plugin(&mut self, plugin: PluginId) -> Result<(), Error> {
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
+        unsafe {
+            let command = ffi::b3CreateCustomCommand(self.handle);
+            ffi::b3CustomCommandUnloadPlugin(command, plugin.0);
+            let _status_handle = self.submit_command_and_wait_status(command);
         }
         Ok(())
     }
+    /// Executes a command of a plugin and returns the result of the plugin.
+    /// The meaning of the arguments and the result depends on the plugin.
+    /// # Arguments
+    /// * `plugin` - the id of a plugin which was loaded with [`load_plugin`](`Self::load_plugin`)
+    /// * `text` - a text argument
+    /// * `ints` - a list of integer arguments
+    /// * `floats` - a list of float arguments
+    pub fn execute_plugin_command(
+        &mut self,
+        plugin: PluginId,
+        text: &str,
+        ints: &[i32],
+        floats: &[f32],
+    ) -> Result<i32, Error> {
+        if !self.can_submit_command() {
+            return Err(Error::disconnected());
+        }
+        if text.len() >= B3_MAX_PLUGIN_ARG_TEXT_LEN {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "text argument is too long. The maximum length is {}",
+                    B3_MAX_PLUGIN_ARG_TEXT_LEN - 1
+                ),
+            ));
+        }
+        if ints.len() > B3_MAX_PLUGIN_ARG_SIZE || floats.len() > B3_MAX_PLUGIN_ARG_SIZE {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "too many arguments. A plugin command can have at most {} int and {} float arguments",
+                    B3_MAX_PLUGIN_ARG_SIZE, B3_MAX_PLUGIN_ARG_SIZE
+                ),
+            ));
+        }
+        let text = CString::new(text).map_err(|_| {
+            Error::of_kind(
+                ErrorKind::InvalidArgument,
+                "text argument must not contain a null byte",
+            )
+        })?;
+        unsafe {
+            let command = ffi::b3CreateCustomCommand(self.handle);
+            ffi::b3CustomCommandExecutePluginCommand(command, plugin.0, text.as_ptr());
+            for &int in ints {
+                ffi::b3CustomCommandExecuteAddIntArgument(command, int);
+            }
+            for &float in floats {
+                ffi::b3CustomCommandExecuteAddFloatArgument(command, float);
+            }
+            let status_handle = self.submit_command_and_wait_status(command);
+            let status_type = ffi::b3GetStatusType(status_handle);
+            if status_type != CMD_CUSTOM_COMMAND_COMPLETED as i32 {
+                return Err(Error::command_failed(
+                    "execute_plugin_command",
+                    status_type,
+                    "Cannot execute plugin command.",
+                ));
+            }
+            Ok(ffi::b3GetStatusPluginCommandResult(status_handle))
+        }
+    }
+    /// captures the bodies which were loaded from files or created as primitives of a scene, their
+    /// state, the user constraints, the gravity and the time step in a
+    /// [`Scene`](`crate::scene::Scene`), which can be loaded again with
+    /// [`load_scene`](`Self::load_scene`). See the [`scene`](`crate::scene`) module for the
+    /// format and for what is not captured.
+    ///
+    /// Returns an error with [`ErrorKind::InvalidArgument`](`crate::ErrorKind::InvalidArgument`)
+    /// which names the bodies that a scene can not describe, e.g. bodies created with
+    /// [`create_multi_body`](`Self::create_multi_body`).
+    /// # Example
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use misfire::*;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut client = PhysicsClient::connect(Mode::Direct)?;
+    ///     client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
+    ///     client.set_gravity([0., 0., -10.])?;
+    ///     client.load_urdf("plane.urdf", None)?;
+    ///     client.load_urdf("r2d2.urdf", None)?;
+    ///     for _ in 0..100 {
+    ///         client.step_simulation()?;
+    ///     }
+    ///     let scene = client.save_scene()?;
+    ///
+    ///     let mut other = PhysicsClient::connect(Mode::Direct)?;
+    ///     other.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
+    ///     let bodies = other.load_scene(&scene)?;
+    ///     let r2d2 = bodies["physics"];
+    ///     println!("{}", other.get_base_transform(r2d2)?);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn save_scene(&mut self) -> Result<Scene, Error> {
+        crate::scene::save_scene(self)
+    }
+    /// instantiates a [`Scene`](`crate::scene::Scene`): loads its files, creates its primitives
+    /// and constraints, applies the materials and sets the state of the bodies and the physics
+    /// parameters. The scene is added to the current world, so call
+    /// [`reset_simulation`](`Self::reset_simulation`) first to get the same world.
+    ///
+    /// Returns the bodies and primitives of the scene by their name.
+    /// # Example
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use misfire::scene::{PrimitiveShape, Scene, SceneMaterial, ScenePrimitive};
+    /// use misfire::*;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut client = PhysicsClient::connect(Mode::Direct)?;
+    ///     let scene = Scene {
+    ///         gravity: [0., 0., -10.],
+    ///         materials: vec![SceneMaterial {
+    ///             name: "rubber".into(),
+    ///             color: Some([1., 0., 0., 1.]),
+    ///             restitution: Some(0.9),
+    ///             ..Default::default()
+    ///         }],
+    ///         primitives: vec![
+    ///             ScenePrimitive {
+    ///                 name: "ground".into(),
+    ///                 shape: PrimitiveShape::Plane { normal: [0., 0., 1.] },
+    ///                 mass: 0.,
+    ///                 pose: Default::default(),
+    ///                 velocity: [0.; 6],
+    ///                 material: None,
+    ///                 color: None,
+    ///             },
+    ///             ScenePrimitive {
+    ///                 name: "ball".into(),
+    ///                 shape: PrimitiveShape::Sphere { radius: 0.1 },
+    ///                 mass: 1.,
+    ///                 pose: nalgebra::Isometry3::translation(0., 0., 1.).into(),
+    ///                 velocity: [0.; 6],
+    ///                 material: Some("rubber".into()),
+    ///                 color: None,
+    ///             },
+    ///         ],
+    ///         ..Default::default()
+    ///     };
+    ///     let bodies = client.load_scene(&scene)?;
+    ///     for _ in 0..100 {
+    ///         client.step_simulation()?;
+    ///     }
+    ///     println!("{}", client.get_base_transform(bodies["ball"])?);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn load_scene(&mut self, scene: &Scene) -> Result<HashMap<String, BodyId>, Error> {
+        crate::scene::load_scene(self, scene)
+    }
+    /// takes ownership of a body, constraint, debug item, state logger or saved state, so that it
+    /// is released when the returned guard is dropped. See [`Owned`](`crate::Owned`).
+    pub fn own<R: Resource>(&mut self, resource: R) -> Owned<'_, R> {
+        Owned::new(self, resource)
+    }
     /// closes the PhysicsClient.
     pub fn disconnect(self) {}
 
@@ -5431,23 +7534,134 @@ impl PhysicsClient {
         unsafe {
             let id = ffi::b3GetBodyUniqueId(self.handle, index as i32);
             if id >= 0 {
-                Ok(BodyId(id))
+                Ok(self.generations.existing(id))
             } else {
-                Err(Error::new("could not get body id"))
+                Err(Error::of_kind(
+                    ErrorKind::InvalidArgument,
+                    "could not get body id",
+                ))
             }
         }
     }
 }
 
+/// Checks the answer to a `b3InitStepSimulationCommand` command.
+pub(crate) unsafe fn check_step_simulation_status(
+    status_handle: b3SharedMemoryStatusHandle,
+) -> Result<(), Error> {
+    let status_type = ffi::b3GetStatusType(status_handle);
+    if status_type
+        != ffi::EnumSharedMemoryServerStatus::CMD_STEP_FORWARD_SIMULATION_COMPLETED as i32
+    {
+        return Err(Error::command_failed(
+            "step_simulation",
+            status_type,
+            "Failed to perform forward step",
+        ));
+    }
+    Ok(())
+}
+
+/// Reads the base transform from the answer to a `b3RequestActualStateCommandInit` command.
+pub(crate) unsafe fn base_transform_from_status(
+    status_handle: b3SharedMemoryStatusHandle,
+) -> Result<Isometry3<f64>, Error> {
+    let status_type = ffi::b3GetStatusType(status_handle);
+    if status_type != ffi::EnumSharedMemoryServerStatus::CMD_ACTUAL_STATE_UPDATE_COMPLETED as c_int
+    {
+        return Err(Error::command_failed(
+            "get_base_transform",
+            status_type,
+            "Failed to get base transform",
+        ));
+    }
+
+    // To be totally honest, I'm not sure this part is correct.
+    let mut actual_state_q: *const f64 = ptr::null();
+    ffi::b3GetStatusActualState(
+        status_handle,
+        ptr::null_mut(),
+        ptr::null_mut(),
+        ptr::null_mut(),
+        ptr::null_mut(),
+        &mut actual_state_q,
+        ptr::null_mut(),
+        ptr::null_mut(),
+    );
+
+    if actual_state_q.is_null() {
+        return Err(Error::of_kind(
+            ErrorKind::InvalidBodyId,
+            "Failed to get base transform",
+        ));
+    }
+
+    let tx = *actual_state_q;
+    let ty = *(actual_state_q.offset(1));
+    let tz = *(actual_state_q.offset(2));
+
+    let rx = *(actual_state_q.offset(3));
+    let ry = *(actual_state_q.offset(4));
+    let rz = *(actual_state_q.offset(5));
+    let rw = *(actual_state_q.offset(6));
+
+    let tra = Translation3::new(tx, ty, tz);
+    let rot = Quaternion::new(rw, rx, ry, rz);
+
+    Ok(Isometry3::from_parts(
+        tra,
+        UnitQuaternion::from_quaternion(rot),
+    ))
+}
+
+/// copies a buffer of the renderer into an image and reuses the memory of the image.
+fn refill_image<P: Pixel>(
+    image: &mut ImageBuffer<P, Vec<P::Subpixel>>,
+    width: usize,
+    height: usize,
+    data: &[P::Subpixel],
+) -> Result<(), Error> {
+    let mut buffer = std::mem::take(image).into_raw();
+    buffer.clear();
+    buffer.extend_from_slice(data);
+    *image = ImageBuffer::from_raw(width as u32, height as u32, buffer)
+        .ok_or_else(|| Error::of_kind(ErrorKind::ParseError, "Invalid camera image size"))?;
+    Ok(())
+}
+
+/// Checks that `values` contains one value for each of the `expected` degrees of freedom of a joint.
+fn check_dof_count(
+    name: &str,
+    joint_index: usize,
+    values: &[f64],
+    expected: i32,
+) -> Result<(), Error> {
+    if values.len() != expected as usize {
+        return Err(Error::of_kind(
+            ErrorKind::InvalidArgument,
+            format!(
+                "{} of joint {} has {} values, but the joint expects {}",
+                name,
+                joint_index,
+                values.len(),
+                expected
+            ),
+        ));
+    }
+    Ok(())
+}
+
 impl Drop for PhysicsClient {
     fn drop(&mut self) {
         unsafe { ffi::b3DisconnectSharedMemory(self.handle) }
     }
 }
 
-/// Module used to enforce the existence of only a single GUI and a single SharedMemory instance per process.
+/// Module used to enforce the existence of only a single GUI and a single SharedMemory instance
+/// per shared memory key and process.
 pub(crate) mod marker {
     use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Mutex, PoisonError};
 
     /// A marker for whether or not a GUI has been started.
     ///
@@ -5462,12 +7676,14 @@ pub(crate) mod marker {
 
     impl GuiMarker {
         /// Attempts to acquire the GUI marker.
+        #[cfg(feature = "gui")]
         pub fn acquire() -> Result<GuiMarker, crate::Error> {
             // We can probably use a weaker ordering but this will be called so little that we
             // may as well be sure about it.
             match GUI_EXISTS.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst) {
                 Ok(false) => Ok(GuiMarker { _unused: () }),
-                _ => Err(crate::Error::new(
+                _ => Err(crate::Error::of_kind(
+                    crate::ErrorKind::GuiUnavailable,
                     "Only one in-process GUI connection allowed",
                 )),
             }
@@ -5481,36 +7697,41 @@ pub(crate) mod marker {
         }
     }
 
-    static SHARED_MEMORY_EXISTS: AtomicBool = AtomicBool::new(false);
+    /// The keys of the shared memory segments which are in use by a client of this process.
+    static SHARED_MEMORY_KEYS: Mutex<Vec<i32>> = Mutex::new(Vec::new());
 
-    /// A marker type for keeping track of the existence of a SharedMemory instance.
+    /// A marker type for keeping track of the existence of a SharedMemory instance per shared
+    /// memory segment.
     pub struct SharedMemoryMarker {
-        _unused: (),
+        key: i32,
     }
 
     impl SharedMemoryMarker {
-        /// Attempts to acquire the GUI marker.
-        pub fn acquire() -> Result<SharedMemoryMarker, crate::Error> {
-            // We can probably use a weaker ordering but this will be called so little that we
-            // may as well be sure about it.
-            match SHARED_MEMORY_EXISTS.compare_exchange(
-                false,
-                true,
-                Ordering::SeqCst,
-                Ordering::SeqCst,
-            ) {
-                Ok(false) => Ok(SharedMemoryMarker { _unused: () }),
-                _ => Err(crate::Error::new(
-                    "Only one in-process SharedMemory connection allowed",
-                )),
+        /// Attempts to acquire the marker of the shared memory segment with the given key.
+        pub fn acquire(key: i32) -> Result<SharedMemoryMarker, crate::Error> {
+            let mut keys = SHARED_MEMORY_KEYS
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            if keys.contains(&key) {
+                return Err(crate::Error::of_kind(
+                    crate::ErrorKind::SharedMemoryUnavailable,
+                    format!(
+                        "Only one in-process SharedMemory connection allowed per key ({})",
+                        key
+                    ),
+                ));
             }
+            keys.push(key);
+            Ok(SharedMemoryMarker { key })
         }
     }
 
     impl Drop for SharedMemoryMarker {
         fn drop(&mut self) {
-            // We are the only marker so no need to CAS
-            SHARED_MEMORY_EXISTS.store(false, Ordering::SeqCst)
+            SHARED_MEMORY_KEYS
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .retain(|key| *key != self.key);
         }
     }
 }
@@ -0,0 +1,856 @@
+//! Gym-style environments for reinforcement learning.
+//!
+//! An [`Environment`] owns a simulation and exposes it through [`reset`](`Environment::reset`) and
+//! [`step`](`Environment::step`), together with [`Space`] descriptors of its observations and
+//! actions. The behavior of an environment can be changed with wrappers like [`TimeLimit`] and
+//! [`FrameSkip`].
+//!
+//! The module comes with a few reference tasks, which are ported from the examples of this crate:
+//! * [`KukaCubeGrasp`] - move the gripper of a Kuka iiwa above a cube
+//! * [`PandaReach`] - move the hand of a Franka Emika Panda to a target position
+//! * [`MinitaurLocomotion`] - make the Minitaur quadruped walk forward
+//!
+//! # Example
+//! ```no_run
+//! use anyhow::Result;
+//! use misfire::env::{Environment, FrameSkip, KukaCubeGrasp, TimeLimit};
+//! use misfire::*;
+//!
+//! fn main() -> Result<()> {
+//!     let mut client = PhysicsClient::connect(Mode::Direct)?;
+//!     client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
+//!     let mut env = TimeLimit::new(FrameSkip::new(KukaCubeGrasp::new(client)?, 4), 200);
+//!     let mut observation = env.reset(Some(42))?;
+//!     loop {
+//!         // a very simple policy: move the gripper towards the cube
+//!         let action = [
+//!             observation[17] - observation[14],
+//!             observation[18] - observation[15],
+//!             observation[19] + 0.1 - observation[16],
+//!         ];
+//!         let step = env.step(&action[..])?;
+//!         if step.done() {
+//!             break;
+//!         }
+//!         observation = step.observation;
+//!     }
+//!     Ok(())
+//! }
+//! ```
+use std::f64::consts::{FRAC_PI_2, PI};
+use std::time::Duration;
+
+use nalgebra::{Isometry3, Translation3, UnitQuaternion, Vector3};
+
+use crate::rng::Rng;
+use crate::{
+    BodyId, ChangeConstraintOptions, ControlCommand, ControlCommandArray, Error, ErrorKind,
+    InverseKinematicsParametersBuilder, JointType, PhysicsClient, StateId, UrdfOptions,
+};
+
+/// Describes the set of valid observations or actions of an [`Environment`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Space {
+    /// real-valued vectors where every element lies within its bounds (inclusive).
+    /// The bounds may be infinite.
+    Box {
+        /// lower bound of each element
+        low: Vec<f64>,
+        /// upper bound of each element
+        high: Vec<f64>,
+    },
+    /// a single integer in the range `0..n`
+    Discrete(usize),
+}
+
+impl Space {
+    /// creates a [`Box`](`Space::Box`) space of dimension `dim` where all elements have the same
+    /// bounds.
+    pub fn bounded(dim: usize, low: f64, high: f64) -> Space {
+        Space::Box {
+            low: vec![low; dim],
+            high: vec![high; dim],
+        }
+    }
+    /// returns the number of values of an element of the space.
+    pub fn dim(&self) -> usize {
+        match self {
+            Space::Box { low, .. } => low.len(),
+            Space::Discrete(_) => 1,
+        }
+    }
+    /// checks whether `values` is an element of the space.
+    pub fn contains(&self, values: &[f64]) -> bool {
+        match self {
+            Space::Box { low, high } => {
+                values.len() == low.len()
+                    && values
+                        .iter()
+                        .zip(low.iter().zip(high))
+                        .all(|(value, (low, high))| low <= value && value <= high)
+            }
+            Space::Discrete(n) => {
+                values.len() == 1
+                    && values[0].fract() == 0.
+                    && values[0] >= 0.
+                    && values[0] < *n as f64
+            }
+        }
+    }
+}
+
+/// The result of [`Environment::step`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Step<O> {
+    /// observation after the action was applied
+    pub observation: O,
+    /// reward for the action
+    pub reward: f64,
+    /// `true` if the task reached a terminal state, e.g. because it was solved or failed
+    pub terminated: bool,
+    /// `true` if the episode was cut off for other reasons, e.g. by a [`TimeLimit`]
+    pub truncated: bool,
+}
+
+impl<O> Step<O> {
+    /// returns `true` if the episode is over and the environment has to be reset.
+    pub fn done(&self) -> bool {
+        self.terminated || self.truncated
+    }
+}
+
+/// A task which an agent can interact with, in the spirit of OpenAI Gym.
+///
+/// An episode starts with [`reset`](`Self::reset`). Afterwards [`step`](`Self::step`) is called
+/// until it reports that the episode is [`done`](`Step::done`).
+pub trait Environment {
+    /// the type of the observations
+    type Observation;
+    /// the type of the actions
+    type Action: ?Sized;
+    /// describes the observations which are returned by the environment.
+    fn observation_space(&self) -> Space;
+    /// describes the actions which are accepted by the environment.
+    fn action_space(&self) -> Space;
+    /// starts a new episode and returns the first observation.
+    ///
+    /// With `Some(seed)` the random number generator of the environment is seeded, so that the
+    /// episode is reproducible. With `None` the generator just continues.
+    fn reset(&mut self, seed: Option<u64>) -> Result<Self::Observation, Error>;
+    /// applies an action and advances the environment.
+    fn step(&mut self, action: &Self::Action) -> Result<Step<Self::Observation>, Error>;
+}
+
+/// Truncates episodes after a maximum number of steps.
+#[derive(Debug)]
+pub struct TimeLimit<E> {
+    env: E,
+    max_steps: usize,
+    elapsed_steps: usize,
+}
+
+impl<E> TimeLimit<E> {
+    /// wraps `env` so that every episode is truncated after `max_steps` steps.
+    pub fn new(env: E, max_steps: usize) -> TimeLimit<E> {
+        TimeLimit {
+            env,
+            max_steps,
+            elapsed_steps: 0,
+        }
+    }
+    /// returns the number of steps since the last reset.
+    pub fn elapsed_steps(&self) -> usize {
+        self.elapsed_steps
+    }
+    /// returns the wrapped environment.
+    pub fn inner(&self) -> &E {
+        &self.env
+    }
+    /// returns the wrapped environment.
+    pub fn inner_mut(&mut self) -> &mut E {
+        &mut self.env
+    }
+    /// removes the wrapper.
+    pub fn into_inner(self) -> E {
+        self.env
+    }
+}
+
+impl<E: Environment> Environment for TimeLimit<E> {
+    type Observation = E::Observation;
+    type Action = E::Action;
+
+    fn observation_space(&self) -> Space {
+        self.env.observation_space()
+    }
+    fn action_space(&self) -> Space {
+        self.env.action_space()
+    }
+    fn reset(&mut self, seed: Option<u64>) -> Result<Self::Observation, Error> {
+        self.elapsed_steps = 0;
+        self.env.reset(seed)
+    }
+    fn step(&mut self, action: &Self::Action) -> Result<Step<Self::Observation>, Error> {
+        let mut step = self.env.step(action)?;
+        self.elapsed_steps += 1;
+        if self.elapsed_steps >= self.max_steps {
+            step.truncated = true;
+        }
+        Ok(step)
+    }
+}
+
+/// Repeats every action for a fixed number of steps of the wrapped environment and sums up the
+/// rewards. The repetition stops early if the episode is over.
+#[derive(Debug)]
+pub struct FrameSkip<E> {
+    env: E,
+    frames: usize,
+}
+
+impl<E> FrameSkip<E> {
+    /// wraps `env` so that every action is applied `frames` times.
+    /// # Panics
+    /// Panics if `frames` is 0.
+    pub fn new(env: E, frames: usize) -> FrameSkip<E> {
+        assert!(
+            frames > 0,
+            "FrameSkip needs to apply an action at least once"
+        );
+        FrameSkip { env, frames }
+    }
+    /// returns the wrapped environment.
+    pub fn inner(&self) -> &E {
+        &self.env
+    }
+    /// returns the wrapped environment.
+    pub fn inner_mut(&mut self) -> &mut E {
+        &mut self.env
+    }
+    /// removes the wrapper.
+    pub fn into_inner(self) -> E {
+        self.env
+    }
+}
+
+impl<E: Environment> Environment for FrameSkip<E> {
+    type Observation = E::Observation;
+    type Action = E::Action;
+
+    fn observation_space(&self) -> Space {
+        self.env.observation_space()
+    }
+    fn action_space(&self) -> Space {
+        self.env.action_space()
+    }
+    fn reset(&mut self, seed: Option<u64>) -> Result<Self::Observation, Error> {
+        self.env.reset(seed)
+    }
+    fn step(&mut self, action: &Self::Action) -> Result<Step<Self::Observation>, Error> {
+        let mut total_reward = 0.;
+        let mut frame = 0;
+        loop {
+            let mut step = self.env.step(action)?;
+            total_reward += step.reward;
+            frame += 1;
+            if frame == self.frames || step.done() {
+                step.reward = total_reward;
+                return Ok(step);
+            }
+        }
+    }
+}
+
+/// All reference tasks run with this time step.
+const TIME_STEP: f64 = 1. / 240.;
+
+/// checks the length of an action and clips its values to \[-1, 1\].
+fn normalize_action(task: &str, action: &[f64], expected: usize) -> Result<Vec<f64>, Error> {
+    if action.len() != expected {
+        return Err(Error::of_kind(
+            ErrorKind::InvalidArgument,
+            format!(
+                "{} expects actions with {} values, but got {}",
+                task,
+                expected,
+                action.len()
+            ),
+        ));
+    }
+    Ok(action.iter().map(|value| value.clamp(-1., 1.)).collect())
+}
+
+/// appends the positions and then the velocities of the given joints to `observation`.
+fn push_joint_states(
+    client: &mut PhysicsClient,
+    body: BodyId,
+    joint_indices: &[usize],
+    observation: &mut Vec<f64>,
+) -> Result<(), Error> {
+    let states = client.get_joint_states(body, joint_indices)?;
+    observation.extend(states.iter().map(|state| state.joint_position));
+    observation.extend(states.iter().map(|state| state.joint_velocity));
+    Ok(())
+}
+
+/// The Kuka iiwa from `kuka_with_cube.rs` has to move its gripper into a grasping position above
+/// a small cube, which is placed randomly on the floor at every reset.
+///
+/// The robot is controlled in Cartesian space: an action are 3 values in \[-1, 1\] which move the
+/// target position of the end effector by up to [`MAX_DISPLACEMENT`](`Self::MAX_DISPLACEMENT`)
+/// along x, y and z. The joint positions for the target are computed with inverse kinematics,
+/// while the gripper keeps pointing down.
+///
+/// An observation consists of 20 values:
+/// * the 7 joint positions
+/// * the 7 joint velocities
+/// * the position of the end effector (x,y,z)
+/// * the position of the cube (x,y,z)
+///
+/// The reward is the negative distance between the end effector and the grasping position.
+/// The episode terminates once the distance is below
+/// [`SUCCESS_DISTANCE`](`Self::SUCCESS_DISTANCE`).
+///
+/// The environment needs the `data` directory of bullet3 in the search path of the client.
+pub struct KukaCubeGrasp {
+    client: PhysicsClient,
+    kuka: BodyId,
+    cube: BodyId,
+    initial_state: StateId,
+    target_position: Vector3<f64>,
+    rng: Rng,
+}
+
+impl KukaCubeGrasp {
+    /// index of the end effector link
+    pub const END_EFFECTOR_INDEX: usize = 6;
+    /// maximum distance in meters which the end effector target moves per step
+    pub const MAX_DISPLACEMENT: f64 = 0.01;
+    /// the task is solved when the end effector is closer than this to the grasping position
+    pub const SUCCESS_DISTANCE: f64 = 0.03;
+    /// height of the grasping position above the center of the cube
+    pub const GRASP_HEIGHT: f64 = 0.1;
+    const JOINT_INDICES: [usize; 7] = [0, 1, 2, 3, 4, 5, 6];
+    const REST_POSES: [f64; 7] = [0., 0., 0., FRAC_PI_2, 0., -FRAC_PI_2 * 0.66, 0.];
+    const JOINT_DAMPING: [f64; 7] = [0.1; 7];
+
+    /// loads the scene into `client`.
+    pub fn new(mut client: PhysicsClient) -> Result<KukaCubeGrasp, Error> {
+        client.reset_simulation()?;
+        client.set_time_step(Duration::from_secs_f64(TIME_STEP))?;
+        client.set_gravity([0., 0., -10.])?;
+        client.load_urdf(
+            "plane.urdf",
+            UrdfOptions {
+                use_fixed_base: true,
+                ..Default::default()
+            },
+        )?;
+        let kuka = client.load_urdf(
+            "kuka_iiwa/model.urdf",
+            UrdfOptions {
+                use_fixed_base: true,
+                ..Default::default()
+            },
+        )?;
+        for (&joint, &position) in Self::JOINT_INDICES.iter().zip(&Self::REST_POSES) {
+            client.reset_joint_state(kuka, joint, position, None)?;
+        }
+        let cube = client.load_urdf(
+            "cube_small.urdf",
+            UrdfOptions {
+                base_transform: Isometry3::translation(0.55, 0., 0.025),
+                ..Default::default()
+            },
+        )?;
+        let initial_state = client.save_state()?;
+        Ok(KukaCubeGrasp {
+            client,
+            kuka,
+            cube,
+            initial_state,
+            target_position: Vector3::zeros(),
+            rng: Rng::from_time(),
+        })
+    }
+    /// gives access to the underlying client, e.g. for rendering.
+    pub fn client(&mut self) -> &mut PhysicsClient {
+        &mut self.client
+    }
+    /// returns the [`BodyId`](`crate::BodyId`) of the robot.
+    pub fn robot(&self) -> BodyId {
+        self.kuka
+    }
+    /// returns the [`BodyId`](`crate::BodyId`) of the cube.
+    pub fn cube(&self) -> BodyId {
+        self.cube
+    }
+    fn end_effector_position(&mut self) -> Result<Vector3<f64>, Error> {
+        let state = self
+            .client
+            .get_link_state(self.kuka, Self::END_EFFECTOR_INDEX, false, true)?;
+        Ok(state.world_link_frame_pose.translation.vector)
+    }
+    fn grasp_position(&mut self) -> Result<Vector3<f64>, Error> {
+        let cube = self.client.get_base_transform(self.cube)?;
+        Ok(cube.translation.vector + Vector3::new(0., 0., Self::GRASP_HEIGHT))
+    }
+    fn observe(&mut self) -> Result<Vec<f64>, Error> {
+        let mut observation = Vec::with_capacity(20);
+        push_joint_states(
+            &mut self.client,
+            self.kuka,
+            &Self::JOINT_INDICES,
+            &mut observation,
+        )?;
+        observation.extend(self.end_effector_position()?.iter());
+        let cube = self.client.get_base_transform(self.cube)?;
+        observation.extend(cube.translation.vector.iter());
+        Ok(observation)
+    }
+}
+
+impl Environment for KukaCubeGrasp {
+    type Observation = Vec<f64>;
+    type Action = [f64];
+
+    fn observation_space(&self) -> Space {
+        Space::bounded(20, f64::NEG_INFINITY, f64::INFINITY)
+    }
+    fn action_space(&self) -> Space {
+        Space::bounded(3, -1., 1.)
+    }
+    fn reset(&mut self, seed: Option<u64>) -> Result<Vec<f64>, Error> {
+        if let Some(seed) = seed {
+            self.rng = Rng::new(seed);
+        }
+        self.client.restore_state(self.initial_state)?;
+        let x = self.rng.uniform(0.45, 0.65);
+        let y = self.rng.uniform(-0.2, 0.2);
+        let yaw = self.rng.uniform(-PI, PI);
+        self.client.reset_base_transform(
+            self.cube,
+            Isometry3::from_parts(
+                Translation3::new(x, y, 0.025),
+                UnitQuaternion::from_euler_angles(0., 0., yaw),
+            ),
+        )?;
+        self.target_position = self.end_effector_position()?;
+        self.observe()
+    }
+    fn step(&mut self, action: &[f64]) -> Result<Step<Vec<f64>>, Error> {
+        let action = normalize_action("KukaCubeGrasp", action, 3)?;
+        self.target_position += Vector3::from_column_slice(&action) * Self::MAX_DISPLACEMENT;
+        self.target_position.z = self.target_position.z.max(0.);
+        let target_pose = Isometry3::from_parts(
+            self.target_position.into(),
+            UnitQuaternion::from_euler_angles(0., -PI, 0.),
+        );
+        let params =
+            InverseKinematicsParametersBuilder::new(Self::END_EFFECTOR_INDEX, &target_pose)
+                .set_joint_damping(&Self::JOINT_DAMPING)
+                .build();
+        let joint_poses = self
+            .client
+            .calculate_inverse_kinematics(self.kuka, params)?;
+        self.client.set_joint_motor_control_array(
+            self.kuka,
+            &Self::JOINT_INDICES,
+            ControlCommandArray::PositionsWithPd {
+                target_positions: &joint_poses,
+                target_velocities: &[0.; 7],
+                position_gains: &[0.03; 7],
+                velocity_gains: &[1.; 7],
+            },
+            Some(&[500.; 7]),
+        )?;
+        self.client.step_simulation()?;
+        let distance = (self.grasp_position()? - self.end_effector_position()?).norm();
+        Ok(Step {
+            observation: self.observe()?,
+            reward: -distance,
+            terminated: distance < Self::SUCCESS_DISTANCE,
+            truncated: false,
+        })
+    }
+}
+
+/// The Franka Emika Panda from `panda_demo.rs` has to move its hand to a random target position.
+///
+/// An action are 7 values in \[-1, 1\] which move the target positions of the arm joints by up to
+/// [`MAX_JOINT_DISPLACEMENT`](`Self::MAX_JOINT_DISPLACEMENT`) radians. The fingers stay open.
+///
+/// An observation consists of 20 values:
+/// * the 7 arm joint positions
+/// * the 7 arm joint velocities
+/// * the position of the hand (x,y,z)
+/// * the target position (x,y,z)
+///
+/// The reward is the negative distance between the hand and the target. The episode terminates
+/// once the distance is below [`SUCCESS_DISTANCE`](`Self::SUCCESS_DISTANCE`).
+///
+/// The environment needs the `pybullet_data` directory of bullet3 in the search path of the client.
+pub struct PandaReach {
+    client: PhysicsClient,
+    panda: BodyId,
+    initial_state: StateId,
+    joint_targets: [f64; 7],
+    target: Vector3<f64>,
+    rng: Rng,
+}
+
+impl PandaReach {
+    /// index of the end effector link
+    pub const END_EFFECTOR_INDEX: usize = 11;
+    /// maximum change of a joint target in radians per step
+    pub const MAX_JOINT_DISPLACEMENT: f64 = 0.05;
+    /// the task is solved when the hand is closer than this to the target
+    pub const SUCCESS_DISTANCE: f64 = 0.05;
+    const ARM_JOINT_INDICES: [usize; 7] = [0, 1, 2, 3, 4, 5, 6];
+    const INITIAL_JOINT_POSITIONS: [f64; 9] =
+        [0.98, 0.458, 0.31, -2.24, -0.30, 2.66, 2.32, 0.02, 0.02];
+    const MAXIMUM_FORCE: f64 = 240. * 5.;
+
+    /// loads the scene into `client`.
+    pub fn new(mut client: PhysicsClient) -> Result<PandaReach, Error> {
+        client.reset_simulation()?;
+        client.set_time_step(Duration::from_secs_f64(TIME_STEP))?;
+        client.set_gravity([0., 0., -9.8])?;
+        client.load_urdf(
+            "plane.urdf",
+            UrdfOptions {
+                use_fixed_base: true,
+                ..Default::default()
+            },
+        )?;
+        let panda = client.load_urdf(
+            "franka_panda/panda.urdf",
+            UrdfOptions {
+                use_fixed_base: true,
+                ..Default::default()
+            },
+        )?;
+        let mut index = 0;
+        for joint in 0..client.get_num_joints(panda)? {
+            let info = client.get_joint_info(panda, joint)?;
+            if info.joint_type == JointType::Revolute || info.joint_type == JointType::Prismatic {
+                let position = *Self::INITIAL_JOINT_POSITIONS.get(index).ok_or_else(|| {
+                    Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "PandaReach expects a model with {} movable joints",
+                            Self::INITIAL_JOINT_POSITIONS.len()
+                        ),
+                    )
+                })?;
+                client.reset_joint_state(panda, joint, position, None)?;
+                if info.joint_type == JointType::Prismatic {
+                    // keeps the fingers open
+                    client.set_joint_motor_control(
+                        panda,
+                        joint,
+                        ControlCommand::Position(position),
+                        Some(Self::MAXIMUM_FORCE),
+                    )?;
+                }
+                index += 1;
+            }
+        }
+        let initial_state = client.save_state()?;
+        Ok(PandaReach {
+            client,
+            panda,
+            initial_state,
+            joint_targets: [0.; 7],
+            target: Vector3::zeros(),
+            rng: Rng::from_time(),
+        })
+    }
+    /// gives access to the underlying client, e.g. for rendering.
+    pub fn client(&mut self) -> &mut PhysicsClient {
+        &mut self.client
+    }
+    /// returns the [`BodyId`](`crate::BodyId`) of the robot.
+    pub fn robot(&self) -> BodyId {
+        self.panda
+    }
+    /// returns the target position of the current episode.
+    pub fn target(&self) -> Vector3<f64> {
+        self.target
+    }
+    fn hand_position(&mut self) -> Result<Vector3<f64>, Error> {
+        let state =
+            self.client
+                .get_link_state(self.panda, Self::END_EFFECTOR_INDEX, false, true)?;
+        Ok(state.world_link_frame_pose.translation.vector)
+    }
+    fn observe(&mut self) -> Result<Vec<f64>, Error> {
+        let mut observation = Vec::with_capacity(20);
+        push_joint_states(
+            &mut self.client,
+            self.panda,
+            &Self::ARM_JOINT_INDICES,
+            &mut observation,
+        )?;
+        observation.extend(self.hand_position()?.iter());
+        observation.extend(self.target.iter());
+        Ok(observation)
+    }
+}
+
+impl Environment for PandaReach {
+    type Observation = Vec<f64>;
+    type Action = [f64];
+
+    fn observation_space(&self) -> Space {
+        Space::bounded(20, f64::NEG_INFINITY, f64::INFINITY)
+    }
+    fn action_space(&self) -> Space {
+        Space::bounded(7, -1., 1.)
+    }
+    fn reset(&mut self, seed: Option<u64>) -> Result<Vec<f64>, Error> {
+        if let Some(seed) = seed {
+            self.rng = Rng::new(seed);
+        }
+        self.client.restore_state(self.initial_state)?;
+        self.joint_targets
+            .copy_from_slice(&Self::INITIAL_JOINT_POSITIONS[..7]);
+        self.target = Vector3::new(
+            self.rng.uniform(0.3, 0.6),
+            self.rng.uniform(-0.3, 0.3),
+            self.rng.uniform(0.2, 0.6),
+        );
+        self.observe()
+    }
+    fn step(&mut self, action: &[f64]) -> Result<Step<Vec<f64>>, Error> {
+        let action = normalize_action("PandaReach", action, 7)?;
+        for (target, delta) in self.joint_targets.iter_mut().zip(&action) {
+            *target += delta * Self::MAX_JOINT_DISPLACEMENT;
+        }
+        self.client.set_joint_motor_control_array(
+            self.panda,
+            &Self::ARM_JOINT_INDICES,
+            ControlCommandArray::Positions(&self.joint_targets),
+            Some(&[Self::MAXIMUM_FORCE; 7]),
+        )?;
+        self.client.step_simulation()?;
+        let distance = (self.target - self.hand_position()?).norm();
+        Ok(Step {
+            observation: self.observe()?,
+            reward: -distance,
+            terminated: distance < Self::SUCCESS_DISTANCE,
+            truncated: false,
+        })
+    }
+}
+
+/// The Minitaur quadruped from `log_minitaur.rs` has to walk forward along the x axis.
+///
+/// Each leg of the Minitaur is a closed kinematic chain, which is driven by two motors. The chains
+/// are closed with point-to-point constraints between the knee links, like in the quadruped
+/// example of PyBullet.
+///
+/// An action are 8 values in \[-1, 1\] which set the target angles of the motors relative to the
+/// standing pose, scaled by [`MAX_MOTOR_OFFSET`](`Self::MAX_MOTOR_OFFSET`).
+///
+/// An observation consists of 28 values:
+/// * the 8 motor angles
+/// * the 8 motor velocities
+/// * the 8 motor torques
+/// * the orientation of the base as quaternion (x,y,z,w)
+///
+/// The reward is the distance walked along the x axis minus the energy used by the motors,
+/// weighted with [`ENERGY_WEIGHT`](`Self::ENERGY_WEIGHT`). The episode terminates when the
+/// Minitaur falls over.
+///
+/// The environment needs the `data` directory of bullet3 in the search path of the client.
+pub struct MinitaurLocomotion {
+    client: PhysicsClient,
+    minitaur: BodyId,
+    motors: [usize; 8],
+    initial_state: StateId,
+    last_x: f64,
+    rng: Rng,
+}
+
+impl MinitaurLocomotion {
+    /// maximum deviation of the motor angles from the standing pose in radians
+    pub const MAX_MOTOR_OFFSET: f64 = 0.5;
+    /// weight of the energy penalty in the reward
+    pub const ENERGY_WEIGHT: f64 = 0.005;
+    /// maximum torque of a motor
+    pub const MOTOR_FORCE: f64 = 5.;
+    const LEGS: [&'static str; 8] = [
+        "front_leftL",
+        "front_leftR",
+        "back_leftL",
+        "back_leftR",
+        "front_rightL",
+        "front_rightR",
+        "back_rightL",
+        "back_rightR",
+    ];
+    const MOTOR_DIRECTIONS: [f64; 8] = [-1., -1., -1., -1., 1., 1., 1., 1.];
+    const KNEE_ANGLE: f64 = -2.1834;
+    /// the Minitaur counts as fallen when the z axis of its base tilts further than this
+    /// (cosine of the angle to the world z axis)
+    const MIN_UPRIGHTNESS: f64 = 0.85;
+
+    /// loads the scene into `client`.
+    pub fn new(mut client: PhysicsClient) -> Result<MinitaurLocomotion, Error> {
+        client.reset_simulation()?;
+        client.set_time_step(Duration::from_secs_f64(TIME_STEP))?;
+        client.set_gravity([0., 0., -10.])?;
+        client.load_urdf(
+            "plane.urdf",
+            UrdfOptions {
+                use_fixed_base: true,
+                ..Default::default()
+            },
+        )?;
+        let minitaur = client.load_urdf(
+            "quadruped/quadruped.urdf",
+            UrdfOptions {
+                base_transform: Isometry3::translation(0., 0., 0.3),
+                ..Default::default()
+            },
+        )?;
+        let num_joints = client.get_num_joints(minitaur)?;
+        let joint_names = (0..num_joints)
+            .map(|joint| Ok(client.get_joint_info(minitaur, joint)?.joint_name))
+            .collect::<Result<Vec<String>, Error>>()?;
+        let find_joint = |name: String| {
+            joint_names
+                .iter()
+                .position(|joint_name| *joint_name == name)
+                .ok_or_else(|| {
+                    Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        format!("quadruped.urdf has no joint named {}", name),
+                    )
+                })
+        };
+        let mut motors = [0; 8];
+        let mut knees = [0; 8];
+        for (i, leg) in Self::LEGS.iter().enumerate() {
+            motors[i] = find_joint(format!("motor_{}_joint", leg))?;
+            knees[i] = find_joint(format!("knee_{}_link", leg))?;
+            let direction = Self::MOTOR_DIRECTIONS[i];
+            client.reset_joint_state(minitaur, motors[i], direction * FRAC_PI_2, None)?;
+            client.reset_joint_state(minitaur, knees[i], direction * Self::KNEE_ANGLE, None)?;
+            // the knees are passive joints
+            client.set_joint_motor_control(
+                minitaur,
+                knees[i],
+                ControlCommand::Velocity(0.),
+                Some(0.),
+            )?;
+        }
+        for leg in knees.chunks(2) {
+            let constraint = client.create_constraint(
+                minitaur,
+                leg[1],
+                minitaur,
+                leg[0],
+                JointType::Point2Point,
+                [0.; 3],
+                Isometry3::translation(0., 0.005, 0.2),
+                Isometry3::translation(0., 0.01, 0.2),
+            )?;
+            client.change_constraint(
+                constraint,
+                ChangeConstraintOptions {
+                    max_force: Some(10000.),
+                    ..Default::default()
+                },
+            )?;
+        }
+        let initial_state = client.save_state()?;
+        Ok(MinitaurLocomotion {
+            client,
+            minitaur,
+            motors,
+            initial_state,
+            last_x: 0.,
+            rng: Rng::from_time(),
+        })
+    }
+    /// gives access to the underlying client, e.g. for rendering.
+    pub fn client(&mut self) -> &mut PhysicsClient {
+        &mut self.client
+    }
+    /// returns the [`BodyId`](`crate::BodyId`) of the robot.
+    pub fn robot(&self) -> BodyId {
+        self.minitaur
+    }
+    fn observe(&mut self, base: &Isometry3<f64>) -> Result<Vec<f64>, Error> {
+        let states = self.client.get_joint_states(self.minitaur, &self.motors)?;
+        let mut observation = Vec::with_capacity(28);
+        observation.extend(states.iter().map(|state| state.joint_position));
+        observation.extend(states.iter().map(|state| state.joint_velocity));
+        observation.extend(states.iter().map(|state| state.joint_motor_torque));
+        observation.extend(base.rotation.coords.iter());
+        Ok(observation)
+    }
+}
+
+impl Environment for MinitaurLocomotion {
+    type Observation = Vec<f64>;
+    type Action = [f64];
+
+    fn observation_space(&self) -> Space {
+        Space::bounded(28, f64::NEG_INFINITY, f64::INFINITY)
+    }
+    fn action_space(&self) -> Space {
+        Space::bounded(8, -1., 1.)
+    }
+    fn reset(&mut self, seed: Option<u64>) -> Result<Vec<f64>, Error> {
+        if let Some(seed) = seed {
+            self.rng = Rng::new(seed);
+        }
+        self.client.restore_state(self.initial_state)?;
+        let base = self.client.get_base_transform(self.minitaur)?;
+        let yaw = self.rng.uniform(-0.1, 0.1);
+        let base = Isometry3::from_parts(
+            base.translation,
+            UnitQuaternion::from_euler_angles(0., 0., yaw) * base.rotation,
+        );
+        self.client.reset_base_transform(self.minitaur, base)?;
+        self.last_x = base.translation.x;
+        self.observe(&base)
+    }
+    fn step(&mut self, action: &[f64]) -> Result<Step<Vec<f64>>, Error> {
+        let action = normalize_action("MinitaurLocomotion", action, 8)?;
+        let targets = action
+            .iter()
+            .zip(&Self::MOTOR_DIRECTIONS)
+            .map(|(offset, direction)| direction * (FRAC_PI_2 + offset * Self::MAX_MOTOR_OFFSET))
+            .collect::<Vec<f64>>();
+        self.client.set_joint_motor_control_array(
+            self.minitaur,
+            &self.motors,
+            ControlCommandArray::Positions(&targets),
+            Some(&[Self::MOTOR_FORCE; 8]),
+        )?;
+        self.client.step_simulation()?;
+        let base = self.client.get_base_transform(self.minitaur)?;
+        let observation = self.observe(&base)?;
+        let energy: f64 = observation[8..16]
+            .iter()
+            .zip(&observation[16..24])
+            .map(|(velocity, torque)| (velocity * torque).abs() * TIME_STEP)
+            .sum();
+        let x = base.translation.x;
+        let reward = x - self.last_x - Self::ENERGY_WEIGHT * energy;
+        self.last_x = x;
+        let uprightness = (base.rotation * Vector3::z()).z;
+        Ok(Step {
+            observation,
+            reward,
+            terminated: uprightness < Self::MIN_UPRIGHTNESS,
+            truncated: false,
+        })
+    }
+}
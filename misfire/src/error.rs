@@ -4,37 +4,122 @@
 //! are C-style return codes) and the underlying C API is a mess. I can't find any documentation,
 //! only endless series of header files and inheritance.
 //!
-//! As such, this library is going to use a single opaque error type that attempts to provide as
-//! much information in the display as possible.
+//! As such, this library uses a single error type that attempts to provide as much information in
+//! the display as possible. On top of that, every error carries an [`ErrorKind`] so that callers
+//! can react to specific failures (e.g. reconnect after [`ErrorKind::Disconnected`]) without
+//! having to match on the message.
+//!
+//! [`PhysicsClient`](`crate::PhysicsClient`) methods never panic because of invalid ids or
+//! indices, malformed arguments or a server which stopped responding. Such failures are reported
+//! as an `Error` instead, so that long-running programs can decide for themselves how to recover.
 use std::{borrow::Cow, error, fmt};
 
+/// The category of an [`Error`].
+///
+/// This enum is `#[non_exhaustive]`, new kinds may be added in the future.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The client is not (or no longer) connected to a physics server.
+    Disconnected,
+    /// The physics server did not answer a command with the expected status.
+    CommandFailed {
+        /// name of the [`PhysicsClient`](`crate::PhysicsClient`) method which submitted the command.
+        command: &'static str,
+        /// raw status type as returned by `b3GetStatusType`. Compare it against
+        /// `EnumSharedMemoryServerStatus` from misfire-sys to find out what the server reported.
+        status: i32,
+    },
+    /// The given [`BodyId`](`crate::BodyId`) does not refer to an existing body.
+    InvalidBodyId,
+    /// The given link or joint index is out of range for the body.
+    InvalidLinkIndex,
+    /// An argument was malformed, e.g. a path containing a nul byte or slices of mismatching length.
+    InvalidArgument,
+    /// A file could not be found.
+    FileNotFound,
+    /// Data could not be parsed, e.g. a corrupted log file or an unknown enum value.
+    ParseError,
+    /// A GUI could not be created, because there already is one in this process.
+    GuiUnavailable,
+    /// A shared memory connection could not be created, because the segment is already in use.
+    SharedMemoryUnavailable,
+    /// Any other error.
+    Other,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::Disconnected => write!(f, "disconnected"),
+            ErrorKind::CommandFailed { command, status } => {
+                write!(f, "command {} failed with status {}", command, status)
+            }
+            ErrorKind::InvalidBodyId => write!(f, "invalid body id"),
+            ErrorKind::InvalidLinkIndex => write!(f, "invalid link index"),
+            ErrorKind::InvalidArgument => write!(f, "invalid argument"),
+            ErrorKind::FileNotFound => write!(f, "file not found"),
+            ErrorKind::ParseError => write!(f, "parse error"),
+            ErrorKind::GuiUnavailable => write!(f, "GUI unavailable"),
+            ErrorKind::SharedMemoryUnavailable => write!(f, "shared memory unavailable"),
+            ErrorKind::Other => write!(f, "other error"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Error {
+    kind: ErrorKind,
     ctx: Cow<'static, str>,
 }
 
 impl Error {
-    /// Creates a new error from the provided static string.
+    /// Creates a new error of kind [`ErrorKind::Other`] from the provided static string.
     ///
     /// This is not implemented as `From<_>` in order to keep the functionality from being exposed
     /// to users of the crate.
     pub(crate) fn new(ctx: &'static str) -> Error {
-        Error {
-            ctx: Cow::Borrowed(ctx),
-        }
+        Error::of_kind(ErrorKind::Other, ctx)
     }
 
-    /// Creates a new error from the provided `String`.
+    /// Creates a new error of kind [`ErrorKind::Other`] from the provided `String`.
     pub(crate) fn with(ctx: String) -> Error {
+        Error::of_kind(ErrorKind::Other, ctx)
+    }
+
+    /// Creates a new error of the given kind.
+    pub(crate) fn of_kind<C: Into<Cow<'static, str>>>(kind: ErrorKind, ctx: C) -> Error {
         Error {
-            ctx: Cow::Owned(ctx),
+            kind,
+            ctx: ctx.into(),
         }
     }
+
+    /// Creates a [`ErrorKind::Disconnected`] error.
+    pub(crate) fn disconnected() -> Error {
+        Error::of_kind(ErrorKind::Disconnected, "Not connected to physics server")
+    }
+
+    /// Creates a [`ErrorKind::CommandFailed`] error for the given method name and the status type
+    /// which was returned by the server.
+    pub(crate) fn command_failed(command: &'static str, status: i32, ctx: &'static str) -> Error {
+        Error::of_kind(ErrorKind::CommandFailed { command, status }, ctx)
+    }
+
+    /// Returns the kind of this error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.ctx)
+        match self.kind {
+            ErrorKind::CommandFailed { command, status } => {
+                write!(f, "{} ({} returned status {})", self.ctx, command, status)
+            }
+            _ => write!(f, "{}", self.ctx),
+        }
     }
 }
 
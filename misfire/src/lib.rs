@@ -12,7 +12,7 @@
 //!     let mut physics_client = PhysicsClient::connect(Mode::Gui)?;
 //!
 //!     physics_client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
-//!     physics_client.set_gravity(Vector3::new(0.0, 0.0, -10.0));
+//!     physics_client.set_gravity(Vector3::new(0.0, 0.0, -10.0))?;
 //!
 //!     let _plane_id = physics_client.load_urdf("plane.urdf", None)?;
 //!
@@ -38,30 +38,50 @@
 //! ```
 pub use crate::{
     client::PhysicsClient,
-    error::Error,
+    error::{Error, ErrorKind},
     mode::Mode,
+    owned::{
+        Owned, OwnedBody, OwnedConstraint, OwnedDebugItem, OwnedState, Resource, StateLoggingGuard,
+    },
+    pending::{BatchResults, BatchTicket, CommandBatch, PendingCommand},
+    pool::{EnvJointStates, PhysicsClientPool, PoolImages, PoolJointStates, PoolLinkStates},
+    robot::Robot,
     server::{PhysicsServer, ServerMode},
+    shared::{RejectedClient, SendablePhysicsClient, SharedPhysicsClient},
     types::{
         Aabb, ActivationState, AddDebugLineOptions, AddDebugTextOptions, BodyId, BodyInfo,
         BodyType, CameraImageOptions, ChangeConstraintOptions, ChangeDynamicsOptions,
         ChangeVisualShapeOptions, CollisionId, ConstraintId, ConstraintInfo, ConstraintSolverType,
-        ContactPoint, ControlCommand, ControlCommandArray, DebugVisualizerCameraInfo,
-        DebugVisualizerFlag, DynamicsInfo, ExternalForceFrame, GeometricCollisionShape,
-        GeometricVisualShape, IkSolver, Images, InverseKinematicsNullSpaceParameters,
-        InverseKinematicsParameters, InverseKinematicsParametersBuilder, ItemId, Jacobian,
-        JointFeedbackMode, JointInfo, JointInfoFlags, JointState, JointType, KeyboardEvent,
-        LinkState, LoadModelFlags, LogFlags, LogId, LoggingType, MouseButtonState, MouseEvent,
-        MultiBodyOptions, OverlappingObject, PhysicsEngineParameters, RayHitInfo,
-        RayTestBatchOptions, RayTestOptions, Renderer, RendererAuxFlags, ResetFlags, SdfOptions,
+        ContactPoint, ControlCommand, ControlCommandArray, ControlCommandMultiDof,
+        DebugVisualizerCameraInfo, DebugVisualizerFlag, DynamicsInfo, ExternalForceFrame,
+        GeometricCollisionShape, GeometricVisualShape, IkSolver, ImageChannels, Images,
+        InverseKinematicsNullSpaceParameters, InverseKinematicsParameters,
+        InverseKinematicsParametersBuilder, ItemId, Jacobian, JointFeedbackMode, JointInfo,
+        JointInfoFlags, JointState, JointStateMultiDof, JointType, KeyboardEvent, LinkState,
+        LoadModelFlags, LogFlags, LogId, LoggingType, MouseButtonState, MouseEvent,
+        MultiBodyOptions, OverlappingObject, PhysicsEngineParameters, PixelBoundingBox, PluginId,
+        PointCloud, PointCloudFrame, RayHitInfo, RayTestBatchOptions, RayTestOptions, Renderer,
+        RendererAuxFlags, ResetFlags, SdfOptions, SegmentationMask,
         SetPhysicsEngineParameterOptions, SoftBodyOptions, StateId, StateLoggingOptions, TextureId,
-        UrdfOptions, Velocity, VisualId, VisualShapeData, VisualShapeFlags, VisualShapeOptions,
+        UrdfOptions, UserDataId, UserDataInfo, UserDataValue, Velocity, VisualId, VisualShapeData,
+        VisualShapeFlags, VisualShapeOptions,
     },
 };
 pub use image;
 pub use nalgebra;
 mod client;
+pub mod env;
 mod error;
 pub mod logging_utils;
 mod mode;
+mod owned;
+mod pending;
+pub mod plugins;
+mod pool;
+mod rng;
+mod robot;
+pub mod scene;
+pub mod sensors;
 mod server;
+mod shared;
 mod types;
@@ -1,22 +1,40 @@
 //! Contains methods and types which are useful for logging
+//!
+//! The state loggers of Bullet ([`Minitaur`](`crate::types::LoggingType::Minitaur`),
+//! [`GenericRobot`](`crate::types::LoggingType::GenericRobot`),
+//! [`VrControllers`](`crate::types::LoggingType::VrControllers`) and
+//! [`ContactPoints`](`crate::types::LoggingType::ContactPoints`)) all write the same binary format:
+//! A line with the comma separated keys of the fields, a line with the struct format of a record
+//! and then the records, each one starting with the bytes `0xaa 0xbb`.
+//! The [`LogReader`](`LogReader`) reads such files record by record. Its records can either be
+//! accessed by key or converted into one of the typed log entries of this module.
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::{Display, Formatter};
 use std::fs::File;
-use std::io::{BufRead, BufReader, Read};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::marker::PhantomData;
 use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::types::combined_position_orientation_array_to_isometry;
-use crate::{BodyId, Error, Velocity};
-use nalgebra::{DVector, Isometry3, Vector6};
-use std::convert::TryInto;
-use std::fmt;
-use std::fmt::{Display, Formatter};
-use std::time::Duration;
+use crate::{BodyId, Error, ErrorKind, JointType, PhysicsClient, Velocity};
+use nalgebra::{DVector, Isometry3, Vector3, Vector6};
+
+/// the bytes which precede every record in a log file.
+const RECORD_MARKER: [u8; 2] = [0xaa, 0xbb];
+/// default number of joints which are logged by the [`GenericRobot`](`crate::types::LoggingType::GenericRobot`) logger.
+pub const DEFAULT_MAX_LOG_DOF: usize = 12;
 
 /// Represents the State of a Body which was recorded with
 /// [`start_state_logging`](`crate::PhysicsClient::start_state_logging`) with the
 /// [`GenericRobot`](`crate::types::LoggingType::GenericRobot`)
 /// [`LoggingType`](`crate::types::LoggingType`).
-/// Use [`read_generic_robot_log`](`read_generic_robot_log`) to read such a log file.
+/// Use [`read_generic_robot_log`](`read_generic_robot_log`) or a [`LogReader`](`LogReader`)
+/// to read such a log file.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GenericRobotLog {
     /// ongoing counter of logs from the log file.
     pub chunk_number: usize,
@@ -30,12 +48,16 @@ pub struct GenericRobotLog {
     pub base_pose: Isometry3<f64>,
     /// Base velocity of the robot. Not the end-effector velocity!
     pub base_velocity: Velocity,
-    /// total number of joints of the robot.
+    /// number of movable (revolute and prismatic) joints of the robot.
     pub num_joints: usize,
-    /// joint positions with length of num_joints
+    /// positions of the movable joints, in the order of their joint indices. Fixed joints are
+    /// skipped, so entry `k` belongs to the `k`-th movable joint and not to joint `k`.
     pub joint_positions: DVector<f64>,
-    /// joint velocities with length of num_joints
+    /// velocities of the movable joints, in the same order as `joint_positions`.
     pub joint_velocities: DVector<f64>,
+    /// joint torques with length of num_joints. Only available if the log was started with one of
+    /// the torque [`LogFlags`](`crate::types::LogFlags`).
+    pub joint_torques: Option<DVector<f64>>,
 }
 impl Display for GenericRobotLog {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -50,7 +72,13 @@ impl Display for GenericRobotLog {
         writeln!(f, "joint positions:")?;
         writeln!(f, "{:?}", self.joint_positions.as_slice())?;
         writeln!(f, "joint velocities:")?;
-        writeln!(f, "{:?}", self.joint_velocities.as_slice())
+        write!(f, "{:?}", self.joint_velocities.as_slice())?;
+        if let Some(joint_torques) = &self.joint_torques {
+            writeln!(f)?;
+            writeln!(f, "joint torques:")?;
+            write!(f, "{:?}", joint_torques.as_slice())?;
+        }
+        writeln!(f)
     }
 }
 impl Default for GenericRobotLog {
@@ -59,32 +87,1135 @@ impl Default for GenericRobotLog {
             chunk_number: 0,
             step_count: 0,
             time_stamp: Default::default(),
-            body: BodyId(-1),
+            body: BodyId::unchecked(-1),
             base_pose: Isometry3::identity(),
             base_velocity: Vector6::zeros().into(),
             num_joints: 0,
             joint_positions: DVector::identity(1),
             joint_velocities: DVector::identity(1),
+            joint_torques: None,
+        }
+    }
+}
+
+/// Represents the state of a Minitaur which was recorded with the
+/// [`Minitaur`](`crate::types::LoggingType::Minitaur`) [`LoggingType`](`crate::types::LoggingType`).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MinitaurLog {
+    /// ongoing counter of logs from the log file.
+    pub chunk_number: usize,
+    /// simulation time stamp (stored in milliseconds)
+    pub time_stamp: Duration,
+    /// roll, pitch and yaw of the IMU
+    pub roll_pitch_yaw: Vector3<f64>,
+    /// positions of the leg motors (q0-q7)
+    pub motor_positions: DVector<f64>,
+    /// torques of the leg motors (u0-u7)
+    pub motor_torques: DVector<f64>,
+    /// forward speed of the torso
+    pub forward_speed: f64,
+    /// mode of the robot. Unused in simulation.
+    pub mode: i64,
+}
+
+/// Represents a contact point which was recorded with the
+/// [`ContactPoints`](`crate::types::LoggingType::ContactPoints`)
+/// [`LoggingType`](`crate::types::LoggingType`).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContactPointLog {
+    /// ongoing counter of logs from the log file.
+    pub chunk_number: usize,
+    /// ongoing counter of the steps in the simulation
+    pub step_count: usize,
+    /// simulation time stamp
+    pub time_stamp: Duration,
+    /// reserved
+    pub contact_flag: i64,
+    /// body unique id of body A
+    pub body_a: BodyId,
+    /// body unique id of body B
+    pub body_b: BodyId,
+    /// link index of body A, `None` for base
+    pub link_index_a: Option<usize>,
+    /// link index of body B, `None` for base
+    pub link_index_b: Option<usize>,
+    /// contact position on A, in Cartesian world coordinates
+    pub position_on_a: Vector3<f64>,
+    /// contact position on B, in Cartesian world coordinates
+    pub position_on_b: Vector3<f64>,
+    /// contact normal on B, pointing towards A
+    pub contact_normal_on_b: Vector3<f64>,
+    /// contact distance, positive for separation, negative for penetration
+    pub contact_distance: f64,
+    /// normal force applied during the last step
+    pub normal_force: f64,
+}
+
+/// Represents the state of a VR controller which was recorded with the
+/// [`VrControllers`](`crate::types::LoggingType::VrControllers`)
+/// [`LoggingType`](`crate::types::LoggingType`).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VrControllerLog {
+    /// ongoing counter of logs from the log file.
+    pub chunk_number: usize,
+    /// ongoing counter of the steps in the simulation
+    pub step_count: usize,
+    /// simulation time stamp
+    pub time_stamp: Duration,
+    /// id of the controller
+    pub controller_id: i64,
+    /// number of move events since the last log
+    pub num_move_events: usize,
+    /// number of button events since the last log
+    pub num_button_events: usize,
+    /// pose of the controller in world coordinates
+    pub pose: Isometry3<f64>,
+    /// state of the analog axis (trigger)
+    pub analog_axis: f64,
+    /// state of each button. Bit 1 is set while the button is down, bit 2 if the button was
+    /// triggered and bit 4 if it was released.
+    pub buttons: Vec<u8>,
+    /// type of the device (controller, HMD or generic tracker)
+    pub device_type: i64,
+}
+
+/// A single value of a log record.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LogValue {
+    /// unsigned integer (`I`)
+    UInt(u32),
+    /// signed integer (`i`)
+    Int(i32),
+    /// 32 bit float (`f`)
+    Float(f32),
+    /// unsigned byte (`B`)
+    Char(u8),
+}
+
+impl LogValue {
+    /// returns the value as float.
+    pub fn as_f64(self) -> f64 {
+        match self {
+            LogValue::UInt(value) => value as f64,
+            LogValue::Int(value) => value as f64,
+            LogValue::Float(value) => value as f64,
+            LogValue::Char(value) => f64::from(value),
+        }
+    }
+    /// returns the character of the data type in the struct format.
+    pub fn data_type(self) -> char {
+        match self {
+            LogValue::UInt(_) => 'I',
+            LogValue::Int(_) => 'i',
+            LogValue::Float(_) => 'f',
+            LogValue::Char(_) => 'B',
+        }
+    }
+    /// returns the value as integer or `None` if it is a float.
+    pub fn as_i64(self) -> Option<i64> {
+        match self {
+            LogValue::UInt(value) => Some(value as i64),
+            LogValue::Int(value) => Some(value as i64),
+            LogValue::Float(_) => None,
+            LogValue::Char(value) => Some(i64::from(value)),
+        }
+    }
+}
+
+impl Display for LogValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LogValue::UInt(value) => write!(f, "{}", value),
+            LogValue::Int(value) => write!(f, "{}", value),
+            LogValue::Float(value) => write!(f, "{}", value),
+            LogValue::Char(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// Describes the records of a log file: the name and the data type of every field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogSchema {
+    keys: Vec<String>,
+    format: String,
+    indices: HashMap<String, usize>,
+    record_size: usize,
+}
+
+impl LogSchema {
+    /// creates a schema from the keys of the fields and the struct format, which contains one
+    /// character per field (`I` for unsigned integers, `i` for integers, `f` for floats and `B`
+    /// for bytes).
+    pub fn new<K: Into<String>>(keys: Vec<K>, format: &str) -> Result<LogSchema, Error> {
+        let keys: Vec<String> = keys.into_iter().map(Into::into).collect();
+        if keys.len() != format.len() {
+            return Err(Error::of_kind(
+                ErrorKind::ParseError,
+                format!(
+                    "the log header has {} keys but the format has {} fields",
+                    keys.len(),
+                    format.len()
+                ),
+            ));
+        }
+        let mut record_size = 0;
+        for data_type in format.chars() {
+            record_size += match data_type {
+                'I' | 'i' | 'f' => 4,
+                'B' => 1,
+                _ => {
+                    return Err(Error::of_kind(
+                        ErrorKind::ParseError,
+                        format!("unknown data type '{}' in log format", data_type),
+                    ))
+                }
+            };
+        }
+        let indices = keys
+            .iter()
+            .enumerate()
+            .map(|(index, key)| (key.clone(), index))
+            .collect();
+        Ok(LogSchema {
+            keys,
+            format: format.to_string(),
+            indices,
+            record_size,
+        })
+    }
+    /// returns the schema which is used by the [`GenericRobot`](`crate::types::LoggingType::GenericRobot`)
+    /// logger of Bullet. Joint torques are part of the schema if the logger was started with one of
+    /// the torque [`LogFlags`](`crate::types::LogFlags`).
+    pub fn generic_robot(max_log_dof: usize, log_torques: bool) -> LogSchema {
+        let mut keys: Vec<String> = [
+            "stepCount",
+            "timeStamp",
+            "objectId",
+            "posX",
+            "posY",
+            "posZ",
+            "oriX",
+            "oriY",
+            "oriZ",
+            "oriW",
+            "velX",
+            "velY",
+            "velZ",
+            "omegaX",
+            "omegaY",
+            "omegaZ",
+            "qNum",
+        ]
+        .iter()
+        .map(|key| key.to_string())
+        .collect();
+        let mut format = "IfifffffffffffffI".to_string();
+        let prefixes: &[&str] = if log_torques {
+            &["q", "u", "t"]
+        } else {
+            &["q", "u"]
+        };
+        for prefix in prefixes {
+            for joint in 0..max_log_dof {
+                keys.push(format!("{}{}", prefix, joint));
+                format.push('f');
+            }
+        }
+        LogSchema::new(keys, &format).expect("the generic robot schema is valid")
+    }
+    /// returns the keys of all fields.
+    pub fn keys(&self) -> &[String] {
+        &self.keys
+    }
+    /// returns the struct format.
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+    /// returns the size of a record in bytes, without the record marker.
+    pub fn record_size(&self) -> usize {
+        self.record_size
+    }
+    /// returns the index of the field with the given key.
+    pub fn index_of(&self, key: &str) -> Option<usize> {
+        self.indices.get(key).copied()
+    }
+    fn parse_record(&self, buf: &[u8]) -> Vec<LogValue> {
+        let mut offset = 0;
+        let mut values = Vec::with_capacity(self.format.len());
+        for data_type in self.format.chars() {
+            if data_type == 'B' {
+                values.push(LogValue::Char(buf[offset]));
+                offset += 1;
+                continue;
+            }
+            let bytes = [
+                buf[offset],
+                buf[offset + 1],
+                buf[offset + 2],
+                buf[offset + 3],
+            ];
+            values.push(match data_type {
+                'I' => LogValue::UInt(u32::from_le_bytes(bytes)),
+                'i' => LogValue::Int(i32::from_le_bytes(bytes)),
+                _ => LogValue::Float(f32::from_le_bytes(bytes)),
+            });
+            offset += 4;
         }
+        values
     }
 }
-fn calc_size(fmt: &str) -> usize {
-    let mut size = 0;
-    for c in fmt.chars() {
-        size += match c {
-            'I' | 'i' | 'f' => 4,
-            'B' => 1,
-            _ => {
-                panic!("can not determine data type")
+
+/// A single record of a log file.
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    chunk_number: usize,
+    schema: Arc<LogSchema>,
+    values: Vec<LogValue>,
+}
+
+impl LogRecord {
+    /// returns the position of the record in the log file.
+    pub fn chunk_number(&self) -> usize {
+        self.chunk_number
+    }
+    /// returns the schema of the log file.
+    pub fn schema(&self) -> &LogSchema {
+        &self.schema
+    }
+    /// returns all values in the order of the [`keys`](`LogSchema::keys`).
+    pub fn values(&self) -> &[LogValue] {
+        &self.values
+    }
+    /// returns the value of the field with the given key.
+    pub fn get(&self, key: &str) -> Option<LogValue> {
+        self.schema.index_of(key).map(|index| self.values[index])
+    }
+    fn value(&self, key: &str) -> Result<LogValue, Error> {
+        self.get(key).ok_or_else(|| missing_field(key))
+    }
+    fn float(&self, key: &str) -> Result<f64, Error> {
+        Ok(self.value(key)?.as_f64())
+    }
+    fn int(&self, key: &str) -> Result<i64, Error> {
+        self.value(key)?.as_i64().ok_or_else(|| {
+            Error::of_kind(
+                ErrorKind::ParseError,
+                format!("the field {} of the log is no integer", key),
+            )
+        })
+    }
+    fn count(&self, key: &str) -> Result<usize, Error> {
+        let value = self.int(key)?;
+        if value < 0 {
+            return Err(Error::of_kind(
+                ErrorKind::ParseError,
+                format!("the field {} of the log is negative ({})", key, value),
+            ));
+        }
+        Ok(value as usize)
+    }
+    fn seconds(&self, key: &str, scale: f64) -> Result<Duration, Error> {
+        Duration::try_from_secs_f64(self.float(key)? * scale).map_err(|_| {
+            Error::of_kind(
+                ErrorKind::ParseError,
+                format!("the field {} of the log is no valid time stamp", key),
+            )
+        })
+    }
+    fn body(&self, key: &str) -> Result<BodyId, Error> {
+        let id = self.int(key)?;
+        if id < 0 {
+            return Err(Error::of_kind(
+                ErrorKind::ParseError,
+                format!("the field {} of the log is no valid body id ({})", key, id),
+            ));
+        }
+        Ok(BodyId::unchecked(id as i32))
+    }
+    fn vector3(&self, keys: [&str; 3]) -> Result<Vector3<f64>, Error> {
+        Ok(Vector3::new(
+            self.float(keys[0])?,
+            self.float(keys[1])?,
+            self.float(keys[2])?,
+        ))
+    }
+    /// reads the fields `prefix0`, `prefix1`, ... up to `len` or up to the first missing field.
+    fn series(&self, prefix: &str, len: Option<usize>) -> Result<DVector<f64>, Error> {
+        let mut values = Vec::new();
+        loop {
+            if Some(values.len()) == len {
+                break;
+            }
+            let key = format!("{}{}", prefix, values.len());
+            match self.get(&key) {
+                Some(value) => values.push(value.as_f64()),
+                None if len.is_none() => break,
+                None => return Err(missing_field(&key)),
+            }
+        }
+        Ok(DVector::from_vec(values))
+    }
+}
+
+/// A typed entry of a log file, which can be created from a [`LogRecord`](`LogRecord`).
+pub trait LogEntry: Sized {
+    /// converts the record. Returns an error if fields are missing or invalid.
+    fn from_record(record: &LogRecord) -> Result<Self, Error>;
+}
+
+impl LogEntry for LogRecord {
+    fn from_record(record: &LogRecord) -> Result<Self, Error> {
+        Ok(record.clone())
+    }
+}
+
+impl LogEntry for GenericRobotLog {
+    fn from_record(record: &LogRecord) -> Result<Self, Error> {
+        let mut pose_elements = [0.; 7];
+        let pose_keys = ["posX", "posY", "posZ", "oriX", "oriY", "oriZ", "oriW"];
+        for (element, key) in pose_elements.iter_mut().zip(pose_keys.iter()) {
+            *element = record.float(key)?;
+        }
+        let mut velocity_elements = [0.; 6];
+        let velocity_keys = ["velX", "velY", "velZ", "omegaX", "omegaY", "omegaZ"];
+        for (element, key) in velocity_elements.iter_mut().zip(velocity_keys.iter()) {
+            *element = record.float(key)?;
+        }
+        let velocity: Vector6<f64> = velocity_elements.into();
+        let num_joints = record.count("qNum")?;
+        let joint_torques = match record.get("t0") {
+            Some(_) => Some(record.series("t", Some(num_joints))?),
+            None => None,
+        };
+        Ok(GenericRobotLog {
+            chunk_number: record.chunk_number(),
+            step_count: record.count("stepCount")?,
+            time_stamp: record.seconds("timeStamp", 1.)?,
+            body: record.body("objectId")?,
+            base_pose: combined_position_orientation_array_to_isometry(pose_elements),
+            base_velocity: velocity.into(),
+            num_joints,
+            joint_positions: record.series("q", Some(num_joints))?,
+            joint_velocities: record.series("u", Some(num_joints))?,
+            joint_torques,
+        })
+    }
+}
+
+impl LogEntry for MinitaurLog {
+    fn from_record(record: &LogRecord) -> Result<Self, Error> {
+        Ok(MinitaurLog {
+            chunk_number: record.chunk_number(),
+            time_stamp: record.seconds("t", 1e-3)?,
+            roll_pitch_yaw: record.vector3(["r", "p", "y"])?,
+            motor_positions: record.series("q", None)?,
+            motor_torques: record.series("u", None)?,
+            forward_speed: record.float("xd")?,
+            mode: record.int("mo")?,
+        })
+    }
+}
+
+impl LogEntry for ContactPointLog {
+    fn from_record(record: &LogRecord) -> Result<Self, Error> {
+        let link_index = |key: &str| -> Result<Option<usize>, Error> {
+            let index = record.int(key)?;
+            Ok(if index < 0 {
+                None
+            } else {
+                Some(index as usize)
+            })
+        };
+        Ok(ContactPointLog {
+            chunk_number: record.chunk_number(),
+            step_count: record.count("stepCount")?,
+            time_stamp: record.seconds("timeStamp", 1.)?,
+            contact_flag: record.int("contactFlag")?,
+            body_a: record.body("bodyUniqueIdA")?,
+            body_b: record.body("bodyUniqueIdB")?,
+            link_index_a: link_index("linkIndexA")?,
+            link_index_b: link_index("linkIndexB")?,
+            position_on_a: record.vector3(["positionOnAX", "positionOnAY", "positionOnAZ"])?,
+            position_on_b: record.vector3(["positionOnBX", "positionOnBY", "positionOnBZ"])?,
+            contact_normal_on_b: record.vector3([
+                "contactNormalOnBX",
+                "contactNormalOnBY",
+                "contactNormalOnBZ",
+            ])?,
+            contact_distance: record.float("contactDistance")?,
+            normal_force: record.float("normalForce")?,
+        })
+    }
+}
+
+impl LogEntry for VrControllerLog {
+    fn from_record(record: &LogRecord) -> Result<Self, Error> {
+        const MAX_VR_BUTTONS: usize = 64;
+        const BUTTONS_PER_FIELD: usize = 10;
+        let mut pose_elements = [0.; 7];
+        let pose_keys = ["posX", "posY", "posZ", "oriX", "oriY", "oriZ", "oriW"];
+        for (element, key) in pose_elements.iter_mut().zip(pose_keys.iter()) {
+            *element = record.float(key)?;
+        }
+        // every field packs the states of ten buttons with three bits each
+        let mut buttons = Vec::with_capacity(MAX_VR_BUTTONS);
+        let mut field = 0;
+        while let Some(packed) = record.get(&format!("buttons{}", field)) {
+            let packed = packed.as_i64().unwrap_or_default();
+            for button in 0..BUTTONS_PER_FIELD {
+                if buttons.len() < MAX_VR_BUTTONS {
+                    buttons.push(((packed >> (3 * button)) & 7) as u8);
+                }
+            }
+            field += 1;
+        }
+        Ok(VrControllerLog {
+            chunk_number: record.chunk_number(),
+            step_count: record.count("stepCount")?,
+            time_stamp: record.seconds("timeStamp", 1.)?,
+            controller_id: record.int("controllerId")?,
+            num_move_events: record.count("numMoveEvents")?,
+            num_button_events: record.count("m_numButtonEvents")?,
+            pose: combined_position_orientation_array_to_isometry(pose_elements),
+            analog_axis: record.float("analogAxis")?,
+            buttons,
+            device_type: record.int("deviceType")?,
+        })
+    }
+}
+
+/// Reads the records of a log file one by one.
+///
+/// Reading stops after the first error, e.g. when the file is truncated or corrupted.
+/// # Example
+/// ```no_run
+/// use anyhow::Result;
+/// use misfire::logging_utils::{GenericRobotLog, LogReader};
+///
+/// fn main() -> Result<()> {
+///     let reader = LogReader::open("LOG0001.txt")?;
+///     println!("keys: {:?}", reader.schema().keys());
+///     for log in reader.entries::<GenericRobotLog>() {
+///         let log = log?;
+///         println!("{}", log);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct LogReader<R: BufRead> {
+    reader: R,
+    schema: Arc<LogSchema>,
+    chunk_number: usize,
+    finished: bool,
+}
+
+impl LogReader<BufReader<File>> {
+    /// opens a log file and reads its header.
+    /// # Arguments
+    /// * `filename` - location of the log file.
+    pub fn open<P: AsRef<Path>>(filename: P) -> Result<Self, Error> {
+        let file = File::open(filename).map_err(|err| match err.kind() {
+            std::io::ErrorKind::NotFound => {
+                Error::of_kind(ErrorKind::FileNotFound, "could not open file")
+            }
+            _ => Error::new("could not open file"),
+        })?;
+        LogReader::new(BufReader::new(file))
+    }
+}
+
+impl<R: BufRead> LogReader<R> {
+    /// reads the header of a log from any reader.
+    pub fn new(mut reader: R) -> Result<Self, Error> {
+        let keys = read_header_line(&mut reader)?;
+        let format = read_header_line(&mut reader)?;
+        let schema = LogSchema::new(keys.split(',').collect(), &format)?;
+        Ok(LogReader {
+            reader,
+            schema: Arc::new(schema),
+            chunk_number: 0,
+            finished: false,
+        })
+    }
+    /// returns the schema of the log.
+    pub fn schema(&self) -> &LogSchema {
+        &self.schema
+    }
+    /// converts the records into typed entries, e.g. [`GenericRobotLog`](`GenericRobotLog`).
+    pub fn entries<T: LogEntry>(self) -> LogEntries<R, T> {
+        LogEntries {
+            reader: self,
+            entry: PhantomData,
+        }
+    }
+    fn read_record(&mut self) -> Result<Option<LogRecord>, Error> {
+        let mut marker = [0_u8; 2];
+        match read_up_to(&mut self.reader, &mut marker)? {
+            0 => return Ok(None),
+            2 if marker == RECORD_MARKER => {}
+            2 => {
+                return Err(Error::of_kind(
+                    ErrorKind::ParseError,
+                    format!(
+                        "expected record marker aabb before chunk {} but found {:02x}{:02x}",
+                        self.chunk_number, marker[0], marker[1]
+                    ),
+                ))
             }
+            _ => return Err(self.truncated()),
+        }
+        let mut buf = vec![0_u8; self.schema.record_size()];
+        if read_up_to(&mut self.reader, &mut buf)? != buf.len() {
+            return Err(self.truncated());
+        }
+        let record = LogRecord {
+            chunk_number: self.chunk_number,
+            schema: self.schema.clone(),
+            values: self.schema.parse_record(&buf),
         };
+        self.chunk_number += 1;
+        Ok(Some(record))
+    }
+    fn truncated(&self) -> Error {
+        Error::of_kind(
+            ErrorKind::ParseError,
+            format!(
+                "the log file ends in the middle of chunk {}",
+                self.chunk_number
+            ),
+        )
+    }
+}
+
+impl<R: BufRead> Iterator for LogReader<R> {
+    type Item = Result<LogRecord, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let record = self.read_record().transpose();
+        if !matches!(record, Some(Ok(_))) {
+            self.finished = true;
+        }
+        record
+    }
+}
+
+/// Iterator over the typed entries of a log. Created with [`LogReader::entries`](`LogReader::entries`).
+pub struct LogEntries<R: BufRead, T: LogEntry> {
+    reader: LogReader<R>,
+    entry: PhantomData<T>,
+}
+
+impl<R: BufRead, T: LogEntry> LogEntries<R, T> {
+    /// returns the schema of the log.
+    pub fn schema(&self) -> &LogSchema {
+        self.reader.schema()
+    }
+}
+
+impl<R: BufRead, T: LogEntry> Iterator for LogEntries<R, T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self
+            .reader
+            .next()?
+            .and_then(|record| T::from_record(&record));
+        if entry.is_err() {
+            self.reader.finished = true;
+        }
+        Some(entry)
+    }
+}
+
+/// Writes records in the binary log format of Bullet.
+///
+/// Files written by a `LogWriter` can be read with a [`LogReader`](`LogReader`), the `dump_log`
+/// example and the log readers of PyBullet. The records are buffered by the writer, so call
+/// [`flush`](`Self::flush`) or drop the writer before the file is read.
+pub struct LogWriter<W: Write> {
+    writer: W,
+    schema: LogSchema,
+    buffer: Vec<u8>,
+}
+
+impl LogWriter<BufWriter<File>> {
+    /// creates a log file and writes the header of the schema.
+    /// # Arguments
+    /// * `filename` - location of the log file. An existing file is overwritten.
+    /// * `schema` - keys and data types of the records.
+    pub fn create<P: AsRef<Path>>(filename: P, schema: LogSchema) -> Result<Self, Error> {
+        let file = File::create(filename)
+            .map_err(|err| Error::with(format!("could not create log file: {}", err)))?;
+        LogWriter::new(BufWriter::new(file), schema)
+    }
+}
+
+impl<W: Write> LogWriter<W> {
+    /// writes the header of the schema to any writer.
+    pub fn new(mut writer: W, schema: LogSchema) -> Result<Self, Error> {
+        let header = format!("{}\n{}\n", schema.keys().join(","), schema.format());
+        write_all(&mut writer, header.as_bytes())?;
+        Ok(LogWriter {
+            writer,
+            schema,
+            buffer: Vec::new(),
+        })
+    }
+    /// returns the schema of the log.
+    pub fn schema(&self) -> &LogSchema {
+        &self.schema
+    }
+    /// writes a record. The values have to match the data types of the schema.
+    pub fn write_record(&mut self, values: &[LogValue]) -> Result<(), Error> {
+        if values.len() != self.schema.keys().len() {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "the record has {} values but the schema has {} fields",
+                    values.len(),
+                    self.schema.keys().len()
+                ),
+            ));
+        }
+        self.buffer.clear();
+        self.buffer.extend_from_slice(&RECORD_MARKER);
+        for ((value, data_type), key) in values
+            .iter()
+            .zip(self.schema.format().chars())
+            .zip(self.schema.keys())
+        {
+            if value.data_type() != data_type {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "the field {} has data type '{}' but the value has data type '{}'",
+                        key,
+                        data_type,
+                        value.data_type()
+                    ),
+                ));
+            }
+            match *value {
+                LogValue::UInt(value) => self.buffer.extend_from_slice(&value.to_le_bytes()),
+                LogValue::Int(value) => self.buffer.extend_from_slice(&value.to_le_bytes()),
+                LogValue::Float(value) => self.buffer.extend_from_slice(&value.to_le_bytes()),
+                LogValue::Char(value) => self.buffer.extend_from_slice(&value.to_le_bytes()),
+            }
+        }
+        write_all(&mut self.writer, &self.buffer)
+    }
+    /// flushes the underlying writer.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer
+            .flush()
+            .map_err(|err| Error::with(format!("could not write log file: {}", err)))
+    }
+    /// flushes the log and returns the underlying writer.
+    pub fn into_inner(mut self) -> Result<W, Error> {
+        self.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Writes [`GenericRobotLog`](`GenericRobotLog`) entries in the same format as the
+/// [`GenericRobot`](`crate::types::LoggingType::GenericRobot`) logger of Bullet.
+///
+/// This can be used to store data of real robots, so that it can be compared with simulation logs.
+/// # Example
+/// ```no_run
+/// use anyhow::Result;
+/// use misfire::logging_utils::{
+///     read_generic_robot_log, GenericRobotLog, GenericRobotLogWriter, DEFAULT_MAX_LOG_DOF,
+/// };
+/// use nalgebra::DVector;
+/// use std::time::Duration;
+///
+/// fn main() -> Result<()> {
+///     let mut writer = GenericRobotLogWriter::create("robot.log", DEFAULT_MAX_LOG_DOF, false)?;
+///     for step in 0..100 {
+///         writer.write(&GenericRobotLog {
+///             step_count: step,
+///             time_stamp: Duration::from_millis(10 * step as u64),
+///             num_joints: 7,
+///             joint_positions: DVector::zeros(7),
+///             joint_velocities: DVector::zeros(7),
+///             ..Default::default()
+///         })?;
+///     }
+///     writer.flush()?;
+///     assert_eq!(read_generic_robot_log("robot.log")?.len(), 100);
+///     Ok(())
+/// }
+/// ```
+pub struct GenericRobotLogWriter<W: Write> {
+    writer: LogWriter<W>,
+    max_log_dof: usize,
+    log_torques: bool,
+}
+
+impl GenericRobotLogWriter<BufWriter<File>> {
+    /// creates a log file.
+    /// # Arguments
+    /// * `filename` - location of the log file. An existing file is overwritten.
+    /// * `max_log_dof` - maximum number of joints of a robot. Bullet uses [`DEFAULT_MAX_LOG_DOF`](`DEFAULT_MAX_LOG_DOF`)
+    ///   unless [`max_log_dof`](`crate::types::StateLoggingOptions::max_log_dof`) is set.
+    /// * `log_torques` - whether joint torques are logged.
+    pub fn create<P: AsRef<Path>>(
+        filename: P,
+        max_log_dof: usize,
+        log_torques: bool,
+    ) -> Result<Self, Error> {
+        let schema = LogSchema::generic_robot(max_log_dof, log_torques);
+        Ok(GenericRobotLogWriter {
+            writer: LogWriter::create(filename, schema)?,
+            max_log_dof,
+            log_torques,
+        })
+    }
+}
+
+impl<W: Write> GenericRobotLogWriter<W> {
+    /// writes the header to any writer. See [`create`](`GenericRobotLogWriter::create`).
+    pub fn new(writer: W, max_log_dof: usize, log_torques: bool) -> Result<Self, Error> {
+        let schema = LogSchema::generic_robot(max_log_dof, log_torques);
+        Ok(GenericRobotLogWriter {
+            writer: LogWriter::new(writer, schema)?,
+            max_log_dof,
+            log_torques,
+        })
+    }
+    /// returns the schema of the log.
+    pub fn schema(&self) -> &LogSchema {
+        self.writer.schema()
+    }
+    /// writes an entry. The chunk number of the entry is ignored.
+    ///
+    /// Returns an error if the robot has more than `max_log_dof` joints or if the joint vectors do
+    /// not match `num_joints`. Joint torques are written as zeros if the entry has none.
+    pub fn write(&mut self, log: &GenericRobotLog) -> Result<(), Error> {
+        if log.num_joints > self.max_log_dof {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "the robot has {} joints, but only {} joints can be logged",
+                    log.num_joints, self.max_log_dof
+                ),
+            ));
+        }
+        let mut joint_vectors = vec![("positions", Some(&log.joint_positions))];
+        joint_vectors.push(("velocities", Some(&log.joint_velocities)));
+        if self.log_torques {
+            joint_vectors.push(("torques", log.joint_torques.as_ref()));
+        }
+        for (name, vector) in joint_vectors.iter() {
+            if let Some(vector) = vector {
+                if vector.len() != log.num_joints {
+                    return Err(Error::of_kind(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "number of joint {} ({}) should match the number of joints ({})",
+                            name,
+                            vector.len(),
+                            log.num_joints
+                        ),
+                    ));
+                }
+            }
+        }
+        let mut values = Vec::with_capacity(self.schema().keys().len());
+        values.push(LogValue::UInt(log.step_count as u32));
+        values.push(LogValue::Float(log.time_stamp.as_secs_f32()));
+        values.push(LogValue::Int(log.body.0));
+        let position = log.base_pose.translation.vector;
+        let orientation = log.base_pose.rotation.coords;
+        values.extend(
+            position
+                .iter()
+                .chain(orientation.iter())
+                .map(|&value| LogValue::Float(value as f32)),
+        );
+        let velocity = log.base_velocity.to_vector();
+        values.extend(velocity.iter().map(|&value| LogValue::Float(value as f32)));
+        values.push(LogValue::UInt(log.num_joints as u32));
+        for (_, vector) in joint_vectors.iter() {
+            for joint in 0..self.max_log_dof {
+                let value = match vector {
+                    Some(vector) if joint < log.num_joints => vector[joint],
+                    _ => 0.,
+                };
+                values.push(LogValue::Float(value as f32));
+            }
+        }
+        self.writer.write_record(&values)
+    }
+    /// flushes the underlying writer.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush()
     }
-    size
+    /// flushes the log and returns the underlying writer.
+    pub fn into_inner(self) -> Result<W, Error> {
+        self.writer.into_inner()
+    }
+}
+
+/// Replays [`GenericRobotLog`](`GenericRobotLog`) entries in a running simulation.
+///
+/// The player applies the logged base poses, base velocities and joint states with
+/// [`reset_base_transform`](`crate::PhysicsClient::reset_base_transform`),
+/// [`reset_base_velocity`](`crate::PhysicsClient::reset_base_velocity`) and
+/// [`reset_joint_state`](`crate::PhysicsClient::reset_joint_state`) at the recorded time stamps.
+/// Logged bodies are applied to the body with the same id unless they are mapped to another body
+/// with [`map_body`](`Self::map_body`).
+///
+/// The playback time starts at the time stamp of the first entry. Either call
+/// [`advance`](`Self::advance`) in your own loop or use [`play`](`Self::play`) to replay the log
+/// in real time.
+/// # Example
+/// ```no_run
+/// use anyhow::Result;
+/// use misfire::logging_utils::LogPlayer;
+/// use misfire::*;
+///
+/// fn main() -> Result<()> {
+///     let mut client = PhysicsClient::connect(Mode::Gui)?;
+///     client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
+///     let r2d2 = client.load_urdf("r2d2.urdf", None)?;
+///     let mut player = LogPlayer::open("LOG0001.txt")?;
+///     // the robot might have had another id when the log was recorded
+///     let logged = player.logs()[0].body;
+///     player.map_body(logged, r2d2);
+///     player.set_speed(2.)?;
+///     player.play(&mut client)?;
+///     Ok(())
+/// }
+/// ```
+pub struct LogPlayer {
+    logs: Vec<GenericRobotLog>,
+    body_map: HashMap<BodyId, BodyId>,
+    speed: f64,
+    looping: bool,
+    position: usize,
+    time: Duration,
+}
+
+impl LogPlayer {
+    /// creates a player for the given entries. The entries are sorted by their time stamp.
+    pub fn new(mut logs: Vec<GenericRobotLog>) -> LogPlayer {
+        logs.sort_by_key(|log| log.time_stamp);
+        LogPlayer {
+            logs,
+            body_map: HashMap::new(),
+            speed: 1.,
+            looping: false,
+            position: 0,
+            time: Duration::default(),
+        }
+    }
+    /// creates a player from a stream of entries, e.g. from
+    /// [`LogReader::entries`](`LogReader::entries`). The entries are read into memory, so that the
+    /// player can seek.
+    pub fn from_entries<I: IntoIterator<Item = Result<GenericRobotLog, Error>>>(
+        entries: I,
+    ) -> Result<LogPlayer, Error> {
+        Ok(LogPlayer::new(
+            entries.into_iter().collect::<Result<_, _>>()?,
+        ))
+    }
+    /// reads a log file which was generated with [`GenericRobot`](`crate::types::LoggingType::GenericRobot`)
+    /// [`LoggingType`](`crate::types::LoggingType`).
+    pub fn open<P: AsRef<Path>>(filename: P) -> Result<LogPlayer, Error> {
+        LogPlayer::from_entries(LogReader::open(filename)?.entries())
+    }
+    /// applies the entries of the logged body to another body.
+    pub fn map_body(&mut self, logged: BodyId, current: BodyId) {
+        self.body_map.insert(logged, current);
+    }
+    /// sets the speed factor of the playback. The default is 1.
+    ///
+    /// Returns an error with [`ErrorKind::InvalidArgument`](`crate::ErrorKind::InvalidArgument`)
+    /// if the speed is not a positive finite number.
+    pub fn set_speed(&mut self, speed: f64) -> Result<(), Error> {
+        if !speed.is_finite() || speed <= 0. {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!("the playback speed has to be positive, but is {}", speed),
+            ));
+        }
+        self.speed = speed;
+        Ok(())
+    }
+    /// starts the playback from the beginning when the end of the log is reached. The default is
+    /// `false`.
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+    /// returns the entries of the player.
+    pub fn logs(&self) -> &[GenericRobotLog] {
+        &self.logs
+    }
+    /// returns the duration of the log.
+    pub fn duration(&self) -> Duration {
+        match (self.logs.first(), self.logs.last()) {
+            (Some(first), Some(last)) => last.time_stamp - first.time_stamp,
+            _ => Duration::default(),
+        }
+    }
+    /// returns the current playback time, relative to the first entry.
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+    /// returns true if all entries were applied and the player does not loop.
+    pub fn is_finished(&self) -> bool {
+        !self.looping && self.position == self.logs.len()
+    }
+    /// jumps to the given playback time and applies the latest entry of every body at that time.
+    pub fn seek(&mut self, client: &mut PhysicsClient, time: Duration) -> Result<(), Error> {
+        self.time = time.min(self.duration());
+        let end = self.end_of(self.time);
+        self.apply_latest(client, 0..end)?;
+        self.position = end;
+        Ok(())
+    }
+    /// advances the playback time by `dt` times the speed factor and applies the entries which
+    /// were recorded in the meantime. Only the latest entry of every body is applied.
+    /// Returns `false` when the end of the log is reached and the player does not loop.
+    pub fn advance(&mut self, client: &mut PhysicsClient, dt: Duration) -> Result<bool, Error> {
+        let time = self.time + dt.mul_f64(self.speed);
+        let duration = self.duration();
+        if time > duration && self.looping {
+            let time = match duration.as_secs_f64() {
+                d if d > 0. => Duration::from_secs_f64(time.as_secs_f64() % d),
+                _ => Duration::default(),
+            };
+            return self.seek(client, time).map(|_| true);
+        }
+        self.time = time.min(duration);
+        let end = self.end_of(self.time);
+        self.apply_latest(client, self.position..end)?;
+        self.position = end;
+        Ok(!self.is_finished())
+    }
+    /// replays the log in real time until it ends. This never returns if the player loops.
+    pub fn play(&mut self, client: &mut PhysicsClient) -> Result<(), Error> {
+        const FRAME: Duration = Duration::from_millis(1000 / 240);
+        let mut last = std::time::Instant::now();
+        if self.position == 0 {
+            self.seek(client, self.time)?;
+        }
+        loop {
+            std::thread::sleep(FRAME);
+            let now = std::time::Instant::now();
+            if !self.advance(client, now - last)? {
+                return Ok(());
+            }
+            last = now;
+        }
+    }
+    /// returns the index of the first entry after the playback time.
+    fn end_of(&self, time: Duration) -> usize {
+        match self.logs.first() {
+            Some(first) => {
+                let time_stamp = first.time_stamp + time;
+                self.logs
+                    .partition_point(|log| log.time_stamp <= time_stamp)
+            }
+            None => 0,
+        }
+    }
+    fn apply_latest(
+        &self,
+        client: &mut PhysicsClient,
+        range: std::ops::Range<usize>,
+    ) -> Result<(), Error> {
+        let mut applied = Vec::new();
+        for log in self.logs[range].iter().rev() {
+            if applied.contains(&log.body) {
+                continue;
+            }
+            applied.push(log.body);
+            self.apply(client, log)?;
+        }
+        Ok(())
+    }
+    fn apply(&self, client: &mut PhysicsClient, log: &GenericRobotLog) -> Result<(), Error> {
+        let body = self.body_map.get(&log.body).copied().unwrap_or(log.body);
+        client.reset_base_transform(body, log.base_pose)?;
+        client.reset_base_velocity(
+            body,
+            log.base_velocity.get_linear_velocity(),
+            log.base_velocity.get_angular_velocity(),
+        )?;
+        // the logger only records revolute and prismatic joints, in the order of their indices
+        let num_joints = client.get_num_joints(body)?;
+        let mut movable_joints = Vec::with_capacity(log.num_joints);
+        for joint in 0..num_joints {
+            let info = client.get_joint_info(body, joint)?;
+            if matches!(info.joint_type, JointType::Revolute | JointType::Prismatic) {
+                movable_joints.push(joint);
+            }
+        }
+        let values = log.joint_positions.iter().zip(log.joint_velocities.iter());
+        for (&joint, (&position, &velocity)) in
+            movable_joints.iter().zip(values).take(log.num_joints)
+        {
+            client.reset_joint_state(body, joint, position, velocity)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_all<W: Write>(writer: &mut W, bytes: &[u8]) -> Result<(), Error> {
+    writer
+        .write_all(bytes)
+        .map_err(|err| Error::with(format!("could not write log file: {}", err)))
+}
+
+fn missing_field(key: &str) -> Error {
+    Error::of_kind(
+        ErrorKind::ParseError,
+        format!("the log has no field named {}", key),
+    )
+}
+
+fn read_header_line<R: BufRead>(reader: &mut R) -> Result<String, Error> {
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .map_err(|err| Error::with(format!("could not read log header: {}", err)))?;
+    match line.strip_suffix('\n') {
+        Some(line) => Ok(line.to_string()),
+        None => Err(Error::of_kind(
+            ErrorKind::ParseError,
+            "the log file ends in the middle of its header",
+        )),
+    }
+}
+
+/// fills the buffer as far as possible and returns the number of bytes which were read.
+fn read_up_to<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<usize, Error> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(Error::with(format!("could not read log file: {}", err))),
+        }
+    }
+    Ok(filled)
 }
 
 /// reads log files which were generated with [`GenericRobot`](`crate::types::LoggingType::GenericRobot`)
 /// [`LoggingType`](`crate::types::LoggingType`).
-/// it returns a list of all entries in the log or an error if the file could not be openend.
+/// it returns a list of all entries in the log or an error if the file could not be opened or
+/// is corrupted. Use a [`LogReader`](`LogReader`) to read large files entry by entry.
 /// # Arguments
 /// * `filename` - location of the log file.
 /// # Example
@@ -93,78 +1224,5 @@ fn calc_size(fmt: &str) -> usize {
 /// let logs = read_generic_robot_log("LOG0001.txt").unwrap();
 /// ```
 pub fn read_generic_robot_log<P: AsRef<Path>>(filename: P) -> Result<Vec<GenericRobotLog>, Error> {
-    let file = File::open(filename).map_err(|_| Error::new("could not open file"))?;
-    let mut reader = BufReader::new(file);
-    let mut key_buf = String::new();
-    reader
-        .read_line(&mut key_buf)
-        .expect("error while reading file");
-    let mut fmt_buf = String::new();
-    reader
-        .read_line(&mut fmt_buf)
-        .expect("error while reading file");
-    let fmt = fmt_buf.strip_suffix("\n").unwrap();
-    assert_eq!("IfifffffffffffffI", &fmt[0..17]);
-    let sz = calc_size(fmt);
-    let mut chunk_index = 0;
-    let mut logs = Vec::<GenericRobotLog>::new();
-    loop {
-        let mut check_buf = [0_u8; 2];
-        match reader.read_exact(&mut check_buf) {
-            Ok(_) => {}
-            Err(_) => {
-                return Ok(logs);
-            }
-        }
-        assert_eq!(
-            &check_buf,
-            &[170_u8, 187_u8],
-            "Error, expected aabb terminal"
-        );
-
-        let mut buf = vec![0_u8; sz];
-        reader.read_exact(&mut buf).unwrap();
-        let mut log = GenericRobotLog {
-            chunk_number: chunk_index,
-            ..Default::default()
-        };
-        chunk_index += 1;
-        log.step_count = u32::from_le_bytes(buf[0..4].try_into().unwrap()) as usize;
-        log.time_stamp = Duration::from_secs_f32(f32::from_le_bytes(buf[4..8].try_into().unwrap()));
-        log.body = BodyId(i32::from_le_bytes(buf[8..12].try_into().unwrap()));
-        assert!(log.body.0 >= 0);
-        let mut pose_elements = [0.; 7];
-        for i in 0..7 {
-            pose_elements[i] =
-                f32::from_le_bytes(buf[12 + 4 * i..16 + 4 * i].try_into().unwrap()) as f64;
-        }
-        log.base_pose = combined_position_orientation_array_to_isometry(pose_elements);
-        let mut velocity_elements = [0.; 6];
-        for i in 0..6 {
-            velocity_elements[i] =
-                f32::from_le_bytes(buf[40 + 4 * i..44 + 4 * i].try_into().unwrap()) as f64;
-        }
-        let vel_vec: Vector6<f64> = velocity_elements.into();
-        log.base_velocity = vel_vec.into();
-        log.num_joints = u32::from_le_bytes(buf[64..68].try_into().unwrap()) as usize;
-        let remaining = sz - 68;
-        assert_eq!(remaining % 8, 0);
-        let mut joint_positions = DVector::<f64>::from(vec![0.; log.num_joints]);
-        for i in 0..log.num_joints {
-            joint_positions[i] =
-                f32::from_le_bytes(buf[68 + 4 * i..72 + 4 * i].try_into().unwrap()) as f64;
-        }
-        let mut joint_velocities = DVector::<f64>::from(vec![0.; log.num_joints]);
-        let start_byte = 68 + remaining / 2;
-        for i in 0..log.num_joints {
-            joint_velocities[i] = f32::from_le_bytes(
-                buf[start_byte + 4 * i..start_byte + 4 + 4 * i]
-                    .try_into()
-                    .unwrap(),
-            ) as f64;
-        }
-        log.joint_positions = joint_positions;
-        log.joint_velocities = joint_velocities;
-        logs.push(log);
-    }
+    LogReader::open(filename)?.entries().collect()
 }
@@ -1,21 +1,37 @@
 //! Various connection modes used by Bullet.
 
 /// Ways to connect to physics clients.
+///
+/// The modes which start a physics server within the process, apart from
+/// [`Direct`](`Self::Direct`), are part of the example browser and require the `gui` feature,
+/// which is enabled by default. Without it, misfire builds without OpenGL and X11.
 #[non_exhaustive]
 pub enum Mode<'a> {
     /// Starts a physics server within the process and connects without a transport layer.
     Direct,
 
     /// Creates a physics server with a graphical frontend and communicates with it.
+    #[cfg(feature = "gui")]
     Gui,
+    #[cfg(feature = "gui")]
     GuiMainThread,
     /// can be used together with sharedMemory
+    #[cfg(feature = "gui")]
     GuiServer,
     /// can be used together with sharedMemory
-    SharedMemoryServer,
-    SharedMemoryGui,
+    #[cfg(feature = "gui")]
+    SharedMemoryServer {
+        /// key of the shared memory segment. `None` is for the default key `12347`
+        key: Option<i32>,
+    },
+    #[cfg(feature = "gui")]
+    SharedMemoryGui {
+        /// key of the shared memory segment. `None` is for the default key `12347`
+        key: Option<i32>,
+    },
     /// connects to a remote [`PhysicsServer`](`crate::PhysicsServer`) via TCP.
     /// See `graphics_server.rs` and `graphics_client.rs` demo
+    #[cfg(feature = "gui")]
     GraphicsServerTcp {
         /// hostname of the server.
         hostname: &'a str,
@@ -23,7 +39,11 @@ pub enum Mode<'a> {
         port: Option<u16>,
     },
     /// get access to an already running PhysicsClient which was created with `GuiServer` or `SharedMemoryServer`
-    SharedMemory,
+    SharedMemory {
+        /// key of the shared memory segment. It has to match the key of the server.
+        /// `None` is for the default key `12347`, which is also used by `GuiServer`.
+        key: Option<i32>,
+    },
 
     Udp {
         /// hostname of the server.
@@ -0,0 +1,261 @@
+//! Owning guards for server resources and the generations of ids.
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::os::raw::c_int;
+
+use crate::types::Generation;
+use crate::{BodyId, ConstraintId, Error, ErrorKind, ItemId, LogId, PhysicsClient, StateId};
+
+/// Remembers the generation of every id which was created by a client and is still alive.
+#[derive(Debug, Default)]
+pub(crate) struct Generations {
+    last: u32,
+    bodies: HashMap<c_int, Generation>,
+    constraints: HashMap<c_int, Generation>,
+    items: HashMap<c_int, Generation>,
+    logs: HashMap<c_int, Generation>,
+    states: HashMap<c_int, Generation>,
+    /// the generations of the bodies at the time each state was saved
+    saved_bodies: HashMap<c_int, HashMap<c_int, Generation>>,
+}
+
+/// An id with a [`Generation`].
+pub(crate) trait GenerationalId: Copy {
+    /// name of the id type for error messages
+    const NAME: &'static str;
+    /// error kind for stale ids
+    const ERROR_KIND: ErrorKind;
+    fn new(id: c_int, generation: Generation) -> Self;
+    fn raw(&self) -> c_int;
+    fn generation(&self) -> Generation;
+    fn registry(generations: &mut Generations) -> &mut HashMap<c_int, Generation>;
+}
+
+macro_rules! impl_generational_id {
+    ($id:ident, $registry:ident, $error_kind:ident) => {
+        impl GenerationalId for $id {
+            const NAME: &'static str = stringify!($id);
+            const ERROR_KIND: ErrorKind = ErrorKind::$error_kind;
+            fn new(id: c_int, generation: Generation) -> $id {
+                $id(id, generation)
+            }
+            fn raw(&self) -> c_int {
+                self.0
+            }
+            fn generation(&self) -> Generation {
+                self.1
+            }
+            fn registry(generations: &mut Generations) -> &mut HashMap<c_int, Generation> {
+                &mut generations.$registry
+            }
+        }
+    };
+}
+
+impl_generational_id!(BodyId, bodies, InvalidBodyId);
+impl_generational_id!(ConstraintId, constraints, InvalidArgument);
+impl_generational_id!(ItemId, items, InvalidArgument);
+impl_generational_id!(LogId, logs, InvalidArgument);
+impl_generational_id!(StateId, states, InvalidArgument);
+
+impl Generations {
+    /// creates the id of a new object with a new generation.
+    pub(crate) fn create<Id: GenerationalId>(&mut self, id: c_int) -> Id {
+        if id < 0 {
+            return Id::new(id, Generation::UNKNOWN);
+        }
+        self.last += 1;
+        let generation = Generation(self.last);
+        Id::registry(self).insert(id, generation);
+        Id::new(id, generation)
+    }
+    /// creates the id of an object which already exists, e.g. when it is returned by a query.
+    pub(crate) fn existing<Id: GenerationalId>(&mut self, id: c_int) -> Id {
+        let generation = Id::registry(self)
+            .get(&id)
+            .copied()
+            .unwrap_or(Generation::UNKNOWN);
+        Id::new(id, generation)
+    }
+    /// returns an error if the object of the id was removed.
+    pub(crate) fn check<Id: GenerationalId>(&mut self, id: Id) -> Result<(), Error> {
+        if id.generation() == Generation::UNKNOWN
+            || Id::registry(self).get(&id.raw()) == Some(&id.generation())
+        {
+            return Ok(());
+        }
+        Err(Error::of_kind(
+            Id::ERROR_KIND,
+            format!(
+                "stale {}: object {} was removed and its id is no longer valid",
+                Id::NAME,
+                id.raw()
+            ),
+        ))
+    }
+    /// marks the object of the id as removed.
+    pub(crate) fn remove<Id: GenerationalId>(&mut self, id: Id) {
+        Id::registry(self).remove(&id.raw());
+    }
+    /// remembers which bodies exist when a state is saved.
+    pub(crate) fn save_state(&mut self, state: StateId) {
+        self.saved_bodies.insert(state.0, self.bodies.clone());
+    }
+    /// restoring a state brings back the bodies which existed when it was saved, so their ids
+    /// become valid again.
+    pub(crate) fn restore_state(&mut self, state: StateId) {
+        if let Some(bodies) = self.saved_bodies.get(&state.0) {
+            self.bodies.extend(bodies);
+        }
+    }
+    /// forgets the bodies of a removed state.
+    pub(crate) fn remove_state(&mut self, state: StateId) {
+        self.remove(state);
+        self.saved_bodies.remove(&state.0);
+    }
+    /// marks all objects as removed which are removed by resetting the simulation.
+    pub(crate) fn reset_simulation(&mut self) {
+        self.bodies.clear();
+        self.constraints.clear();
+        self.items.clear();
+        self.saved_bodies.clear();
+    }
+}
+
+/// A resource on the physics server which can be released by a client.
+pub trait Resource: Copy {
+    /// releases the resource, e.g. by removing the body.
+    fn release(self, client: &mut PhysicsClient) -> Result<(), Error>;
+}
+
+impl Resource for BodyId {
+    fn release(self, client: &mut PhysicsClient) -> Result<(), Error> {
+        client.remove_body(self)
+    }
+}
+
+impl Resource for ConstraintId {
+    fn release(self, client: &mut PhysicsClient) -> Result<(), Error> {
+        client.remove_constraint(self)
+    }
+}
+
+impl Resource for ItemId {
+    fn release(self, client: &mut PhysicsClient) -> Result<(), Error> {
+        client.remove_user_debug_item(self)
+    }
+}
+
+impl Resource for LogId {
+    fn release(self, client: &mut PhysicsClient) -> Result<(), Error> {
+        client.stop_state_logging(self)
+    }
+}
+
+impl Resource for StateId {
+    fn release(self, client: &mut PhysicsClient) -> Result<(), Error> {
+        client.remove_state(self)
+    }
+}
+
+/// Owns a resource on the physics server and releases it when it is dropped.
+///
+/// The guard borrows the client mutably for its whole lifetime, but it dereferences to the client,
+/// so the simulation can be used through the guard. Guards can be nested this way and are released
+/// in reverse order. Errors while releasing the resource in `drop` are ignored; use
+/// [`release`](`Self::release`) to handle them.
+///
+/// A guard is created with [`PhysicsClient::own`](`crate::PhysicsClient::own`).
+///
+/// # Example
+/// ```no_run
+/// use anyhow::Result;
+/// use misfire::*;
+///
+/// fn main() -> Result<()> {
+///     let mut client = PhysicsClient::connect(Mode::Direct)?;
+///     client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
+///     let plane = client.load_urdf("plane.urdf", None)?;
+///     {
+///         let r2d2 = client.load_urdf("r2d2.urdf", None)?;
+///         let mut r2d2 = client.own(r2d2);
+///         let log = r2d2.start_state_logging(
+///             LoggingType::GenericRobot,
+///             "r2d2.log",
+///             StateLoggingOptions::default(),
+///         )?;
+///         let mut log = r2d2.own(log);
+///         for _ in 0..100 {
+///             log.step_simulation()?;
+///         }
+///         // the logging stops and the robot is removed here
+///     }
+///     assert_eq!(client.get_num_bodies()?, 1);
+///     client.remove_body(plane)?;
+///     Ok(())
+/// }
+/// ```
+pub struct Owned<'c, R: Resource> {
+    client: &'c mut PhysicsClient,
+    resource: Option<R>,
+}
+
+/// A body which is removed when the guard is dropped.
+pub type OwnedBody<'c> = Owned<'c, BodyId>;
+/// A constraint which is removed when the guard is dropped.
+pub type OwnedConstraint<'c> = Owned<'c, ConstraintId>;
+/// A user debug item which is removed when the guard is dropped.
+pub type OwnedDebugItem<'c> = Owned<'c, ItemId>;
+/// A state logger which is stopped when the guard is dropped.
+pub type StateLoggingGuard<'c> = Owned<'c, LogId>;
+/// A saved state which is removed when the guard is dropped.
+pub type OwnedState<'c> = Owned<'c, StateId>;
+
+impl<'c, R: Resource> Owned<'c, R> {
+    pub(crate) fn new(client: &'c mut PhysicsClient, resource: R) -> Owned<'c, R> {
+        Owned {
+            client,
+            resource: Some(resource),
+        }
+    }
+    /// returns the id of the resource.
+    pub fn id(&self) -> R {
+        self.resource
+            .expect("the resource is only taken when the guard is consumed")
+    }
+    /// releases the resource now and reports errors.
+    pub fn release(mut self) -> Result<(), Error> {
+        match self.resource.take() {
+            Some(resource) => resource.release(self.client),
+            None => Ok(()),
+        }
+    }
+    /// gives up the ownership without releasing the resource and returns its id.
+    pub fn keep(mut self) -> R {
+        self.resource
+            .take()
+            .expect("the resource is only taken when the guard is consumed")
+    }
+}
+
+impl<R: Resource> Deref for Owned<'_, R> {
+    type Target = PhysicsClient;
+
+    fn deref(&self) -> &PhysicsClient {
+        self.client
+    }
+}
+
+impl<R: Resource> DerefMut for Owned<'_, R> {
+    fn deref_mut(&mut self) -> &mut PhysicsClient {
+        self.client
+    }
+}
+
+impl<R: Resource> Drop for Owned<'_, R> {
+    fn drop(&mut self) {
+        if let Some(resource) = self.resource.take() {
+            let _ = resource.release(self.client);
+        }
+    }
+}
@@ -0,0 +1,607 @@
+//! Non-blocking command submission.
+//!
+//! Normally every [`PhysicsClient`](`crate::PhysicsClient`) method submits a command and then
+//! blocks until the physics server answered. With [`Mode::Tcp`](`crate::Mode::Tcp`) or
+//! [`Mode::Udp`](`crate::Mode::Udp`) this costs a full network round trip per call. The `*_async`
+//! methods of the client instead return a [`PendingCommand`] right after the command was sent,
+//! so that the calling thread can do other work during that one round trip.
+//!
+//! Bullet only allows one command in flight per client, so a [`PendingCommand`] borrows the
+//! client mutably until it is resolved, either by polling it, by
+//! [`wait`](`PendingCommand::wait`) or by `.await`ing it. To send several commands without
+//! blocking in between, queue them in a [`CommandBatch`], which is created with
+//! [`PhysicsClient::batch`](`crate::PhysicsClient::batch`). The batch sends the next command as
+//! soon as the answer to the previous one arrived, and all results are collected together in a
+//! [`BatchResults`]. To run several commands at the same time, use several clients.
+use std::any::Any;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::Mutex;
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+use misfire_sys as ffi;
+use misfire_sys::{b3SharedMemoryCommandHandle, b3SharedMemoryStatusHandle};
+
+use nalgebra::Isometry3;
+
+use crate::client::{base_transform_from_status, check_step_simulation_status};
+use crate::{BodyId, ControlCommandArray, Error, ErrorKind, JointState, PhysicsClient};
+
+type Finish<'a, T> =
+    Box<dyn FnOnce(&mut PhysicsClient, b3SharedMemoryStatusHandle) -> Result<T, Error> + 'a>;
+
+/// A command which was submitted to the physics server, but whose answer was not yet processed.
+///
+/// It is returned by the `*_async` methods of [`PhysicsClient`](`crate::PhysicsClient`), e.g.
+/// [`step_simulation_async`](`crate::PhysicsClient::step_simulation_async`).
+/// The result can be obtained with [`wait`](`Self::wait`), by checking [`is_ready`](`Self::is_ready`)
+/// from time to time or by `.await`ing the command. The future checks the server every
+/// millisecond, so it works with any executor and does not need a reactor.
+///
+/// Dropping an unresolved `PendingCommand` does not block. The client throws the outstanding
+/// answer away before it submits its next command, so that command waits for it instead. If the
+/// server does not answer in time, the command fails with
+/// [`Disconnected`](`crate::ErrorKind::Disconnected`) and so do the following commands until the
+/// answer finally arrives.
+///
+/// # Example
+/// ```no_run
+/// use anyhow::Result;
+/// use misfire::*;
+///
+/// fn main() -> Result<()> {
+///     let mut client = PhysicsClient::connect(Mode::Tcp {
+///         hostname: "localhost",
+///         port: None,
+///     })?;
+///     client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
+///     let r2d2 = client.load_urdf("r2d2.urdf", None)?;
+///     let mut step = client.step_simulation_async()?;
+///     while !step.is_ready() {
+///         // do something useful while the server is stepping the simulation
+///     }
+///     step.wait()?;
+///     let joint_states = client.get_joint_states_async(r2d2, &[2, 3])?.wait()?;
+///     println!("{:?}", joint_states);
+///     Ok(())
+/// }
+/// ```
+#[must_use = "a PendingCommand does nothing unless it is resolved"]
+pub struct PendingCommand<'a, T> {
+    client: &'a mut PhysicsClient,
+    status_handle: Option<b3SharedMemoryStatusHandle>,
+    finish: Option<Finish<'a, T>>,
+    deadline: Instant,
+}
+
+impl<'a, T> PendingCommand<'a, T> {
+    /// Submits a command without waiting for the answer. `finish` is called with the status
+    /// handle once the server answered.
+    ///
+    /// # Safety
+    /// `command_handle` has to be a valid command which was created with `client.handle`.
+    pub(crate) unsafe fn submit<F>(
+        client: &'a mut PhysicsClient,
+        command_handle: b3SharedMemoryCommandHandle,
+        finish: F,
+    ) -> Result<PendingCommand<'a, T>, Error>
+    where
+        F: FnOnce(&mut PhysicsClient, b3SharedMemoryStatusHandle) -> Result<T, Error> + 'a,
+    {
+        if !client.drain_status() {
+            return Err(Error::disconnected());
+        }
+        let time_out = ffi::b3GetTimeOut(client.handle);
+        if ffi::b3SubmitClientCommand(client.handle, command_handle) == 0 {
+            return Err(Error::disconnected());
+        }
+        Ok(PendingCommand {
+            client,
+            status_handle: None,
+            finish: Some(Box::new(finish)),
+            deadline: Instant::now() + Duration::from_secs_f64(time_out.max(0.)),
+        })
+    }
+    /// Checks whether the server answered. It never blocks.
+    pub fn is_ready(&mut self) -> bool {
+        if self.status_handle.is_none() {
+            let status_handle = unsafe { ffi::b3ProcessServerStatus(self.client.handle) };
+            if !status_handle.is_null() {
+                self.status_handle = Some(status_handle);
+            }
+        }
+        self.status_handle.is_some()
+    }
+    /// Blocks until the server answered and returns the result of the command.
+    pub fn wait(mut self) -> Result<T, Error> {
+        self.block();
+        self.complete()
+    }
+    fn block(&mut self) {
+        let mut backoff = Backoff::new();
+        while !self.is_ready() && Instant::now() < self.deadline {
+            backoff.wait();
+        }
+    }
+    fn complete(&mut self) -> Result<T, Error> {
+        let finish = self.finish.take().ok_or_else(|| {
+            Error::of_kind(
+                ErrorKind::InvalidArgument,
+                "the result of the command was already taken",
+            )
+        })?;
+        match self.status_handle {
+            Some(status_handle) => finish(self.client, status_handle),
+            None => {
+                self.client.awaiting_status = true;
+                Err(Error::of_kind(
+                    ErrorKind::Disconnected,
+                    "the physics server did not answer in time",
+                ))
+            }
+        }
+    }
+}
+
+impl<T> Future for PendingCommand<'_, T> {
+    type Output = Result<T, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.is_ready() || Instant::now() >= self.deadline {
+            Poll::Ready(self.complete())
+        } else {
+            wake_later(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl<T> Drop for PendingCommand<'_, T> {
+    fn drop(&mut self) {
+        if self.finish.is_some() && !self.is_ready() {
+            self.client.awaiting_status = true;
+        }
+    }
+}
+
+type Build<'a> =
+    Box<dyn FnOnce(&mut PhysicsClient) -> Result<b3SharedMemoryCommandHandle, Error> + 'a>;
+
+/// A queue of commands which are sent to the physics server one after another, without blocking
+/// the calling thread in between.
+///
+/// It is created with [`PhysicsClient::batch`](`crate::PhysicsClient::batch`). Queuing a command
+/// returns a [`BatchTicket`], with which its result can be taken from the [`BatchResults`] once
+/// the whole batch was resolved, either by [`wait`](`Self::wait`), by checking
+/// [`is_ready`](`Self::is_ready`) from time to time or by `.await`ing the batch.
+/// The first command is sent as soon as it is queued and every following one as soon as the
+/// server answered the one before.
+///
+/// Each command is built right before it is sent, so its arguments are checked against the state
+/// which the previous commands left behind. A command which fails does not stop the batch. If the
+/// server does not answer in time, that command and all commands queued after it fail with
+/// [`Disconnected`](`crate::ErrorKind::Disconnected`).
+///
+/// Dropping an unresolved batch does not block. The commands which were not sent yet are thrown
+/// away and the answer to the one in flight is handled like the one of a dropped
+/// [`PendingCommand`].
+///
+/// # Example
+/// ```no_run
+/// use anyhow::Result;
+/// use misfire::*;
+///
+/// fn main() -> Result<()> {
+///     let mut client = PhysicsClient::connect(Mode::Tcp {
+///         hostname: "localhost",
+///         port: None,
+///     })?;
+///     client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
+///     let r2d2 = client.load_urdf("r2d2.urdf", None)?;
+///     let mut batch = client.batch();
+///     let motors = batch.set_joint_motor_control_array(
+///         r2d2,
+///         &[2, 3],
+///         ControlCommandArray::Velocities(&[1., 1.]),
+///         None,
+///     );
+///     let step = batch.step_simulation();
+///     let joint_states = batch.get_joint_states(r2d2, &[2, 3]);
+///     let base = batch.get_base_transform(r2d2);
+///     let mut results = batch.wait();
+///     results.take(motors)?;
+///     results.take(step)?;
+///     println!("{:?}", results.take(joint_states)?);
+///     println!("{}", results.take(base)?);
+///     Ok(())
+/// }
+/// ```
+#[must_use = "a CommandBatch does nothing unless it is resolved"]
+pub struct CommandBatch<'a> {
+    client: &'a mut PhysicsClient,
+    queue: VecDeque<QueuedCommand<'a>>,
+    in_flight: Option<InFlight<'a>>,
+    results: Vec<Option<Result<Box<dyn Any>, Error>>>,
+}
+
+struct QueuedCommand<'a> {
+    index: usize,
+    build: Build<'a>,
+    finish: Finish<'a, Box<dyn Any>>,
+}
+
+struct InFlight<'a> {
+    index: usize,
+    finish: Finish<'a, Box<dyn Any>>,
+    deadline: Instant,
+}
+
+impl<'a> CommandBatch<'a> {
+    pub(crate) fn new(client: &'a mut PhysicsClient) -> CommandBatch<'a> {
+        CommandBatch {
+            client,
+            queue: VecDeque::new(),
+            in_flight: None,
+            results: Vec::new(),
+        }
+    }
+    /// Queues [`set_joint_motor_control_array`](`crate::PhysicsClient::set_joint_motor_control_array`).
+    pub fn set_joint_motor_control_array(
+        &mut self,
+        body: BodyId,
+        joint_indices: &[usize],
+        control_command: ControlCommandArray,
+        maximum_force: Option<&[f64]>,
+    ) -> BatchTicket<()> {
+        let joint_indices = joint_indices.to_vec();
+        let control_command = OwnedControlCommandArray::from(control_command);
+        let maximum_force = maximum_force.map(<[f64]>::to_vec);
+        self.push(
+            move |client| {
+                client.generations.check(body)?;
+                client.joint_motor_control_array_command(
+                    body,
+                    &joint_indices,
+                    control_command.borrow(),
+                    maximum_force.as_deref(),
+                )
+            },
+            |_, _| Ok(()),
+        )
+    }
+    /// Queues [`step_simulation`](`crate::PhysicsClient::step_simulation`).
+    pub fn step_simulation(&mut self) -> BatchTicket<()> {
+        self.push(
+            |client| unsafe { Ok(ffi::b3InitStepSimulationCommand(client.handle)) },
+            |_, status_handle| unsafe { check_step_simulation_status(status_handle) },
+        )
+    }
+    /// Queues [`get_joint_states`](`crate::PhysicsClient::get_joint_states`).
+    pub fn get_joint_states(
+        &mut self,
+        body: BodyId,
+        joint_indices: &[usize],
+    ) -> BatchTicket<Vec<JointState>> {
+        let joint_indices = joint_indices.to_vec();
+        let requested_indices = joint_indices.clone();
+        self.push(
+            move |client| {
+                client.check_joint_states_request(body, &requested_indices)?;
+                unsafe { Ok(ffi::b3RequestActualStateCommandInit(client.handle, body.0)) }
+            },
+            move |client, status_handle| {
+                let num_joints = client.get_num_joints(body)?;
+                unsafe {
+                    client.joint_states_from_status(status_handle, &joint_indices, num_joints)
+                }
+            },
+        )
+    }
+    /// Queues [`get_base_transform`](`crate::PhysicsClient::get_base_transform`).
+    pub fn get_base_transform(&mut self, body: BodyId) -> BatchTicket<Isometry3<f64>> {
+        self.push(
+            move |client| {
+                client.generations.check(body)?;
+                unsafe { Ok(ffi::b3RequestActualStateCommandInit(client.handle, body.0)) }
+            },
+            |_, status_handle| unsafe { base_transform_from_status(status_handle) },
+        )
+    }
+    /// Checks whether the server answered all queued commands and sends the next one whenever
+    /// the previous one was answered. It never blocks.
+    pub fn is_ready(&mut self) -> bool {
+        self.advance();
+        self.in_flight.is_none() && self.queue.is_empty()
+    }
+    /// Blocks until the server answered all queued commands and returns their results.
+    pub fn wait(mut self) -> BatchResults {
+        let mut backoff = Backoff::new();
+        while !self.is_ready() {
+            backoff.wait();
+        }
+        self.take_results()
+    }
+    fn push<T: 'static>(
+        &mut self,
+        build: impl FnOnce(&mut PhysicsClient) -> Result<b3SharedMemoryCommandHandle, Error> + 'a,
+        finish: impl FnOnce(&mut PhysicsClient, b3SharedMemoryStatusHandle) -> Result<T, Error> + 'a,
+    ) -> BatchTicket<T> {
+        let index = self.results.len();
+        self.results.push(None);
+        self.queue.push_back(QueuedCommand {
+            index,
+            build: Box::new(build),
+            finish: Box::new(move |client, status_handle| {
+                finish(client, status_handle).map(|value| Box::new(value) as Box<dyn Any>)
+            }),
+        });
+        self.advance();
+        BatchTicket {
+            index,
+            result: PhantomData,
+        }
+    }
+    /// Processes the answer to the command in flight, if there is one, and sends queued commands
+    /// until one of them is waiting for its answer or the queue is empty.
+    fn advance(&mut self) {
+        loop {
+            if let Some(in_flight) = self.in_flight.take() {
+                let status_handle = unsafe { ffi::b3ProcessServerStatus(self.client.handle) };
+                if !status_handle.is_null() {
+                    self.results[in_flight.index] =
+                        Some((in_flight.finish)(self.client, status_handle));
+                } else if Instant::now() < in_flight.deadline {
+                    self.in_flight = Some(in_flight);
+                    return;
+                } else {
+                    self.client.awaiting_status = true;
+                    self.results[in_flight.index] = Some(Err(Error::of_kind(
+                        ErrorKind::Disconnected,
+                        "the physics server did not answer in time",
+                    )));
+                    for queued in self.queue.drain(..) {
+                        self.results[queued.index] = Some(Err(Error::disconnected()));
+                    }
+                    return;
+                }
+            }
+            let queued = match self.queue.pop_front() {
+                Some(queued) => queued,
+                None => return,
+            };
+            match self.submit(queued.build) {
+                Ok(deadline) => {
+                    self.in_flight = Some(InFlight {
+                        index: queued.index,
+                        finish: queued.finish,
+                        deadline,
+                    })
+                }
+                Err(error) => self.results[queued.index] = Some(Err(error)),
+            }
+        }
+    }
+    /// Builds a command and sends it. Returns until when the server has to answer.
+    fn submit(&mut self, build: Build<'a>) -> Result<Instant, Error> {
+        if !self.client.can_submit_command() || !self.client.drain_status() {
+            return Err(Error::disconnected());
+        }
+        let command_handle = build(self.client)?;
+        unsafe {
+            let time_out = ffi::b3GetTimeOut(self.client.handle);
+            if ffi::b3SubmitClientCommand(self.client.handle, command_handle) == 0 {
+                return Err(Error::disconnected());
+            }
+            Ok(Instant::now() + Duration::from_secs_f64(time_out.max(0.)))
+        }
+    }
+    fn take_results(&mut self) -> BatchResults {
+        BatchResults {
+            results: std::mem::take(&mut self.results),
+        }
+    }
+}
+
+impl Future for CommandBatch<'_> {
+    type Output = BatchResults;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.is_ready() {
+            Poll::Ready(self.take_results())
+        } else {
+            wake_later(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for CommandBatch<'_> {
+    fn drop(&mut self) {
+        self.queue.clear();
+        if self.in_flight.is_some() && !self.is_ready() {
+            self.client.awaiting_status = true;
+        }
+    }
+}
+
+/// Identifies a command of a [`CommandBatch`] and the type of its result.
+#[derive(Debug)]
+pub struct BatchTicket<T> {
+    index: usize,
+    result: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for BatchTicket<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for BatchTicket<T> {}
+
+/// The results of all commands of a resolved [`CommandBatch`].
+pub struct BatchResults {
+    results: Vec<Option<Result<Box<dyn Any>, Error>>>,
+}
+
+impl BatchResults {
+    /// Takes the result of the command which returned `ticket` when it was queued.
+    /// Returns an [`InvalidArgument`](`crate::ErrorKind::InvalidArgument`) error if the result
+    /// was already taken or if the ticket was returned by another batch and does not match the
+    /// commands of this one.
+    pub fn take<T: 'static>(&mut self, ticket: BatchTicket<T>) -> Result<T, Error> {
+        let result = self
+            .results
+            .get_mut(ticket.index)
+            .and_then(Option::take)
+            .ok_or_else(|| {
+                Error::of_kind(
+                    ErrorKind::InvalidArgument,
+                    "the result of the command was already taken",
+                )
+            })?;
+        result?.downcast::<T>().map(|value| *value).map_err(|_| {
+            Error::of_kind(
+                ErrorKind::InvalidArgument,
+                "the ticket belongs to another batch",
+            )
+        })
+    }
+}
+
+/// An owned copy of a [`ControlCommandArray`], so that a [`CommandBatch`] can build the command
+/// later.
+enum OwnedControlCommandArray {
+    Positions(Vec<f64>),
+    PositionsWithPd([Vec<f64>; 4]),
+    Velocities(Vec<f64>),
+    Torques(Vec<f64>),
+    Pd([Vec<f64>; 4]),
+}
+
+impl From<ControlCommandArray<'_>> for OwnedControlCommandArray {
+    fn from(command: ControlCommandArray<'_>) -> Self {
+        match command {
+            ControlCommandArray::Positions(positions) => {
+                OwnedControlCommandArray::Positions(positions.to_vec())
+            }
+            ControlCommandArray::PositionsWithPd {
+                target_positions,
+                target_velocities,
+                position_gains,
+                velocity_gains,
+            } => OwnedControlCommandArray::PositionsWithPd([
+                target_positions.to_vec(),
+                target_velocities.to_vec(),
+                position_gains.to_vec(),
+                velocity_gains.to_vec(),
+            ]),
+            ControlCommandArray::Velocities(velocities) => {
+                OwnedControlCommandArray::Velocities(velocities.to_vec())
+            }
+            ControlCommandArray::Torques(torques) => {
+                OwnedControlCommandArray::Torques(torques.to_vec())
+            }
+            ControlCommandArray::Pd {
+                target_positions,
+                target_velocities,
+                position_gains,
+                velocity_gains,
+            } => OwnedControlCommandArray::Pd([
+                target_positions.to_vec(),
+                target_velocities.to_vec(),
+                position_gains.to_vec(),
+                velocity_gains.to_vec(),
+            ]),
+        }
+    }
+}
+
+impl OwnedControlCommandArray {
+    fn borrow(&self) -> ControlCommandArray<'_> {
+        match self {
+            OwnedControlCommandArray::Positions(positions) => {
+                ControlCommandArray::Positions(positions)
+            }
+            OwnedControlCommandArray::PositionsWithPd([positions, velocities, kp, kd]) => {
+                ControlCommandArray::PositionsWithPd {
+                    target_positions: positions,
+                    target_velocities: velocities,
+                    position_gains: kp,
+                    velocity_gains: kd,
+                }
+            }
+            OwnedControlCommandArray::Velocities(velocities) => {
+                ControlCommandArray::Velocities(velocities)
+            }
+            OwnedControlCommandArray::Torques(torques) => ControlCommandArray::Torques(torques),
+            OwnedControlCommandArray::Pd([positions, velocities, kp, kd]) => {
+                ControlCommandArray::Pd {
+                    target_positions: positions,
+                    target_velocities: velocities,
+                    position_gains: kp,
+                    velocity_gains: kd,
+                }
+            }
+        }
+    }
+}
+
+/// How long a polled [`PendingCommand`] or [`CommandBatch`] waits before it checks the server again.
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Sleeps between two checks of the server while blocking on an answer, so that waiting does not
+/// keep a core busy. The first pauses are short, as most answers arrive quickly, and then grow
+/// up to [`POLL_INTERVAL`].
+pub(crate) struct Backoff(Duration);
+
+impl Backoff {
+    pub(crate) fn new() -> Backoff {
+        Backoff(Duration::from_micros(10))
+    }
+    pub(crate) fn wait(&mut self) {
+        std::thread::sleep(self.0);
+        self.0 = (self.0 * 2).min(POLL_INTERVAL);
+    }
+}
+
+/// Wakes `waker` after [`POLL_INTERVAL`]. All wake-ups are handled by one timer thread, which is
+/// started on first use. As every wake-up is delayed by the same interval, they arrive in the
+/// order of their deadlines and the thread can simply sleep until the next one.
+fn wake_later(waker: Waker) {
+    static TIMER: Mutex<Option<Sender<(Instant, Waker)>>> = Mutex::new(None);
+    let deadline = Instant::now() + POLL_INTERVAL;
+    let mut timer = TIMER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if timer.is_none() {
+        let (sender, receiver) = channel::<(Instant, Waker)>();
+        let spawned = std::thread::Builder::new()
+            .name("misfire-pending-command".into())
+            .spawn(move || {
+                for (deadline, waker) in receiver {
+                    let now = Instant::now();
+                    if deadline > now {
+                        std::thread::sleep(deadline - now);
+                    }
+                    waker.wake();
+                }
+            });
+        if spawned.is_ok() {
+            *timer = Some(sender);
+        }
+    }
+    match timer.as_ref() {
+        Some(sender) => {
+            if let Err(error) = sender.send((deadline, waker)) {
+                *timer = None;
+                (error.0).1.wake();
+            }
+        }
+        None => waker.wake(),
+    }
+}
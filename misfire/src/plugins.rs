@@ -0,0 +1,252 @@
+//! Typed helpers for the plugins which are statically linked into the physics server.
+//!
+//! Plugins can also be used directly with [`load_plugin`](`crate::PhysicsClient::load_plugin`),
+//! [`execute_plugin_command`](`crate::PhysicsClient::execute_plugin_command`) and
+//! [`unload_plugin`](`crate::PhysicsClient::unload_plugin`).
+use crate::{BodyId, Error, ErrorKind, PhysicsClient, PluginId, SetPhysicsEngineParameterOptions};
+
+/// Commands which are understood by the `pdControlPlugin`.
+#[derive(Debug, Copy, Clone)]
+enum PdControlCommand {
+    SetPdControl = 1,
+    RemovePdControl = 2,
+}
+
+/// Targets and gains of a PD controller which is run by the [`PdControlPlugin`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PdControlOptions {
+    /// desired joint position
+    pub target_position: f64,
+    /// desired joint velocity
+    pub target_velocity: f64,
+    /// position gain (kp)
+    pub position_gain: f64,
+    /// velocity gain (kd)
+    pub velocity_gain: f64,
+    /// the motor force is clamped to this value
+    pub maximum_force: f64,
+}
+
+/// The `pdControlPlugin` runs a PD controller for single joints before each simulation step and
+/// applies the resulting torque to the joint. In contrast to
+/// [`ControlCommand::Pd`](`crate::ControlCommand::Pd`) the controller keeps running without having
+/// to send a new command every step.
+///
+/// The default velocity motor of the joint should be disabled, as it would otherwise work against
+/// the PD controller. This can be done by setting a velocity control with a maximum force of 0.
+///
+/// # Example
+/// ```no_run
+/// use anyhow::Result;
+/// use misfire::plugins::{PdControlOptions, PdControlPlugin};
+/// use misfire::*;
+///
+/// fn main() -> Result<()> {
+///     let mut client = PhysicsClient::connect(Mode::Direct)?;
+///     client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
+///     let door = client.load_urdf("door.urdf", None)?;
+///     client.set_joint_motor_control(door, 1, ControlCommand::Velocity(0.), Some(0.))?;
+///     let pd_control = PdControlPlugin::load(&mut client)?;
+///     pd_control.set_pd_control(
+///         &mut client,
+///         door,
+///         1,
+///         PdControlOptions {
+///             target_position: 1.,
+///             target_velocity: 0.,
+///             position_gain: 10.,
+///             velocity_gain: 1.,
+///             maximum_force: 100.,
+///         },
+///     )?;
+///     for _ in 0..100 {
+///         client.step_simulation()?;
+///     }
+///     pd_control.remove_pd_control(&mut client, door, 1)?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PdControlPlugin(PluginId);
+
+impl PdControlPlugin {
+    /// loads the plugin into the physics server.
+    pub fn load(client: &mut PhysicsClient) -> Result<PdControlPlugin, Error> {
+        Ok(PdControlPlugin(
+            client.load_plugin("pdControlPlugin", None)?,
+        ))
+    }
+    /// returns the [`PluginId`](`crate::PluginId`) of the plugin.
+    pub fn id(&self) -> PluginId {
+        self.0
+    }
+    /// starts or updates the PD controller of a joint.
+    /// # Arguments
+    /// * `client` - the client which loaded the plugin
+    /// * `body` - the body which contains the joint
+    /// * `joint_index` - a joint index in the range \[0..[`get_num_joints(body)`](`crate::PhysicsClient::get_num_joints()`)\]
+    /// * `options` - targets and gains of the controller
+    pub fn set_pd_control(
+        &self,
+        client: &mut PhysicsClient,
+        body: BodyId,
+        joint_index: usize,
+        options: PdControlOptions,
+    ) -> Result<(), Error> {
+        let floats = [
+            options.target_position as f32,
+            options.target_velocity as f32,
+            options.velocity_gain as f32,
+            options.position_gain as f32,
+            options.maximum_force as f32,
+        ];
+        self.execute(
+            client,
+            PdControlCommand::SetPdControl,
+            body,
+            joint_index,
+            &floats,
+        )
+    }
+    /// stops the PD controller of a joint.
+    pub fn remove_pd_control(
+        &self,
+        client: &mut PhysicsClient,
+        body: BodyId,
+        joint_index: usize,
+    ) -> Result<(), Error> {
+        self.execute(
+            client,
+            PdControlCommand::RemovePdControl,
+            body,
+            joint_index,
+            &[],
+        )
+    }
+    /// unloads the plugin. All PD controllers are stopped.
+    pub fn unload(self, client: &mut PhysicsClient) -> Result<(), Error> {
+        client.unload_plugin(self.0)
+    }
+    fn execute(
+        &self,
+        client: &mut PhysicsClient,
+        command: PdControlCommand,
+        body: BodyId,
+        joint_index: usize,
+        floats: &[f32],
+    ) -> Result<(), Error> {
+        let result = client.execute_plugin_command(
+            self.0,
+            "",
+            &[command as i32, body.0, joint_index as i32],
+            floats,
+        )?;
+        if result < 0 {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidLinkIndex,
+                format!(
+                    "pdControlPlugin rejected the command for joint {} of body {:?}",
+                    joint_index, body
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Decides how the collision filter groups and masks of two objects are combined.
+/// See [`set_collision_filter_group_mask`](`crate::PhysicsClient::set_collision_filter_group_mask`).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CollisionFilterMode {
+    /// two objects collide if `group_a & mask_b` **and** `group_b & mask_a` are non-zero. This is the default.
+    And = 0,
+    /// two objects collide if `group_a & mask_b` **or** `group_b & mask_a` is non-zero.
+    Or = 1,
+}
+
+/// The `collisionFilterPlugin` decides which pairs of objects are checked for collisions.
+/// It is the default collision filter of the physics server, so loading it just returns the
+/// already running instance. Custom pair filters override the group/mask filtering.
+///
+/// # Example
+/// ```no_run
+/// use anyhow::Result;
+/// use misfire::plugins::{CollisionFilterMode, CollisionFilterPlugin};
+/// use misfire::*;
+///
+/// fn main() -> Result<()> {
+///     let mut client = PhysicsClient::connect(Mode::Direct)?;
+///     client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
+///     let plane = client.load_urdf("plane.urdf", None)?;
+///     let cube = client.load_urdf("cube_small.urdf", None)?;
+///     let filter = CollisionFilterPlugin::load(&mut client)?;
+///     filter.set_filter_mode(&mut client, CollisionFilterMode::And)?;
+///     // the cube will fall through the plane
+///     filter.set_pair_filter(&mut client, plane, cube, None, None, false);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CollisionFilterPlugin(PluginId);
+
+impl CollisionFilterPlugin {
+    /// loads the plugin into the physics server.
+    pub fn load(client: &mut PhysicsClient) -> Result<CollisionFilterPlugin, Error> {
+        Ok(CollisionFilterPlugin(
+            client.load_plugin("collisionFilterPlugin", None)?,
+        ))
+    }
+    /// returns the [`PluginId`](`crate::PluginId`) of the plugin.
+    pub fn id(&self) -> PluginId {
+        self.0
+    }
+    /// enables or disables the collision detection between two links.
+    /// See [`set_collision_filter_pair`](`crate::PhysicsClient::set_collision_filter_pair`).
+    pub fn set_pair_filter<LinkA: Into<Option<usize>>, LinkB: Into<Option<usize>>>(
+        &self,
+        client: &mut PhysicsClient,
+        body_a: BodyId,
+        body_b: BodyId,
+        link_index_a: LinkA,
+        link_index_b: LinkB,
+        enable_collision: bool,
+    ) -> Result<(), Error> {
+        client.set_collision_filter_pair(
+            body_a,
+            body_b,
+            link_index_a,
+            link_index_b,
+            enable_collision,
+        )
+    }
+    /// sets the collision filter group and mask of a link.
+    /// See [`set_collision_filter_group_mask`](`crate::PhysicsClient::set_collision_filter_group_mask`).
+    pub fn set_group_mask<Link: Into<Option<usize>>>(
+        &self,
+        client: &mut PhysicsClient,
+        body: BodyId,
+        link_index: Link,
+        collision_filter_group: i32,
+        collision_filter_mask: i32,
+    ) -> Result<(), Error> {
+        client.set_collision_filter_group_mask(
+            body,
+            link_index,
+            collision_filter_group,
+            collision_filter_mask,
+        )
+    }
+    /// sets how groups and masks are combined.
+    pub fn set_filter_mode(
+        &self,
+        client: &mut PhysicsClient,
+        mode: CollisionFilterMode,
+    ) -> Result<(), Error> {
+        client.set_physics_engine_parameter(SetPhysicsEngineParameterOptions {
+            collision_filter_mode: Some(mode as usize),
+            ..Default::default()
+        })
+    }
+}
@@ -0,0 +1,349 @@
+//! Running many simulations in parallel, e.g. for reinforcement learning.
+use std::sync::Arc;
+
+use crate::shared::worker_stopped;
+use crate::{
+    BodyId, CameraImageOptions, Error, ErrorKind, Mode, PhysicsClient, SharedPhysicsClient, StateId,
+};
+
+/// A pool of [`Direct`](`crate::Mode::Direct`) physics clients where every client runs on its
+/// own thread.
+///
+/// Each client is called an environment and is identified by its index in `0..len()`. Commands
+/// are sent to all environments at once and the results are gathered in the order of the
+/// environments. State queries return contiguous buffers, so that they can be handed to a
+/// learning framework without copying them again.
+///
+/// # Example
+/// ```no_run
+/// use anyhow::Result;
+/// use misfire::*;
+///
+/// fn main() -> Result<()> {
+///     let pool = PhysicsClientPool::new(8)?;
+///     let robots = pool.broadcast(|_env, client| -> Result<BodyId, Error> {
+///         client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
+///         client.load_urdf("plane.urdf", None)?;
+///         client.load_urdf("r2d2.urdf", None)
+///     })?;
+///     let robots = robots.into_iter().collect::<Result<Vec<BodyId>, Error>>()?;
+///     let initial_states = pool.save_states()?;
+///     for _ in 0..100 {
+///         pool.step_simulation()?;
+///     }
+///     let joint_states = pool.get_joint_states(&robots, &[2, 3, 6, 7])?;
+///     println!("{:?}", joint_states.env(0)?.positions);
+///     // start the first environment over
+///     pool.restore_state(0, initial_states[0])?;
+///     Ok(())
+/// }
+/// ```
+pub struct PhysicsClientPool {
+    envs: Vec<SharedPhysicsClient>,
+}
+
+impl PhysicsClientPool {
+    /// creates `num_envs` clients in [`Direct`](`crate::Mode::Direct`) mode, each on its own thread.
+    pub fn new(num_envs: usize) -> Result<PhysicsClientPool, Error> {
+        let envs = (0..num_envs)
+            .map(|_| {
+                Ok(SharedPhysicsClient::new(PhysicsClient::connect(
+                    Mode::Direct,
+                )?)?)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(PhysicsClientPool { envs })
+    }
+    /// returns the number of environments.
+    pub fn len(&self) -> usize {
+        self.envs.len()
+    }
+    /// returns `true` if the pool has no environments.
+    pub fn is_empty(&self) -> bool {
+        self.envs.is_empty()
+    }
+    /// gives access to a single environment.
+    pub fn env(&self, env: usize) -> Result<&SharedPhysicsClient, Error> {
+        self.envs.get(env).ok_or_else(|| {
+            Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "invalid environment index ({}). The pool only has {} environments",
+                    env,
+                    self.envs.len()
+                ),
+            )
+        })
+    }
+    /// runs `command` in all environments in parallel and returns the results in the order of
+    /// the environments. The first argument of `command` is the index of the environment.
+    pub fn broadcast<F, R>(&self, command: F) -> Result<Vec<R>, Error>
+    where
+        F: Fn(usize, &mut PhysicsClient) -> R + Send + Sync + 'static,
+        R: Send + 'static,
+    {
+        let command = Arc::new(command);
+        let receivers = self
+            .envs
+            .iter()
+            .enumerate()
+            .map(|(env, client)| {
+                let command = Arc::clone(&command);
+                client.submit(move |client| command(env, client))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        receivers
+            .into_iter()
+            .map(|receiver| receiver.recv().map_err(|_| worker_stopped()))
+            .collect()
+    }
+    /// like [`broadcast`](`Self::broadcast`), but for commands which can fail.
+    /// Returns the first error of any environment.
+    fn try_broadcast<F, R>(&self, command: F) -> Result<Vec<R>, Error>
+    where
+        F: Fn(usize, &mut PhysicsClient) -> Result<R, Error> + Send + Sync + 'static,
+        R: Send + 'static,
+    {
+        self.broadcast(command)?.into_iter().collect()
+    }
+    /// performs one [`step_simulation`](`crate::PhysicsClient::step_simulation`) in all
+    /// environments and returns once all of them are done.
+    pub fn step_simulation(&self) -> Result<(), Error> {
+        self.try_broadcast(|_, client| client.step_simulation())?;
+        Ok(())
+    }
+    /// saves the state of every environment in memory. See
+    /// [`save_state`](`crate::PhysicsClient::save_state`).
+    pub fn save_states(&self) -> Result<Vec<StateId>, Error> {
+        self.try_broadcast(|_, client| client.save_state())
+    }
+    /// resets a single environment to a state which was saved with
+    /// [`save_states`](`Self::save_states`).
+    pub fn restore_state(&self, env: usize, state: StateId) -> Result<(), Error> {
+        self.env(env)?
+            .execute(move |client| client.restore_state(state))?
+    }
+    /// resets every environment to its own state. `states` needs one state per environment.
+    pub fn restore_states(&self, states: &[StateId]) -> Result<(), Error> {
+        let states = self.per_env("states", states)?;
+        self.try_broadcast(move |env, client| client.restore_state(states[env]))?;
+        Ok(())
+    }
+    /// gathers the joint states of one body per environment.
+    /// # Arguments
+    /// * `bodies` - the body of each environment
+    /// * `joint_indices` - the joints to query. They are the same for all environments.
+    pub fn get_joint_states(
+        &self,
+        bodies: &[BodyId],
+        joint_indices: &[usize],
+    ) -> Result<PoolJointStates, Error> {
+        let bodies = self.per_env("bodies", bodies)?;
+        let num_joints = joint_indices.len();
+        let joint_indices = joint_indices.to_vec();
+        let states = self.try_broadcast(move |env, client| {
+            client.get_joint_states(bodies[env], &joint_indices)
+        })?;
+        let mut result = PoolJointStates {
+            num_envs: self.len(),
+            num_joints,
+            positions: Vec::with_capacity(self.len() * num_joints),
+            velocities: Vec::with_capacity(self.len() * num_joints),
+            motor_torques: Vec::with_capacity(self.len() * num_joints),
+        };
+        for state in states.iter().flatten() {
+            result.positions.push(state.joint_position);
+            result.velocities.push(state.joint_velocity);
+            result.motor_torques.push(state.joint_motor_torque);
+        }
+        Ok(result)
+    }
+    /// gathers the link states of one body per environment.
+    /// # Arguments
+    /// * `bodies` - the body of each environment
+    /// * `link_indices` - the links to query. They are the same for all environments.
+    /// * `compute_link_velocity` - if `true`, the velocities are gathered as well
+    pub fn get_link_states(
+        &self,
+        bodies: &[BodyId],
+        link_indices: &[usize],
+        compute_link_velocity: bool,
+    ) -> Result<PoolLinkStates, Error> {
+        let bodies = self.per_env("bodies", bodies)?;
+        let num_links = link_indices.len();
+        let link_indices = link_indices.to_vec();
+        let states = self.try_broadcast(move |env, client| {
+            client.get_link_states(bodies[env], &link_indices, compute_link_velocity, true)
+        })?;
+        let num_values = self.len() * num_links;
+        let mut result = PoolLinkStates {
+            num_envs: self.len(),
+            num_links,
+            positions: Vec::with_capacity(num_values * 3),
+            orientations: Vec::with_capacity(num_values * 4),
+            velocities: Vec::with_capacity(if compute_link_velocity {
+                num_values * 6
+            } else {
+                0
+            }),
+        };
+        for state in states.iter().flatten() {
+            let pose = &state.world_pose;
+            result
+                .positions
+                .extend_from_slice(pose.translation.vector.as_slice());
+            result
+                .orientations
+                .extend_from_slice(pose.rotation.coords.as_slice());
+            if compute_link_velocity {
+                let velocity = state.get_world_velocity()?.to_vector();
+                result.velocities.extend_from_slice(velocity.as_slice());
+            }
+        }
+        Ok(result)
+    }
+    /// renders a camera image in every environment.
+    /// # Arguments
+    /// * `width` - horizontal image resolution in pixels
+    /// * `height` - vertical image resolution in pixels
+    /// * `options` - the camera options of each environment
+    pub fn get_camera_images(
+        &self,
+        width: usize,
+        height: usize,
+        options: &[CameraImageOptions],
+    ) -> Result<PoolImages, Error> {
+        let options = self.per_env("camera options", options)?;
+        let images = self.try_broadcast(move |env, client| {
+            client.get_camera_image(width, height, options[env].clone())
+        })?;
+        let num_pixels = self.len() * width * height;
+        let mut result = PoolImages {
+            num_envs: self.len(),
+            width,
+            height,
+            rgba: Vec::with_capacity(num_pixels * 4),
+            depth: Vec::with_capacity(num_pixels),
+            segmentation: Vec::with_capacity(num_pixels),
+        };
+        for images in images {
+            if images.width != width || images.height != height {
+                return Err(Error::of_kind(
+                    ErrorKind::ParseError,
+                    "the physics server returned an image of the wrong size",
+                ));
+            }
+            result.rgba.extend_from_slice(&images.rgba);
+            result.depth.extend_from_slice(&images.depth);
+            result.segmentation.extend_from_slice(&images.segmentation);
+        }
+        Ok(result)
+    }
+    /// checks that there is one value per environment.
+    fn per_env<T: Clone>(&self, name: &str, values: &[T]) -> Result<Vec<T>, Error> {
+        if values.len() != self.len() {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "number of {} ({}) should match the number of environments ({})",
+                    name,
+                    values.len(),
+                    self.len()
+                ),
+            ));
+        }
+        Ok(values.to_vec())
+    }
+}
+
+/// Joint states of all environments of a [`PhysicsClientPool`]. The values are stored
+/// environment by environment, i.e. the value of joint `j` in environment `e` is at index
+/// `e * num_joints + j`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PoolJointStates {
+    /// number of environments
+    pub num_envs: usize,
+    /// number of joints per environment
+    pub num_joints: usize,
+    /// joint positions
+    pub positions: Vec<f64>,
+    /// joint velocities
+    pub velocities: Vec<f64>,
+    /// applied motor torques
+    pub motor_torques: Vec<f64>,
+}
+
+/// The joint states of a single environment. See [`PoolJointStates::env`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EnvJointStates<'a> {
+    /// joint positions
+    pub positions: &'a [f64],
+    /// joint velocities
+    pub velocities: &'a [f64],
+    /// applied motor torques
+    pub motor_torques: &'a [f64],
+}
+
+impl PoolJointStates {
+    /// returns the joint states of a single environment or an error if there is no environment
+    /// with this index.
+    pub fn env(&self, env: usize) -> Result<EnvJointStates<'_>, Error> {
+        let invalid_env = || {
+            Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "invalid environment index ({}). The joint states only contain {} environments",
+                    env, self.num_envs
+                ),
+            )
+        };
+        if env >= self.num_envs {
+            return Err(invalid_env());
+        }
+        let range = env * self.num_joints..(env + 1) * self.num_joints;
+        Ok(EnvJointStates {
+            positions: self.positions.get(range.clone()).ok_or_else(invalid_env)?,
+            velocities: self.velocities.get(range.clone()).ok_or_else(invalid_env)?,
+            motor_torques: self.motor_torques.get(range).ok_or_else(invalid_env)?,
+        })
+    }
+}
+
+/// Link states of all environments of a [`PhysicsClientPool`]. The values are stored environment by
+/// environment and link by link. The poses are the poses of the center of mass of each link, just
+/// like [`LinkState::world_pose`](`crate::LinkState::world_pose`).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PoolLinkStates {
+    /// number of environments
+    pub num_envs: usize,
+    /// number of links per environment
+    pub num_links: usize,
+    /// world positions (x,y,z), 3 values per link
+    pub positions: Vec<f64>,
+    /// world orientations as quaternions (x,y,z,w), 4 values per link
+    pub orientations: Vec<f64>,
+    /// linear and angular world velocities (x,y,z,wx,wy,wz), 6 values per link.
+    /// Empty if the velocities were not computed.
+    pub velocities: Vec<f64>,
+}
+
+/// Camera images of all environments of a [`PhysicsClientPool`]. The images are stored
+/// environment by environment in row-major order.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PoolImages {
+    /// number of environments
+    pub num_envs: usize,
+    /// width image resolution in pixels (horizontal)
+    pub width: usize,
+    /// height image resolution in pixels (vertical)
+    pub height: usize,
+    /// RGBA images, 4 values per pixel
+    pub rgba: Vec<u8>,
+    /// depth images, 1 value per pixel
+    pub depth: Vec<f32>,
+    /// segmentation images, 1 value per pixel
+    pub segmentation: Vec<i32>,
+}
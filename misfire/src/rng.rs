@@ -0,0 +1,38 @@
+use std::f64::consts::PI;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A small random number generator (SplitMix64) which is used for randomizing the episodes of
+/// the reference tasks and for the sensor noise.
+#[derive(Debug, Clone)]
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+    pub(crate) fn from_time() -> Rng {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or_default();
+        Rng(nanos)
+    }
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+    /// returns a uniformly distributed number in `[low, high)`.
+    pub(crate) fn uniform(&mut self, low: f64, high: f64) -> f64 {
+        let unit = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        low + (high - low) * unit
+    }
+    /// returns a normally distributed number (Box-Muller transform).
+    pub(crate) fn normal(&mut self, mean: f64, std_dev: f64) -> f64 {
+        let u1 = 1. - self.uniform(0., 1.);
+        let u2 = self.uniform(0., 1.);
+        mean + std_dev * (-2. * u1.ln()).sqrt() * (2. * PI * u2).cos()
+    }
+}
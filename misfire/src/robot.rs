@@ -0,0 +1,243 @@
+//! A typed view on an articulated body.
+use std::collections::HashMap;
+
+use crate::{
+    BodyId, ControlCommandArray, Error, ErrorKind, JointInfo, JointState, JointType, PhysicsClient,
+};
+
+/// Caches the [`JointInfo`](`crate::JointInfo`) of a body, so that joints and links can be looked
+/// up by name and the movable joints can be read and controlled all at once.
+///
+/// Most vectorized methods work on the movable joints of the robot, i.e. all revolute and
+/// prismatic joints, in the order of their joint index. As long as the robot has no spherical or
+/// planar joints, this is the same order which is used by
+/// [`calculate_inverse_kinematics`](`crate::PhysicsClient::calculate_inverse_kinematics`) and
+/// [`calculate_jacobian`](`crate::PhysicsClient::calculate_jacobian`).
+/// Joints with more than one degree of freedom are skipped by these methods.
+///
+/// Like the plugin helpers, a `Robot` does not hold on to the client, which has to be passed to
+/// every method that talks to the physics server.
+///
+/// # Example
+/// ```no_run
+/// use anyhow::Result;
+/// use misfire::*;
+///
+/// fn main() -> Result<()> {
+///     let mut client = PhysicsClient::connect(Mode::Direct)?;
+///     client.set_additional_search_path(
+///         "../misfire-sys/bullet3/libbullet3/examples/pybullet/gym/pybullet_data",
+///     )?;
+///     let panda = client.load_urdf(
+///         "franka_panda/panda.urdf",
+///         UrdfOptions {
+///             use_fixed_base: true,
+///             ..Default::default()
+///         },
+///     )?;
+///     let panda = Robot::new(&mut client, panda)?;
+///     let joint = panda.joint("panda_joint5")?;
+///     let hand = panda.link("panda_hand")?;
+///     println!("joint {} moves the links up to link {}", joint, hand);
+///     let positions = panda.joint_positions(&mut client)?;
+///     panda.apply_command(
+///         &mut client,
+///         ControlCommandArray::Positions(&positions),
+///         None,
+///     )?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Robot {
+    body: BodyId,
+    joints: Vec<JointInfo>,
+    movable_joints: Vec<usize>,
+    movable_indices: Vec<Option<usize>>,
+    joint_names: HashMap<String, usize>,
+    link_names: HashMap<String, usize>,
+}
+
+impl Robot {
+    /// queries the joints of `body` from the physics server.
+    pub fn new(client: &mut PhysicsClient, body: BodyId) -> Result<Robot, Error> {
+        let num_joints = client.get_num_joints(body)?;
+        let joints = (0..num_joints)
+            .map(|joint| client.get_joint_info(body, joint))
+            .collect::<Result<Vec<JointInfo>, Error>>()?;
+        let mut movable_joints = Vec::new();
+        let mut movable_indices = Vec::with_capacity(num_joints);
+        for joint in joints.iter() {
+            if matches!(joint.joint_type, JointType::Revolute | JointType::Prismatic) {
+                movable_indices.push(Some(movable_joints.len()));
+                movable_joints.push(joint.joint_index);
+            } else {
+                movable_indices.push(None);
+            }
+        }
+        let joint_names = joints
+            .iter()
+            .map(|joint| (joint.joint_name.clone(), joint.joint_index))
+            .collect();
+        let link_names = joints
+            .iter()
+            .map(|joint| (joint.link_name.clone(), joint.joint_index))
+            .collect();
+        Ok(Robot {
+            body,
+            joints,
+            movable_joints,
+            movable_indices,
+            joint_names,
+            link_names,
+        })
+    }
+    /// returns the [`BodyId`](`crate::BodyId`) of the robot.
+    pub fn body(&self) -> BodyId {
+        self.body
+    }
+    /// returns the number of joints, including fixed joints.
+    pub fn num_joints(&self) -> usize {
+        self.joints.len()
+    }
+    /// returns the number of movable joints.
+    pub fn num_movable_joints(&self) -> usize {
+        self.movable_joints.len()
+    }
+    /// returns the cached information of all joints.
+    pub fn joint_infos(&self) -> &[JointInfo] {
+        &self.joints
+    }
+    /// returns the cached information of a single joint.
+    pub fn joint_info(&self, joint_index: usize) -> Result<&JointInfo, Error> {
+        self.joints.get(joint_index).ok_or_else(|| {
+            Error::of_kind(
+                ErrorKind::InvalidLinkIndex,
+                format!(
+                    "Joint index out-of-range ({}). The robot only has {} joints",
+                    joint_index,
+                    self.joints.len()
+                ),
+            )
+        })
+    }
+    /// returns the index of the joint with the given name.
+    pub fn joint(&self, name: &str) -> Result<usize, Error> {
+        self.joint_names.get(name).copied().ok_or_else(|| {
+            Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!("the robot has no joint named {}", name),
+            )
+        })
+    }
+    /// returns the index of the link with the given name. The base is not a link.
+    pub fn link(&self, name: &str) -> Result<usize, Error> {
+        self.link_names.get(name).copied().ok_or_else(|| {
+            Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!("the robot has no link named {}", name),
+            )
+        })
+    }
+    /// returns the joint indices of all movable joints.
+    pub fn movable_joints(&self) -> &[usize] {
+        &self.movable_joints
+    }
+    /// returns the position of a joint within the movable joints, or `None` if the joint is not
+    /// movable or does not exist. It is the inverse of [`movable_joints`](`Self::movable_joints`).
+    pub fn movable_index(&self, joint_index: usize) -> Option<usize> {
+        self.movable_indices.get(joint_index).copied().flatten()
+    }
+    /// returns the lower position limits of the movable joints.
+    pub fn lower_limits(&self) -> Vec<f64> {
+        self.movable_map(|joint| joint.joint_lower_limit)
+    }
+    /// returns the upper position limits of the movable joints.
+    pub fn upper_limits(&self) -> Vec<f64> {
+        self.movable_map(|joint| joint.joint_upper_limit)
+    }
+    /// returns the maximum forces of the movable joints as specified in the URDF file.
+    pub fn max_forces(&self) -> Vec<f64> {
+        self.movable_map(|joint| joint.joint_max_force)
+    }
+    /// returns the maximum velocities of the movable joints as specified in the URDF file.
+    pub fn max_velocities(&self) -> Vec<f64> {
+        self.movable_map(|joint| joint.joint_max_velocity)
+    }
+    /// returns the states of the movable joints.
+    pub fn joint_states(&self, client: &mut PhysicsClient) -> Result<Vec<JointState>, Error> {
+        if self.movable_joints.is_empty() {
+            return Ok(Vec::new());
+        }
+        client.get_joint_states(self.body, &self.movable_joints)
+    }
+    /// returns the positions of the movable joints.
+    pub fn joint_positions(&self, client: &mut PhysicsClient) -> Result<Vec<f64>, Error> {
+        Ok(self
+            .joint_states(client)?
+            .iter()
+            .map(|state| state.joint_position)
+            .collect())
+    }
+    /// returns the velocities of the movable joints.
+    pub fn joint_velocities(&self, client: &mut PhysicsClient) -> Result<Vec<f64>, Error> {
+        Ok(self
+            .joint_states(client)?
+            .iter()
+            .map(|state| state.joint_velocity)
+            .collect())
+    }
+    /// resets the positions of the movable joints and sets their velocities to zero.
+    /// Like [`reset_joint_state`](`crate::PhysicsClient::reset_joint_state`) this overrides the
+    /// physics simulation, so it is best used at the start.
+    pub fn set_joint_positions(
+        &self,
+        client: &mut PhysicsClient,
+        positions: &[f64],
+    ) -> Result<(), Error> {
+        self.check_movable_len("positions", positions.len())?;
+        for (&joint, &position) in self.movable_joints.iter().zip(positions) {
+            client.reset_joint_state(self.body, joint, position, None)?;
+        }
+        Ok(())
+    }
+    /// controls all movable joints at once. See
+    /// [`set_joint_motor_control_array`](`crate::PhysicsClient::set_joint_motor_control_array`).
+    /// The command and the maximum forces need one value per movable joint.
+    pub fn apply_command(
+        &self,
+        client: &mut PhysicsClient,
+        control_command: ControlCommandArray,
+        maximum_force: Option<&[f64]>,
+    ) -> Result<(), Error> {
+        if let Some(forces) = maximum_force {
+            self.check_movable_len("maximum forces", forces.len())?;
+        }
+        client.set_joint_motor_control_array(
+            self.body,
+            &self.movable_joints,
+            control_command,
+            maximum_force,
+        )
+    }
+    fn movable_map<F: Fn(&JointInfo) -> f64>(&self, value: F) -> Vec<f64> {
+        self.movable_joints
+            .iter()
+            .map(|&joint| value(&self.joints[joint]))
+            .collect()
+    }
+    fn check_movable_len(&self, name: &str, len: usize) -> Result<(), Error> {
+        if len != self.movable_joints.len() {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "number of {} ({}) should match the number of movable joints ({})",
+                    name,
+                    len,
+                    self.movable_joints.len()
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,1210 @@
+//! Scene descriptions which can be stored in a file and loaded into a simulation.
+//!
+//! A [`Scene`] describes a world declaratively: the model files (URDF, SDF and MuJoCo) with
+//! their load options, [primitive](`ScenePrimitive`) bodies like boxes and spheres, shared
+//! [materials](`SceneMaterial`), user constraints, [cameras](`SceneCamera`), the gravity, the
+//! time step and further [physics parameters](`ScenePhysics`).
+//! [`PhysicsClient::load_scene`](`crate::PhysicsClient::load_scene`) instantiates a scene and
+//! returns the created bodies by their name, so test scenes do not need dozens of calls to
+//! [`load_urdf`](`crate::PhysicsClient::load_urdf`),
+//! [`create_multi_body`](`crate::PhysicsClient::create_multi_body`) and
+//! [`change_dynamics`](`crate::PhysicsClient::change_dynamics`).
+//!
+//! [`PhysicsClient::save_scene`](`crate::PhysicsClient::save_scene`) goes the other way and
+//! captures a snapshot of a simulation:
+//! the files from which the bodies were loaded together with their load options, the base poses
+//! and velocities, the joint states, the dynamics of every link, the user constraints, the
+//! gravity, the time step and the physics parameters.
+//!
+//! Unlike [`save_world`](`crate::PhysicsClient::save_world`) and
+//! [`save_bullet`](`crate::PhysicsClient::save_bullet`), a scene is plain data. With the `serde`
+//! feature it can be written in any serde format, e.g. JSON, TOML or RON. In the serialized form,
+//! poses are written as a position `[x, y, z]` and a quaternion `[x, y, z, w]` and every body is
+//! referred to by its name, so that scene files can be versioned and diffed. Most fields can be
+//! left out and get their default value. A hand-written scene in JSON looks like this:
+//! ```text
+//! {
+//!   "gravity": [0.0, 0.0, -10.0],
+//!   "physics": { "num_solver_iterations": 100 },
+//!   "materials": [
+//!     { "name": "ice", "color": [0.8, 0.9, 1.0, 1.0], "lateral_friction": 0.01 }
+//!   ],
+//!   "assets": [
+//!     {
+//!       "source": {
+//!         "Urdf": {
+//!           "file": "r2d2.urdf",
+//!           "use_fixed_base": false,
+//!           "use_maximal_coordinates": null,
+//!           "flags": "",
+//!           "global_scaling": -1.0
+//!         }
+//!       },
+//!       "bodies": [
+//!         {
+//!           "name": "r2d2",
+//!           "pose": { "position": [0.0, 0.0, 0.5] },
+//!           "joints": [{ "name": "head_swivel", "position": [0.3] }]
+//!         }
+//!       ]
+//!     }
+//!   ],
+//!   "primitives": [
+//!     { "name": "ground", "shape": { "Plane": { "normal": [0.0, 0.0, 1.0] } }, "material": "ice" },
+//!     {
+//!       "name": "crate",
+//!       "shape": { "Box": { "half_extents": [0.2, 0.2, 0.2] } },
+//!       "mass": 1.0,
+//!       "pose": { "position": [1.0, 0.0, 0.2] }
+//!     }
+//!   ],
+//!   "constraints": [
+//!     { "parent": "r2d2", "child": "crate", "joint_type": "Point2Point" }
+//!   ],
+//!   "cameras": [{ "name": "overview", "eye": [3.0, 3.0, 2.0], "target": [0.0, 0.0, 0.5] }]
+//! }
+//! ```
+//! Primitives which were created by [`load_scene`](`crate::PhysicsClient::load_scene`) are saved
+//! as primitives together with their materials. Other bodies which were not loaded from a file,
+//! e.g. bodies created with [`create_multi_body`](`crate::PhysicsClient::create_multi_body`) or
+//! loaded with [`load_bullet`](`crate::PhysicsClient::load_bullet`), can not be described by a
+//! scene, so `save_scene` returns an error which names them. Cameras are never saved.
+use std::collections::{HashMap, HashSet};
+use std::os::raw::c_int;
+use std::path::PathBuf;
+
+use nalgebra::{Isometry3, Matrix4, Quaternion, Translation3, UnitQuaternion, Vector3};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BodyId, CameraImageOptions, ChangeConstraintOptions, ChangeDynamicsOptions,
+    ChangeVisualShapeOptions, Error, ErrorKind, GeometricCollisionShape, GeometricVisualShape,
+    Images, JointType, LoadModelFlags, MultiBodyOptions, PhysicsClient, PhysicsEngineParameters,
+    SdfOptions, SetPhysicsEngineParameterOptions, UrdfOptions, VisualShapeOptions,
+};
+
+/// version of the scene format which is written by [`save_scene`](`crate::PhysicsClient::save_scene`).
+pub const SCENE_FORMAT_VERSION: u32 = 1;
+
+/// A snapshot of a simulation. See the [module documentation](`self`).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Scene {
+    /// version of the scene format. Scenes with a newer version can not be loaded.
+    pub version: u32,
+    /// gravity vector
+    pub gravity: [f64; 3],
+    /// physics engine time step in seconds
+    pub time_step: f64,
+    /// further physics engine parameters
+    pub physics: ScenePhysics,
+    /// named materials which can be used by the bodies and primitives
+    pub materials: Vec<SceneMaterial>,
+    /// files from which the bodies are loaded
+    pub assets: Vec<SceneAsset>,
+    /// bodies with a single geometric shape
+    pub primitives: Vec<ScenePrimitive>,
+    /// user constraints between the bodies
+    pub constraints: Vec<SceneConstraint>,
+    /// named cameras. They are not part of the simulation, see [`SceneCamera`].
+    pub cameras: Vec<SceneCamera>,
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Scene {
+            version: SCENE_FORMAT_VERSION,
+            gravity: [0.; 3],
+            time_step: 1. / 240.,
+            physics: ScenePhysics::default(),
+            materials: vec![],
+            assets: vec![],
+            primitives: vec![],
+            constraints: vec![],
+            cameras: vec![],
+        }
+    }
+}
+
+impl Scene {
+    /// returns the material with the given name.
+    pub fn material(&self, name: &str) -> Option<&SceneMaterial> {
+        self.materials.iter().find(|material| material.name == name)
+    }
+    /// returns the camera with the given name.
+    pub fn camera(&self, name: &str) -> Option<&SceneCamera> {
+        self.cameras.iter().find(|camera| camera.name == name)
+    }
+}
+
+/// Physics engine parameters of a scene. Only the given values are changed when the scene is
+/// loaded. See [`SetPhysicsEngineParameterOptions`](`crate::SetPhysicsEngineParameterOptions`)
+/// for a description of the parameters.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ScenePhysics {
+    /// maximum number of constraint solver iterations
+    pub num_solver_iterations: Option<usize>,
+    /// number of sub steps of every time step
+    pub num_sub_steps: Option<usize>,
+    /// whether the split impulse method is used for penetration recovery
+    pub use_split_impulse: Option<bool>,
+    /// penetration depth above which the split impulse method is used
+    pub split_impulse_penetration_threshold: Option<f64>,
+    /// collision filter mode
+    pub collision_filter_mode: Option<usize>,
+    /// distance at which contact points are discarded
+    pub contact_breaking_threshold: Option<f64>,
+    /// velocity below which the restitution is ignored
+    pub restitution_velocity_threshold: Option<f64>,
+    /// error reduction parameter of non-contact constraints
+    pub erp: Option<f64>,
+    /// error reduction parameter of contact constraints
+    pub contact_erp: Option<f64>,
+    /// error reduction parameter of friction constraints
+    pub friction_erp: Option<f64>,
+    /// whether the friction is modeled as a cone instead of a pyramid
+    pub enable_cone_friction: Option<bool>,
+    /// whether the overlapping pairs are sorted to make the simulation deterministic
+    pub deterministic_overlapping_pairs: Option<bool>,
+    /// penetration depth which is allowed by continuous collision detection
+    pub allowed_ccd_penetration: Option<f64>,
+    /// residual below which the constraint solver stops iterating
+    pub solver_residual_threshold: Option<f64>,
+    /// penetration depth which is accepted without correction
+    pub contact_slop: Option<f64>,
+    /// whether the separating axis theorem is used for convex collisions
+    pub enable_sat: Option<bool>,
+}
+
+impl From<&ScenePhysics> for SetPhysicsEngineParameterOptions {
+    fn from(physics: &ScenePhysics) -> Self {
+        SetPhysicsEngineParameterOptions {
+            num_solver_iterations: physics.num_solver_iterations,
+            num_sub_steps: physics.num_sub_steps,
+            use_split_impulse: physics.use_split_impulse,
+            split_impulse_penetration_threshold: physics.split_impulse_penetration_threshold,
+            collision_filter_mode: physics.collision_filter_mode,
+            contact_breaking_threshold: physics.contact_breaking_threshold,
+            restitution_velocity_threshold: physics.restitution_velocity_threshold,
+            erp: physics.erp,
+            contact_erp: physics.contact_erp,
+            friction_erp: physics.friction_erp,
+            enable_cone_friction: physics.enable_cone_friction,
+            deterministic_overlapping_pairs: physics.deterministic_overlapping_pairs,
+            allowed_ccd_penetration: physics.allowed_ccd_penetration,
+            solver_residual_threshold: physics.solver_residual_threshold,
+            contact_slop: physics.contact_slop,
+            enable_sat: physics.enable_sat,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&PhysicsEngineParameters> for ScenePhysics {
+    fn from(parameters: &PhysicsEngineParameters) -> Self {
+        ScenePhysics {
+            num_solver_iterations: Some(parameters.num_solver_iterations),
+            num_sub_steps: Some(parameters.num_sub_steps),
+            use_split_impulse: Some(parameters.use_split_impulse),
+            split_impulse_penetration_threshold: Some(
+                parameters.split_impulse_penetration_threshold,
+            ),
+            collision_filter_mode: Some(parameters.collision_filter_mode),
+            contact_breaking_threshold: Some(parameters.contact_breaking_threshold),
+            restitution_velocity_threshold: Some(parameters.restitution_velocity_threshold),
+            erp: Some(parameters.erp),
+            contact_erp: Some(parameters.contact_erp),
+            friction_erp: Some(parameters.friction_erp),
+            enable_cone_friction: Some(parameters.enable_cone_friction),
+            deterministic_overlapping_pairs: Some(parameters.deterministic_overlapping_pairs),
+            allowed_ccd_penetration: Some(parameters.allowed_ccd_penetration),
+            solver_residual_threshold: Some(parameters.solver_residual_threshold),
+            contact_slop: Some(parameters.contact_slop),
+            enable_sat: Some(parameters.enable_sat),
+        }
+    }
+}
+
+/// Surface properties which are shared by several bodies. Only the given values are changed.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SceneMaterial {
+    /// unique name of the material
+    pub name: String,
+    /// color components for red, green, blue and alpha, each in range \[0,1\]
+    pub color: Option<[f64; 4]>,
+    /// specular color components for red, green and blue
+    pub specular_color: Option<[f64; 3]>,
+    /// lateral (linear) contact friction
+    pub lateral_friction: Option<f64>,
+    /// torsional friction around the contact normal
+    pub spinning_friction: Option<f64>,
+    /// torsional friction orthogonal to contact normal
+    pub rolling_friction: Option<f64>,
+    /// bounciness of contact
+    pub restitution: Option<f64>,
+    /// stiffness and damping of the contact constraints
+    pub contact_stiffness_and_damping: Option<(f64, f64)>,
+}
+
+impl From<&SceneMaterial> for ChangeDynamicsOptions {
+    fn from(material: &SceneMaterial) -> Self {
+        ChangeDynamicsOptions {
+            lateral_friction: material.lateral_friction,
+            spinning_friction: material.spinning_friction,
+            rolling_friction: material.rolling_friction,
+            restitution: material.restitution,
+            contact_stiffness_and_damping: material.contact_stiffness_and_damping,
+            ..Default::default()
+        }
+    }
+}
+
+/// A body with a single shape, which is created with
+/// [`create_multi_body`](`crate::PhysicsClient::create_multi_body`).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScenePrimitive {
+    /// unique name of the body within the scene
+    pub name: String,
+    /// shape which is used for collisions and rendering
+    pub shape: PrimitiveShape,
+    /// mass in kg. A mass of zero creates a static body.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mass: f64,
+    /// pose of the body
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub pose: ScenePose,
+    /// linear and angular velocity of the body
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub velocity: [f64; 6],
+    /// name of the material of the body
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub material: Option<String>,
+    /// color of the body, which takes precedence over the color of the material
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub color: Option<[f64; 4]>,
+}
+
+/// The shape of a [`ScenePrimitive`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PrimitiveShape {
+    /// a sphere
+    Sphere {
+        /// radius in meter
+        radius: f64,
+    },
+    /// a cuboid
+    Box {
+        /// \[x,y,z\] lengths starting from the middle of the box
+        half_extents: [f64; 3],
+    },
+    /// a cylinder with a half sphere on each end
+    Capsule {
+        /// radius in meter
+        radius: f64,
+        /// length of the cylindric part in meter
+        length: f64,
+    },
+    /// a cylinder
+    Cylinder {
+        /// radius in meter
+        radius: f64,
+        /// length in meter
+        length: f64,
+    },
+    /// an infinite plane
+    Plane {
+        /// normal of the plane
+        normal: [f64; 3],
+    },
+    /// a .obj (Wavefront) file
+    Mesh {
+        /// path to the file on the physics server
+        file: PathBuf,
+        /// scaling of the mesh
+        #[cfg_attr(feature = "serde", serde(default))]
+        scale: Option<[f64; 3]>,
+    },
+}
+
+impl PrimitiveShape {
+    fn collision_shape(&self) -> GeometricCollisionShape {
+        match self {
+            PrimitiveShape::Sphere { radius } => {
+                GeometricCollisionShape::Sphere { radius: *radius }
+            }
+            PrimitiveShape::Box { half_extents } => GeometricCollisionShape::Box {
+                half_extents: Vector3::from(*half_extents),
+            },
+            PrimitiveShape::Capsule { radius, length } => GeometricCollisionShape::Capsule {
+                radius: *radius,
+                height: *length,
+            },
+            PrimitiveShape::Cylinder { radius, length } => GeometricCollisionShape::Cylinder {
+                radius: *radius,
+                height: *length,
+            },
+            PrimitiveShape::Plane { normal } => GeometricCollisionShape::Plane {
+                plane_normal: Vector3::from(*normal),
+            },
+            PrimitiveShape::Mesh { file, scale } => GeometricCollisionShape::MeshFile {
+                filename: file.clone(),
+                mesh_scaling: scale.map(Vector3::from),
+                flags: None,
+            },
+        }
+    }
+    fn visual_shape(&self) -> GeometricVisualShape {
+        match self {
+            PrimitiveShape::Sphere { radius } => GeometricVisualShape::Sphere { radius: *radius },
+            PrimitiveShape::Box { half_extents } => GeometricVisualShape::Box {
+                half_extents: Vector3::from(*half_extents),
+            },
+            PrimitiveShape::Capsule { radius, length } => GeometricVisualShape::Capsule {
+                radius: *radius,
+                length: *length,
+            },
+            PrimitiveShape::Cylinder { radius, length } => GeometricVisualShape::Cylinder {
+                radius: *radius,
+                length: *length,
+            },
+            PrimitiveShape::Plane { normal } => GeometricVisualShape::Plane {
+                plane_normal: Vector3::from(*normal),
+            },
+            PrimitiveShape::Mesh { file, scale } => GeometricVisualShape::MeshFile {
+                filename: file.clone(),
+                mesh_scaling: scale.map(Vector3::from),
+            },
+        }
+    }
+}
+
+/// A named pinhole camera. Cameras are not part of the simulation, so they are neither created
+/// by [`load_scene`](`crate::PhysicsClient::load_scene`) nor captured by
+/// [`save_scene`](`crate::PhysicsClient::save_scene`). They are kept in the scene file so that
+/// the views of a scene can be rendered with [`get_image`](`Self::get_image`).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SceneCamera {
+    /// unique name of the camera
+    pub name: String,
+    /// position of the camera
+    pub eye: [f32; 3],
+    /// point the camera is looking at
+    pub target: [f32; 3],
+    /// up vector of the camera
+    pub up: [f32; 3],
+    /// vertical field of view in degrees
+    pub fov: f32,
+    /// distance of the near clipping plane
+    pub near: f32,
+    /// distance of the far clipping plane
+    pub far: f32,
+    /// image width in pixels
+    pub width: usize,
+    /// image height in pixels
+    pub height: usize,
+}
+
+impl Default for SceneCamera {
+    fn default() -> Self {
+        SceneCamera {
+            name: String::new(),
+            eye: [1., 1., 1.],
+            target: [0.; 3],
+            up: [0., 0., 1.],
+            fov: 60.,
+            near: 0.01,
+            far: 100.,
+            width: 320,
+            height: 240,
+        }
+    }
+}
+
+impl SceneCamera {
+    /// computes the view matrix of the camera.
+    pub fn view_matrix(&self) -> Matrix4<f32> {
+        PhysicsClient::compute_view_matrix(self.eye, self.target, self.up)
+    }
+    /// computes the projection matrix of the camera.
+    pub fn projection_matrix(&self) -> Matrix4<f32> {
+        let aspect = self.width as f32 / self.height as f32;
+        PhysicsClient::compute_projection_matrix_fov(self.fov, aspect, self.near, self.far)
+    }
+    /// returns the options for [`get_camera_image`](`crate::PhysicsClient::get_camera_image`)
+    /// with the view and projection matrix of the camera.
+    pub fn image_options(&self) -> CameraImageOptions {
+        CameraImageOptions {
+            view_matrix: Some(self.view_matrix()),
+            projection_matrix: Some(self.projection_matrix()),
+            ..Default::default()
+        }
+    }
+    /// renders an image of the simulation from the view of the camera.
+    pub fn get_image(&self, client: &mut PhysicsClient) -> Result<Images, Error> {
+        client.get_camera_image(self.width, self.height, self.image_options())
+    }
+}
+
+/// A file which is loaded into the scene and the state of the bodies it contains.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SceneAsset {
+    /// the file and its load options
+    pub source: AssetSource,
+    /// the bodies of the file which are part of the scene. Bodies of an SDF or MuJoCo file which
+    /// are not listed here are removed after loading the file.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub bodies: Vec<SceneBody>,
+}
+
+/// A model file and the options which were used to load it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AssetSource {
+    /// loaded with [`load_urdf`](`crate::PhysicsClient::load_urdf`)
+    Urdf {
+        /// path to the file on the physics server
+        file: PathBuf,
+        /// see [`UrdfOptions`](`crate::UrdfOptions`)
+        use_fixed_base: bool,
+        /// see [`UrdfOptions`](`crate::UrdfOptions`)
+        use_maximal_coordinates: Option<bool>,
+        /// see [`UrdfOptions`](`crate::UrdfOptions`)
+        flags: LoadModelFlags,
+        /// see [`UrdfOptions`](`crate::UrdfOptions`)
+        global_scaling: f64,
+    },
+    /// loaded with [`load_sdf`](`crate::PhysicsClient::load_sdf`)
+    Sdf {
+        /// path to the file on the physics server
+        file: PathBuf,
+        /// see [`SdfOptions`](`crate::SdfOptions`)
+        use_maximal_coordinates: bool,
+        /// see [`SdfOptions`](`crate::SdfOptions`)
+        global_scaling: f64,
+    },
+    /// loaded with [`load_mjcf`](`crate::PhysicsClient::load_mjcf`)
+    Mjcf {
+        /// path to the file on the physics server
+        file: PathBuf,
+        /// flags for loading the model
+        flags: Option<LoadModelFlags>,
+    },
+}
+
+/// The state of a single body.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SceneBody {
+    /// unique name of the body within the scene. Constraints refer to bodies by their name.
+    pub name: String,
+    /// index of the body within its file. Always 0 for URDF files.
+    pub index: usize,
+    /// pose of the base
+    pub pose: ScenePose,
+    /// linear and angular velocity of the base
+    pub velocity: [f64; 6],
+    /// states of the movable joints
+    pub joints: Vec<SceneJoint>,
+    /// name of a material which is applied to the base and all links before the dynamics
+    pub material: Option<String>,
+    /// dynamics of the base and the links
+    pub dynamics: Vec<SceneDynamics>,
+}
+
+/// A pose in world coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ScenePose {
+    /// position \[x, y, z\]
+    pub position: [f64; 3],
+    /// orientation as quaternion \[x, y, z, w\]
+    pub orientation: [f64; 4],
+}
+
+impl Default for ScenePose {
+    fn default() -> Self {
+        Isometry3::identity().into()
+    }
+}
+
+impl From<Isometry3<f64>> for ScenePose {
+    fn from(pose: Isometry3<f64>) -> Self {
+        let orientation = pose.rotation.coords;
+        ScenePose {
+            position: pose.translation.vector.into(),
+            orientation: [orientation.x, orientation.y, orientation.z, orientation.w],
+        }
+    }
+}
+
+impl From<ScenePose> for Isometry3<f64> {
+    fn from(pose: ScenePose) -> Self {
+        let [x, y, z, w] = pose.orientation;
+        Isometry3::from_parts(
+            Translation3::from(Vector3::from(pose.position)),
+            UnitQuaternion::from_quaternion(Quaternion::new(w, x, y, z)),
+        )
+    }
+}
+
+/// The state of a joint. Joints with several degrees of freedom, like spherical joints, have
+/// several values.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SceneJoint {
+    /// name of the joint as specified in the model file
+    pub name: String,
+    /// joint position. A quaternion \[x, y, z, w\] for spherical joints.
+    pub position: Vec<f64>,
+    /// joint velocity
+    pub velocity: Vec<f64>,
+}
+
+/// Dynamics of the base or a link. Only the given values are changed when the scene is loaded.
+/// See [`ChangeDynamicsOptions`](`crate::ChangeDynamicsOptions`).
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SceneDynamics {
+    /// link index or `None` for the base
+    pub link: Option<usize>,
+    /// mass of the link (or base for `link` = `None`)
+    pub mass: Option<f64>,
+    /// lateral (linear) contact friction
+    pub lateral_friction: Option<f64>,
+    /// torsional friction around the contact normal
+    pub spinning_friction: Option<f64>,
+    /// torsional friction orthogonal to contact normal
+    pub rolling_friction: Option<f64>,
+    /// bounciness of contact
+    pub restitution: Option<f64>,
+    /// stiffness and damping of the contact constraints
+    pub contact_stiffness_and_damping: Option<(f64, f64)>,
+    /// diagonal of the local inertia tensor
+    pub local_inertia_diagonal: Option<[f64; 3]>,
+    /// collision margin
+    pub collision_margin: Option<f64>,
+}
+
+impl From<&SceneDynamics> for ChangeDynamicsOptions {
+    fn from(dynamics: &SceneDynamics) -> Self {
+        ChangeDynamicsOptions {
+            mass: dynamics.mass,
+            lateral_friction: dynamics.lateral_friction,
+            spinning_friction: dynamics.spinning_friction,
+            rolling_friction: dynamics.rolling_friction,
+            restitution: dynamics.restitution,
+            contact_stiffness_and_damping: dynamics.contact_stiffness_and_damping,
+            local_inertia_diagonal: dynamics.local_inertia_diagonal.map(Vector3::from),
+            collision_margin: dynamics.collision_margin,
+            ..Default::default()
+        }
+    }
+}
+
+/// A user constraint, see [`create_constraint`](`crate::PhysicsClient::create_constraint`).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SceneConstraint {
+    /// name of the parent body
+    pub parent: String,
+    /// parent link index or `None` for the base
+    pub parent_link: Option<usize>,
+    /// name of the child body or `None` for a fixed frame in world coordinates
+    pub child: Option<String>,
+    /// child link index or `None` for the base
+    pub child_link: Option<usize>,
+    /// type of the constraint
+    pub joint_type: JointType,
+    /// joint axis in child link frame
+    pub joint_axis: [f64; 3],
+    /// pose of the joint frame relative to the parent center of mass frame
+    pub parent_frame: ScenePose,
+    /// pose of the joint frame relative to the child center of mass frame
+    pub child_frame: ScenePose,
+    /// maximum force that the constraint can apply
+    pub max_force: f64,
+    /// ratio between the rates at which the two gears rotate
+    pub gear_ratio: f64,
+    /// auxiliary link of gear constraints
+    pub gear_aux_link: Option<usize>,
+    /// relative position target offset between two gears
+    pub relative_position_target: f64,
+    /// constraint error reduction parameter. The default of the physics engine is used for
+    /// values which are not positive.
+    pub erp: f64,
+}
+
+impl Default for SceneConstraint {
+    fn default() -> Self {
+        SceneConstraint {
+            parent: String::new(),
+            parent_link: None,
+            child: None,
+            child_link: None,
+            joint_type: JointType::Fixed,
+            joint_axis: [0.; 3],
+            parent_frame: ScenePose::default(),
+            child_frame: ScenePose::default(),
+            max_force: 500.,
+            gear_ratio: 1.,
+            gear_aux_link: None,
+            relative_position_target: 0.,
+            erp: 0.,
+        }
+    }
+}
+
+/// Remembers from which file every body of a client was loaded.
+#[derive(Debug, Default)]
+pub(crate) struct LoadedAssets {
+    sources: Vec<AssetSource>,
+    /// body -> (index into sources, index of the body within the file)
+    bodies: HashMap<c_int, (usize, usize)>,
+    /// primitives which were created by `load_scene` and their materials
+    primitives: HashMap<c_int, (ScenePrimitive, Option<SceneMaterial>)>,
+}
+
+impl LoadedAssets {
+    pub(crate) fn insert_urdf(&mut self, file: PathBuf, options: &UrdfOptions, body: BodyId) {
+        let source = AssetSource::Urdf {
+            file,
+            use_fixed_base: options.use_fixed_base,
+            use_maximal_coordinates: options.use_maximal_coordinates,
+            flags: options.flags,
+            global_scaling: options.global_scaling,
+        };
+        self.insert(source, &[body]);
+    }
+    pub(crate) fn insert_sdf(
+        &mut self,
+        file: PathBuf,
+        options: Option<&SdfOptions>,
+        bodies: &[BodyId],
+    ) {
+        let default = SdfOptions::default();
+        let options = options.unwrap_or(&default);
+        let source = AssetSource::Sdf {
+            file,
+            use_maximal_coordinates: options.use_maximal_coordinates,
+            global_scaling: options.global_scaling,
+        };
+        self.insert(source, bodies);
+    }
+    pub(crate) fn insert_mjcf(
+        &mut self,
+        file: PathBuf,
+        flags: Option<LoadModelFlags>,
+        bodies: &[BodyId],
+    ) {
+        self.insert(AssetSource::Mjcf { file, flags }, bodies);
+    }
+    fn insert(&mut self, source: AssetSource, bodies: &[BodyId]) {
+        let source_index = self.sources.len();
+        self.sources.push(source);
+        for (index, body) in bodies.iter().enumerate() {
+            self.bodies.insert(body.0, (source_index, index));
+        }
+    }
+    fn insert_primitive(
+        &mut self,
+        body: BodyId,
+        primitive: &ScenePrimitive,
+        material: Option<&SceneMaterial>,
+    ) {
+        self.primitives
+            .insert(body.0, (primitive.clone(), material.cloned()));
+    }
+    pub(crate) fn remove(&mut self, body: BodyId) {
+        self.bodies.remove(&body.0);
+        self.primitives.remove(&body.0);
+    }
+    pub(crate) fn clear(&mut self) {
+        self.sources.clear();
+        self.bodies.clear();
+        self.primitives.clear();
+    }
+}
+
+pub(crate) fn save_scene(client: &mut PhysicsClient) -> Result<Scene, Error> {
+    let parameters = client.get_physics_engine_parameters()?;
+    let num_bodies = client.get_num_bodies()?;
+    let mut bodies = Vec::with_capacity(num_bodies);
+    let mut primitive_bodies = Vec::new();
+    let mut unsaveable = Vec::new();
+    for serial_index in 0..num_bodies {
+        let body = client.get_body_id(serial_index)?;
+        if let Some(&(source, index)) = client.assets.bodies.get(&body.0) {
+            bodies.push((source, index, body));
+        } else if client.assets.primitives.contains_key(&body.0) {
+            primitive_bodies.push(body);
+        } else {
+            unsaveable.push(body);
+        }
+    }
+    if !unsaveable.is_empty() {
+        let mut descriptions = Vec::with_capacity(unsaveable.len());
+        for body in unsaveable {
+            descriptions.push(format!("{} ({})", body.0, body_name(client, body)?));
+        }
+        return Err(Error::of_kind(
+            ErrorKind::InvalidArgument,
+            format!(
+                "the bodies {} were neither loaded from a file nor created by load_scene and can not be saved",
+                descriptions.join(", ")
+            ),
+        ));
+    }
+    bodies.sort_by_key(|&(source, index, _)| (source, index));
+
+    let mut assets: Vec<SceneAsset> = Vec::new();
+    let mut last_source = None;
+    let mut names = HashMap::new();
+    let mut used_names = HashSet::new();
+    for (source, index, body) in bodies {
+        let name = unique_name(body_name(client, body)?, &mut used_names);
+        names.insert(body.0, name.clone());
+        let scene_body = save_body(client, body, name, index)?;
+        if last_source != Some(source) {
+            last_source = Some(source);
+            assets.push(SceneAsset {
+                source: client.assets.sources[source].clone(),
+                bodies: vec![],
+            });
+        }
+        assets.last_mut().unwrap().bodies.push(scene_body);
+    }
+
+    let mut materials = Vec::new();
+    let mut primitives = Vec::with_capacity(primitive_bodies.len());
+    for body in primitive_bodies {
+        let (primitive, material) = client.assets.primitives[&body.0].clone();
+        let name = unique_name(primitive.name.clone(), &mut used_names);
+        names.insert(body.0, name.clone());
+        primitives.push(ScenePrimitive {
+            name,
+            mass: client.get_dynamics_info(body, None)?.mass,
+            pose: client.get_base_transform(body)?.into(),
+            velocity: client.get_base_velocity(body)?.to_vector().into(),
+            material: material.map(|material| save_material(&mut materials, material)),
+            ..primitive
+        });
+    }
+
+    let mut constraints = Vec::new();
+    for serial_index in 0..client.get_num_constraints()? {
+        let constraint = client.get_constraint(serial_index)?;
+        let info = client.get_constraint_info(constraint)?;
+        let parent = match names.get(&info.parent_body.0) {
+            Some(name) => name.clone(),
+            None => continue,
+        };
+        let child = match info.child_body {
+            Some(child) => match names.get(&child.0) {
+                Some(name) => Some(name.clone()),
+                None => continue,
+            },
+            None => None,
+        };
+        constraints.push(SceneConstraint {
+            parent,
+            parent_link: info.parent_link_index,
+            child,
+            child_link: info.child_link_index,
+            joint_type: info.constraint_type,
+            joint_axis: info.joint_axis.into(),
+            parent_frame: info.joint_parent_frame_pose.into(),
+            child_frame: info.joint_child_frame_pose.into(),
+            max_force: info.max_applied_force,
+            gear_ratio: info.gear_ratio,
+            gear_aux_link: info.gear_aux_link,
+            relative_position_target: info.relative_position_target,
+            erp: info.erp,
+        });
+    }
+
+    Ok(Scene {
+        version: SCENE_FORMAT_VERSION,
+        gravity: parameters.gravity.into(),
+        time_step: parameters.fixed_time_step.as_secs_f64(),
+        physics: (&parameters).into(),
+        materials,
+        assets,
+        primitives,
+        constraints,
+        ..Default::default()
+    })
+}
+
+/// returns the robot name of the body in its file or the name of its base.
+fn body_name(client: &mut PhysicsClient, body: BodyId) -> Result<String, Error> {
+    let info = client.get_body_info(body)?;
+    if info.body_name.is_empty() {
+        Ok(info.base_name)
+    } else {
+        Ok(info.body_name)
+    }
+}
+
+/// appends a number to duplicate names, skipping numbered names which are already taken.
+fn unique_name(name: String, used_names: &mut HashSet<String>) -> String {
+    let mut unique_name = name.clone();
+    let mut count = 1;
+    while used_names.contains(&unique_name) {
+        count += 1;
+        unique_name = format!("{}_{}", name, count);
+    }
+    used_names.insert(unique_name.clone());
+    unique_name
+}
+
+/// adds the material of a primitive to the saved materials and returns its name there. Materials
+/// of different scenes which share a name get a number appended.
+fn save_material(materials: &mut Vec<SceneMaterial>, mut material: SceneMaterial) -> String {
+    let name = material.name.clone();
+    let mut count = 1;
+    loop {
+        match materials.iter().find(|saved| saved.name == material.name) {
+            None => {
+                let unique_name = material.name.clone();
+                materials.push(material);
+                return unique_name;
+            }
+            Some(saved) if *saved == material => return material.name,
+            Some(_) => {
+                count += 1;
+                material.name = format!("{}_{}", name, count);
+            }
+        }
+    }
+}
+
+fn save_body(
+    client: &mut PhysicsClient,
+    body: BodyId,
+    name: String,
+    index: usize,
+) -> Result<SceneBody, Error> {
+    let num_joints = client.get_num_joints(body)?;
+    let mut joints = Vec::new();
+    for joint_index in 0..num_joints {
+        let info = client.get_joint_info(body, joint_index)?;
+        if info.q_index < 0 {
+            continue;
+        }
+        let state = client.get_joint_state_multi_dof(body, joint_index)?;
+        joints.push(SceneJoint {
+            name: info.joint_name,
+            position: state.joint_position,
+            velocity: state.joint_velocity,
+        });
+    }
+    let mut dynamics = Vec::with_capacity(num_joints + 1);
+    let links = std::iter::once(None).chain((0..num_joints).map(Some));
+    for link in links {
+        let info = client.get_dynamics_info(body, link)?;
+        dynamics.push(SceneDynamics {
+            link,
+            mass: Some(info.mass),
+            lateral_friction: Some(info.lateral_friction),
+            spinning_friction: Some(info.spinning_friction),
+            rolling_friction: Some(info.rolling_friction),
+            restitution: Some(info.restitution),
+            contact_stiffness_and_damping: info.contact_stiffness_and_damping,
+            local_inertia_diagonal: Some(info.local_inertia_diagonal.into()),
+            collision_margin: Some(info.collision_margin),
+        });
+    }
+    Ok(SceneBody {
+        name,
+        index,
+        pose: client.get_base_transform(body)?.into(),
+        velocity: client.get_base_velocity(body)?.to_vector().into(),
+        joints,
+        material: None,
+        dynamics,
+    })
+}
+
+pub(crate) fn load_scene(
+    client: &mut PhysicsClient,
+    scene: &Scene,
+) -> Result<HashMap<String, BodyId>, Error> {
+    if scene.version > SCENE_FORMAT_VERSION {
+        return Err(Error::of_kind(
+            ErrorKind::InvalidArgument,
+            format!(
+                "scene format version {} is newer than the supported version {}",
+                scene.version, SCENE_FORMAT_VERSION
+            ),
+        ));
+    }
+    let mut materials = HashMap::new();
+    for material in scene.materials.iter() {
+        if materials.insert(material.name.as_str(), material).is_some() {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "the scene contains several materials named {}",
+                    material.name
+                ),
+            ));
+        }
+    }
+    let material = |name: &Option<String>| match name {
+        None => Ok(None),
+        Some(name) => materials
+            .get(name.as_str())
+            .copied()
+            .map(Some)
+            .ok_or_else(|| {
+                Error::of_kind(
+                    ErrorKind::InvalidArgument,
+                    format!("the scene has no material named {}", name),
+                )
+            }),
+    };
+    client.set_physics_engine_parameter((&scene.physics).into())?;
+    client.set_gravity(scene.gravity)?;
+    client.set_time_step(std::time::Duration::from_secs_f64(scene.time_step))?;
+    let mut bodies = HashMap::new();
+    for asset in scene.assets.iter() {
+        let loaded = load_asset(client, asset)?;
+        for (index, &body) in loaded.iter().enumerate() {
+            if !asset
+                .bodies
+                .iter()
+                .any(|scene_body| scene_body.index == index)
+            {
+                client.remove_body(body)?;
+            }
+        }
+        for scene_body in asset.bodies.iter() {
+            let body = *loaded.get(scene_body.index).ok_or_else(|| {
+                Error::of_kind(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "body {} has index {}, but its file only contains {} bodies",
+                        scene_body.name,
+                        scene_body.index,
+                        loaded.len()
+                    ),
+                )
+            })?;
+            apply_body(client, body, scene_body, material(&scene_body.material)?)?;
+            insert_body(&mut bodies, &scene_body.name, body)?;
+        }
+    }
+    for primitive in scene.primitives.iter() {
+        let body = load_primitive(client, primitive, material(&primitive.material)?)?;
+        insert_body(&mut bodies, &primitive.name, body)?;
+    }
+    for constraint in scene.constraints.iter() {
+        load_constraint(client, constraint, &bodies)?;
+    }
+    Ok(bodies)
+}
+
+fn insert_body(
+    bodies: &mut HashMap<String, BodyId>,
+    name: &str,
+    body: BodyId,
+) -> Result<(), Error> {
+    if bodies.insert(name.to_string(), body).is_some() {
+        return Err(Error::of_kind(
+            ErrorKind::InvalidArgument,
+            format!("the scene contains several bodies named {}", name),
+        ));
+    }
+    Ok(())
+}
+
+fn load_asset(client: &mut PhysicsClient, asset: &SceneAsset) -> Result<Vec<BodyId>, Error> {
+    match &asset.source {
+        AssetSource::Urdf {
+            file,
+            use_fixed_base,
+            use_maximal_coordinates,
+            flags,
+            global_scaling,
+        } => {
+            let base_transform = match asset.bodies.first() {
+                Some(body) => body.pose.into(),
+                None => Isometry3::identity(),
+            };
+            let options = UrdfOptions {
+                base_transform,
+                use_fixed_base: *use_fixed_base,
+                use_maximal_coordinates: *use_maximal_coordinates,
+                flags: *flags,
+                global_scaling: *global_scaling,
+            };
+            Ok(vec![client.load_urdf(file, options)?])
+        }
+        AssetSource::Sdf {
+            file,
+            use_maximal_coordinates,
+            global_scaling,
+        } => client.load_sdf(
+            file,
+            SdfOptions {
+                use_maximal_coordinates: *use_maximal_coordinates,
+                global_scaling: *global_scaling,
+            },
+        ),
+        AssetSource::Mjcf { file, flags } => client.load_mjcf(file, *flags),
+    }
+}
+
+fn apply_body(
+    client: &mut PhysicsClient,
+    body: BodyId,
+    scene_body: &SceneBody,
+    material: Option<&SceneMaterial>,
+) -> Result<(), Error> {
+    client.reset_base_transform(body, scene_body.pose.into())?;
+    let [vx, vy, vz, wx, wy, wz] = scene_body.velocity;
+    client.reset_base_velocity(body, [vx, vy, vz], [wx, wy, wz])?;
+    if !scene_body.joints.is_empty() {
+        let mut joint_indices = HashMap::new();
+        for joint_index in 0..client.get_num_joints(body)? {
+            let info = client.get_joint_info(body, joint_index)?;
+            joint_indices.insert(info.joint_name, joint_index);
+        }
+        for joint in scene_body.joints.iter() {
+            let joint_index = *joint_indices.get(&joint.name).ok_or_else(|| {
+                Error::of_kind(
+                    ErrorKind::InvalidArgument,
+                    format!("body {} has no joint named {}", scene_body.name, joint.name),
+                )
+            })?;
+            let velocity = if joint.velocity.is_empty() {
+                None
+            } else {
+                Some(joint.velocity.as_slice())
+            };
+            client.reset_joint_state_multi_dof(body, joint_index, &joint.position, velocity)?;
+        }
+    }
+    if let Some(material) = material {
+        let links = std::iter::once(None).chain((0..client.get_num_joints(body)?).map(Some));
+        for link in links {
+            apply_material(client, body, link, material)?;
+        }
+    }
+    for dynamics in scene_body.dynamics.iter() {
+        client.change_dynamics(body, dynamics.link, dynamics.into())?;
+    }
+    Ok(())
+}
+
+fn apply_material(
+    client: &mut PhysicsClient,
+    body: BodyId,
+    link: Option<usize>,
+    material: &SceneMaterial,
+) -> Result<(), Error> {
+    client.change_dynamics(body, link, material.into())?;
+    if material.color.is_some() || material.specular_color.is_some() {
+        client.change_visual_shape(
+            body,
+            link,
+            ChangeVisualShapeOptions {
+                rgba_color: material.color,
+                specular_color: material.specular_color,
+                ..Default::default()
+            },
+        )?;
+    }
+    Ok(())
+}
+
+fn load_primitive(
+    client: &mut PhysicsClient,
+    primitive: &ScenePrimitive,
+    material: Option<&SceneMaterial>,
+) -> Result<BodyId, Error> {
+    let collision = client.create_collision_shape(primitive.shape.collision_shape(), None)?;
+    let mut visual_options = VisualShapeOptions::default();
+    if let Some(color) = primitive.color.or_else(|| material.and_then(|m| m.color)) {
+        visual_options.rgba_colors = color;
+    }
+    if let Some(specular_color) = material.and_then(|m| m.specular_color) {
+        visual_options.specular_colors = specular_color;
+    }
+    let visual = client.create_visual_shape(primitive.shape.visual_shape(), visual_options)?;
+    let body = client.create_multi_body(
+        collision,
+        visual,
+        MultiBodyOptions {
+            base_mass: primitive.mass,
+            base_pose: primitive.pose.into(),
+            ..Default::default()
+        },
+    )?;
+    client.assets.insert_primitive(body, primitive, material);
+    let [vx, vy, vz, wx, wy, wz] = primitive.velocity;
+    client.reset_base_velocity(body, [vx, vy, vz], [wx, wy, wz])?;
+    if let Some(material) = material {
+        client.change_dynamics(body, None, material.into())?;
+    }
+    Ok(body)
+}
+
+fn load_constraint(
+    client: &mut PhysicsClient,
+    constraint: &SceneConstraint,
+    bodies: &HashMap<String, BodyId>,
+) -> Result<(), Error> {
+    let body = |name: &String| {
+        bodies.get(name).copied().ok_or_else(|| {
+            Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!("the constraint refers to an unknown body {}", name),
+            )
+        })
+    };
+    let parent = body(&constraint.parent)?;
+    let child = match &constraint.child {
+        Some(child) => Some(body(child)?),
+        None => None,
+    };
+    let id = client.create_constraint(
+        parent,
+        constraint.parent_link,
+        child,
+        constraint.child_link,
+        constraint.joint_type,
+        constraint.joint_axis,
+        constraint.parent_frame.into(),
+        constraint.child_frame.into(),
+    )?;
+    let mut options = ChangeConstraintOptions {
+        max_force: Some(constraint.max_force),
+        ..Default::default()
+    };
+    if constraint.joint_type == JointType::Gear {
+        options.gear_ratio = Some(constraint.gear_ratio);
+        options.gear_aux_link = constraint.gear_aux_link;
+        options.relative_position_target = Some(constraint.relative_position_target);
+    }
+    if constraint.erp > 0. {
+        options.erp = Some(constraint.erp);
+    }
+    client.change_constraint(id, options)
+}
@@ -0,0 +1,667 @@
+//! Simulated sensors which are mounted on a body.
+//!
+//! A [`Lidar`] casts a fan (2D) or several stacked fans (3D) of rays from the pose of a body or
+//! link and reports the measured ranges and the hit points in the sensor frame. Scans with more
+//! rays than a single [`ray_test_batch`](`crate::PhysicsClient::ray_test_batch`) call can handle
+//! are split into several batches.
+//!
+//! A [`Camera`] is a pinhole camera which is described by its [intrinsics](`CameraIntrinsics`)
+//! instead of raw view and projection matrices. It can either be placed in the world or be
+//! mounted on a link, which it follows when it renders an image.
+//!
+//! # Example
+//! ```no_run
+//! use std::f64::consts::PI;
+//!
+//! use anyhow::Result;
+//! use misfire::sensors::{Lidar, LidarOptions};
+//! use misfire::*;
+//! use nalgebra::Isometry3;
+//!
+//! fn main() -> Result<()> {
+//!     let mut client = PhysicsClient::connect(Mode::Direct)?;
+//!     client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
+//!     client.load_urdf("plane.urdf", None)?;
+//!     let r2d2 = client.load_urdf(
+//!         "r2d2.urdf",
+//!         UrdfOptions {
+//!             base_transform: Isometry3::translation(0., 0., 0.5),
+//!             ..Default::default()
+//!         },
+//!     )?;
+//!     // a 16 beam 3D lidar on top of the robot
+//!     let mut lidar = Lidar::new(
+//!         r2d2,
+//!         None,
+//!         LidarOptions {
+//!             mount_pose: Isometry3::translation(0., 0., 0.5),
+//!             num_horizontal_beams: 1024,
+//!             vertical_angles: (0..16).map(|i| (-15. + 2. * i as f64).to_radians()).collect(),
+//!             min_range: 0.3,
+//!             max_range: 20.,
+//!             range_noise_std_dev: 0.01,
+//!             ..Default::default()
+//!         },
+//!     )?;
+//!     let scan = lidar.scan(&mut client)?;
+//!     println!("{} of {} beams hit", scan.num_hits(), scan.ranges.len());
+//!     let _cloud = scan.world_point_cloud();
+//!     assert!(lidar.horizontal_angles()[0] >= -PI);
+//!     Ok(())
+//! }
+//! ```
+use std::f64::consts::PI;
+
+use misfire_sys::MAX_RAY_INTERSECTION_BATCH_SIZE_STREAMING;
+use nalgebra::{Isometry3, Matrix3, Matrix3xX, Matrix4, Point3, UnitQuaternion, Vector2, Vector3};
+
+use crate::rng::Rng;
+use crate::{
+    BodyId, CameraImageOptions, Error, ErrorKind, Images, PhysicsClient, RayTestBatchOptions,
+};
+
+/// Options for [`Lidar::new`].
+///
+/// The sensor frame has its x-axis pointing forward, the y-axis to the left and the z-axis up.
+/// Horizontal angles are measured counterclockwise from the x-axis, vertical angles upwards from
+/// the xy-plane.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LidarOptions {
+    /// pose of the sensor frame relative to the frame of the body or link it is mounted on.
+    pub mount_pose: Isometry3<f64>,
+    /// horizontal field of view in radians. With a value of 2π (default) the beams cover a full
+    /// circle starting at -π, otherwise they are spread evenly from -fov/2 to fov/2.
+    pub horizontal_fov: f64,
+    /// number of beams per horizontal scan line. Default: 360
+    pub num_horizontal_beams: usize,
+    /// vertical angle of every scan line in radians. A single angle of 0 (default) gives a planar
+    /// 2D scan, more angles give a multi-beam 3D scan.
+    pub vertical_angles: Vec<f64>,
+    /// beams start at this distance from the sensor origin, so that the body the sensor is mounted
+    /// on is not seen. Default: 0.1
+    pub min_range: f64,
+    /// maximum distance at which a hit is reported. Default: 10
+    pub max_range: f64,
+    /// standard deviation of the gaussian noise which is added to every measured range.
+    /// Noisy ranges are clamped to \[min_range, max_range\]. Default: 0 (no noise)
+    pub range_noise_std_dev: f64,
+    /// seed for the noise. If None, the generator is seeded with the current time.
+    pub seed: Option<u64>,
+    /// only bodies whose collision filter group has a common bit with this mask are seen.
+    /// See [`RayTestBatchOptions`](`crate::RayTestBatchOptions`).
+    pub collision_filter_mask: Option<i32>,
+    /// number of threads used for the ray tests. See
+    /// [`RayTestBatchOptions`](`crate::RayTestBatchOptions`).
+    pub num_threads: Option<usize>,
+}
+
+impl Default for LidarOptions {
+    fn default() -> Self {
+        LidarOptions {
+            mount_pose: Isometry3::identity(),
+            horizontal_fov: 2. * PI,
+            num_horizontal_beams: 360,
+            vertical_angles: vec![0.],
+            min_range: 0.1,
+            max_range: 10.,
+            range_noise_std_dev: 0.,
+            seed: None,
+            collision_filter_mask: None,
+            num_threads: None,
+        }
+    }
+}
+
+/// A simulated lidar which is mounted on a body or one of its links.
+///
+/// Like [`Robot`](`crate::Robot`), a `Lidar` does not hold on to the client, which has to be
+/// passed to [`scan`](`Self::scan`). See the [module documentation](`self`) for an example.
+#[derive(Debug, Clone)]
+pub struct Lidar {
+    body: BodyId,
+    link_index: Option<usize>,
+    options: LidarOptions,
+    horizontal_angles: Vec<f64>,
+    directions: Vec<Vector3<f64>>,
+    rng: Rng,
+}
+
+impl Lidar {
+    /// creates a lidar which is attached to a body.
+    ///
+    /// # Arguments
+    /// * `body` - the body the sensor is mounted on.
+    /// * `link_index` - link the sensor is mounted on or None for the base of the body.
+    /// * `options` - scan pattern, range limits and noise. Pass None for a planar 360° scan.
+    ///
+    /// Returns an error with [`ErrorKind::InvalidArgument`](`crate::ErrorKind::InvalidArgument`)
+    /// if the options describe an empty scan or invalid ranges.
+    pub fn new<Options: Into<Option<LidarOptions>>>(
+        body: BodyId,
+        link_index: Option<usize>,
+        options: Options,
+    ) -> Result<Lidar, Error> {
+        let options = options.into().unwrap_or_default();
+        if options.num_horizontal_beams == 0 || options.vertical_angles.is_empty() {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                "a lidar needs at least one horizontal beam and one vertical angle",
+            ));
+        }
+        if !(options.horizontal_fov > 0. && options.horizontal_fov <= 2. * PI) {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "the horizontal field of view must be in (0, 2π], but is {}",
+                    options.horizontal_fov
+                ),
+            ));
+        }
+        if !(options.min_range >= 0.
+            && options.min_range < options.max_range
+            && options.max_range.is_finite())
+        {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "invalid lidar range [{}, {}]",
+                    options.min_range, options.max_range
+                ),
+            ));
+        }
+        if options.range_noise_std_dev.is_nan() || options.range_noise_std_dev < 0. {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                "the range noise must not be negative",
+            ));
+        }
+        let num_beams = options.num_horizontal_beams;
+        let fov = options.horizontal_fov;
+        let horizontal_angles: Vec<f64> = (0..num_beams)
+            .map(|i| {
+                if fov >= 2. * PI {
+                    -PI + fov * i as f64 / num_beams as f64
+                } else if num_beams == 1 {
+                    0.
+                } else {
+                    -fov / 2. + fov * i as f64 / (num_beams - 1) as f64
+                }
+            })
+            .collect();
+        let mut directions = Vec::with_capacity(num_beams * options.vertical_angles.len());
+        for &vertical in options.vertical_angles.iter() {
+            for &horizontal in horizontal_angles.iter() {
+                directions.push(Vector3::new(
+                    vertical.cos() * horizontal.cos(),
+                    vertical.cos() * horizontal.sin(),
+                    vertical.sin(),
+                ));
+            }
+        }
+        let rng = match options.seed {
+            None => Rng::from_time(),
+            Some(seed) => Rng::new(seed),
+        };
+        Ok(Lidar {
+            body,
+            link_index,
+            options,
+            horizontal_angles,
+            directions,
+            rng,
+        })
+    }
+    /// the body the sensor is mounted on.
+    pub fn body(&self) -> BodyId {
+        self.body
+    }
+    /// the link the sensor is mounted on or None if it is mounted on the base.
+    pub fn link_index(&self) -> Option<usize> {
+        self.link_index
+    }
+    /// the options the lidar was created with.
+    pub fn options(&self) -> &LidarOptions {
+        &self.options
+    }
+    /// the horizontal angle of every beam in a scan line.
+    pub fn horizontal_angles(&self) -> &[f64] {
+        &self.horizontal_angles
+    }
+    /// unit direction of every beam in the sensor frame, in the same order as the
+    /// [`ranges`](`LidarScan::ranges`) of a scan.
+    pub fn directions(&self) -> &[Vector3<f64>] {
+        &self.directions
+    }
+    /// current world pose of the sensor frame.
+    pub fn sensor_pose(&self, client: &mut PhysicsClient) -> Result<Isometry3<f64>, Error> {
+        Ok(link_frame_pose(client, self.body, self.link_index)? * self.options.mount_pose)
+    }
+    /// performs a scan at the current state of the simulation.
+    ///
+    /// Scans with more beams than
+    /// [`MAX_RAY_INTERSECTION_BATCH_SIZE_STREAMING`](`misfire_sys::MAX_RAY_INTERSECTION_BATCH_SIZE_STREAMING`)
+    /// are split into several ray tests.
+    pub fn scan(&mut self, client: &mut PhysicsClient) -> Result<LidarScan, Error> {
+        let sensor_pose = self.sensor_pose(client)?;
+        let origin = sensor_pose.translation.vector;
+        let min_range = self.options.min_range;
+        let max_range = self.options.max_range;
+        let (ray_from, ray_to): (Vec<Vector3<f64>>, Vec<Vector3<f64>>) = self
+            .directions
+            .iter()
+            .map(|direction| {
+                let direction = sensor_pose.rotation * direction;
+                (
+                    origin + direction * min_range,
+                    origin + direction * max_range,
+                )
+            })
+            .unzip();
+
+        let mut ranges = Vec::with_capacity(self.directions.len());
+        let mut points = Vec::with_capacity(self.directions.len());
+        for (from, to) in ray_from
+            .chunks(MAX_RAY_INTERSECTION_BATCH_SIZE_STREAMING)
+            .zip(ray_to.chunks(MAX_RAY_INTERSECTION_BATCH_SIZE_STREAMING))
+        {
+            let hits = client.ray_test_batch(
+                from,
+                to,
+                RayTestBatchOptions {
+                    num_threads: self.options.num_threads,
+                    collision_filter_mask: self.options.collision_filter_mask,
+                    ..Default::default()
+                },
+            )?;
+            for i in 0..from.len() {
+                let direction = self.directions[ranges.len()];
+                match hits.get(i).copied().flatten() {
+                    None => {
+                        ranges.push(None);
+                        points.push(None);
+                    }
+                    Some(hit) => {
+                        let mut range = min_range + hit.hit_fraction * (max_range - min_range);
+                        if self.options.range_noise_std_dev > 0. {
+                            range = self
+                                .rng
+                                .normal(range, self.options.range_noise_std_dev)
+                                .clamp(min_range, max_range);
+                        }
+                        ranges.push(Some(range));
+                        points.push(Some(direction * range));
+                    }
+                }
+            }
+        }
+        Ok(LidarScan {
+            sensor_pose,
+            num_horizontal_beams: self.options.num_horizontal_beams,
+            num_vertical_beams: self.options.vertical_angles.len(),
+            ranges,
+            points,
+        })
+    }
+}
+
+/// The result of a [`Lidar`] scan.
+///
+/// The beams are stored scan line by scan line, i.e. the beam with vertical index `v` and
+/// horizontal index `h` is at index `v * num_horizontal_beams + h`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LidarScan {
+    /// world pose of the sensor frame at the time of the scan.
+    pub sensor_pose: Isometry3<f64>,
+    /// number of beams per scan line.
+    pub num_horizontal_beams: usize,
+    /// number of scan lines.
+    pub num_vertical_beams: usize,
+    /// measured distance of every beam or None if nothing was hit within the range of the sensor.
+    pub ranges: Vec<Option<f64>>,
+    /// hit point of every beam in the sensor frame.
+    pub points: Vec<Option<Vector3<f64>>>,
+}
+
+impl LidarScan {
+    /// returns the range of a single beam or None if the beam did not hit anything or the
+    /// indices are out of bounds.
+    pub fn range(&self, vertical_index: usize, horizontal_index: usize) -> Option<f64> {
+        if vertical_index >= self.num_vertical_beams
+            || horizontal_index >= self.num_horizontal_beams
+        {
+            return None;
+        }
+        self.ranges
+            .get(vertical_index * self.num_horizontal_beams + horizontal_index)
+            .copied()
+            .flatten()
+    }
+    /// number of beams which hit something.
+    pub fn num_hits(&self) -> usize {
+        self.ranges.iter().filter(|range| range.is_some()).count()
+    }
+    /// returns the hit points in the sensor frame as the columns of a matrix.
+    /// Beams without a hit are left out.
+    pub fn point_cloud(&self) -> Matrix3xX<f64> {
+        let points: Vec<Vector3<f64>> = self.points.iter().flatten().copied().collect();
+        Matrix3xX::from_columns(&points)
+    }
+    /// returns the hit points in world coordinates as the columns of a matrix.
+    /// Beams without a hit are left out.
+    pub fn world_point_cloud(&self) -> Matrix3xX<f64> {
+        let points: Vec<Vector3<f64>> = self
+            .points
+            .iter()
+            .flatten()
+            .map(|point| (self.sensor_pose * Point3::from(*point)).coords)
+            .collect();
+        Matrix3xX::from_columns(&points)
+    }
+}
+
+/// The intrinsic parameters of a pinhole camera in the OpenCV convention.
+///
+/// Pixel coordinates start with (0, 0) at the center of the top left pixel, with u pointing
+/// right and v pointing down. A point (x, y, z) in the camera frame, whose z-axis is the optical
+/// axis, lands on the pixel (fx * x / z + cx, fy * y / z + cy).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CameraIntrinsics {
+    /// focal length in pixels along u
+    pub fx: f64,
+    /// focal length in pixels along v
+    pub fy: f64,
+    /// u coordinate of the principal point
+    pub cx: f64,
+    /// v coordinate of the principal point
+    pub cy: f64,
+    /// image width in pixels
+    pub width: usize,
+    /// image height in pixels
+    pub height: usize,
+    /// near clipping plane in meters
+    pub near: f64,
+    /// far clipping plane in meters
+    pub far: f64,
+}
+
+impl CameraIntrinsics {
+    /// creates intrinsics with square pixels and the principal point in the center of the image,
+    /// which are equivalent to
+    /// [`compute_projection_matrix_fov`](`crate::PhysicsClient::compute_projection_matrix_fov`).
+    ///
+    /// # Arguments
+    /// * `fov` - vertical field of view in degrees
+    /// * `width` - image width in pixels
+    /// * `height` - image height in pixels
+    /// * `near` - near clipping plane in meters
+    /// * `far` - far clipping plane in meters
+    pub fn from_fov(fov: f64, width: usize, height: usize, near: f64, far: f64) -> Self {
+        let focal_length = height as f64 / 2. / (fov.to_radians() / 2.).tan();
+        CameraIntrinsics {
+            fx: focal_length,
+            fy: focal_length,
+            cx: (width as f64 - 1.) / 2.,
+            cy: (height as f64 - 1.) / 2.,
+            width,
+            height,
+            near,
+            far,
+        }
+    }
+    /// recovers the intrinsics from an OpenGL projection matrix, e.g. one from
+    /// [`compute_projection_matrix_fov`](`crate::PhysicsClient::compute_projection_matrix_fov`).
+    ///
+    /// Returns an error with [`ErrorKind::InvalidArgument`](`crate::ErrorKind::InvalidArgument`)
+    /// if the matrix is not a perspective projection.
+    pub fn from_projection_matrix(
+        projection_matrix: &Matrix4<f32>,
+        width: usize,
+        height: usize,
+    ) -> Result<Self, Error> {
+        let m = projection_matrix.cast::<f64>();
+        let is_perspective = (m[(3, 2)] + 1.).abs() < 1e-6
+            && m[(3, 0)] == 0.
+            && m[(3, 1)] == 0.
+            && m[(3, 3)] == 0.
+            && m[(0, 0)] != 0.
+            && m[(1, 1)] != 0.;
+        if !is_perspective {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                "the projection matrix is not a perspective projection",
+            ));
+        }
+        let (width_f, height_f) = (width as f64, height as f64);
+        Ok(CameraIntrinsics {
+            fx: m[(0, 0)] * width_f / 2.,
+            fy: m[(1, 1)] * height_f / 2.,
+            cx: (1. - m[(0, 2)]) * width_f / 2. - 0.5,
+            cy: (1. + m[(1, 2)]) * height_f / 2. - 0.5,
+            width,
+            height,
+            near: m[(2, 3)] / (m[(2, 2)] - 1.),
+            far: m[(2, 3)] / (m[(2, 2)] + 1.),
+        })
+    }
+    /// computes the OpenGL projection matrix which renders images with these intrinsics.
+    pub fn projection_matrix(&self) -> Matrix4<f32> {
+        let (width, height) = (self.width as f64, self.height as f64);
+        let (near, far) = (self.near, self.far);
+        Matrix4::new(
+            2. * self.fx / width,
+            0.,
+            1. - 2. * (self.cx + 0.5) / width,
+            0.,
+            0.,
+            2. * self.fy / height,
+            2. * (self.cy + 0.5) / height - 1.,
+            0.,
+            0.,
+            0.,
+            -(far + near) / (far - near),
+            -2. * far * near / (far - near),
+            0.,
+            0.,
+            -1.,
+            0.,
+        )
+        .cast::<f32>()
+    }
+    /// returns the camera matrix K.
+    pub fn camera_matrix(&self) -> Matrix3<f64> {
+        Matrix3::new(
+            self.fx, 0., self.cx, //
+            0., self.fy, self.cy, //
+            0., 0., 1.,
+        )
+    }
+    /// projects a point in the camera frame onto the image. Returns None for points which are
+    /// not in front of the camera. The pixel may lie outside of the image.
+    pub fn project(&self, point: Vector3<f64>) -> Option<Vector2<f64>> {
+        if point.z <= 0. {
+            return None;
+        }
+        Some(Vector2::new(
+            self.fx * point.x / point.z + self.cx,
+            self.fy * point.y / point.z + self.cy,
+        ))
+    }
+    /// returns the point in the camera frame which is seen at a pixel with the given depth,
+    /// i.e. the distance along the optical axis as returned by
+    /// [`Images::metric_depth`](`crate::Images::metric_depth`).
+    pub fn unproject(&self, pixel: Vector2<f64>, depth: f64) -> Vector3<f64> {
+        Vector3::new(
+            (pixel.x - self.cx) / self.fx * depth,
+            (pixel.y - self.cy) / self.fy * depth,
+            depth,
+        )
+    }
+}
+
+/// Describes where a [`Camera`] is mounted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CameraMount {
+    /// the body the camera is mounted on.
+    pub body: BodyId,
+    /// link the camera is mounted on or None for the base of the body.
+    pub link_index: Option<usize>,
+    /// pose of the camera frame relative to the frame of the link.
+    pub offset: Isometry3<f64>,
+}
+
+/// A pinhole camera.
+///
+/// The camera frame follows the OpenCV convention: the z-axis is the optical axis, the x-axis
+/// points right and the y-axis points down in the image. [`Camera::look_at`] creates such a pose
+/// from an eye and a target position.
+///
+/// # Example
+/// ```no_run
+/// use std::f64::consts::FRAC_PI_2;
+///
+/// use anyhow::Result;
+/// use misfire::sensors::{Camera, CameraIntrinsics};
+/// use misfire::*;
+/// use nalgebra::{Isometry3, Translation3, UnitQuaternion};
+///
+/// fn main() -> Result<()> {
+///     let mut client = PhysicsClient::connect(Mode::Direct)?;
+///     client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
+///     client.load_urdf("plane.urdf", None)?;
+///     let r2d2 = client.load_urdf("r2d2.urdf", None)?;
+///     // a camera on the head of the robot which looks along the x-axis of the head link
+///     let offset = Isometry3::from_parts(
+///         Translation3::new(0., 0., 0.3),
+///         UnitQuaternion::from_euler_angles(-FRAC_PI_2, 0., -FRAC_PI_2),
+///     );
+///     let mut camera = Camera::mounted(
+///         CameraIntrinsics::from_fov(60., 320, 240, 0.01, 10.),
+///         r2d2,
+///         Some(13),
+///         offset,
+///     );
+///     for _ in 0..100 {
+///         client.step_simulation()?;
+///         let images = camera.get_image(&mut client)?;
+///         let _depth = images.metric_depth(&camera.image_options())?;
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Camera {
+    /// intrinsic parameters
+    pub intrinsics: CameraIntrinsics,
+    /// world pose of the camera frame. Mounted cameras update it in
+    /// [`update_pose`](`Self::update_pose`).
+    pub pose: Isometry3<f64>,
+    /// the link the camera is mounted on or None for a camera which stays where it is.
+    pub mount: Option<CameraMount>,
+}
+
+impl Camera {
+    /// creates a camera with a fixed pose in the world.
+    pub fn new(intrinsics: CameraIntrinsics, pose: Isometry3<f64>) -> Camera {
+        Camera {
+            intrinsics,
+            pose,
+            mount: None,
+        }
+    }
+    /// creates a camera which is mounted on a body.
+    ///
+    /// # Arguments
+    /// * `intrinsics` - intrinsic parameters of the camera.
+    /// * `body` - the body the camera is mounted on.
+    /// * `link_index` - link the camera is mounted on or None for the base of the body.
+    /// * `offset` - pose of the camera frame relative to the frame of the link.
+    pub fn mounted(
+        intrinsics: CameraIntrinsics,
+        body: BodyId,
+        link_index: Option<usize>,
+        offset: Isometry3<f64>,
+    ) -> Camera {
+        Camera {
+            intrinsics,
+            pose: Isometry3::identity(),
+            mount: Some(CameraMount {
+                body,
+                link_index,
+                offset,
+            }),
+        }
+    }
+    /// returns the pose of a camera at `eye` which looks at `target`. The `up` vector points
+    /// upwards in the image.
+    pub fn look_at(eye: Vector3<f64>, target: Vector3<f64>, up: Vector3<f64>) -> Isometry3<f64> {
+        Isometry3::face_towards(&eye.into(), &target.into(), &-up)
+    }
+    /// moves a mounted camera to the current pose of its link and returns the new pose.
+    /// Cameras which are not mounted keep their pose.
+    pub fn update_pose(&mut self, client: &mut PhysicsClient) -> Result<Isometry3<f64>, Error> {
+        if let Some(mount) = self.mount {
+            self.pose = link_frame_pose(client, mount.body, mount.link_index)? * mount.offset;
+        }
+        Ok(self.pose)
+    }
+    /// computes the view matrix at the current pose.
+    pub fn view_matrix(&self) -> Matrix4<f32> {
+        // the OpenGL camera frame looks along its negative z-axis and has its y-axis pointing up
+        let opengl_from_opencv = UnitQuaternion::from_axis_angle(&Vector3::x_axis(), PI);
+        (opengl_from_opencv * self.pose.inverse())
+            .to_homogeneous()
+            .cast::<f32>()
+    }
+    /// computes the projection matrix of the camera.
+    pub fn projection_matrix(&self) -> Matrix4<f32> {
+        self.intrinsics.projection_matrix()
+    }
+    /// returns the options for [`get_camera_image`](`crate::PhysicsClient::get_camera_image`)
+    /// with the view and projection matrix of the camera at its current pose.
+    pub fn image_options(&self) -> CameraImageOptions {
+        CameraImageOptions {
+            view_matrix: Some(self.view_matrix()),
+            projection_matrix: Some(self.projection_matrix()),
+            ..Default::default()
+        }
+    }
+    /// updates the pose of a mounted camera and renders an image.
+    pub fn get_image(&mut self, client: &mut PhysicsClient) -> Result<Images, Error> {
+        self.update_pose(client)?;
+        client.get_camera_image(
+            self.intrinsics.width,
+            self.intrinsics.height,
+            self.image_options(),
+        )
+    }
+    /// projects a point in world coordinates onto the image. Returns None for points which are
+    /// not in front of the camera. The pixel may lie outside of the image.
+    pub fn project(&self, point: Vector3<f64>) -> Option<Vector2<f64>> {
+        self.intrinsics
+            .project(self.pose.inverse_transform_point(&point.into()).coords)
+    }
+    /// returns the point in world coordinates which is seen at a pixel with the given depth
+    /// along the optical axis.
+    pub fn unproject(&self, pixel: Vector2<f64>, depth: f64) -> Vector3<f64> {
+        (self.pose * Point3::from(self.intrinsics.unproject(pixel, depth))).coords
+    }
+}
+
+/// returns the world pose of the frame of a link or of the base if no link is given.
+fn link_frame_pose(
+    client: &mut PhysicsClient,
+    body: BodyId,
+    link_index: Option<usize>,
+) -> Result<Isometry3<f64>, Error> {
+    match link_index {
+        None => client.get_base_transform(body),
+        Some(link_index) => Ok(client
+            .get_link_state(body, link_index, false, true)?
+            .world_link_frame_pose),
+    }
+}
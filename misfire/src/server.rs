@@ -1,23 +1,62 @@
+#[cfg(feature = "gui")]
 use crate::client::marker::GuiMarker;
-use crate::{Error, PhysicsClient};
+#[cfg(feature = "gui")]
+use crate::PhysicsClient;
+use crate::{Error, ErrorKind};
 use misfire_sys as ffi;
+use misfire_sys::b3NetworkPhysicsServerHandle;
+#[cfg(feature = "gui")]
+use misfire_sys::b3PhysicsClientHandle;
 
 /// Choose which type of server you want.
 pub enum ServerMode {
+    /// Like [`Graphics`](`Self::Graphics`), but renders the GUI on the thread which creates the
+    /// server. Requires the `gui` feature.
+    #[cfg(feature = "gui")]
     GraphicsMainThread {
         /// port on which the server listens. `None` is for the default port "6667"
         tcp_port: Option<u16>,
     },
+    /// Server which renders the simulation of a [`Mode::GraphicsServerTcp`](`crate::Mode::GraphicsServerTcp`)
+    /// client in a local GUI. Requires the `gui` feature.
+    #[cfg(feature = "gui")]
     Graphics {
         /// port on which the server listens. `None` is for the default port "6667"
         tcp_port: Option<u16>,
     },
+    /// Headless server which simulates everything itself and accepts
+    /// [`Mode::Tcp`](`crate::Mode::Tcp`) clients. It needs neither a display nor OpenGL.
+    DirectTcp {
+        /// port on which the server listens. `None` is for the default port "6667"
+        port: Option<u16>,
+    },
+    /// Headless server which simulates everything itself and accepts
+    /// [`Mode::Udp`](`crate::Mode::Udp`) clients. It needs neither a display nor OpenGL.
+    DirectUdp {
+        /// port on which the server listens. `None` is for the default port "1234"
+        port: Option<u16>,
+    },
 }
-/// A PhysicsServer is actually a PhysicsClient which is run as a TCP Server and with
-/// all methods apart from [`is_connected`](`Self::is_connected`) disabled,
-/// as they would fail otherwise. This is for advanced users and you are usually you should be
+/// A PhysicsServer which remote clients can connect to.
+///
+/// With [`Graphics`](`ServerMode::Graphics`) and
+/// [`GraphicsMainThread`](`ServerMode::GraphicsMainThread`) it is actually a PhysicsClient which is
+/// run as a TCP Server and with all methods apart from [`is_connected`](`Self::is_connected`)
+/// disabled, as they would fail otherwise.
+/// With [`DirectTcp`](`ServerMode::DirectTcp`) and [`DirectUdp`](`ServerMode::DirectUdp`) the
+/// physics server runs headless on a background thread and executes the commands of its clients.
+/// The server stops when it is dropped.
+///
+/// This is for advanced users and you are usually you should be
 /// better of with a normal [`PhysicsClient`](`crate::PhysicsClient`).
-pub struct PhysicsServer(pub(crate) PhysicsClient);
+pub struct PhysicsServer(ServerHandle);
+
+enum ServerHandle {
+    #[cfg(feature = "gui")]
+    Graphics(Box<PhysicsClient>),
+    Network(b3NetworkPhysicsServerHandle),
+}
+
 impl PhysicsServer {
     /// creates a new TCP Server. The GUI is rendered locally but everything else happens on the client.
     /// # Example
@@ -34,22 +73,38 @@ impl PhysicsServer {
     ///     Ok(())
     /// }
     /// ```
+    ///
+    /// A headless server for clients on other machines:
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use misfire::*;
+    /// use std::time::Duration;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut physics_server = PhysicsServer::new(ServerMode::DirectTcp { port: Some(6667) })?;
+    ///     while physics_server.is_connected() {
+    ///         std::thread::sleep(Duration::from_secs(1));
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
     pub fn new(mode: ServerMode) -> Result<PhysicsServer, Error> {
-        let (raw_handle, _gui_marker) = match mode {
+        match mode {
+            #[cfg(feature = "gui")]
             ServerMode::GraphicsMainThread { tcp_port } => {
                 let tcp_port = tcp_port.unwrap_or(6667);
                 // Only one GUI is allowed per process. Try to get the marker and fail if there is
                 // another.
                 let gui_marker = GuiMarker::acquire()?;
 
-                unsafe {
-                    let raw_handle =
-                        ffi::b3CreateInProcessGraphicsServerAndConnectMainThreadSharedMemory(
-                            tcp_port as i32,
-                        );
-                    (raw_handle, Some(gui_marker))
-                }
+                let raw_handle = unsafe {
+                    ffi::b3CreateInProcessGraphicsServerAndConnectMainThreadSharedMemory(
+                        tcp_port as i32,
+                    )
+                };
+                PhysicsServer::graphics(raw_handle, gui_marker)
             }
+            #[cfg(feature = "gui")]
             ServerMode::Graphics { tcp_port } => {
                 let tcp_port = tcp_port.unwrap_or(6667);
                 // Only one GUI is allowed per process. Try to get the marker and fail if there is
@@ -67,29 +122,87 @@ impl PhysicsServer {
                         ffi::b3CreateInProcessGraphicsServerAndConnectSharedMemory(tcp_port as i32)
                     }
                 };
-
-                (raw_handle, Some(gui_marker))
+                PhysicsServer::graphics(raw_handle, gui_marker)
             }
-        };
-        let handle = raw_handle.expect("Bullet returned a null pointer");
+            ServerMode::DirectTcp { port } => {
+                let port = port.unwrap_or(6667);
+                let handle = unsafe { ffi::b3CreateNetworkPhysicsServerTCP(port as i32) };
+                PhysicsServer::network(handle, "TCP", port)
+            }
+            ServerMode::DirectUdp { port } => {
+                let port = port.unwrap_or(1234);
+                let handle = unsafe { ffi::b3CreateNetworkPhysicsServerUDP(port as i32) };
+                PhysicsServer::network(handle, "UDP", port)
+            }
+        }
+    }
+    #[cfg(feature = "gui")]
+    fn graphics(
+        raw_handle: Option<b3PhysicsClientHandle>,
+        gui_marker: GuiMarker,
+    ) -> Result<PhysicsServer, Error> {
+        let handle = raw_handle.ok_or_else(|| {
+            Error::of_kind(ErrorKind::Disconnected, "Bullet returned a null pointer")
+        })?;
 
         // At this point, we need to disconnect the physics client at any error. So we create the
         // Rust struct and allow the `Drop` implementation to take care of that.
         let mut client = PhysicsClient {
             handle,
-            _gui_marker,
+            _gui_marker: Some(gui_marker),
             _shared_memory_marker: None,
+            generations: Default::default(),
+            assets: Default::default(),
+            sendable: false,
+            awaiting_status: false,
         };
 
         //Make sure it is up and running.
         if !client.can_submit_command() {
-            return Err(Error::new("Physics server is not running"));
+            return Err(Error::of_kind(
+                ErrorKind::Disconnected,
+                "Physics server is not running",
+            ));
         }
 
         // The client is up and running
-        Ok(PhysicsServer(client))
+        Ok(PhysicsServer(ServerHandle::Graphics(Box::new(client))))
     }
+    fn network(
+        handle: Option<b3NetworkPhysicsServerHandle>,
+        protocol: &str,
+        port: u16,
+    ) -> Result<PhysicsServer, Error> {
+        let handle = handle.ok_or_else(|| {
+            Error::of_kind(
+                ErrorKind::Disconnected,
+                format!(
+                    "could not start the {} physics server on port {}",
+                    protocol, port
+                ),
+            )
+        })?;
+        Ok(PhysicsServer(ServerHandle::Network(handle)))
+    }
+    /// returns `true` as long as the server is running.
     pub fn is_connected(&mut self) -> bool {
-        self.0.is_connected()
+        match &mut self.0 {
+            #[cfg(feature = "gui")]
+            ServerHandle::Graphics(client) => client.is_connected(),
+            ServerHandle::Network(handle) => unsafe {
+                ffi::b3NetworkPhysicsServerIsRunning(*handle) != 0
+            },
+        }
+    }
+}
+
+impl Drop for ServerHandle {
+    fn drop(&mut self) {
+        match self {
+            ServerHandle::Network(handle) => unsafe { ffi::b3DestroyNetworkPhysicsServer(*handle) },
+            // the client disconnects itself
+            #[cfg(feature = "gui")]
+            ServerHandle::Graphics(_) => {}
+        }
     }
 }
@@ -0,0 +1,259 @@
+//! Physics clients which can be used from other threads.
+use std::fmt;
+use std::sync::mpsc::{channel, Receiver, SendError, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use crate::{Error, ErrorKind, PhysicsClient};
+
+type Job = Box<dyn FnOnce(&mut PhysicsClient) + Send>;
+
+/// A [`PhysicsClient`](`crate::PhysicsClient`) which can be moved to another thread.
+///
+/// Only clients in [`Direct`](`crate::Mode::Direct`) and [`Tcp`](`crate::Mode::Tcp`) mode can be
+/// wrapped, see [`is_sendable`](`crate::PhysicsClient::is_sendable`). The client can only be
+/// taken out again with [`into_inner`](`Self::into_inner`), so that it cannot be swapped for a
+/// client which has to stay on its thread.
+///
+/// # Example
+/// ```no_run
+/// use anyhow::Result;
+/// use misfire::*;
+/// use std::thread;
+///
+/// fn main() -> Result<()> {
+///     let client = PhysicsClient::connect(Mode::Direct)?;
+///     let client = SendablePhysicsClient::new(client).map_err(Error::from)?;
+///     let simulation = thread::spawn(move || -> Result<(), Error> {
+///         let mut client = client.into_inner();
+///         client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
+///         client.load_urdf("r2d2.urdf", None)?;
+///         for _ in 0..100 {
+///             client.step_simulation()?;
+///         }
+///         Ok(())
+///     });
+///     simulation.join().unwrap()?;
+///     Ok(())
+/// }
+/// ```
+pub struct SendablePhysicsClient {
+    client: PhysicsClient,
+}
+
+// The Bullet client of these modes does not use thread-local state, so it can be moved between
+// threads as long as it is only used by one thread at a time, which `&mut self` guarantees.
+unsafe impl Send for SendablePhysicsClient {}
+
+impl SendablePhysicsClient {
+    /// wraps a client so that it can be sent to another thread.
+    ///
+    /// Returns a [`RejectedClient`] with an error of kind
+    /// [`InvalidArgument`](`crate::ErrorKind::InvalidArgument`) if the client is neither in
+    /// [`Direct`](`crate::Mode::Direct`) nor in [`Tcp`](`crate::Mode::Tcp`) mode. The rejected
+    /// client is still connected and can be taken out of the error.
+    pub fn new(client: PhysicsClient) -> Result<SendablePhysicsClient, RejectedClient> {
+        if !client.is_sendable() {
+            return Err(RejectedClient {
+                client: Box::new(client),
+                error: Error::of_kind(
+                    ErrorKind::InvalidArgument,
+                    "only clients in Direct or Tcp mode can be moved to another thread",
+                ),
+            });
+        }
+        Ok(SendablePhysicsClient { client })
+    }
+    /// returns the client, e.g. once it arrived on its new thread.
+    pub fn into_inner(self) -> PhysicsClient {
+        self.client
+    }
+}
+
+/// The error returned when a [`PhysicsClient`](`crate::PhysicsClient`) could not be moved to
+/// another thread by [`SendablePhysicsClient::new`] or [`SharedPhysicsClient::new`]. It gives the
+/// client back, so that it can still be used on the current thread. It converts into an
+/// [`Error`](`crate::Error`), which drops the client.
+pub struct RejectedClient {
+    client: Box<PhysicsClient>,
+    error: Error,
+}
+
+impl RejectedClient {
+    /// returns why the client was rejected.
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+    /// returns the rejected client.
+    pub fn into_inner(self) -> PhysicsClient {
+        *self.client
+    }
+    /// returns the rejected client and why it was rejected.
+    pub fn into_parts(self) -> (PhysicsClient, Error) {
+        (*self.client, self.error)
+    }
+}
+
+impl fmt::Debug for RejectedClient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RejectedClient")
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for RejectedClient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl From<RejectedClient> for Error {
+    fn from(rejected: RejectedClient) -> Error {
+        rejected.error
+    }
+}
+
+/// A handle to a [`PhysicsClient`](`crate::PhysicsClient`) which lives on its own worker thread.
+///
+/// The handle can be cloned and sent to other threads. Commands from all handles are executed one
+/// after another on the worker thread, so each closure passed to [`execute`](`Self::execute`)
+/// sees a consistent state of the simulation. The worker thread stops and the client disconnects
+/// when the last handle is dropped.
+///
+/// # Example
+/// ```no_run
+/// use anyhow::Result;
+/// use misfire::*;
+/// use std::thread;
+///
+/// fn main() -> Result<()> {
+///     let mut client = PhysicsClient::connect(Mode::Direct)?;
+///     client.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")?;
+///     let r2d2 = client.load_urdf("r2d2.urdf", None)?;
+///     let shared = SharedPhysicsClient::new(client).map_err(Error::from)?;
+///
+///     let simulation = shared.clone();
+///     let stepper = thread::spawn(move || -> Result<(), Error> {
+///         for _ in 0..100 {
+///             simulation.execute(|client| client.step_simulation())??;
+///         }
+///         Ok(())
+///     });
+///     let pose = shared.execute(move |client| client.get_base_transform(r2d2))??;
+///     println!("{}", pose);
+///     stepper.join().unwrap()?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct SharedPhysicsClient {
+    worker: Arc<Worker>,
+}
+
+struct Worker {
+    sender: Option<Sender<Job>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl SharedPhysicsClient {
+    /// moves the client onto a new worker thread.
+    ///
+    /// Returns a [`RejectedClient`] with an error of kind
+    /// [`InvalidArgument`](`crate::ErrorKind::InvalidArgument`) if the client cannot be moved to
+    /// another thread, see [`SendablePhysicsClient`](`crate::SendablePhysicsClient`). The client
+    /// is also given back if the worker thread could not be started.
+    pub fn new(client: PhysicsClient) -> Result<SharedPhysicsClient, RejectedClient> {
+        let client = SendablePhysicsClient::new(client)?;
+        // the client is handed over once the thread runs, so that it can be given back if the
+        // thread could not be started
+        let (client_sender, client_receiver) = channel::<SendablePhysicsClient>();
+        let (sender, receiver) = channel::<Job>();
+        let spawned = std::thread::Builder::new()
+            .name("misfire-physics-client".to_string())
+            .spawn(move || {
+                if let Ok(client) = client_receiver.recv() {
+                    let mut client = client.into_inner();
+                    for job in receiver {
+                        job(&mut client);
+                    }
+                }
+            });
+        let thread = match spawned {
+            Ok(thread) => thread,
+            Err(err) => {
+                return Err(RejectedClient {
+                    client: Box::new(client.into_inner()),
+                    error: Error::with(format!(
+                        "could not start the physics client thread: {}",
+                        err
+                    )),
+                })
+            }
+        };
+        if let Err(SendError(client)) = client_sender.send(client) {
+            return Err(RejectedClient {
+                client: Box::new(client.into_inner()),
+                error: Error::new("the physics client thread stopped unexpectedly"),
+            });
+        }
+        Ok(SharedPhysicsClient {
+            worker: Arc::new(Worker {
+                sender: Some(sender),
+                thread: Some(thread),
+            }),
+        })
+    }
+    /// runs `command` on the worker thread and blocks until it returned.
+    ///
+    /// Returns an error with kind [`Disconnected`](`crate::ErrorKind::Disconnected`) if the worker
+    /// thread stopped, e.g. because an earlier command panicked.
+    /// `command` must not call `execute` itself, as that would wait for the worker thread forever.
+    pub fn execute<F, R>(&self, command: F) -> Result<R, Error>
+    where
+        F: FnOnce(&mut PhysicsClient) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.submit(command)?.recv().map_err(|_| worker_stopped())
+    }
+    /// sends `command` to the worker thread without waiting for it. The result can be received
+    /// from the returned channel.
+    pub(crate) fn submit<F, R>(&self, command: F) -> Result<Receiver<R>, Error>
+    where
+        F: FnOnce(&mut PhysicsClient) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (result_sender, result_receiver) = channel();
+        let job: Job = Box::new(move |client| {
+            // The caller might have stopped waiting, so a failed send is not an error.
+            let _ = result_sender.send(command(client));
+        });
+        let sender = self
+            .worker
+            .sender
+            .as_ref()
+            .ok_or_else(Error::disconnected)?;
+        sender.send(job).map_err(|_| worker_stopped())?;
+        Ok(result_receiver)
+    }
+}
+
+pub(crate) fn worker_stopped() -> Error {
+    Error::of_kind(
+        ErrorKind::Disconnected,
+        "the physics client thread is no longer running",
+    )
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        // closing the channel ends the loop of the worker thread.
+        self.sender = None;
+        if let Some(thread) = self.thread.take() {
+            // the last handle might be dropped by a command on the worker thread itself.
+            if thread.thread().id() != std::thread::current().id() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
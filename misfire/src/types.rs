@@ -1,15 +1,16 @@
 //! Custom data types for RuBullet
-use crate::Error;
-use image::{ImageBuffer, Luma, RgbaImage};
+use crate::{Error, ErrorKind};
+use image::{ImageBuffer, Luma, Pixel, RgbaImage};
 use misfire_sys::{
-    b3BodyInfo, b3ContactPointData, b3DynamicsInfo, b3JointInfo, b3JointSensorState, b3LinkState,
-    b3OpenGLVisualizerCameraInfo, b3PhysicsSimulationParameters, b3RayHitInfo, b3UserConstraint,
-    b3VisualShapeData,
+    b3BodyInfo, b3ContactPointData, b3DynamicsInfo, b3JointInfo, b3JointSensorState,
+    b3JointSensorState2, b3LinkState, b3OpenGLVisualizerCameraInfo, b3PhysicsSimulationParameters,
+    b3RayHitInfo, b3UserConstraint, b3VisualShapeData,
 };
 use nalgebra::{
     DVector, Isometry3, Matrix3xX, Matrix4, Matrix6xX, Quaternion, Translation3, UnitQuaternion,
-    Vector3, Vector6,
+    Vector3, Vector4, Vector6,
 };
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::ffi::CStr;
 
@@ -17,12 +18,94 @@ use std::os::raw::c_int;
 use std::path::PathBuf;
 use std::time::Duration;
 
+/// The generation of an id. The [`PhysicsClient`](`crate::PhysicsClient`) stamps every id it
+/// creates with a new generation, so that ids of removed objects can be told apart from the ids of
+/// new objects to which the physics server gave the same number.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct Generation(pub(crate) u32);
+
+impl Generation {
+    /// generation of ids which did not come from the client which uses them, e.g. ids which are
+    /// part of a contact point. They are never reported as stale.
+    pub(crate) const UNKNOWN: Generation = Generation(0);
+}
+
+/// Implements comparison and hashing for ids with a [`Generation`]. Only the number of the id is
+/// compared, so that ids of the same object are equal no matter where they came from. Ids which
+/// are reported by the physics server, e.g. in contact points, ray hits or segmentation masks,
+/// carry no generation and would otherwise never be equal to the id returned when the object was
+/// created. As a consequence, a stale id is equal to the id of a new object which got the same
+/// number and both hash to the same key. `is_same_generation` tells them apart.
+///
+/// With the `serde` feature, the ids are serialized as their number. The generation only has a
+/// meaning within the client which created the id, so deserialized ids have no known generation.
+macro_rules! generational_id {
+    ($id:ident) => {
+        impl $id {
+            /// returns `true` if both ids refer to the same object. Unlike `==`, this is `false`
+            /// for the id of a removed object and the id of a new object to which the physics
+            /// server gave the same number. Ids without a known generation, e.g. the ones in
+            /// contact points, can not be told apart and only their numbers are compared.
+            pub fn is_same_generation(&self, other: &$id) -> bool {
+                self.0 == other.0
+                    && (self.1 == other.1
+                        || self.1 == Generation::UNKNOWN
+                        || other.1 == Generation::UNKNOWN)
+            }
+        }
+        impl PartialEq for $id {
+            fn eq(&self, other: &$id) -> bool {
+                self.0 == other.0
+            }
+        }
+        impl Eq for $id {}
+        impl std::hash::Hash for $id {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                self.0.hash(state)
+            }
+        }
+        #[cfg(feature = "serde")]
+        impl serde::Serialize for $id {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                self.0.serialize(serializer)
+            }
+        }
+        #[cfg(feature = "serde")]
+        impl<'de> serde::Deserialize<'de> for $id {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<$id, D::Error> {
+                c_int::deserialize(deserializer).map(|id| $id(id, Generation::UNKNOWN))
+            }
+        }
+    };
+}
+
 /// The unique ID for a body within a physics server.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
-pub struct BodyId(pub(crate) c_int);
+///
+/// The physics server may reuse the number of a removed body for a new body. The
+/// [`PhysicsClient`](`crate::PhysicsClient`) detects ids of removed bodies when they are passed to
+/// any of its methods and reports them as errors with kind
+/// [`InvalidBodyId`](`crate::ErrorKind::InvalidBodyId`). Ids become valid again when
+/// [`restore_state`](`crate::PhysicsClient::restore_state`) brings their body back.
+///
+/// `==` and hashing only look at the number, so that ids reported by the physics server, e.g. in
+/// contact points, compare equal to the id returned by [`load_urdf`](`crate::PhysicsClient::load_urdf`).
+/// Use [`is_same_generation`](`Self::is_same_generation`) to check that two ids refer to the very
+/// same body.
+#[derive(Clone, Copy, Debug)]
+pub struct BodyId(pub(crate) c_int, pub(crate) Generation);
+generational_id!(BodyId);
+
+impl BodyId {
+    /// creates an id without a known generation.
+    pub(crate) fn unchecked(id: c_int) -> BodyId {
+        BodyId(id, Generation::UNKNOWN)
+    }
+}
 
 /// The unique ID for a Visual Shape
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct VisualId(pub(crate) c_int);
 impl VisualId {
     /// Use it to create an object which does not have a visual appearance. It will be just be
@@ -31,6 +114,8 @@ impl VisualId {
 }
 /// The unique ID for a Collision Shape.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct CollisionId(pub(crate) c_int);
 
 impl CollisionId {
@@ -40,26 +125,52 @@ impl CollisionId {
 
 /// The unique ID for a Texture
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct TextureId(pub(crate) c_int);
 
 /// The unique ID for a User Debug Parameter Item
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
-pub struct ItemId(pub(crate) c_int);
+#[derive(Clone, Copy, Debug)]
+pub struct ItemId(pub(crate) c_int, pub(crate) Generation);
+generational_id!(ItemId);
 
 /// The unique ID for a constraint.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
-pub struct ConstraintId(pub(crate) c_int);
+#[derive(Clone, Copy, Debug)]
+pub struct ConstraintId(pub(crate) c_int, pub(crate) Generation);
+generational_id!(ConstraintId);
+
+impl ConstraintId {
+    /// creates an id without a known generation.
+    pub(crate) fn unchecked(id: c_int) -> ConstraintId {
+        ConstraintId(id, Generation::UNKNOWN)
+    }
+}
 
 /// The unique ID for a Logging Object.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
-pub struct LogId(pub(crate) c_int);
+#[derive(Clone, Copy, Debug)]
+pub struct LogId(pub(crate) c_int, pub(crate) Generation);
+generational_id!(LogId);
 
 /// The unique ID for a State Object.
+#[derive(Clone, Copy, Debug)]
+pub struct StateId(pub(crate) c_int, pub(crate) Generation);
+generational_id!(StateId);
+
+/// The unique ID for a piece of user data.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct UserDataId(pub(crate) c_int);
+
+/// The unique ID for a plugin which was loaded into the physics server.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
-pub struct StateId(pub(crate) c_int);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct PluginId(pub(crate) c_int);
 
 /// An enum to represent different types of joints
 #[derive(Debug, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum JointType {
     Revolute = 0,
     Prismatic = 1,
@@ -82,7 +193,10 @@ impl TryFrom<i32> for JointType {
             4 => Ok(JointType::Fixed),
             5 => Ok(JointType::Point2Point),
             6 => Ok(JointType::Gear),
-            _ => Err(Error::new("could not convert into a valid joint type")),
+            _ => Err(Error::of_kind(
+                ErrorKind::ParseError,
+                "could not convert into a valid joint type",
+            )),
         }
     }
 }
@@ -102,14 +216,15 @@ impl TryFrom<i32> for JointType {
 ///         "../misfire-sys/bullet3/libbullet3/examples/pybullet/gym/pybullet_data",
 ///         )?;
 ///     let panda_id = client.load_urdf("franka_panda/panda.urdf", UrdfOptions::default())?;
-///     let joint_info = client.get_joint_info(panda_id,4);
+///     let joint_info = client.get_joint_info(panda_id,4)?;
 ///     assert_eq!("panda_joint5",joint_info.joint_name);
 ///     Ok(())
 /// }
 /// ```
 /// # See also
 /// * [`JointState`](`crate::types::JointState`) - For information about the current state of the joint.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct JointInfo {
     /// the same joint index as the input parameter
     pub joint_index: usize,
@@ -145,8 +260,10 @@ pub struct JointInfo {
     /// parent link index. None means that the base is the parent link
     pub parent_index: Option<usize>,
 }
-impl From<b3JointInfo> for JointInfo {
-    fn from(b3: b3JointInfo) -> Self {
+impl TryFrom<b3JointInfo> for JointInfo {
+    type Error = Error;
+
+    fn try_from(b3: b3JointInfo) -> Result<Self, Self::Error> {
         unsafe {
             let b3JointInfo {
                 m_link_name,
@@ -174,18 +291,22 @@ impl From<b3JointInfo> for JointInfo {
                 index => Some(index as usize),
             };
 
-            JointInfo {
+            let flags = JointInfoFlags::from_bits(m_flags).ok_or_else(|| {
+                Error::of_kind(ErrorKind::ParseError, "Could not parse JointInfoFlags")
+            })?;
+
+            Ok(JointInfo {
                 link_name: CStr::from_ptr(m_link_name.as_ptr())
                     .to_string_lossy()
                     .into_owned(),
                 joint_name: CStr::from_ptr(m_joint_name.as_ptr())
                     .to_string_lossy()
                     .into_owned(),
-                joint_type: JointType::try_from(m_joint_type).unwrap(),
+                joint_type: JointType::try_from(m_joint_type)?,
                 q_index: m_q_index,
                 u_index: m_u_index,
                 joint_index: m_joint_index as usize,
-                flags: JointInfoFlags::from_bits(m_flags).expect("Could not parse JointInfoFlags"),
+                flags,
                 joint_damping: m_joint_damping,
                 joint_friction: m_joint_friction,
                 joint_upper_limit: m_joint_upper_limit,
@@ -201,7 +322,7 @@ impl From<b3JointInfo> for JointInfo {
                 ),
                 joint_axis: m_joint_axis.into(),
                 parent_index,
-            }
+            })
         }
     }
 }
@@ -242,6 +363,7 @@ pub struct InverseKinematicsParameters<'a> {
 }
 /// Specifies which Inverse Kinematics Solver to use in
 /// [`calculate_inverse_kinematics()`](`crate::client::PhysicsClient::calculate_inverse_kinematics()`)
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IkSolver {
     /// Damped Least Squares
     Dls = 0,
@@ -362,6 +484,7 @@ impl<'a> InverseKinematicsParametersBuilder<'a> {
     }
 }
 /// Represents options for [`add_user_debug_text`](`crate::PhysicsClient::add_user_debug_text()`)
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddDebugTextOptions {
     /// RGB color [Red, Green, Blue] each component in range [0..1]. Default is [1.,1.,1.]
     pub text_color_rgb: [f64; 3],
@@ -399,6 +522,7 @@ impl Default for AddDebugTextOptions {
     }
 }
 /// Represents options for [`add_user_debug_line`](`crate::PhysicsClient::add_user_debug_line()`)
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddDebugLineOptions {
     /// RGB color [Red, Green, Blue] each component in range [0..1]. Default is [1.,1.,1.]
     pub line_color_rgb: [f64; 3],
@@ -443,6 +567,7 @@ impl Default for AddDebugLineOptions {
 /// # See also
 /// * [`PhysicsClient::calculate_jacobian()`](`crate::PhysicsClient::calculate_jacobian()`)
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Jacobian {
     pub jacobian: Matrix6xX<f64>,
 }
@@ -467,6 +592,7 @@ impl Jacobian {
 }
 /// Frame for [`apply_external_torque()`](`crate::PhysicsClient::apply_external_torque()`) and
 /// [`apply_external_force()`](`crate::PhysicsClient::apply_external_force()`)
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExternalForceFrame {
     /// Local Link Coordinates
     LinkFrame = 1,
@@ -475,6 +601,7 @@ pub enum ExternalForceFrame {
 }
 /// Represents a key press Event
 #[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct KeyboardEvent {
     /// specifies which key the event is about.
     pub key: char,
@@ -498,6 +625,7 @@ impl KeyboardEvent {
 /// Mouse Events can either be a "Move" or a "Button" event. A "Move" event is when the mouse is moved
 /// in the OpenGL window and a "Button" even is when a mouse button is clicked.
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MouseEvent {
     /// Contains the mouse position
     Move {
@@ -521,6 +649,7 @@ pub enum MouseEvent {
 
 /// Represents the different possible states of a mouse button
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MouseButtonState {
     pub(crate) flag: i32,
 }
@@ -545,6 +674,7 @@ impl MouseButtonState {
 /// # See also
 /// * [`JointInfo`](`JointInfo`) - For basic information about a joint
 #[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct JointState {
     /// The position value of this joint.
     pub joint_position: f64,
@@ -576,8 +706,82 @@ impl From<b3JointSensorState> for JointState {
         }
     }
 }
+/// Represents the current state of a joint with multiple degrees of freedom, such as a spherical or
+/// a planar joint. It can be retrieved via
+/// [`get_joint_state_multi_dof()`](`crate::PhysicsClient::get_joint_state_multi_dof()`).
+///
+/// The number of position and velocity values depends on the [`JointType`](`JointType`):
+///
+/// | JointType           | positions                | velocities |
+/// |---------------------|--------------------------|------------|
+/// | Fixed               | 0                        | 0          |
+/// | Revolute, Prismatic | 1                        | 1          |
+/// | Planar              | 3                        | 3          |
+/// | Spherical           | 4 (quaternion \[x,y,z,w\]) | 3          |
+/// # See also
+/// * [`JointState`](`JointState`) - The state of a joint with a single degree of freedom
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JointStateMultiDof {
+    /// The position values of this joint. For spherical joints this is a quaternion in the order
+    /// \[x,y,z,w\]. See [`orientation()`](`Self::orientation()`).
+    pub joint_position: Vec<f64>,
+    /// The velocity values of this joint. For spherical joints this is the angular velocity.
+    pub joint_velocity: Vec<f64>,
+    /// These are the joint reaction forces, if a torque sensor is enabled for this joint it is [Fx, Fy, Fz, Mx, My, Mz].
+    /// Without torque sensor, it is \[0,0,0,0,0,0\].
+    pub joint_reaction_forces: [f64; 6],
+    /// This is the motor torque applied during the last [`step_simulation()`](`crate::PhysicsClient::step_simulation()`)
+    /// for each degree of freedom.
+    pub joint_motor_torque: Vec<f64>,
+}
+impl JointStateMultiDof {
+    /// returns the joint position as quaternion if this is the state of a spherical joint.
+    pub fn orientation(&self) -> Option<UnitQuaternion<f64>> {
+        match self.joint_position.as_slice() {
+            &[x, y, z, w] => Some(UnitQuaternion::from_quaternion(Quaternion::new(w, x, y, z))),
+            _ => None,
+        }
+    }
+}
+impl TryFrom<b3JointSensorState2> for JointStateMultiDof {
+    type Error = Error;
+
+    fn try_from(b3: b3JointSensorState2) -> Result<Self, Error> {
+        let b3JointSensorState2 {
+            m_joint_position,
+            m_joint_velocity,
+            m_joint_reaction_force_torque,
+            m_joint_motor_torque_multi_dof,
+            m_q_dof_size,
+            m_u_dof_size,
+        } = b3;
+        let q_size = m_q_dof_size as usize;
+        let u_size = m_u_dof_size as usize;
+        if m_q_dof_size < 0
+            || m_u_dof_size < 0
+            || q_size > m_joint_position.len()
+            || u_size > m_joint_velocity.len()
+        {
+            return Err(Error::of_kind(
+                ErrorKind::ParseError,
+                format!(
+                    "Invalid number of degrees of freedom (q: {}, u: {})",
+                    m_q_dof_size, m_u_dof_size
+                ),
+            ));
+        }
+        Ok(JointStateMultiDof {
+            joint_position: m_joint_position[..q_size].to_vec(),
+            joint_velocity: m_joint_velocity[..u_size].to_vec(),
+            joint_reaction_forces: m_joint_reaction_force_torque,
+            joint_motor_torque: m_joint_motor_torque_multi_dof[..u_size].to_vec(),
+        })
+    }
+}
 
 /// Options for loading a URDF into the physics server.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UrdfOptions {
     /// Creates the base of the object with the given transform.
     pub base_transform: Isometry3<f64>,
@@ -609,6 +813,7 @@ impl Default for UrdfOptions {
     }
 }
 /// Options for loading models from an SDF file into the physics server.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SdfOptions {
     /// Experimental. By default, the joints in the URDF file are created using the reduced
     /// coordinate method: the joints are simulated using the
@@ -638,6 +843,7 @@ impl Default for SdfOptions {
 /// | Velocity                | constraint     | pure velocity constraint         | error = desired_velocity - actual_velocity                                                                |
 /// | Torque                  | External Force |                                  |                                                                                                           |
 /// | Pd                      | ???            | ???                              | ???                                                                                                       |
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ControlCommand {
     /// Position Control with the desired joint position.
     Position(f64),
@@ -728,7 +934,59 @@ impl ControlCommandArray<'_> {
         }
     }
 }
+/// Can be used in [`set_joint_motor_control_multi_dof()`](`crate::client::PhysicsClient::set_joint_motor_control_multi_dof()`).
+/// It is basically the same as [`ControlCommand`](`ControlCommand`), but every target is given for
+/// each degree of freedom of the joint. Position targets of spherical joints are quaternions in the
+/// order \[x,y,z,w\], whereas velocities, torques and gains are 3-vectors.
+/// See [`JointStateMultiDof`](`JointStateMultiDof`) for the number of values per joint type.
+pub enum ControlCommandMultiDof<'a> {
+    /// Position Control with the desired joint position.
+    Position(&'a [f64]),
+    /// Same as Position, but you can set your own gains
+    PositionWithPd {
+        /// desired target position
+        target_position: &'a [f64],
+        /// desired target velocity
+        target_velocity: &'a [f64],
+        /// position gain for each degree of freedom
+        position_gain: &'a [f64],
+        /// velocity gain for each degree of freedom
+        velocity_gain: &'a [f64],
+        /// limits the velocity of a joint
+        maximum_velocity: Option<f64>,
+    },
+    /// Velocity control with the desired joint velocity
+    Velocity(&'a [f64]),
+    /// Torque control with the desired joint torque.
+    Torque(&'a [f64]),
+    /// PD Control
+    Pd {
+        /// desired target position
+        target_position: &'a [f64],
+        /// desired target velocity
+        target_velocity: &'a [f64],
+        /// position gain for each degree of freedom
+        position_gain: &'a [f64],
+        /// velocity gain for each degree of freedom
+        velocity_gain: &'a [f64],
+        /// limits the velocity of a joint
+        maximum_velocity: Option<f64>,
+    },
+}
+
+impl ControlCommandMultiDof<'_> {
+    pub(crate) fn get_int(&self) -> i32 {
+        match self {
+            ControlCommandMultiDof::Position(_) => 2,
+            ControlCommandMultiDof::Velocity(_) => 0,
+            ControlCommandMultiDof::Torque(_) => 1,
+            ControlCommandMultiDof::Pd { .. } => 3,
+            ControlCommandMultiDof::PositionWithPd { .. } => 2,
+        }
+    }
+}
 /// Flags for [`configure_debug_visualizer()`](`crate::PhysicsClient::configure_debug_visualizer`)
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DebugVisualizerFlag {
     CovEnableGui = 1,
     CovEnableShadows,
@@ -787,6 +1045,7 @@ pub enum DebugVisualizerFlag {
 /// * [`get_link_state()`](`crate::client::PhysicsClient::get_link_state()`)
 /// * [`get_link_states()`](`crate::client::PhysicsClient::get_link_states()`)
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinkState {
     /// Cartesian pose of the center of mass
     pub world_pose: Isometry3<f64>,
@@ -901,6 +1160,7 @@ pub(crate) fn combined_position_orientation_array_to_isometry(
 
 /// VisualShape options are for the [create_visual_shape](`crate::PhysicsClient::create_visual_shape`)
 /// function to specify additional options like the color.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VisualShapeOptions {
     /// offset of the shape with respect to the link frame
     pub frame_offset: Isometry3<f64>,
@@ -924,6 +1184,7 @@ impl Default for VisualShapeOptions {
 }
 /// Collision shape which can be put
 /// the [create_collision_shape](`crate::PhysicsClient::create_collision_shape`) method
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GeometricCollisionShape {
     /// A Sphere determined by the radius in meter
     Sphere {
@@ -1004,6 +1265,7 @@ pub enum GeometricCollisionShape {
 }
 /// Visual shapes to put into the [create_visual_shape](`crate::PhysicsClient::create_visual_shape`)
 /// method together with [VisualShapeOptions](`VisualShapeOptions`)
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GeometricVisualShape {
     /// A Sphere determined by the radius in meter
     Sphere {
@@ -1064,6 +1326,7 @@ pub enum GeometricVisualShape {
 /// Specifies all options for [create_multi_body](`crate::PhysicsClient::create_multi_body`).
 /// Most of the the time you are probably fine using `MultiBodyOptions::default()` or just setting
 /// the base_pose and/or mass
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MultiBodyOptions {
     /// mass of the base, in kg (if using SI units)
     pub base_mass: f64,
@@ -1117,6 +1380,7 @@ impl Default for MultiBodyOptions {
 
 /// This struct keeps the information to change a visual shape with the
 /// [change_visual_shape](`crate::PhysicsClient::change_visual_shape`) method.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChangeVisualShapeOptions {
     /// Experimental for internal use, recommended ignore shapeIndex or leave it -1.
     /// Intention is to let you pick a specific shape index to modify, since URDF (and SDF etc)
@@ -1147,6 +1411,7 @@ impl Default for ChangeVisualShapeOptions {
 /// Contains the body name and base name of a Body. BodyInfo is returned by
 /// [get_body_info](`crate::PhysicsClient::get_body_info`)
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BodyInfo {
     /// base name (first link) as extracted from the URDF etc.
     pub base_name: String,
@@ -1168,9 +1433,78 @@ impl From<b3BodyInfo> for BodyInfo {
         }
     }
 }
+
+/// The value of a piece of user data which can be attached to bodies, links and visual shapes
+/// with [`add_user_data`](`crate::PhysicsClient::add_user_data`).
+/// User data which is specified in a URDF `<user-data>` block is stored as a
+/// [`String`](`UserDataValue::String`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UserDataValue {
+    /// a UTF-8 string
+    String(String),
+    /// generic binary data
+    Bytes(Vec<u8>),
+}
+
+impl UserDataValue {
+    /// returns the value as string slice or `None` if it is binary data.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            UserDataValue::String(value) => Some(value.as_str()),
+            UserDataValue::Bytes(_) => None,
+        }
+    }
+    /// returns the raw bytes of the value. For strings this does not include a null terminator.
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            UserDataValue::String(value) => value.as_bytes(),
+            UserDataValue::Bytes(value) => value.as_slice(),
+        }
+    }
+}
+
+impl From<&str> for UserDataValue {
+    fn from(value: &str) -> Self {
+        UserDataValue::String(value.to_string())
+    }
+}
+impl From<String> for UserDataValue {
+    fn from(value: String) -> Self {
+        UserDataValue::String(value)
+    }
+}
+impl From<&[u8]> for UserDataValue {
+    fn from(value: &[u8]) -> Self {
+        UserDataValue::Bytes(value.to_vec())
+    }
+}
+impl From<Vec<u8>> for UserDataValue {
+    fn from(value: Vec<u8>) -> Self {
+        UserDataValue::Bytes(value)
+    }
+}
+
+/// Describes where a piece of user data is attached to. It is returned by
+/// [`get_user_data_info`](`crate::PhysicsClient::get_user_data_info`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UserDataInfo {
+    /// unique id of the user data
+    pub user_data_id: UserDataId,
+    /// the key under which the user data is stored
+    pub key: String,
+    /// body the user data is attached to
+    pub body: BodyId,
+    /// link the user data is attached to or `None` for the base
+    pub link_index: Option<usize>,
+    /// visual shape the user data is attached to or `None` if it is not attached to a visual shape
+    pub visual_shape_index: Option<usize>,
+}
 /// Contains information about the visual shape of a body. It is returned by
 /// [get_visual_shape_data](`crate::PhysicsClient::get_visual_shape_data`)
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VisualShapeData {
     /// same id as in the input of [get_visual_shape_data](`crate::PhysicsClient::get_visual_shape_data`)
     pub body_id: BodyId,
@@ -1199,7 +1533,7 @@ impl From<b3VisualShapeData> for VisualShapeData {
                 index => Some(index as usize),
             };
             VisualShapeData {
-                body_id: BodyId(b3.m_objectUniqueId),
+                body_id: BodyId::unchecked(b3.m_objectUniqueId),
                 link_index,
                 visual_geometry_type: b3.m_visualGeometryType,
                 dimensions: b3.m_dimensions,
@@ -1216,6 +1550,11 @@ impl From<b3VisualShapeData> for VisualShapeData {
     }
 }
 /// Stores the images from [`get_camera_image()`](`crate::PhysicsClient::get_camera_image()`)
+///
+/// The default value contains empty images, which can be filled by
+/// [`get_camera_image_into()`](`crate::PhysicsClient::get_camera_image_into()`).
+/// Images which were not rendered have a size of 0x0 pixels.
+#[derive(Default)]
 pub struct Images {
     /// width image resolution in pixels (horizontal)
     pub width: usize,
@@ -1223,12 +1562,406 @@ pub struct Images {
     pub height: usize,
     /// RGB image with additional alpha channel
     pub rgba: RgbaImage,
-    /// Depth image. Every pixel represents a distance in meters
+    /// Depth image. Every pixel holds the raw value of the OpenGL depth buffer in \[0, 1\].
+    /// Use [`metric_depth()`](`Self::metric_depth()`) to get distances in meters.
     pub depth: ImageBuffer<Luma<f32>, Vec<f32>>,
     /// Segmentation image. Every pixel represents a unique [`BodyId`](`crate::types::BodyId`)
+    /// or -1 if there is no body. With
+    /// [`SEGMENTATION_MASK_OBJECT_AND_LINKINDEX`](`RendererAuxFlags::SEGMENTATION_MASK_OBJECT_AND_LINKINDEX`)
+    /// the link index is encoded as well. Use
+    /// [`segmentation_mask()`](`Self::segmentation_mask()`) to decode it.
     pub segmentation: ImageBuffer<Luma<i32>, Vec<i32>>,
 }
 
+impl Images {
+    /// converts the raw depth buffer into the distance of every pixel to the image plane of the
+    /// camera in meters. Pixels without geometry get the distance of the far plane.
+    /// Returns an error if the depth image was not rendered.
+    ///
+    /// # Arguments
+    /// * `options` - the options which were passed to
+    ///   [`get_camera_image()`](`crate::PhysicsClient::get_camera_image()`). They need to contain
+    ///   the view and projection matrix.
+    pub fn metric_depth(
+        &self,
+        options: &CameraImageOptions,
+    ) -> Result<ImageBuffer<Luma<f32>, Vec<f32>>, Error> {
+        self.check_channel(&self.depth, "depth")?;
+        let inverse = self.inverse_camera_matrix(options, PointCloudFrame::Camera)?;
+        let mut depth = ImageBuffer::new(self.width as u32, self.height as u32);
+        for (x, y, pixel) in depth.enumerate_pixels_mut() {
+            let point = self.unproject(&inverse, x, y);
+            *pixel = Luma([point.z as f32]);
+        }
+        Ok(depth)
+    }
+    /// reconstructs the points seen by the camera from the depth image. Pixels without geometry
+    /// are left out. Returns an error if the depth image was not rendered. Colors and labels are
+    /// only filled in if the RGBA and the segmentation image were rendered as well.
+    ///
+    /// # Arguments
+    /// * `options` - the options which were passed to
+    ///   [`get_camera_image()`](`crate::PhysicsClient::get_camera_image()`). They need to contain
+    ///   the view and projection matrix.
+    /// * `frame` - the frame in which the points are expressed.
+    pub fn point_cloud(
+        &self,
+        options: &CameraImageOptions,
+        frame: PointCloudFrame,
+    ) -> Result<PointCloud, Error> {
+        let organized = self.organized_point_cloud(options, frame)?;
+        let mut cloud = PointCloud {
+            width: 0,
+            height: 1,
+            points: Vec::new(),
+            colors: Vec::new(),
+            labels: Vec::new(),
+        };
+        for (i, point) in organized.points.into_iter().enumerate() {
+            if !point.x.is_nan() {
+                cloud.points.push(point);
+                if let Some(&color) = organized.colors.get(i) {
+                    cloud.colors.push(color);
+                }
+                if let Some(&label) = organized.labels.get(i) {
+                    cloud.labels.push(label);
+                }
+            }
+        }
+        cloud.width = cloud.points.len();
+        Ok(cloud)
+    }
+    /// like [`point_cloud()`](`Self::point_cloud()`), but keeps one point per pixel in the
+    /// layout of the image. Pixels without geometry have NaN coordinates.
+    pub fn organized_point_cloud(
+        &self,
+        options: &CameraImageOptions,
+        frame: PointCloudFrame,
+    ) -> Result<PointCloud, Error> {
+        self.check_channel(&self.depth, "depth")?;
+        let inverse = self.inverse_camera_matrix(options, frame)?;
+        let num_pixels = self.width * self.height;
+        let mut points = Vec::with_capacity(num_pixels);
+        for y in 0..self.height as u32 {
+            for x in 0..self.width as u32 {
+                let depth = self.depth.get_pixel(x, y)[0];
+                if depth < 1. {
+                    points.push(self.unproject(&inverse, x, y));
+                } else {
+                    points.push(Vector3::repeat(f64::NAN));
+                }
+            }
+        }
+        let colors = match self.check_channel(&self.rgba, "RGBA") {
+            Ok(()) => self.rgba.pixels().map(|pixel| pixel.0).collect(),
+            Err(_) => Vec::new(),
+        };
+        let labels = match self.segmentation_mask(options) {
+            Ok(mask) => mask.labels,
+            Err(_) => Vec::new(),
+        };
+        Ok(PointCloud {
+            width: self.width,
+            height: self.height,
+            points,
+            colors,
+            labels,
+        })
+    }
+    /// decodes the segmentation image into the body and link of every pixel.
+    /// Returns an error if the segmentation image was not rendered.
+    ///
+    /// # Arguments
+    /// * `options` - the options which were passed to
+    ///   [`get_camera_image()`](`crate::PhysicsClient::get_camera_image()`). Their flags tell
+    ///   whether the link indices are part of the segmentation image.
+    pub fn segmentation_mask(
+        &self,
+        options: &CameraImageOptions,
+    ) -> Result<SegmentationMask, Error> {
+        let flags = options.flags.unwrap_or_else(RendererAuxFlags::empty);
+        let with_link_index =
+            flags.contains(RendererAuxFlags::SEGMENTATION_MASK_OBJECT_AND_LINKINDEX);
+        let labels = if flags.contains(RendererAuxFlags::NO_SEGMENTATION_MASK) {
+            vec![None; self.width * self.height]
+        } else {
+            self.check_channel(&self.segmentation, "segmentation")?;
+            self.segmentation
+                .pixels()
+                .map(|pixel| decode_segmentation(pixel[0], with_link_index))
+                .collect()
+        };
+        Ok(SegmentationMask {
+            width: self.width,
+            height: self.height,
+            labels,
+        })
+    }
+    /// makes sure that an image was rendered along with the others, i.e. it has their size.
+    fn check_channel<P: Pixel>(
+        &self,
+        image: &ImageBuffer<P, Vec<P::Subpixel>>,
+        name: &str,
+    ) -> Result<(), Error> {
+        if image.width() as usize != self.width || image.height() as usize != self.height {
+            return Err(Error::of_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "the {} image was not rendered ({}x{} instead of {}x{} pixels)",
+                    name,
+                    image.width(),
+                    image.height(),
+                    self.width,
+                    self.height
+                ),
+            ));
+        }
+        Ok(())
+    }
+    /// returns the matrix which maps normalized device coordinates into the given frame.
+    fn inverse_camera_matrix(
+        &self,
+        options: &CameraImageOptions,
+        frame: PointCloudFrame,
+    ) -> Result<Matrix4<f64>, Error> {
+        let (view_matrix, projection_matrix) = match (options.view_matrix, options.projection_matrix)
+        {
+            (Some(view_matrix), Some(projection_matrix)) => (view_matrix, projection_matrix),
+            _ => {
+                return Err(Error::of_kind(
+                    ErrorKind::InvalidArgument,
+                    "the camera image options need a view and a projection matrix to reconstruct the depth",
+                ))
+            }
+        };
+        let projection_matrix = projection_matrix.cast::<f64>();
+        let matrix = match frame {
+            // rotates the OpenCV camera frame into the OpenGL frame of the view matrix
+            PointCloudFrame::Camera => {
+                projection_matrix * Matrix4::from_diagonal(&Vector4::new(1., -1., -1., 1.))
+            }
+            PointCloudFrame::World => projection_matrix * view_matrix.cast::<f64>(),
+        };
+        matrix.try_inverse().ok_or_else(|| {
+            Error::of_kind(
+                ErrorKind::InvalidArgument,
+                "the camera matrices of the image are not invertible",
+            )
+        })
+    }
+    /// back-projects the center of a pixel with the inverse of the camera matrices.
+    fn unproject(&self, inverse: &Matrix4<f64>, x: u32, y: u32) -> Vector3<f64> {
+        let depth = self.depth.get_pixel(x, y)[0] as f64;
+        let ndc = Vector4::new(
+            2. * (x as f64 + 0.5) / self.width as f64 - 1.,
+            1. - 2. * (y as f64 + 0.5) / self.height as f64,
+            2. * depth - 1.,
+            1.,
+        );
+        let point = inverse * ndc;
+        point.xyz() / point.w
+    }
+}
+
+/// decodes a pixel of the segmentation mask. Pixels without a body are negative.
+fn decode_segmentation(value: i32, with_link_index: bool) -> Option<(BodyId, Option<usize>)> {
+    if value < 0 {
+        return None;
+    }
+    if !with_link_index {
+        return Some((BodyId::unchecked(value), None));
+    }
+    let body = BodyId::unchecked(value & ((1 << 24) - 1));
+    let link_index = match (value >> 24) - 1 {
+        index if index < 0 => None,
+        index => Some(index as usize),
+    };
+    Some((body, link_index))
+}
+
+/// The decoded segmentation image of [`Images`], see
+/// [`segmentation_mask()`](`Images::segmentation_mask()`).
+///
+/// The link index of a pixel is only known if the image was rendered with
+/// [`SEGMENTATION_MASK_OBJECT_AND_LINKINDEX`](`RendererAuxFlags::SEGMENTATION_MASK_OBJECT_AND_LINKINDEX`),
+/// otherwise it is always None. A link index of None stands for the base of the body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SegmentationMask {
+    /// width of the image in pixels
+    pub width: usize,
+    /// height of the image in pixels
+    pub height: usize,
+    /// the body and link of every pixel, row by row, or None if the pixel shows no body
+    pub labels: Vec<Option<(BodyId, Option<usize>)>>,
+}
+
+impl SegmentationMask {
+    /// returns the body and link at a pixel.
+    ///
+    /// # Panics
+    /// if the pixel is outside of the image.
+    pub fn label(&self, x: usize, y: usize) -> Option<(BodyId, Option<usize>)> {
+        assert!(x < self.width && y < self.height);
+        self.labels[y * self.width + x]
+    }
+    /// returns the bodies which are visible in the image, ordered by their id.
+    pub fn bodies(&self) -> Vec<BodyId> {
+        let mut bodies: Vec<BodyId> = self.pixel_counts().into_keys().collect();
+        bodies.sort_by_key(|body| body.0);
+        bodies
+    }
+    /// returns a binary mask which is 255 where the body is visible and 0 everywhere else.
+    pub fn body_mask(&self, body: BodyId) -> ImageBuffer<Luma<u8>, Vec<u8>> {
+        self.mask(|(label_body, _)| label_body == body)
+    }
+    /// returns a binary mask which is 255 where the link of the body is visible and 0 everywhere
+    /// else. Use None as link index for the base.
+    pub fn link_mask(
+        &self,
+        body: BodyId,
+        link_index: Option<usize>,
+    ) -> ImageBuffer<Luma<u8>, Vec<u8>> {
+        self.mask(|label| label == (body, link_index))
+    }
+    /// returns the number of pixels of every visible body.
+    pub fn pixel_counts(&self) -> HashMap<BodyId, usize> {
+        let mut counts = HashMap::new();
+        for &(body, _) in self.labels.iter().flatten() {
+            *counts.entry(body).or_insert(0) += 1;
+        }
+        counts
+    }
+    /// returns the number of pixels of every visible link.
+    pub fn link_pixel_counts(&self) -> HashMap<(BodyId, Option<usize>), usize> {
+        let mut counts = HashMap::new();
+        for &label in self.labels.iter().flatten() {
+            *counts.entry(label).or_insert(0) += 1;
+        }
+        counts
+    }
+    /// returns the smallest box which contains all pixels of every visible body.
+    pub fn bounding_boxes(&self) -> HashMap<BodyId, PixelBoundingBox> {
+        let mut boxes = HashMap::new();
+        for (x, y, (body, _)) in self.labeled_pixels() {
+            boxes
+                .entry(body)
+                .and_modify(|bounding_box: &mut PixelBoundingBox| bounding_box.extend(x, y))
+                .or_insert_with(|| PixelBoundingBox::new(x, y));
+        }
+        boxes
+    }
+    /// returns the smallest box which contains all pixels of every visible link.
+    pub fn link_bounding_boxes(&self) -> HashMap<(BodyId, Option<usize>), PixelBoundingBox> {
+        let mut boxes = HashMap::new();
+        for (x, y, label) in self.labeled_pixels() {
+            boxes
+                .entry(label)
+                .and_modify(|bounding_box: &mut PixelBoundingBox| bounding_box.extend(x, y))
+                .or_insert_with(|| PixelBoundingBox::new(x, y));
+        }
+        boxes
+    }
+    fn labeled_pixels(&self) -> impl Iterator<Item = (usize, usize, (BodyId, Option<usize>))> + '_ {
+        let width = self.width;
+        self.labels
+            .iter()
+            .enumerate()
+            .filter_map(move |(i, label)| label.map(|label| (i % width, i / width, label)))
+    }
+    fn mask<F: Fn((BodyId, Option<usize>)) -> bool>(
+        &self,
+        predicate: F,
+    ) -> ImageBuffer<Luma<u8>, Vec<u8>> {
+        let pixels = self
+            .labels
+            .iter()
+            .map(|label| match label {
+                Some(label) if predicate(*label) => 255,
+                _ => 0,
+            })
+            .collect();
+        ImageBuffer::from_raw(self.width as u32, self.height as u32, pixels)
+            .expect("the mask has one value per pixel")
+    }
+}
+
+/// An axis-aligned box in pixel coordinates. Both corners are part of the box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PixelBoundingBox {
+    /// column of the left edge
+    pub x_min: usize,
+    /// row of the top edge
+    pub y_min: usize,
+    /// column of the right edge
+    pub x_max: usize,
+    /// row of the bottom edge
+    pub y_max: usize,
+}
+
+impl PixelBoundingBox {
+    fn new(x: usize, y: usize) -> PixelBoundingBox {
+        PixelBoundingBox {
+            x_min: x,
+            y_min: y,
+            x_max: x,
+            y_max: y,
+        }
+    }
+    fn extend(&mut self, x: usize, y: usize) {
+        self.x_min = self.x_min.min(x);
+        self.y_min = self.y_min.min(y);
+        self.x_max = self.x_max.max(x);
+        self.y_max = self.y_max.max(y);
+    }
+    /// width of the box in pixels
+    pub fn width(&self) -> usize {
+        self.x_max - self.x_min + 1
+    }
+    /// height of the box in pixels
+    pub fn height(&self) -> usize {
+        self.y_max - self.y_min + 1
+    }
+}
+
+/// The frame in which the points of a [`PointCloud`] are expressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PointCloudFrame {
+    /// world coordinates
+    World,
+    /// the camera frame in the OpenCV convention, which is also used by
+    /// [`sensors::Camera`](`crate::sensors::Camera`): the z-axis is the optical axis, the x-axis
+    /// points right and the y-axis points down in the image. Note that the view matrix uses the
+    /// OpenGL convention instead, which looks along the negative z-axis with the y-axis pointing
+    /// up.
+    Camera,
+}
+
+/// A point cloud which was reconstructed from [`Images`].
+///
+/// The points are stored row by row. Organized clouds have the layout of the image they were
+/// created from, unorganized clouds have a height of 1.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointCloud {
+    /// number of points per row
+    pub width: usize,
+    /// number of rows
+    pub height: usize,
+    /// position of every point. Points of an organized cloud which do not hit any geometry
+    /// have NaN coordinates.
+    pub points: Vec<Vector3<f64>>,
+    /// RGBA color of every point. Empty if the RGBA image was not rendered.
+    pub colors: Vec<[u8; 4]>,
+    /// the body and link which every point belongs to. The link index is only known if the
+    /// image was rendered with
+    /// [`SEGMENTATION_MASK_OBJECT_AND_LINKINDEX`](`RendererAuxFlags::SEGMENTATION_MASK_OBJECT_AND_LINKINDEX`),
+    /// otherwise it is always None. Empty if the segmentation image was not rendered.
+    pub labels: Vec<Option<(BodyId, Option<usize>)>>,
+}
+
 /// Contains the cartesian velocity stored as Vector with 6 elements (x,y,z,wx,wy,wz).
 /// # Example
 /// ```rust
@@ -1238,6 +1971,8 @@ pub struct Images {
 /// let vel: Velocity = Vector6::zeros().into(); // creation from vector
 /// ```
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Velocity(Vector6<f64>);
 
 impl Velocity {
@@ -1272,6 +2007,8 @@ bitflags::bitflags! {
     /// let flags = LoadModelFlags::URDF_ENABLE_CACHED_GRAPHICS_SHAPES | LoadModelFlags::URDF_PRINT_URDF_INFO;
     /// assert!(flags.contains(LoadModelFlags::URDF_PRINT_URDF_INFO));
     /// ```
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct LoadModelFlags : i32 {
         /// use no flags (Default)
          const NONE = 0;
@@ -1332,7 +2069,8 @@ impl Default for LoadModelFlags {
 }
 bitflags::bitflags! {
     #[doc(hidden)]
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct JointInfoFlags : i32 {
         const NONE = 0;
         const JOINT_CHANGE_MAX_FORCE = 1;
@@ -1349,6 +2087,7 @@ impl Default for JointInfoFlags {
 
 /// contains the parameters for [`change_constraint`](`crate::PhysicsClient::change_constraint`) method.
 #[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChangeConstraintOptions {
     /// updated child pivot, see [`create_constraint`](`crate::PhysicsClient::create_constraint`)
     pub joint_child_pivot: Option<Vector3<f64>>,
@@ -1368,6 +2107,7 @@ pub struct ChangeConstraintOptions {
 
 /// contains the parameters for [`change_constraint`](`crate::PhysicsClient::change_constraint`) method.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConstraintInfo {
     /// the constraint for which this info is generated
     pub id: ConstraintId,
@@ -1398,8 +2138,10 @@ pub struct ConstraintInfo {
     /// constraint error reduction parameter
     pub erp: f64,
 }
-impl From<b3UserConstraint> for ConstraintInfo {
-    fn from(b3: b3UserConstraint) -> Self {
+impl TryFrom<b3UserConstraint> for ConstraintInfo {
+    type Error = Error;
+
+    fn try_from(b3: b3UserConstraint) -> Result<Self, Error> {
         #[allow(non_snake_case)]
         let b3UserConstraint {
             m_parentBodyIndex,
@@ -1440,18 +2182,18 @@ impl From<b3UserConstraint> for ConstraintInfo {
         };
         let child_body = {
             if m_childBodyIndex >= 0 {
-                Some(BodyId(m_childBodyIndex))
+                Some(BodyId::unchecked(m_childBodyIndex))
             } else {
                 None
             }
         };
-        ConstraintInfo {
-            id: ConstraintId(m_userConstraintUniqueId),
-            parent_body: BodyId(m_parentBodyIndex),
+        Ok(ConstraintInfo {
+            id: ConstraintId::unchecked(m_userConstraintUniqueId),
+            parent_body: BodyId::unchecked(m_parentBodyIndex),
             parent_link_index: parent_joint_index,
             child_body,
             child_link_index,
-            constraint_type: JointType::try_from(m_jointType).unwrap(),
+            constraint_type: JointType::try_from(m_jointType)?,
             joint_axis: m_jointAxis.into(),
             joint_parent_frame_pose: combined_position_orientation_array_to_isometry(m_parentFrame),
             joint_child_frame_pose: combined_position_orientation_array_to_isometry(m_childFrame),
@@ -1460,11 +2202,12 @@ impl From<b3UserConstraint> for ConstraintInfo {
             gear_aux_link,
             relative_position_target: m_relativePositionTarget,
             erp: m_erp,
-        }
+        })
     }
 }
 bitflags::bitflags! {
     #[derive(Clone, Debug, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct ActivationState : i32 {
         const ENABLE_SLEEPING = 1;
         const DISABLE_SLEEPING = 2;
@@ -1483,6 +2226,7 @@ bitflags::bitflags! {
 /// * `max_joint_velocity` - PyBullet claims that you can set it per joint, but that is not true
 /// * `collision_margin`
 #[derive(Default, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChangeDynamicsOptions {
     /// change the mass of the link
     pub mass: Option<f64>,
@@ -1539,6 +2283,7 @@ pub struct ChangeDynamicsOptions {
 /// Contains information about the mass, center of mass, friction and other properties of the base and links.
 /// Is returned by [`get_dynamics_info`](`crate::PhysicsClient::get_dynamics_info`).
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DynamicsInfo {
     /// mass in kg
     pub mass: f64,
@@ -1567,14 +2312,17 @@ pub struct DynamicsInfo {
     pub collision_margin: f64,
 }
 #[derive(Debug, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BodyType {
     RigidBody = 1,
     MultiBody = 2,
     SoftBody = 3,
 }
 
-impl From<b3DynamicsInfo> for DynamicsInfo {
-    fn from(b3: b3DynamicsInfo) -> Self {
+impl TryFrom<b3DynamicsInfo> for DynamicsInfo {
+    type Error = Error;
+
+    fn try_from(b3: b3DynamicsInfo) -> Result<Self, Error> {
         #[allow(unused, non_snake_case)]
         let b3DynamicsInfo {
             m_mass,
@@ -1603,7 +2351,7 @@ impl From<b3DynamicsInfo> for DynamicsInfo {
                 Some((m_contactStiffness, m_contactDamping))
             }
         };
-        DynamicsInfo {
+        Ok(DynamicsInfo {
             mass: m_mass,
             lateral_friction: m_lateralFrictionCoeff,
             spinning_friction: m_spinningFrictionCoeff,
@@ -1618,14 +2366,20 @@ impl From<b3DynamicsInfo> for DynamicsInfo {
                 1 => BodyType::RigidBody,
                 2 => BodyType::MultiBody,
                 3 => BodyType::SoftBody,
-                _ => panic!("internal error: Unknown BodyType ({})", m_bodyType),
+                _ => {
+                    return Err(Error::of_kind(
+                        ErrorKind::ParseError,
+                        format!("Unknown BodyType ({})", m_bodyType),
+                    ))
+                }
             },
             collision_margin: m_collisionMargin,
-        }
+        })
     }
 }
 /// axis-aligned minimum bounding box
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Aabb {
     /// minimum coordinates of the aabb
     pub min: Vector3<f64>,
@@ -1636,6 +2390,7 @@ pub struct Aabb {
 /// Is the result of [`get_overlapping_objects`](`crate::PhysicsClient::get_overlapping_objects`).
 /// Each object specifies a link of a body.
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OverlappingObject {
     /// BodyID of the overlapping object
     pub body: BodyId,
@@ -1645,6 +2400,7 @@ pub struct OverlappingObject {
 
 /// Is the result of the get_closest_points and get_contact_points methods.
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ContactPoint {
     /// reserved
     #[doc(hidden)]
@@ -1715,14 +2471,14 @@ impl From<b3ContactPointData> for ContactPoint {
             if m_bodyUniqueIdA < 0 {
                 None
             } else {
-                Some(BodyId(m_bodyUniqueIdA))
+                Some(BodyId::unchecked(m_bodyUniqueIdA))
             }
         };
         let body_b = {
             if m_bodyUniqueIdB < 0 {
                 None
             } else {
-                Some(BodyId(m_bodyUniqueIdB))
+                Some(BodyId::unchecked(m_bodyUniqueIdB))
             }
         };
         ContactPoint {
@@ -1741,6 +2497,7 @@ impl From<b3ContactPointData> for ContactPoint {
         }
     }
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LoggingType {
     /// This will require to load the quadruped/quadruped.urdf and object unique
     /// id from the quadruped. It logs the timestamp, IMU roll/pitch/yaw, 8 leg
@@ -1766,6 +2523,7 @@ pub enum LoggingType {
     CustomTimer,
 }
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StateLoggingOptions {
     /// If left empty, the logger may log every object, otherwise the logger just logs the objects in the list.
     pub object_ids: Vec<BodyId>,
@@ -1794,6 +2552,7 @@ pub struct StateLoggingOptions {
 }
 bitflags::bitflags! {
     #[derive(Debug, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct LogFlags : i32 {
         const JOINT_MOTOR_TORQUES = 1;
         const JOINT_USER_TORQUES = 2;
@@ -1803,6 +2562,7 @@ bitflags::bitflags! {
 
 /// Options for the [`set_physics_engine_parameter`](`crate::PhysicsClient::set_physics_engine_parameter`) method.
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetPhysicsEngineParameterOptions {
     /// See the warning in the [`set_time_step`](`crate::PhysicsClient::set_time_step`) section.
     /// physics engine time step,
@@ -1879,6 +2639,7 @@ pub struct SetPhysicsEngineParameterOptions {
     pub num_non_contact_inner_iterations: Option<usize>,
 }
 #[derive(Debug, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ConstraintSolverType {
     None,
     Si = 1,
@@ -1891,6 +2652,7 @@ pub enum ConstraintSolverType {
 /// Specifies joint feedback frame. Is used in
 /// [`SetPhysicsEngineParameterOptions::joint_feedback_mode`](`SetPhysicsEngineParameterOptions::joint_feedback_mode`)
 #[derive(Debug, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum JointFeedbackMode {
     None,
     /// gets the joint feedback in world space
@@ -1901,6 +2663,7 @@ pub enum JointFeedbackMode {
 ///
 /// See [`SetPhysicsEngineParameterOptions`](`SetPhysicsEngineParameterOptions`) for a description of the parameters.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PhysicsEngineParameters {
     pub fixed_time_step: Duration,
     pub simulation_time_stamp: Duration,
@@ -1956,15 +2719,20 @@ pub struct PhysicsEngineParameters {
     pub internal_sim_flags: i32,
     pub friction_cfm: f64,
 }
-fn int_to_bool(int: i32) -> bool {
+fn int_to_bool(int: i32) -> Result<bool, Error> {
     match int {
-        0 => false,
-        1 => true,
-        _ => panic!("could not convert \"{}\" to boolean", int),
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(Error::of_kind(
+            ErrorKind::ParseError,
+            format!("could not convert \"{}\" to boolean", int),
+        )),
     }
 }
-impl From<b3PhysicsSimulationParameters> for PhysicsEngineParameters {
-    fn from(b3: b3PhysicsSimulationParameters) -> Self {
+impl TryFrom<b3PhysicsSimulationParameters> for PhysicsEngineParameters {
+    type Error = Error;
+
+    fn try_from(b3: b3PhysicsSimulationParameters) -> Result<Self, Error> {
         #[allow(non_snake_case)]
         let b3PhysicsSimulationParameters {
             m_deltaTime,
@@ -2005,7 +2773,12 @@ impl From<b3PhysicsSimulationParameters> for PhysicsEngineParameters {
                 0 => JointFeedbackMode::None,
                 1 => JointFeedbackMode::WorldSpace,
                 2 => JointFeedbackMode::JointFrame,
-                n => panic!("Unexpected JointFeedbackMode  \"{}\"", n),
+                n => {
+                    return Err(Error::of_kind(
+                        ErrorKind::ParseError,
+                        format!("Unexpected JointFeedbackMode  \"{}\"", n),
+                    ))
+                }
             }
         };
         let constraint_solver_type = {
@@ -2017,49 +2790,55 @@ impl From<b3PhysicsSimulationParameters> for PhysicsEngineParameters {
                 4 => ConstraintSolverType::Lemke,
                 5 => ConstraintSolverType::Nncg,
                 6 => ConstraintSolverType::BlockPgs,
-                n => panic!("Unexpected ConstraintSolverType  \"{}\"", n),
+                n => {
+                    return Err(Error::of_kind(
+                        ErrorKind::ParseError,
+                        format!("Unexpected ConstraintSolverType  \"{}\"", n),
+                    ))
+                }
             }
         };
-        PhysicsEngineParameters {
+        Ok(PhysicsEngineParameters {
             fixed_time_step: Duration::from_secs_f64(m_deltaTime),
             simulation_time_stamp: Duration::from_secs_f64(m_simulationTimestamp),
             num_solver_iterations: m_numSolverIterations as usize,
-            use_split_impulse: int_to_bool(m_useSplitImpulse),
+            use_split_impulse: int_to_bool(m_useSplitImpulse)?,
             split_impulse_penetration_threshold: m_splitImpulsePenetrationThreshold,
             num_sub_steps: m_numSimulationSubSteps as usize,
             collision_filter_mode: m_collisionFilterMode as usize,
             contact_breaking_threshold: m_contactBreakingThreshold,
 
-            enable_file_caching: int_to_bool(m_enableFileCaching),
+            enable_file_caching: int_to_bool(m_enableFileCaching)?,
             restitution_velocity_threshold: m_restitutionVelocityThreshold,
             erp: m_defaultNonContactERP,
             contact_erp: m_defaultContactERP,
             friction_erp: m_frictionERP,
-            enable_cone_friction: int_to_bool(m_enableConeFriction),
-            deterministic_overlapping_pairs: int_to_bool(m_deterministicOverlappingPairs),
+            enable_cone_friction: int_to_bool(m_enableConeFriction)?,
+            deterministic_overlapping_pairs: int_to_bool(m_deterministicOverlappingPairs)?,
             allowed_ccd_penetration: m_allowedCcdPenetration,
             joint_feedback_mode,
             solver_residual_threshold: m_solverResidualThreshold,
             contact_slop: m_contactSlop,
-            enable_sat: int_to_bool(m_enableSAT),
+            enable_sat: int_to_bool(m_enableSAT)?,
             constraint_solver_type,
             global_cfm: m_defaultGlobalCFM,
             minimum_solver_island_size: m_minimumSolverIslandSize as usize,
-            report_solver_analytics: int_to_bool(m_reportSolverAnalytics),
+            report_solver_analytics: int_to_bool(m_reportSolverAnalytics)?,
             warm_starting_factor: m_warmStartingFactor,
             sparse_sdf_voxel_size: m_sparseSdfVoxelSize,
             num_non_contact_inner_iterations: m_numNonContactInnerIterations as usize,
-            use_real_time_simulation: int_to_bool(m_useRealTimeSimulation),
+            use_real_time_simulation: int_to_bool(m_useRealTimeSimulation)?,
             gravity: m_gravityAcceleration.into(),
             articulated_warm_starting_factor: m_articulatedWarmStartingFactor,
             internal_sim_flags: m_internalSimFlags,
             friction_cfm: m_frictionCFM,
-        }
+        })
     }
 }
 /// Contains the state of the Gui camera.
 /// Is returned by [`get_debug_visualizer_camera`](`crate::PhysicsClient::get_debug_visualizer_camera`).
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DebugVisualizerCameraInfo {
     /// width of the camera image in pixels
     pub width: usize,
@@ -2123,6 +2902,7 @@ impl From<b3OpenGLVisualizerCameraInfo> for DebugVisualizerCameraInfo {
 
 /// Options for [`ray_test`](`crate::PhysicsClient::ray_test`)
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RayTestOptions {
     /// instead of first closest hit, you can report the n-th hit
     pub report_hit_number: Option<usize>,
@@ -2133,6 +2913,7 @@ pub struct RayTestOptions {
 }
 /// Options for [`ray_test_batch`](`crate::PhysicsClient::ray_test_batch`)
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RayTestBatchOptions {
     /// ray from/to is in local space of a parent object
     pub parent_object_id: Option<BodyId>,
@@ -2155,6 +2936,7 @@ pub struct RayTestBatchOptions {
     pub collision_filter_mask: Option<i32>,
 }
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RayHitInfo {
     pub body_id: BodyId,
     pub link_index: Option<usize>,
@@ -2164,20 +2946,17 @@ pub struct RayHitInfo {
 }
 impl RayHitInfo {
     pub fn new(ray: b3RayHitInfo) -> Option<Self> {
-        let link_index = {
-            assert!(ray.m_hitObjectLinkIndex >= -1);
-            if ray.m_hitObjectLinkIndex == -1 {
-                None
-            } else {
-                Some(ray.m_hitObjectLinkIndex as usize)
-            }
+        let link_index = if ray.m_hitObjectLinkIndex < 0 {
+            None
+        } else {
+            Some(ray.m_hitObjectLinkIndex as usize)
         };
 
         if ray.m_hitObjectUniqueId < 0 {
             None
         } else {
             Some(RayHitInfo {
-                body_id: BodyId(ray.m_hitObjectUniqueId),
+                body_id: BodyId::unchecked(ray.m_hitObjectUniqueId),
                 link_index,
                 hit_fraction: ray.m_hitFraction,
                 hit_position: ray.m_hitPositionWorld.into(),
@@ -2188,6 +2967,7 @@ impl RayHitInfo {
 }
 /// options for [`load_soft_body`](`crate::PhysicsClient::load_soft_body`)
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SoftBodyOptions {
     /// initial pose of the deformable object
     pub base_pose: Isometry3<f64>,
@@ -2255,6 +3035,7 @@ impl Default for SoftBodyOptions {
 }
 bitflags::bitflags! {
     /// Experimental flags, best to ignore.
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct ResetFlags : i32 {
         const DEFORMABLE_WORLD = 1;
         const DISCRETE_DYNAMICS_WORLD = 2;
@@ -2264,14 +3045,31 @@ bitflags::bitflags! {
 
 bitflags::bitflags! {
     /// Experimental flags, best to ignore.
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct VisualShapeFlags : i32 {
         const TEXTURE_UNIQUE_IDS = 1;
         const DOUBLE_SIDED = 4;
     }
 }
 bitflags::bitflags! {
-    #[derive(Debug)]
+    /// selects the images which
+    /// [`get_camera_image_into()`](`crate::PhysicsClient::get_camera_image_into()`) copies out of
+    /// the renderer. Flags can be combined with the `|`-operator.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct ImageChannels : u8 {
+        /// the RGBA image
+        const RGBA = 1;
+        /// the depth image
+        const DEPTH = 2;
+        /// the segmentation image. If it is not selected, the renderer skips computing it.
+        const SEGMENTATION = 4;
+    }
+}
+bitflags::bitflags! {
+    #[derive(Debug, Clone, Copy)]
     /// flags for camera rendering
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct RendererAuxFlags : i32 {
         /// if used the pixels of the segmentation mask are calculated with this formula:
         /// bodyId + (linkIndex+1)<<24
@@ -2281,7 +3079,8 @@ bitflags::bitflags! {
         const NO_SEGMENTATION_MASK = 4;
     }
 }
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Renderer {
     TinyRenderer = 1 << 16,
     /// Direct mode has no OpenGL, so you can not use this setting in direct mode.
@@ -2289,7 +3088,8 @@ pub enum Renderer {
 }
 
 /// Options for [`get_camera_image`](`crate::PhysicsClient::get_camera_image`)
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CameraImageOptions {
     /// view matrix, see [compute_view_matrix](`crate::PhysicsClient::compute_view_matrix`)
     pub view_matrix: Option<Matrix4<f32>>,
@@ -1,13 +1,32 @@
-use nalgebra::{DVector, Isometry3, Matrix3xX, Translation3, UnitQuaternion, Vector3};
+use nalgebra::{
+    DVector, Isometry3, Matrix3xX, Matrix4, Point3, Translation3, UnitQuaternion, Vector3,
+};
 
 use anyhow::Result;
+use misfire::env::{
+    Environment, FrameSkip, KukaCubeGrasp, MinitaurLocomotion, PandaReach, Space, TimeLimit,
+};
+use misfire::logging_utils::{
+    read_generic_robot_log, ContactPointLog, GenericRobotLog, GenericRobotLogWriter, LogPlayer,
+    LogReader, LogSchema, LogValue, LogWriter,
+};
+use misfire::plugins::{PdControlOptions, PdControlPlugin};
+use misfire::scene::{
+    AssetSource, PrimitiveShape, Scene, SceneAsset, SceneBody, SceneCamera, SceneConstraint,
+    SceneMaterial, ScenePhysics, ScenePrimitive,
+};
+use misfire::sensors::{Camera, CameraIntrinsics, Lidar, LidarOptions};
 use misfire::ControlCommandArray::Torques;
 use misfire::Mode::Direct;
 use misfire::{
-    BodyId, ChangeDynamicsOptions, ConstraintSolverType, ControlCommand, ControlCommandArray,
-    DebugVisualizerFlag, Error, InverseKinematicsParametersBuilder, JointFeedbackMode,
-    JointInfoFlags, JointType, LoadModelFlags, PhysicsClient, SetPhysicsEngineParameterOptions,
-    UrdfOptions,
+    BodyId, CameraImageOptions, ChangeDynamicsOptions, ConstraintSolverType, ControlCommand,
+    ControlCommandArray, ControlCommandMultiDof, DebugVisualizerFlag, Error, ErrorKind,
+    ExternalForceFrame, GeometricCollisionShape, ImageChannels, Images,
+    InverseKinematicsParametersBuilder, JointFeedbackMode, JointInfoFlags, JointType,
+    LoadModelFlags, LogFlags, LoggingType, Mode, MultiBodyOptions, PhysicsClient,
+    PhysicsClientPool, PhysicsServer, PointCloudFrame, RayTestBatchOptions, RendererAuxFlags,
+    Robot, SendablePhysicsClient, ServerMode, SetPhysicsEngineParameterOptions,
+    SharedPhysicsClient, StateLoggingOptions, UrdfOptions, UserDataValue, VisualId,
 };
 use misfire::{JointInfo, JointState};
 use std::f64::consts::PI;
@@ -55,15 +74,15 @@ fn test_add_and_remove_bodies() {
     physics_client
         .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
         .unwrap();
-    assert_eq!(physics_client.get_num_bodies(), 0);
+    assert_eq!(physics_client.get_num_bodies().unwrap(), 0);
     let _plane_id = physics_client.load_urdf("plane.urdf", None).unwrap();
-    assert_eq!(physics_client.get_num_bodies(), 1);
+    assert_eq!(physics_client.get_num_bodies().unwrap(), 1);
     let r2d2 = physics_client.load_urdf("r2d2.urdf", None).unwrap();
-    assert_eq!(physics_client.get_num_bodies(), 2);
-    physics_client.remove_body(r2d2);
-    assert_eq!(physics_client.get_num_bodies(), 1);
-    physics_client.reset_simulation();
-    assert_eq!(physics_client.get_num_bodies(), 0);
+    assert_eq!(physics_client.get_num_bodies().unwrap(), 2);
+    physics_client.remove_body(r2d2).unwrap();
+    assert_eq!(physics_client.get_num_bodies().unwrap(), 1);
+    physics_client.reset_simulation().unwrap();
+    assert_eq!(physics_client.get_num_bodies().unwrap(), 0);
 }
 #[test]
 fn test_get_and_reset_base_transformation() {
@@ -76,7 +95,9 @@ fn test_get_and_reset_base_transformation() {
         Translation3::new(0.2, 0.3, 0.4),
         UnitQuaternion::from_euler_angles(0.1, 0.2, 0.3),
     );
-    physics_client.reset_base_transform(r2d2, desired_transform);
+    physics_client
+        .reset_base_transform(r2d2, desired_transform)
+        .unwrap();
     let actual_transform = physics_client.get_base_transform(r2d2).unwrap();
     slice_compare(
         desired_transform.translation.vector.as_slice(),
@@ -93,7 +114,9 @@ fn test_get_and_reset_base_transformation() {
         Translation3::new(3.7, -0.23, 10.4),
         UnitQuaternion::from_euler_angles(1.1, -0.2, 2.3),
     );
-    physics_client.reset_base_transform(r2d2, desired_transform);
+    physics_client
+        .reset_base_transform(r2d2, desired_transform)
+        .unwrap();
     let actual_transform = physics_client.get_base_transform(r2d2).unwrap();
     slice_compare(
         desired_transform.translation.vector.as_slice(),
@@ -118,16 +141,103 @@ fn test_get_body_info() {
     assert_eq!(body_info.body_name.as_str(), "physics");
 }
 #[test]
-#[should_panic]
+fn test_error_kinds() {
+    let mut physics_client = PhysicsClient::connect(Direct).unwrap();
+    physics_client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    let err = physics_client
+        .load_urdf("does_not_exist.urdf", None)
+        .unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        ErrorKind::CommandFailed {
+            command: "load_urdf",
+            ..
+        }
+    ));
+    let err = physics_client
+        .set_additional_search_path("invalid\0path")
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    let r2d2 = physics_client.load_urdf("r2d2.urdf", None).unwrap();
+    let num_joints = physics_client.get_num_joints(r2d2).unwrap();
+    let err = physics_client
+        .get_joint_state(r2d2, num_joints)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidLinkIndex);
+}
+#[test]
 fn test_get_joint_info_index_out_of_range() {
     let mut physics_client = PhysicsClient::connect(Direct).unwrap();
     physics_client
         .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
         .unwrap();
     let r2d2 = physics_client.load_urdf("r2d2.urdf", None).unwrap();
-    let num_joints = physics_client.get_num_joints(r2d2);
-    let joint_info = physics_client.get_joint_info(r2d2, num_joints);
-    println!("{:?}", joint_info);
+    let num_joints = physics_client.get_num_joints(r2d2).unwrap();
+    let err = physics_client.get_joint_info(r2d2, num_joints).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidLinkIndex);
+    let err = physics_client
+        .set_joint_motor_control(r2d2, num_joints, ControlCommand::Velocity(1.), None)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidLinkIndex);
+}
+
+#[test]
+fn test_joint_state_multi_dof() {
+    let mut physics_client = PhysicsClient::connect(Direct).unwrap();
+    physics_client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    let r2d2 = physics_client.load_urdf("r2d2.urdf", None).unwrap();
+    physics_client
+        .reset_joint_state_multi_dof(r2d2, 2, &[0.5], Some(&[0.1]))
+        .unwrap();
+    let state = physics_client.get_joint_state_multi_dof(r2d2, 2).unwrap();
+    slice_compare(&[0.5], &state.joint_position, 1e-10);
+    slice_compare(&[0.1], &state.joint_velocity, 1e-10);
+    assert!(state.orientation().is_none());
+    let single = physics_client.get_joint_state(r2d2, 2).unwrap();
+    float_compare(single.joint_position, state.joint_position[0], 1e-10);
+
+    // a fixed joint has no degrees of freedom
+    let state = physics_client.get_joint_state_multi_dof(r2d2, 1).unwrap();
+    assert!(state.joint_position.is_empty());
+    assert!(state.joint_velocity.is_empty());
+
+    let err = physics_client
+        .reset_joint_state_multi_dof(r2d2, 2, &[0., 0., 0., 1.], None)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    let err = physics_client
+        .set_joint_motor_control_multi_dof(
+            r2d2,
+            2,
+            ControlCommandMultiDof::Velocity(&[1., 2., 3.]),
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    let err = physics_client
+        .set_joint_motor_control_multi_dof_array(
+            r2d2,
+            &[2, 3],
+            &[
+                ControlCommandMultiDof::Velocity(&[1.]),
+                ControlCommandMultiDof::Torque(&[1.]),
+            ],
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    physics_client
+        .set_joint_motor_control_multi_dof(
+            r2d2,
+            2,
+            ControlCommandMultiDof::Velocity(&[1.]),
+            Some(&[10.]),
+        )
+        .unwrap();
 }
 
 #[test]
@@ -138,7 +248,7 @@ fn test_get_joint_info() {
         .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
         .unwrap();
     let r2d2 = physics_client.load_urdf("r2d2.urdf", None).unwrap();
-    let joint_info = physics_client.get_joint_info(r2d2, 1);
+    let joint_info = physics_client.get_joint_info(r2d2, 1).unwrap();
     assert_eq!(1, joint_info.joint_index);
     assert_eq!("right_base_joint", joint_info.joint_name);
 
@@ -166,7 +276,7 @@ fn test_get_joint_info() {
         1e-7,
     );
     assert_eq!(0, joint_info.parent_index.unwrap());
-    let joint_info = physics_client.get_joint_info(r2d2, 2);
+    let joint_info = physics_client.get_joint_info(r2d2, 2).unwrap();
     assert_eq!(2, joint_info.joint_index);
     assert_eq!("right_front_wheel_joint", joint_info.joint_name);
 
@@ -197,7 +307,7 @@ fn test_get_joint_info() {
 }
 
 pub fn set_joint_positions(client: &mut PhysicsClient, robot: BodyId, position: &[f64]) {
-    let num_joints = client.get_num_joints(robot);
+    let num_joints = client.get_num_joints(robot).unwrap();
     assert_eq!(num_joints, position.len());
     let indices = (0..num_joints).collect::<Vec<usize>>();
     let zero_vec = vec![0.; num_joints];
@@ -222,7 +332,7 @@ pub fn get_joint_states(
     client: &mut PhysicsClient,
     robot: BodyId,
 ) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
-    let num_joints = client.get_num_joints(robot);
+    let num_joints = client.get_num_joints(robot).unwrap();
     let indices = (0..num_joints).collect::<Vec<usize>>();
     let joint_states = client.get_joint_states(robot, indices.as_slice()).unwrap();
     let pos = joint_states
@@ -244,11 +354,11 @@ pub fn get_motor_joint_states(
     client: &mut PhysicsClient,
     robot: BodyId,
 ) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
-    let num_joints = client.get_num_joints(robot);
+    let num_joints = client.get_num_joints(robot).unwrap();
     let indices = (0..num_joints).collect::<Vec<usize>>();
     let joint_states = client.get_joint_states(robot, indices.as_slice()).unwrap();
     let joint_infos: Vec<JointInfo> = (0..num_joints)
-        .map(|y| client.get_joint_info(robot, y))
+        .map(|y| client.get_joint_info(robot, y).unwrap())
         .collect::<Vec<JointInfo>>();
     let joint_states = joint_states
         .iter()
@@ -279,7 +389,7 @@ pub fn multiply_jacobian(
     let mut result = Vector3::new(0., 0., 0.);
     let mut i = 0;
     for c in 0..vector.len() {
-        if client.get_joint_info(robot, c).q_index > -1 {
+        if client.get_joint_info(robot, c).unwrap().q_index > -1 {
             for r in 0..3 {
                 result[r] += jacobian[(r, i)] * vector[c];
             }
@@ -295,7 +405,7 @@ fn test_jacobian() {
     let mut p = PhysicsClient::connect(Direct).unwrap();
     p.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
         .unwrap();
-    p.set_time_step(delta_t);
+    p.set_time_step(delta_t).unwrap();
 
     let kuka_id = p
         .load_urdf(
@@ -307,7 +417,7 @@ fn test_jacobian() {
         )
         .unwrap();
 
-    let num_joints = p.get_num_joints(kuka_id);
+    let num_joints = p.get_num_joints(kuka_id).unwrap();
     let kuka_end_effector_index = num_joints - 1;
     set_joint_positions(&mut p, kuka_id, vec![0.1; num_joints].as_slice());
     p.step_simulation().unwrap();
@@ -391,7 +501,7 @@ fn test_get_link_state() {
     p.set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
         .unwrap();
 
-    p.set_time_step(delta_t);
+    p.set_time_step(delta_t).unwrap();
 
     let kuka_id = p
         .load_urdf(
@@ -403,7 +513,7 @@ fn test_get_link_state() {
         )
         .unwrap();
 
-    let num_joints = p.get_num_joints(kuka_id);
+    let num_joints = p.get_num_joints(kuka_id).unwrap();
     let kuka_end_effector_index = num_joints - 1;
     set_joint_positions(&mut p, kuka_id, vec![0.1; num_joints].as_slice());
     p.step_simulation().unwrap();
@@ -506,7 +616,7 @@ pub fn inverse_dynamics_test() {
     physics_client
         .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
         .unwrap();
-    physics_client.set_time_step(delta_t);
+    physics_client.set_time_step(delta_t).unwrap();
     let id_revolute_joints = [0, 3];
     let id_robot = physics_client
         .load_urdf(
@@ -517,15 +627,17 @@ pub fn inverse_dynamics_test() {
             },
         )
         .unwrap();
-    physics_client.change_dynamics(
-        id_robot,
-        None,
-        ChangeDynamicsOptions {
-            linear_damping: Some(0.),
-            angular_damping: Some(0.),
-            ..Default::default()
-        },
-    );
+    physics_client
+        .change_dynamics(
+            id_robot,
+            None,
+            ChangeDynamicsOptions {
+                linear_damping: Some(0.),
+                angular_damping: Some(0.),
+                ..Default::default()
+            },
+        )
+        .unwrap();
     physics_client
         .set_joint_motor_control_array(
             id_robot,
@@ -592,9 +704,13 @@ pub fn inverse_dynamics_test() {
 #[test]
 fn test_mass_matrix_and_inverse_kinematics() -> Result<()> {
     let mut physics_client = PhysicsClient::connect(Direct)?;
-    physics_client.configure_debug_visualizer(DebugVisualizerFlag::CovEnableYAxisUp, true);
-    physics_client.set_time_step(Duration::from_secs_f64(1. / 60.));
-    physics_client.set_gravity(Vector3::new(0.0, -9.8, 0.));
+    physics_client
+        .configure_debug_visualizer(DebugVisualizerFlag::CovEnableYAxisUp, true)
+        .unwrap();
+    physics_client
+        .set_time_step(Duration::from_secs_f64(1. / 60.))
+        .unwrap();
+    physics_client.set_gravity(Vector3::new(0.0, -9.8, 0.))?;
 
     println!("a");
     let mut panda = PandaSim::new(&mut physics_client, Vector3::zeros())?;
@@ -639,10 +755,10 @@ impl PandaSim {
                 angular_damping: Some(0.),
                 ..Default::default()
             },
-        );
+        )?;
         let mut index = 0;
-        for i in 0..client.get_num_joints(panda_id) {
-            let info = client.get_joint_info(panda_id, i);
+        for i in 0..client.get_num_joints(panda_id)? {
+            let info = client.get_joint_info(panda_id, i)?;
             if info.joint_type == JointType::Revolute || info.joint_type == JointType::Prismatic {
                 client.reset_joint_state(
                     panda_id,
@@ -681,12 +797,14 @@ impl PandaSim {
             .unwrap();
 
         for i in 0..PandaSim::PANDA_NUM_DOFS {
-            client.set_joint_motor_control(
-                self.id,
-                i,
-                ControlCommand::Position(joint_poses[i]),
-                Some(240. * 5.),
-            );
+            client
+                .set_joint_motor_control(
+                    self.id,
+                    i,
+                    ControlCommand::Position(joint_poses[i]),
+                    Some(240. * 5.),
+                )
+                .unwrap();
         }
         let target_mass_matrix = [
             1.2851012047449573,
@@ -912,7 +1030,7 @@ fn save_and_restore_test() {
     );
     let start_state = client.save_state().unwrap();
     let transform = Isometry3::translation(1., 1., 1.);
-    client.reset_base_transform(cube, transform);
+    client.reset_base_transform(cube, transform).unwrap();
     let cube_pose_end = client.get_base_transform(cube).unwrap();
     slice_compare(
         cube_pose_end.translation.vector.as_slice(),
@@ -938,7 +1056,7 @@ fn save_and_restore_test() {
         1e-10,
     );
 
-    client.remove_state(start_state);
+    client.remove_state(start_state).unwrap();
 }
 #[test]
 fn save_and_restore_from_file_test() {
@@ -962,7 +1080,7 @@ fn save_and_restore_from_file_test() {
         .save_bullet("save_and_restore_from_file_test.bullet")
         .unwrap();
     let transform = Isometry3::translation(1., 1., 1.);
-    client.reset_base_transform(cube, transform);
+    client.reset_base_transform(cube, transform).unwrap();
     let cube_pose_end = client.get_base_transform(cube).unwrap();
     slice_compare(
         cube_pose_end.translation.vector.as_slice(),
@@ -1011,35 +1129,37 @@ fn set_and_get_physics_engine_parameters() {
     let f = 0.3;
     let u = 5;
     let dur = Duration::from_secs_f64(0.11);
-    client.set_physics_engine_parameter(SetPhysicsEngineParameterOptions {
-        fixed_time_step: Some(dur),
-        num_solver_iterations: Some(u),
-        use_split_impulse: Some(b),
-        split_impulse_penetration_threshold: Some(f),
-        num_sub_steps: Some(u),
-        collision_filter_mode: Some(u),
-        contact_breaking_threshold: Some(f),
-        max_num_cmd_per_1_ms: Some(u as i32),
-        enable_file_caching: Some(b),
-        restitution_velocity_threshold: Some(f),
-        erp: Some(f),
-        contact_erp: Some(f),
-        friction_erp: Some(f),
-        enable_cone_friction: Some(b),
-        deterministic_overlapping_pairs: Some(b),
-        allowed_ccd_penetration: Some(f),
-        joint_feedback_mode: Some(JointFeedbackMode::WorldSpace),
-        solver_residual_threshold: Some(f),
-        contact_slop: Some(f),
-        enable_sat: Some(b),
-        constraint_solver_type: Some(ConstraintSolverType::Dantzig),
-        global_cfm: Some(f),
-        minimum_solver_island_size: Some(u),
-        report_solver_analytics: Some(b),
-        warm_starting_factor: Some(f),
-        sparse_sdf_voxel_size: Some(f),
-        num_non_contact_inner_iterations: Some(u),
-    });
+    client
+        .set_physics_engine_parameter(SetPhysicsEngineParameterOptions {
+            fixed_time_step: Some(dur),
+            num_solver_iterations: Some(u),
+            use_split_impulse: Some(b),
+            split_impulse_penetration_threshold: Some(f),
+            num_sub_steps: Some(u),
+            collision_filter_mode: Some(u),
+            contact_breaking_threshold: Some(f),
+            max_num_cmd_per_1_ms: Some(u as i32),
+            enable_file_caching: Some(b),
+            restitution_velocity_threshold: Some(f),
+            erp: Some(f),
+            contact_erp: Some(f),
+            friction_erp: Some(f),
+            enable_cone_friction: Some(b),
+            deterministic_overlapping_pairs: Some(b),
+            allowed_ccd_penetration: Some(f),
+            joint_feedback_mode: Some(JointFeedbackMode::WorldSpace),
+            solver_residual_threshold: Some(f),
+            contact_slop: Some(f),
+            enable_sat: Some(b),
+            constraint_solver_type: Some(ConstraintSolverType::Dantzig),
+            global_cfm: Some(f),
+            minimum_solver_island_size: Some(u),
+            report_solver_analytics: Some(b),
+            warm_starting_factor: Some(f),
+            sparse_sdf_voxel_size: Some(f),
+            num_non_contact_inner_iterations: Some(u),
+        })
+        .unwrap();
     let params = client.get_physics_engine_parameters().unwrap();
     assert_eq!(params.fixed_time_step, dur);
     assert_eq!(params.num_solver_iterations, u);
@@ -1068,3 +1188,2117 @@ fn set_and_get_physics_engine_parameters() {
     // assert_eq!(params.sparse_sdf_voxel_size, f);// bug in bullet3
     assert_eq!(params.num_non_contact_inner_iterations, u);
 }
+
+#[test]
+fn test_user_data() {
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    let r2d2 = client.load_urdf("r2d2.urdf", None).unwrap();
+    let plane = client.load_urdf("plane.urdf", None).unwrap();
+    assert_eq!(client.get_num_user_data(r2d2).unwrap(), 0);
+
+    let class_id = client
+        .add_user_data(r2d2, "object_class", "robot", None, None)
+        .unwrap();
+    let bytes_id = client
+        .add_user_data(r2d2, "raw", vec![1u8, 0, 255], 2, None)
+        .unwrap();
+    let visual_id = client
+        .add_user_data(plane, "object_class", "floor", None, 0)
+        .unwrap();
+    client.sync_user_data(&[]).unwrap();
+
+    assert_eq!(
+        client.get_user_data(class_id).unwrap(),
+        UserDataValue::String("robot".to_string())
+    );
+    assert_eq!(
+        client.get_user_data(bytes_id).unwrap().as_bytes(),
+        &[1, 0, 255]
+    );
+    assert_eq!(
+        client.get_user_data(visual_id).unwrap().as_str(),
+        Some("floor")
+    );
+    assert_eq!(
+        client
+            .get_user_data_id(r2d2, "object_class", None, None)
+            .unwrap(),
+        Some(class_id)
+    );
+    assert_eq!(
+        client.get_user_data_id(r2d2, "raw", None, None).unwrap(),
+        None
+    );
+    assert_eq!(
+        client.get_user_data_id(r2d2, "raw", 2, None).unwrap(),
+        Some(bytes_id)
+    );
+    assert_eq!(client.get_num_user_data(r2d2).unwrap(), 2);
+
+    let info = client.get_user_data_info(plane, 0).unwrap();
+    assert_eq!(info.user_data_id, visual_id);
+    assert_eq!(info.key, "object_class");
+    assert_eq!(info.body, plane);
+    assert_eq!(info.link_index, None);
+    assert_eq!(info.visual_shape_index, Some(0));
+    assert!(client.get_user_data_info(plane, 1).is_err());
+
+    // adding a value with an existing key updates the entry
+    let updated_id = client
+        .add_user_data(r2d2, "object_class", "droid", None, None)
+        .unwrap();
+    assert_eq!(updated_id, class_id);
+    assert_eq!(
+        client.get_user_data(class_id).unwrap().as_str(),
+        Some("droid")
+    );
+
+    client.remove_user_data(class_id).unwrap();
+    assert_eq!(client.get_num_user_data(r2d2).unwrap(), 1);
+    assert!(client.get_user_data(class_id).is_err());
+}
+
+#[test]
+fn test_pd_control_plugin() {
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    let r2d2 = client
+        .load_urdf(
+            "r2d2.urdf",
+            UrdfOptions {
+                use_fixed_base: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    client
+        .set_joint_motor_control(r2d2, 2, ControlCommand::Velocity(0.), Some(0.))
+        .unwrap();
+    let pd_control = PdControlPlugin::load(&mut client).unwrap();
+    pd_control
+        .set_pd_control(
+            &mut client,
+            r2d2,
+            2,
+            PdControlOptions {
+                target_position: 1.,
+                target_velocity: 0.,
+                position_gain: 10.,
+                velocity_gain: 1.,
+                maximum_force: 100.,
+            },
+        )
+        .unwrap();
+    for _ in 0..1000 {
+        client.step_simulation().unwrap();
+    }
+    float_compare(
+        1.,
+        client.get_joint_state(r2d2, 2).unwrap().joint_position,
+        1e-2,
+    );
+
+    let num_joints = client.get_num_joints(r2d2).unwrap();
+    assert!(pd_control
+        .remove_pd_control(&mut client, r2d2, num_joints)
+        .is_err());
+    pd_control.remove_pd_control(&mut client, r2d2, 2).unwrap();
+    pd_control.unload(&mut client).unwrap();
+    assert!(client.load_plugin("thisPluginDoesNotExist", None).is_err());
+}
+
+#[test]
+fn test_async_commands() {
+    use std::future::Future;
+    use std::task::{Context, Poll, Waker};
+
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    let r2d2 = client.load_urdf("r2d2.urdf", None).unwrap();
+    client
+        .set_joint_motor_control_array_async(
+            r2d2,
+            &[2, 3],
+            ControlCommandArray::Velocities(&[1., 1.]),
+            None,
+        )
+        .unwrap()
+        .wait()
+        .unwrap();
+    let mut step = client.step_simulation_async().unwrap();
+    while !step.is_ready() {}
+    step.wait().unwrap();
+
+    let mut pending = Box::pin(client.get_joint_states_async(r2d2, &[2, 3]).unwrap());
+    let mut context = Context::from_waker(Waker::noop());
+    let states = loop {
+        if let Poll::Ready(states) = pending.as_mut().poll(&mut context) {
+            break states.unwrap();
+        }
+    };
+    drop(pending);
+    let expected = client.get_joint_states(r2d2, &[2, 3]).unwrap();
+    assert_eq!(states.len(), 2);
+    for (state, expected) in states.iter().zip(expected.iter()) {
+        float_compare(expected.joint_position, state.joint_position, 1e-10);
+        float_compare(expected.joint_velocity, state.joint_velocity, 1e-10);
+    }
+
+    let num_joints = client.get_num_joints(r2d2).unwrap();
+    let err = client
+        .get_joint_states_async(r2d2, &[num_joints])
+        .unwrap()
+        .wait()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidLinkIndex);
+    // a dropped command does not block the client and its answer is not mistaken for the answer
+    // to the next command
+    drop(client.step_simulation_async().unwrap());
+    let states = client.get_joint_states(r2d2, &[2, 3]).unwrap();
+    assert_eq!(states.len(), 2);
+    client.step_simulation().unwrap();
+
+    // the future wakes its task again instead of relying on the executor to spin
+    struct Unpark(std::thread::Thread);
+    impl std::task::Wake for Unpark {
+        fn wake(self: std::sync::Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+    let waker = Waker::from(std::sync::Arc::new(Unpark(std::thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut pending = Box::pin(client.get_joint_states_async(r2d2, &[2, 3]).unwrap());
+    let states = loop {
+        if let Poll::Ready(states) = pending.as_mut().poll(&mut context) {
+            break states.unwrap();
+        }
+        std::thread::park();
+    };
+    assert_eq!(states.len(), 2);
+}
+
+#[test]
+fn test_command_batch() {
+    use std::future::Future;
+    use std::task::{Context, Poll, Waker};
+
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    let r2d2 = client.load_urdf("r2d2.urdf", None).unwrap();
+    let num_joints = client.get_num_joints(r2d2).unwrap();
+    let mut batch = client.batch();
+    let motors = batch.set_joint_motor_control_array(
+        r2d2,
+        &[2, 3],
+        ControlCommandArray::Velocities(&[1., 1.]),
+        None,
+    );
+    let first_step = batch.step_simulation();
+    let before = batch.get_joint_states(r2d2, &[2, 3]);
+    // a failing command does not stop the batch
+    let invalid = batch.get_joint_states(r2d2, &[num_joints]);
+    let second_step = batch.step_simulation();
+    let after = batch.get_joint_states(r2d2, &[2, 3]);
+    let base = batch.get_base_transform(r2d2);
+    let mut results = batch.wait();
+    results.take(motors).unwrap();
+    results.take(first_step).unwrap();
+    results.take(second_step).unwrap();
+    assert_eq!(
+        results.take(invalid).unwrap_err().kind(),
+        ErrorKind::InvalidLinkIndex
+    );
+    let before = results.take(before).unwrap();
+    let after = results.take(after).unwrap();
+    let base = results.take(base).unwrap();
+    assert_eq!(
+        results.take(motors).unwrap_err().kind(),
+        ErrorKind::InvalidArgument
+    );
+
+    let expected = client.get_joint_states(r2d2, &[2, 3]).unwrap();
+    for ((before, after), expected) in before.iter().zip(after.iter()).zip(expected.iter()) {
+        assert!(after.joint_position > before.joint_position);
+        float_compare(expected.joint_position, after.joint_position, 1e-10);
+        float_compare(expected.joint_velocity, after.joint_velocity, 1e-10);
+    }
+    let expected = client.get_base_transform(r2d2).unwrap();
+    float_compare(
+        expected.translation.vector.norm(),
+        base.translation.vector.norm(),
+        1e-10,
+    );
+
+    // a batch can be awaited and a ticket of another batch is rejected
+    let mut batch = Box::pin(client.batch());
+    let step = batch.step_simulation();
+    let states = batch.get_joint_states(r2d2, &[2, 3]);
+    let mut context = Context::from_waker(Waker::noop());
+    let mut results = loop {
+        if let Poll::Ready(results) = batch.as_mut().poll(&mut context) {
+            break results;
+        }
+    };
+    drop(batch);
+    assert_eq!(results.take(states).unwrap().len(), 2);
+    let mut other = client.batch();
+    other.get_base_transform(r2d2);
+    assert_eq!(
+        other.wait().take(step).unwrap_err().kind(),
+        ErrorKind::InvalidArgument
+    );
+
+    // an unresolved batch does not block the client when it is dropped
+    let mut batch = client.batch();
+    batch.step_simulation();
+    batch.step_simulation();
+    drop(batch);
+    let states = client.get_joint_states(r2d2, &[2, 3]).unwrap();
+    assert_eq!(states.len(), 2);
+}
+
+#[test]
+fn test_shared_physics_client() {
+    fn assert_send<T: Send>() {}
+    assert_send::<SendablePhysicsClient>();
+    assert_send::<SharedPhysicsClient>();
+
+    // a Direct client can be moved to another thread
+    let client = PhysicsClient::connect(Direct).unwrap();
+    assert!(client.is_sendable());
+    let client = SendablePhysicsClient::new(client).unwrap();
+    let num_bodies = std::thread::spawn(move || client.into_inner().get_num_bodies().unwrap())
+        .join()
+        .unwrap();
+    assert_eq!(num_bodies, 0);
+
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    let plane = client.load_urdf("plane.urdf", None).unwrap();
+    let cube = client
+        .load_urdf(
+            "cube_small.urdf",
+            UrdfOptions {
+                base_transform: Isometry3::translation(0., 0., 1.),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    client.set_gravity([0., 0., -10.]).unwrap();
+    let shared = SharedPhysicsClient::new(client).unwrap();
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let shared = shared.clone();
+            std::thread::spawn(move || {
+                for _ in 0..100 {
+                    shared
+                        .execute(|client| client.step_simulation())
+                        .unwrap()
+                        .unwrap();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    let height = shared
+        .execute(move |client| client.get_base_transform(cube).unwrap().translation.z)
+        .unwrap();
+    assert!(height < 1.);
+    let num_bodies = shared
+        .execute(|client| client.get_num_bodies().unwrap())
+        .unwrap();
+    assert_eq!(num_bodies, 2);
+    assert!(shared
+        .execute(move |client| client.get_body_info(plane).is_ok())
+        .unwrap());
+
+    // a panicking command stops the worker thread
+    let _ = shared.execute(|_| panic!("stop the worker"));
+    let err = shared
+        .execute(|client| client.get_num_bodies().unwrap())
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Disconnected);
+}
+
+#[test]
+fn test_physics_client_pool() {
+    let pool = PhysicsClientPool::new(3).unwrap();
+    assert_eq!(pool.len(), 3);
+    let cubes = pool
+        .broadcast(|env, client| {
+            client
+                .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+                .unwrap();
+            client.set_gravity([0., 0., -10.]).unwrap();
+            client.load_urdf("plane.urdf", None).unwrap();
+            client
+                .load_urdf(
+                    "cube_small.urdf",
+                    UrdfOptions {
+                        base_transform: Isometry3::translation(0., 0., 1. + env as f64),
+                        ..Default::default()
+                    },
+                )
+                .unwrap()
+        })
+        .unwrap();
+    let r2d2s = pool
+        .broadcast(|_, client| client.load_urdf("r2d2.urdf", None).unwrap())
+        .unwrap();
+    let initial_states = pool.save_states().unwrap();
+    for _ in 0..10 {
+        pool.step_simulation().unwrap();
+    }
+
+    let link_states = pool.get_link_states(&r2d2s, &[0, 1], true).unwrap();
+    assert_eq!(link_states.positions.len(), 3 * 2 * 3);
+    assert_eq!(link_states.orientations.len(), 3 * 2 * 4);
+    assert_eq!(link_states.velocities.len(), 3 * 2 * 6);
+    let joint_states = pool.get_joint_states(&r2d2s, &[2, 3]).unwrap();
+    assert_eq!(joint_states.positions.len(), 6);
+    assert_eq!(joint_states.env(2).unwrap().velocities.len(), 2);
+    assert_eq!(
+        joint_states.env(3).unwrap_err().kind(),
+        ErrorKind::InvalidArgument
+    );
+
+    let cube_ids = cubes.clone();
+    let heights = pool
+        .broadcast(move |env, client| {
+            client
+                .get_base_transform(cube_ids[env])
+                .unwrap()
+                .translation
+                .z
+        })
+        .unwrap();
+    for (env, height) in heights.iter().enumerate() {
+        assert!(*height < 1. + env as f64);
+    }
+    pool.restore_state(1, initial_states[1]).unwrap();
+    let restored = pool
+        .env(1)
+        .unwrap()
+        .execute(move |client| client.get_base_transform(cubes[1]).unwrap().translation.z)
+        .unwrap();
+    float_compare(2., restored, 1e-10);
+    pool.restore_states(&initial_states).unwrap();
+
+    let images = pool
+        .get_camera_images(8, 6, &vec![Default::default(); 3])
+        .unwrap();
+    assert_eq!(images.rgba.len(), 3 * 8 * 6 * 4);
+    assert_eq!(images.depth.len(), 3 * 8 * 6);
+
+    let err = pool.get_joint_states(&r2d2s[..2], &[2]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    assert!(pool.env(3).is_err());
+}
+
+#[test]
+fn test_environments() {
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    let mut env = TimeLimit::new(FrameSkip::new(KukaCubeGrasp::new(client).unwrap(), 4), 5);
+    assert_eq!(env.action_space(), Space::bounded(3, -1., 1.));
+    let first = env.reset(Some(42)).unwrap();
+    assert_eq!(first.len(), env.observation_space().dim());
+    assert!(env.observation_space().contains(&first));
+    let other = env.reset(Some(7)).unwrap();
+    assert_ne!(first[17..20], other[17..20]);
+    let again = env.reset(Some(42)).unwrap();
+    slice_compare(&first, &again, 1e-10);
+
+    let mut observation = first;
+    for i in 1..=5 {
+        let step = env.step(&[0., 0., -1.][..]).unwrap();
+        assert_eq!(step.observation.len(), 20);
+        assert!(step.reward <= 0.);
+        assert!(!step.terminated);
+        assert_eq!(step.truncated, i == 5);
+        observation = step.observation;
+    }
+    let grasp = env.inner_mut().inner_mut();
+    let (kuka, cube) = (grasp.robot(), grasp.cube());
+    assert_eq!(grasp.client().get_num_joints(kuka).unwrap(), 7);
+    let cube_position = grasp.client().get_base_transform(cube).unwrap();
+    slice_compare(
+        &observation[17..20],
+        cube_position.translation.vector.as_slice(),
+        1e-10,
+    );
+    let err = env.step(&[0.; 2][..]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path(
+            "../misfire-sys/bullet3/libbullet3/examples/pybullet/gym/pybullet_data",
+        )
+        .unwrap();
+    let mut reach = PandaReach::new(client).unwrap();
+    assert_eq!(reach.action_space(), Space::bounded(7, -1., 1.));
+    let first = reach.reset(Some(3)).unwrap();
+    assert_eq!(first.len(), reach.observation_space().dim());
+    let target = reach.target();
+    slice_compare(&first[17..20], target.as_slice(), 1e-10);
+    assert!(target.x >= 0.3 && target.x < 0.6);
+    assert!(target.y >= -0.3 && target.y < 0.3);
+    assert!(target.z >= 0.2 && target.z < 0.6);
+    let again = reach.reset(Some(3)).unwrap();
+    slice_compare(&first, &again, 1e-10);
+    let mut observation = first.clone();
+    for _ in 0..20 {
+        let step = reach.step(&[1.; 7]).unwrap();
+        assert_eq!(step.observation.len(), 20);
+        let distance = (Vector3::from_column_slice(&step.observation[14..17])
+            - Vector3::from_column_slice(&step.observation[17..20]))
+        .norm();
+        float_compare(step.reward, -distance, 1e-10);
+        assert!(!step.truncated);
+        observation = step.observation;
+    }
+    // the arm follows the joint targets
+    assert!(observation[..7]
+        .iter()
+        .zip(&first[..7])
+        .all(|(moved, initial)| moved > initial));
+    let robot = reach.robot();
+    let hand = reach
+        .client()
+        .get_link_state(robot, PandaReach::END_EFFECTOR_INDEX, false, true)
+        .unwrap();
+    slice_compare(
+        &observation[14..17],
+        hand.world_link_frame_pose.translation.vector.as_slice(),
+        1e-10,
+    );
+    let err = reach.step(&[0.; 3]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    let mut minitaur = MinitaurLocomotion::new(client).unwrap();
+    let observation = minitaur.reset(Some(1)).unwrap();
+    assert_eq!(observation.len(), 28);
+    for _ in 0..10 {
+        let step = minitaur.step(&[0.; 8]).unwrap();
+        assert!(!step.terminated);
+    }
+}
+
+#[test]
+fn test_direct_tcp_server() {
+    let mut server = PhysicsServer::new(ServerMode::DirectTcp { port: Some(6671) }).unwrap();
+    assert!(server.is_connected());
+    let mut client = PhysicsClient::connect(Mode::Tcp {
+        hostname: "localhost",
+        port: Some(6671),
+    })
+    .unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    let r2d2 = client.load_urdf("r2d2.urdf", None).unwrap();
+    client.set_gravity([0., 0., -10.]).unwrap();
+    let start = client.get_base_transform(r2d2).unwrap();
+    for _ in 0..10 {
+        client.step_simulation().unwrap();
+    }
+    let end = client.get_base_transform(r2d2).unwrap();
+    assert!(end.translation.z < start.translation.z);
+    assert!(PhysicsServer::new(ServerMode::DirectTcp { port: Some(6671) }).is_err());
+}
+
+#[test]
+fn test_direct_udp_server() {
+    let mut server = PhysicsServer::new(ServerMode::DirectUdp { port: Some(6672) }).unwrap();
+    assert!(server.is_connected());
+    let mut client = PhysicsClient::connect(Mode::Udp {
+        hostname: "localhost",
+        port: Some(6672),
+    })
+    .unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    let r2d2 = client.load_urdf("r2d2.urdf", None).unwrap();
+    // the body info with the joints is sent in the data stream after the status
+    assert_eq!(client.get_num_joints(r2d2).unwrap(), 15);
+    assert_eq!(
+        client.get_joint_info(r2d2, 2).unwrap().joint_name,
+        "right_front_wheel_joint"
+    );
+    client.set_gravity([0., 0., -10.]).unwrap();
+    let start = client.get_base_transform(r2d2).unwrap();
+    // simulation steps only reply with the status type
+    for _ in 0..10 {
+        client.step_simulation().unwrap();
+    }
+    let end = client.get_base_transform(r2d2).unwrap();
+    assert!(end.translation.z < start.translation.z);
+}
+
+#[cfg(feature = "gui")]
+#[test]
+fn test_thread_bound_clients() {
+    // only Direct and Tcp clients may leave their thread
+    let client = PhysicsClient::connect(Mode::SharedMemoryServer { key: Some(12363) }).unwrap();
+    assert!(!client.is_sendable());
+    let rejected = SharedPhysicsClient::new(client).err().unwrap();
+    assert_eq!(rejected.error().kind(), ErrorKind::InvalidArgument);
+    // the rejected client is given back and still connected
+    let mut client = rejected.into_inner();
+    assert!(client.is_connected());
+    let rejected = SendablePhysicsClient::new(client).err().unwrap();
+    assert_eq!(rejected.error().kind(), ErrorKind::InvalidArgument);
+    let (mut client, _) = rejected.into_parts();
+    assert_eq!(client.get_num_bodies().unwrap(), 0);
+}
+
+#[cfg(feature = "gui")]
+#[test]
+fn test_shared_memory_keys() {
+    let mut servers = Vec::new();
+    let mut clients = Vec::new();
+    for key in [12361, 12362].iter() {
+        let key = Some(*key);
+        servers.push(PhysicsClient::connect(Mode::SharedMemoryServer { key }).unwrap());
+        clients.push(PhysicsClient::connect(Mode::SharedMemory { key }).unwrap());
+    }
+    match PhysicsClient::connect(Mode::SharedMemory { key: Some(12361) }) {
+        Err(err) => assert_eq!(err.kind(), ErrorKind::SharedMemoryUnavailable),
+        Ok(_) => panic!("a second client for the same key should be rejected"),
+    }
+
+    clients[0]
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    clients[0].load_urdf("r2d2.urdf", None).unwrap();
+    assert_eq!(clients[0].get_num_bodies().unwrap(), 1);
+    assert_eq!(clients[1].get_num_bodies().unwrap(), 0);
+
+    // the key can be used again once the client is gone
+    clients.remove(0);
+    PhysicsClient::connect(Mode::SharedMemory { key: Some(12361) }).unwrap();
+}
+
+#[test]
+fn test_owned_resources() {
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    client.load_urdf("plane.urdf", None).unwrap();
+    let state = client.save_state().unwrap();
+    {
+        let r2d2 = client.load_urdf("r2d2.urdf", None).unwrap();
+        let mut r2d2 = client.own(r2d2);
+        let cube = r2d2.load_urdf("cube_small.urdf", None).unwrap();
+        let mut cube = r2d2.own(cube);
+        assert_eq!(cube.get_num_bodies().unwrap(), 3);
+        cube.release().unwrap();
+        assert_eq!(r2d2.get_num_bodies().unwrap(), 2);
+    }
+    assert_eq!(client.get_num_bodies().unwrap(), 1);
+
+    let kept = {
+        let cube = client.load_urdf("cube_small.urdf", None).unwrap();
+        client.own(cube).keep()
+    };
+    assert_eq!(client.get_num_bodies().unwrap(), 2);
+    client.remove_body(kept).unwrap();
+
+    // the server may reuse the id of a removed body, but the old id stays invalid
+    let old = client.load_urdf("cube_small.urdf", None).unwrap();
+    client.remove_body(old).unwrap();
+    let new = client.load_urdf("cube_small.urdf", None).unwrap();
+    assert!(!old.is_same_generation(&new));
+    assert!(new.is_same_generation(&new));
+    let err = client.remove_body(old).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidBodyId);
+    assert_eq!(client.get_num_bodies().unwrap(), 2);
+    // every method which takes a body rejects the old id
+    let err = client.get_base_transform(old).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidBodyId);
+    let err = client
+        .change_dynamics(old, None, ChangeDynamicsOptions::default())
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidBodyId);
+    let err = client
+        .get_contact_points(old, None, None, None)
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidBodyId);
+    let pose = client.get_base_transform(new).unwrap();
+    let err = client
+        .reset_base_transform(old, Isometry3::translation(5., 0., 0.))
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidBodyId);
+    let err = client
+        .apply_external_force(
+            old,
+            None,
+            [1., 0., 0.],
+            [0., 0., 0.],
+            ExternalForceFrame::WorldFrame,
+        )
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidBodyId);
+    let unchanged = client.get_base_transform(new).unwrap();
+    slice_compare(
+        unchanged.translation.vector.as_slice(),
+        pose.translation.vector.as_slice(),
+        1e-10,
+    );
+    client.remove_body(new).unwrap();
+
+    // restoring a state brings back the bodies which existed when it was saved
+    let cube = client.load_urdf("cube_small.urdf", None).unwrap();
+    let with_cube = client.save_state().unwrap();
+    client.remove_body(cube).unwrap();
+    client.restore_state(with_cube).unwrap();
+    client.get_base_transform(cube).unwrap();
+    client.remove_body(cube).unwrap();
+    client.remove_state(with_cube).unwrap();
+
+    let owned_state = client.own(state);
+    assert_eq!(owned_state.id(), state);
+    drop(owned_state);
+    let err = client.restore_state(state).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+}
+
+#[test]
+fn test_robot() {
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path(
+            "../misfire-sys/bullet3/libbullet3/examples/pybullet/gym/pybullet_data",
+        )
+        .unwrap();
+    let panda = client
+        .load_urdf(
+            "franka_panda/panda.urdf",
+            UrdfOptions {
+                use_fixed_base: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let robot = Robot::new(&mut client, panda).unwrap();
+    assert_eq!(robot.body(), panda);
+    assert_eq!(robot.num_joints(), client.get_num_joints(panda).unwrap());
+    assert_eq!(robot.joint("panda_joint5").unwrap(), 4);
+    assert_eq!(robot.joint_info(4).unwrap().joint_name, "panda_joint5");
+    let hand = robot.link("panda_hand").unwrap();
+    assert_eq!(robot.joint_info(hand).unwrap().link_name, "panda_hand");
+    assert_eq!(
+        robot.joint("panda_joint42").unwrap_err().kind(),
+        ErrorKind::InvalidArgument
+    );
+    assert!(robot.link("panda_base").is_err());
+
+    assert_eq!(robot.num_movable_joints(), 9);
+    for (index, &joint) in robot.movable_joints().iter().enumerate() {
+        assert!(matches!(
+            robot.joint_info(joint).unwrap().joint_type,
+            JointType::Revolute | JointType::Prismatic
+        ));
+        assert_eq!(robot.movable_index(joint), Some(index));
+    }
+    assert_eq!(robot.movable_index(hand), None);
+    let lower = robot.lower_limits();
+    let upper = robot.upper_limits();
+    assert_eq!(lower.len(), 9);
+    assert!(lower.iter().zip(upper.iter()).all(|(l, u)| l < u));
+    assert!(robot.max_forces().iter().all(|&force| force > 0.));
+    assert_eq!(robot.max_velocities().len(), 9);
+
+    let positions = [0.98, 0.458, 0.31, -2.24, -0.30, 2.66, 2.32, 0.02, 0.02];
+    robot.set_joint_positions(&mut client, &positions).unwrap();
+    assert_eq!(
+        robot
+            .set_joint_positions(&mut client, &positions[..7])
+            .unwrap_err()
+            .kind(),
+        ErrorKind::InvalidArgument
+    );
+    let actual = robot.joint_positions(&mut client).unwrap();
+    assert!((DVector::from_vec(actual) - DVector::from_column_slice(&positions)).norm() < 1e-6);
+    assert!(robot
+        .joint_velocities(&mut client)
+        .unwrap()
+        .iter()
+        .all(|&velocity| velocity == 0.));
+
+    robot
+        .apply_command(
+            &mut client,
+            ControlCommandArray::Positions(&positions),
+            Some(&robot.max_forces()),
+        )
+        .unwrap();
+    for _ in 0..10 {
+        client.step_simulation().unwrap();
+    }
+    let actual = robot.joint_positions(&mut client).unwrap();
+    assert!((DVector::from_vec(actual) - DVector::from_column_slice(&positions)).norm() < 1e-2);
+}
+
+#[test]
+fn test_robot_without_single_dof_joints() {
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path(
+            "../misfire-sys/bullet3/libbullet3/examples/pybullet/gym/pybullet_data",
+        )
+        .unwrap();
+    // spherical joints are not movable joints of a robot
+    let humanoid = client.load_urdf("humanoid/humanoid.urdf", None).unwrap();
+    let robot = Robot::new(&mut client, humanoid).unwrap();
+    let spherical = (0..robot.num_joints())
+        .find(|&joint| robot.joint_info(joint).unwrap().joint_type == JointType::Spherical)
+        .unwrap();
+    assert_eq!(robot.movable_index(spherical), None);
+    for &joint in robot.movable_joints() {
+        assert!(matches!(
+            robot.joint_info(joint).unwrap().joint_type,
+            JointType::Revolute | JointType::Prismatic
+        ));
+    }
+    let positions = robot.joint_positions(&mut client).unwrap();
+    assert_eq!(positions.len(), robot.num_movable_joints());
+
+    // a body without any movable joints
+    let plane = client.load_urdf("plane.urdf", None).unwrap();
+    let robot = Robot::new(&mut client, plane).unwrap();
+    assert_eq!(robot.num_movable_joints(), 0);
+    assert!(robot.joint_positions(&mut client).unwrap().is_empty());
+    assert!(robot.joint_velocities(&mut client).unwrap().is_empty());
+}
+
+#[test]
+fn test_log_reader() {
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    client.set_gravity([0., 0., -10.]).unwrap();
+    let plane = client.load_urdf("plane.urdf", None).unwrap();
+    let r2d2 = client
+        .load_urdf(
+            "r2d2.urdf",
+            UrdfOptions {
+                base_transform: Isometry3::translation(0., 0., 0.5),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let num_joints = client.get_num_joints(r2d2).unwrap();
+    let robot_file = std::env::temp_dir().join("misfire_test_generic_robot.log");
+    let contact_file = std::env::temp_dir().join("misfire_test_contact_points.log");
+    let robot_log = client
+        .start_state_logging(
+            LoggingType::GenericRobot,
+            &robot_file,
+            StateLoggingOptions {
+                object_ids: vec![r2d2],
+                max_log_dof: Some(num_joints),
+                log_flags: Some(LogFlags::JOINT_MOTOR_TORQUES),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let contact_log = client
+        .start_state_logging(LoggingType::ContactPoints, &contact_file, None)
+        .unwrap();
+    for _ in 0..100 {
+        client.step_simulation().unwrap();
+    }
+    client.stop_state_logging(robot_log).unwrap();
+    client.stop_state_logging(contact_log).unwrap();
+
+    let reader = LogReader::open(&robot_file).unwrap();
+    assert_eq!(reader.schema().keys()[0], "stepCount");
+    assert_eq!(reader.schema().keys().len(), reader.schema().format().len());
+    let logs = reader
+        .entries::<GenericRobotLog>()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert!(logs.len() >= 100);
+    for (chunk, log) in logs.iter().enumerate() {
+        assert_eq!(log.chunk_number, chunk);
+        assert_eq!(log.body, r2d2);
+        assert_eq!(log.num_joints, num_joints);
+        assert_eq!(log.joint_positions.len(), num_joints);
+        assert_eq!(log.joint_velocities.len(), num_joints);
+        assert_eq!(log.joint_torques.as_ref().unwrap().len(), num_joints);
+    }
+    assert!(logs.last().unwrap().time_stamp > logs[0].time_stamp);
+    assert_eq!(
+        read_generic_robot_log(&robot_file).unwrap().len(),
+        logs.len()
+    );
+
+    let contacts = LogReader::open(&contact_file)
+        .unwrap()
+        .entries::<ContactPointLog>()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert!(!contacts.is_empty());
+    assert!(contacts
+        .iter()
+        .all(|contact| contact.body_a == plane || contact.body_b == plane));
+
+    // reading a log with the wrong entry type fails instead of panicking
+    let mut wrong_type = LogReader::open(&robot_file)
+        .unwrap()
+        .entries::<ContactPointLog>();
+    assert_eq!(
+        wrong_type.next().unwrap().unwrap_err().kind(),
+        ErrorKind::ParseError
+    );
+    assert!(wrong_type.next().is_none());
+
+    // a truncated log yields all complete records and then an error
+    let bytes = std::fs::read(&robot_file).unwrap();
+    let truncated = &bytes[..bytes.len() - 3];
+    let records = LogReader::new(truncated).unwrap().collect::<Vec<_>>();
+    assert_eq!(records.len(), logs.len());
+    assert_eq!(
+        records.last().unwrap().as_ref().unwrap_err().kind(),
+        ErrorKind::ParseError
+    );
+    let header_len = bytes.iter().position(|&b| b == b'\n').unwrap() + 1;
+    let header_len = header_len
+        + bytes[header_len..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .unwrap()
+        + 1;
+    let mut corrupted = bytes.clone();
+    corrupted[header_len] = 0;
+    let mut reader = LogReader::new(corrupted.as_slice()).unwrap();
+    assert_eq!(
+        reader.next().unwrap().unwrap_err().kind(),
+        ErrorKind::ParseError
+    );
+    assert!(reader.next().is_none());
+    assert!(LogReader::new(&bytes[..header_len - 1]).is_err());
+    assert_eq!(
+        LogReader::open("does_not_exist.log").err().unwrap().kind(),
+        ErrorKind::FileNotFound
+    );
+
+    std::fs::remove_file(robot_file).unwrap();
+    std::fs::remove_file(contact_file).unwrap();
+}
+
+#[test]
+fn test_generic_robot_log_writer() {
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    client.set_gravity([0., 0., -10.]).unwrap();
+    client.load_urdf("plane.urdf", None).unwrap();
+    let r2d2 = client.load_urdf("r2d2.urdf", None).unwrap();
+    let num_joints = client.get_num_joints(r2d2).unwrap();
+    let file = std::env::temp_dir().join("misfire_test_log_writer.log");
+    let log = client
+        .start_state_logging(
+            LoggingType::GenericRobot,
+            &file,
+            StateLoggingOptions {
+                object_ids: vec![r2d2],
+                max_log_dof: Some(num_joints),
+                log_flags: Some(LogFlags::JOINT_MOTOR_TORQUES),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    for _ in 0..50 {
+        client.step_simulation().unwrap();
+    }
+    client.stop_state_logging(log).unwrap();
+    let recorded = std::fs::read(&file).unwrap();
+    let logs = read_generic_robot_log(&file).unwrap();
+    std::fs::remove_file(file).unwrap();
+
+    let mut writer = GenericRobotLogWriter::new(Vec::new(), num_joints, true).unwrap();
+    for log in logs.iter() {
+        writer.write(log).unwrap();
+    }
+    let written = writer.into_inner().unwrap();
+    // the header and the size of the records are the same as the ones of Bullet
+    assert_eq!(written.len(), recorded.len());
+    let header_len = recorded.iter().position(|&b| b == b'\n').unwrap() + 1;
+    let header_len = header_len
+        + recorded[header_len..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .unwrap()
+        + 1;
+    assert_eq!(written[..header_len], recorded[..header_len]);
+
+    let reread = LogReader::new(written.as_slice())
+        .unwrap()
+        .entries::<GenericRobotLog>()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(reread.len(), logs.len());
+    for (a, b) in logs.iter().zip(reread.iter()) {
+        assert_eq!(a.chunk_number, b.chunk_number);
+        assert_eq!(a.step_count, b.step_count);
+        assert_eq!(a.body, b.body);
+        assert_eq!(a.num_joints, b.num_joints);
+        float_compare(a.time_stamp.as_secs_f64(), b.time_stamp.as_secs_f64(), 1e-6);
+        assert!((a.base_pose.translation.vector - b.base_pose.translation.vector).norm() < 1e-6);
+        assert!(a.base_pose.rotation.angle_to(&b.base_pose.rotation) < 1e-5);
+        assert!((a.base_velocity.to_vector() - b.base_velocity.to_vector()).norm() < 1e-6);
+        assert!((&a.joint_positions - &b.joint_positions).norm() < 1e-6);
+        assert!((&a.joint_velocities - &b.joint_velocities).norm() < 1e-6);
+        let torques_a = a.joint_torques.as_ref().unwrap();
+        let torques_b = b.joint_torques.as_ref().unwrap();
+        assert!((torques_a - torques_b).norm() < 1e-6);
+    }
+
+    // robots with too many joints can not be logged
+    let mut writer = GenericRobotLogWriter::new(Vec::new(), num_joints - 1, false).unwrap();
+    assert_eq!(
+        writer.write(&logs[0]).unwrap_err().kind(),
+        ErrorKind::InvalidArgument
+    );
+    let mut writer = LogWriter::new(
+        Vec::new(),
+        LogSchema::new(vec!["a", "b", "c"], "IfB").unwrap(),
+    )
+    .unwrap();
+    writer
+        .write_record(&[LogValue::UInt(1), LogValue::Float(2.), LogValue::Char(200)])
+        .unwrap();
+    assert_eq!(
+        writer
+            .write_record(&[LogValue::Float(1.), LogValue::Float(2.), LogValue::Char(0)])
+            .unwrap_err()
+            .kind(),
+        ErrorKind::InvalidArgument
+    );
+    let written = writer.into_inner().unwrap();
+    let records = LogReader::new(written.as_slice())
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].get("a"), Some(LogValue::UInt(1)));
+    assert_eq!(records[0].get("b"), Some(LogValue::Float(2.)));
+    // 'B' is an unsigned byte
+    assert_eq!(records[0].get("c"), Some(LogValue::Char(200)));
+    float_compare(records[0].get("c").unwrap().as_f64(), 200., 1e-10);
+}
+
+#[test]
+fn test_log_player() {
+    let mut recorder = PhysicsClient::connect(Direct).unwrap();
+    recorder
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    recorder.set_gravity([0., 0., -10.]).unwrap();
+    recorder.load_urdf("plane.urdf", None).unwrap();
+    let r2d2 = recorder
+        .load_urdf(
+            "r2d2.urdf",
+            UrdfOptions {
+                base_transform: Isometry3::translation(0., 0., 1.),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let num_joints = recorder.get_num_joints(r2d2).unwrap();
+    // r2d2 has fixed joints, which the log skips
+    let movable_joints: Vec<usize> = (0..num_joints)
+        .filter(|&joint| {
+            let info = recorder.get_joint_info(r2d2, joint).unwrap();
+            matches!(info.joint_type, JointType::Revolute | JointType::Prismatic)
+        })
+        .collect();
+    assert!(movable_joints.len() < num_joints);
+    // every movable joint moves differently, so that mixing them up is detected
+    let velocities: Vec<f64> = (0..movable_joints.len())
+        .map(|k| 0.1 * (k + 1) as f64)
+        .collect();
+    recorder
+        .set_joint_motor_control_array(
+            r2d2,
+            &movable_joints,
+            ControlCommandArray::Velocities(&velocities),
+            None,
+        )
+        .unwrap();
+    let file = std::env::temp_dir().join("misfire_test_log_player.log");
+    let log = recorder
+        .start_state_logging(
+            LoggingType::GenericRobot,
+            &file,
+            StateLoggingOptions {
+                object_ids: vec![r2d2],
+                max_log_dof: Some(num_joints),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    for _ in 0..100 {
+        recorder.step_simulation().unwrap();
+    }
+    recorder.stop_state_logging(log).unwrap();
+    let logs = read_generic_robot_log(&file).unwrap();
+    let mut player = LogPlayer::open(&file).unwrap();
+    std::fs::remove_file(file).unwrap();
+
+    // the robot gets another id in the client which replays the log
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    let replayed = client.load_urdf("r2d2.urdf", None).unwrap();
+    assert_ne!(replayed, r2d2);
+    player.map_body(r2d2, replayed);
+    assert_eq!(player.logs().len(), logs.len());
+    let duration = player.duration();
+    assert_eq!(
+        duration,
+        logs.last().unwrap().time_stamp - logs[0].time_stamp
+    );
+
+    assert_eq!(logs[0].num_joints, movable_joints.len());
+    let assert_pose = |client: &mut PhysicsClient, expected: &GenericRobotLog| {
+        let pose = client.get_base_transform(replayed).unwrap();
+        assert!((pose.translation.vector - expected.base_pose.translation.vector).norm() < 1e-5);
+        for (k, &joint) in movable_joints.iter().enumerate() {
+            let state = client.get_joint_state(replayed, joint).unwrap();
+            float_compare(state.joint_position, expected.joint_positions[k], 1e-5);
+            float_compare(state.joint_velocity, expected.joint_velocities[k], 1e-5);
+        }
+    };
+    player.seek(&mut client, duration / 2).unwrap();
+    assert_eq!(player.time(), duration / 2);
+    let expected = logs
+        .iter()
+        .rfind(|log| log.time_stamp <= logs[0].time_stamp + duration / 2)
+        .unwrap();
+    assert_pose(&mut client, expected);
+
+    assert_eq!(
+        player.set_speed(0.).err().unwrap().kind(),
+        ErrorKind::InvalidArgument
+    );
+    assert!(player.set_speed(f64::NAN).is_err());
+    player.set_speed(2.).unwrap();
+    let mut steps = 0;
+    while player
+        .advance(&mut client, Duration::from_secs_f64(1. / 240.))
+        .unwrap()
+    {
+        steps += 1;
+    }
+    assert!(steps < logs.len() / 2);
+    assert!(player.is_finished());
+    assert_eq!(player.time(), duration);
+    assert_pose(&mut client, logs.last().unwrap());
+
+    player.set_looping(true);
+    player.seek(&mut client, Duration::default()).unwrap();
+    assert_pose(&mut client, &logs[0]);
+    assert!(player.advance(&mut client, duration * 3 / 2).unwrap());
+    assert!(!player.is_finished());
+    assert!(player.time() < duration);
+
+    // playback of a removed body fails instead of silently leaving the base alone
+    client.remove_body(replayed).unwrap();
+    let err = player.seek(&mut client, Duration::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidBodyId);
+}
+
+fn scene_world() -> (PhysicsClient, BodyId) {
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    client.set_gravity([0., 0., -9.]).unwrap();
+    client.load_urdf("plane.urdf", None).unwrap();
+    let r2d2 = client
+        .load_urdf(
+            "r2d2.urdf",
+            UrdfOptions {
+                base_transform: Isometry3::translation(0., 0., 1.),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let cubes = client.load_sdf("two_cubes.sdf", None).unwrap();
+    client.remove_body(cubes[0]).unwrap();
+    client
+        .change_dynamics(
+            cubes[1],
+            None,
+            ChangeDynamicsOptions {
+                mass: Some(3.),
+                lateral_friction: Some(0.7),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    client
+        .create_constraint(
+            r2d2,
+            None,
+            cubes[1],
+            None,
+            JointType::Point2Point,
+            [0., 0., 0.],
+            Isometry3::translation(0., 0., 0.5),
+            Isometry3::identity(),
+        )
+        .unwrap();
+    for _ in 0..100 {
+        client.step_simulation().unwrap();
+    }
+    (client, r2d2)
+}
+
+#[test]
+fn test_scene() {
+    let (mut client, r2d2) = scene_world();
+    let scene = client.save_scene().unwrap();
+    assert_eq!(scene.assets.len(), 3);
+    assert_eq!(scene.assets[2].bodies.len(), 1);
+    assert_eq!(scene.constraints.len(), 1);
+    float_compare(scene.gravity[2], -9., 1e-9);
+
+    let mut loaded = PhysicsClient::connect(Direct).unwrap();
+    loaded
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    let bodies = loaded.load_scene(&scene).unwrap();
+    assert_eq!(bodies.len(), 3);
+    assert_eq!(
+        loaded.get_num_bodies().unwrap(),
+        client.get_num_bodies().unwrap()
+    );
+    assert_eq!(loaded.get_num_constraints().unwrap(), 1);
+    let name = &scene.assets[1].bodies[0].name;
+    let loaded_r2d2 = bodies[name];
+    let expected = client.get_base_transform(r2d2).unwrap();
+    let actual = loaded.get_base_transform(loaded_r2d2).unwrap();
+    assert!((expected.translation.vector - actual.translation.vector).norm() < 1e-6);
+    assert!(expected.rotation.angle_to(&actual.rotation) < 1e-6);
+    for joint in 0..client.get_num_joints(r2d2).unwrap() {
+        let expected = client.get_joint_state(r2d2, joint).unwrap();
+        let actual = loaded.get_joint_state(loaded_r2d2, joint).unwrap();
+        float_compare(expected.joint_position, actual.joint_position, 1e-6);
+    }
+    let cube = bodies[&scene.assets[2].bodies[0].name];
+    let dynamics = loaded.get_dynamics_info(cube, None).unwrap();
+    float_compare(dynamics.mass, 3., 1e-9);
+    float_compare(dynamics.lateral_friction, 0.7, 1e-9);
+    let parameters = loaded.get_physics_engine_parameters().unwrap();
+    float_compare(parameters.gravity[2], -9., 1e-9);
+    let names = |scene: &Scene| {
+        scene
+            .assets
+            .iter()
+            .flat_map(|asset| asset.bodies.iter().map(|body| body.name.clone()))
+            .collect::<Vec<String>>()
+    };
+    assert_eq!(names(&loaded.save_scene().unwrap()), names(&scene));
+
+    // a body which a scene can not describe is reported instead of being left out
+    let shape = client
+        .create_collision_shape(GeometricCollisionShape::Sphere { radius: 0.1 }, None)
+        .unwrap();
+    let sphere = client
+        .create_multi_body(shape, VisualId::NONE, MultiBodyOptions::default())
+        .unwrap();
+    let err = client.save_scene().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    assert!(err.to_string().contains("can not be saved"));
+    client.remove_body(sphere).unwrap();
+    client.save_scene().unwrap();
+}
+
+#[test]
+fn test_scene_unique_body_names() {
+    let urdf = |name: &str| {
+        format!(
+            r#"<robot name="{}"><link name="base"><inertial><mass value="1"/><inertia ixx="0.1" ixy="0" ixz="0" iyy="0.1" iyz="0" izz="0.1"/></inertial><collision><geometry><box size="0.2 0.2 0.2"/></geometry></collision></link></robot>"#,
+            name
+        )
+    };
+    let cube_file = std::env::temp_dir().join("misfire_test_scene_cube.urdf");
+    let numbered_file = std::env::temp_dir().join("misfire_test_scene_cube_2.urdf");
+    std::fs::write(&cube_file, urdf("cube")).unwrap();
+    std::fs::write(&numbered_file, urdf("cube_2")).unwrap();
+
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    for (file, x) in [(&cube_file, 0.), (&cube_file, 1.), (&numbered_file, 2.)] {
+        client
+            .load_urdf(
+                file,
+                UrdfOptions {
+                    base_transform: Isometry3::translation(x, 0., 0.),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+    }
+    let scene = client.save_scene().unwrap();
+    let mut names: Vec<String> = scene
+        .assets
+        .iter()
+        .flat_map(|asset| asset.bodies.iter().map(|body| body.name.clone()))
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["cube", "cube_2", "cube_3"]);
+
+    let mut loaded = PhysicsClient::connect(Direct).unwrap();
+    assert_eq!(loaded.load_scene(&scene).unwrap().len(), 3);
+    std::fs::remove_file(cube_file).unwrap();
+    std::fs::remove_file(numbered_file).unwrap();
+}
+#[cfg(feature = "serde")]
+#[test]
+fn test_scene_serde() {
+    let (mut client, _) = scene_world();
+    let scene = client.save_scene().unwrap();
+    let json = serde_json::to_string_pretty(&scene).unwrap();
+    let parsed: Scene = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, scene);
+}
+
+fn primitive(name: &str, shape: PrimitiveShape, mass: f64, z: f64) -> ScenePrimitive {
+    ScenePrimitive {
+        name: name.into(),
+        shape,
+        mass,
+        pose: Isometry3::translation(0., 0., z).into(),
+        velocity: [0.; 6],
+        material: None,
+        color: None,
+    }
+}
+
+#[test]
+fn test_declarative_scene() {
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    let mut ground = primitive(
+        "ground",
+        PrimitiveShape::Plane {
+            normal: [0., 0., 1.],
+        },
+        0.,
+        0.,
+    );
+    ground.material = Some("ice".into());
+    let mut crate_box = primitive(
+        "crate",
+        PrimitiveShape::Box {
+            half_extents: [0.2; 3],
+        },
+        2.,
+        0.2,
+    );
+    crate_box.color = Some([0., 0., 1., 1.]);
+    crate_box.material = Some("ice".into());
+    let scene = Scene {
+        gravity: [0., 0., -10.],
+        physics: ScenePhysics {
+            num_solver_iterations: Some(77),
+            ..Default::default()
+        },
+        materials: vec![SceneMaterial {
+            name: "ice".into(),
+            color: Some([0.8, 0.9, 1., 1.]),
+            lateral_friction: Some(0.01),
+            ..Default::default()
+        }],
+        assets: vec![SceneAsset {
+            source: AssetSource::Urdf {
+                file: "r2d2.urdf".into(),
+                use_fixed_base: false,
+                use_maximal_coordinates: None,
+                flags: LoadModelFlags::empty(),
+                global_scaling: -1.,
+            },
+            bodies: vec![SceneBody {
+                name: "r2d2".into(),
+                pose: Isometry3::translation(2., 0., 0.5).into(),
+                material: Some("ice".into()),
+                ..Default::default()
+            }],
+        }],
+        primitives: vec![
+            ground,
+            crate_box,
+            primitive("ball", PrimitiveShape::Sphere { radius: 0.1 }, 1., 1.),
+        ],
+        constraints: vec![SceneConstraint {
+            parent: "crate".into(),
+            child: Some("ball".into()),
+            joint_type: JointType::Point2Point,
+            parent_frame: Isometry3::translation(0., 0., 0.5).into(),
+            ..Default::default()
+        }],
+        cameras: vec![SceneCamera {
+            name: "overview".into(),
+            eye: [3., 3., 2.],
+            width: 64,
+            height: 48,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let bodies = client.load_scene(&scene).unwrap();
+    assert_eq!(bodies.len(), 4);
+    assert_eq!(client.get_num_bodies().unwrap(), 4);
+    assert_eq!(client.get_num_constraints().unwrap(), 1);
+    let parameters = client.get_physics_engine_parameters().unwrap();
+    assert_eq!(parameters.num_solver_iterations, 77);
+    float_compare(parameters.gravity[2], -10., 1e-9);
+
+    let crate_dynamics = client.get_dynamics_info(bodies["crate"], None).unwrap();
+    float_compare(crate_dynamics.mass, 2., 1e-9);
+    float_compare(crate_dynamics.lateral_friction, 0.01, 1e-6);
+    let crate_color = client
+        .get_visual_shape_data(bodies["crate"], false)
+        .unwrap()[0]
+        .rgba_color;
+    assert_eq!(crate_color, [0., 0., 1., 1.]);
+    let r2d2 = bodies["r2d2"];
+    let r2d2_pose = client.get_base_transform(r2d2).unwrap();
+    assert!((r2d2_pose.translation.vector - Vector3::new(2., 0., 0.5)).norm() < 1e-6);
+    for link in 0..client.get_num_joints(r2d2).unwrap() {
+        let dynamics = client.get_dynamics_info(r2d2, link).unwrap();
+        float_compare(dynamics.lateral_friction, 0.01, 1e-6);
+    }
+
+    let camera = scene.camera("overview").unwrap();
+    let images = camera.get_image(&mut client).unwrap();
+    assert_eq!((images.width, images.height), (64, 48));
+    assert!(scene.camera("missing").is_none());
+
+    // primitives are saved together with their materials
+    let saved = client.save_scene().unwrap();
+    let mut primitive_names: Vec<&str> = saved
+        .primitives
+        .iter()
+        .map(|primitive| primitive.name.as_str())
+        .collect();
+    primitive_names.sort_unstable();
+    assert_eq!(primitive_names, vec!["ball", "crate", "ground"]);
+    assert_eq!(saved.materials, scene.materials);
+    let mut reloaded = PhysicsClient::connect(Direct).unwrap();
+    reloaded
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    let reloaded_bodies = reloaded.load_scene(&saved).unwrap();
+    assert_eq!(reloaded_bodies.len(), 4);
+    assert_eq!(reloaded.get_num_constraints().unwrap(), 1);
+    let expected = client.get_base_transform(bodies["crate"]).unwrap();
+    let actual = reloaded
+        .get_base_transform(reloaded_bodies["crate"])
+        .unwrap();
+    assert!((expected.translation.vector - actual.translation.vector).norm() < 1e-6);
+    let dynamics = reloaded
+        .get_dynamics_info(reloaded_bodies["crate"], None)
+        .unwrap();
+    float_compare(dynamics.mass, 2., 1e-9);
+    float_compare(dynamics.lateral_friction, 0.01, 1e-6);
+
+    let mut unknown_material = scene.clone();
+    unknown_material.primitives[2].material = Some("lava".into());
+    client.reset_simulation().unwrap();
+    let error = client.load_scene(&unknown_material).err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::InvalidArgument);
+    let mut duplicate = scene;
+    duplicate.primitives[2].name = "crate".into();
+    client.reset_simulation().unwrap();
+    let error = client.load_scene(&duplicate).err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::InvalidArgument);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_declarative_scene_serde() {
+    let json = r#"{
+        "gravity": [0.0, 0.0, -10.0],
+        "materials": [{ "name": "ice", "lateral_friction": 0.01 }],
+        "primitives": [
+            { "name": "ground", "shape": { "Plane": { "normal": [0.0, 0.0, 1.0] } }, "material": "ice" },
+            {
+                "name": "crate",
+                "shape": { "Box": { "half_extents": [0.2, 0.2, 0.2] } },
+                "mass": 1.0,
+                "pose": { "position": [1.0, 0.0, 0.2] }
+            }
+        ],
+        "constraints": [{ "parent": "crate", "joint_type": "Fixed" }]
+    }"#;
+    let scene: Scene = serde_json::from_str(json).unwrap();
+    assert_eq!(scene.version, misfire::scene::SCENE_FORMAT_VERSION);
+    assert_eq!(scene.primitives[1].pose.orientation, [0., 0., 0., 1.]);
+    assert_eq!(scene.constraints[0].max_force, 500.);
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    let bodies = client.load_scene(&scene).unwrap();
+    let pose = client.get_base_transform(bodies["crate"]).unwrap();
+    assert!((pose.translation.vector - Vector3::new(1., 0., 0.2)).norm() < 1e-6);
+    let dynamics = client.get_dynamics_info(bodies["ground"], None).unwrap();
+    float_compare(dynamics.lateral_friction, 0.01, 1e-6);
+}
+
+#[cfg(feature = "serde")]
+fn assert_serde_round_trip<T: serde::Serialize + serde::de::DeserializeOwned>(value: &T) -> T {
+    let json = serde_json::to_value(value).unwrap();
+    let parsed: T = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&parsed).unwrap(), json);
+    parsed
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_data_types() {
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    client.set_gravity([0., 0., -10.]).unwrap();
+    let plane = client.load_urdf("plane.urdf", None).unwrap();
+    let r2d2 = client
+        .load_urdf(
+            "r2d2.urdf",
+            UrdfOptions {
+                flags: LoadModelFlags::URDF_USE_SELF_COLLISION
+                    | LoadModelFlags::URDF_USE_INERTIA_FROM_FILE,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    for _ in 0..100 {
+        client.step_simulation().unwrap();
+    }
+
+    // ids are plain numbers and keep their identity
+    assert_eq!(serde_json::to_string(&r2d2).unwrap(), "1");
+    assert_eq!(assert_serde_round_trip(&r2d2), r2d2);
+    assert_eq!(
+        serde_json::to_string(&LoadModelFlags::URDF_USE_SELF_COLLISION).unwrap(),
+        "\"URDF_USE_SELF_COLLISION\""
+    );
+
+    let joint_info = client.get_joint_info(r2d2, 2).unwrap();
+    let parsed = assert_serde_round_trip(&joint_info);
+    assert_eq!(parsed.joint_name, joint_info.joint_name);
+    assert_eq!(parsed.joint_type, joint_info.joint_type);
+    assert_serde_round_trip(&client.get_joint_state(r2d2, 2).unwrap());
+    assert_serde_round_trip(&client.get_link_state(r2d2, 2, true, true).unwrap());
+    assert_serde_round_trip(&client.get_dynamics_info(r2d2, None).unwrap());
+    assert_serde_round_trip(&client.get_physics_engine_parameters().unwrap());
+    assert_serde_round_trip(&client.get_visual_shape_data(r2d2, false).unwrap());
+    assert_serde_round_trip(&client.get_base_velocity(r2d2).unwrap());
+    let contacts = client.get_contact_points(plane, r2d2, None, None).unwrap();
+    assert!(!contacts.is_empty());
+    assert_serde_round_trip(&contacts);
+    let hit = client.ray_test([0., 0., 2.], [0., 0., -2.], None).unwrap();
+    assert_serde_round_trip(&hit);
+    let constraint = client
+        .create_constraint(
+            r2d2,
+            None,
+            None,
+            None,
+            JointType::Fixed,
+            [0., 0., 0.],
+            Isometry3::identity(),
+            Isometry3::translation(0., 0., 1.),
+        )
+        .unwrap();
+    assert_serde_round_trip(&client.get_constraint_info(constraint).unwrap());
+    assert_serde_round_trip(&constraint);
+
+    let log = GenericRobotLog {
+        joint_positions: DVector::from_vec(vec![0.1, 0.2]),
+        joint_velocities: DVector::from_vec(vec![0.3, 0.4]),
+        joint_torques: Some(DVector::from_vec(vec![0.5, 0.6])),
+        num_joints: 2,
+        ..Default::default()
+    };
+    let parsed = assert_serde_round_trip(&log);
+    assert_eq!(parsed.joint_torques, log.joint_torques);
+    assert_serde_round_trip(&UrdfOptions {
+        base_transform: Isometry3::translation(1., 2., 3.),
+        ..Default::default()
+    });
+    assert_serde_round_trip(&ChangeDynamicsOptions {
+        mass: Some(2.),
+        ..Default::default()
+    });
+    assert_serde_round_trip(&SetPhysicsEngineParameterOptions {
+        num_solver_iterations: Some(50),
+        constraint_solver_type: Some(ConstraintSolverType::Dantzig),
+        ..Default::default()
+    });
+}
+#[test]
+fn test_ray_test_batch_threads() {
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    let plane = client.load_urdf("plane.urdf", None).unwrap();
+    let cube = client
+        .load_urdf(
+            "cube.urdf",
+            UrdfOptions {
+                base_transform: Isometry3::translation(0., 0., 0.5),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let ray_from: Vec<Vector3<f64>> = (0..100)
+        .map(|i| Vector3::new(-2. + 0.04 * i as f64, 0.1, 3.))
+        .collect();
+    let ray_to: Vec<Vector3<f64>> = ray_from
+        .iter()
+        .map(|from| from - Vector3::new(0., 0., 4.))
+        .collect();
+    let single_threaded = client.ray_test_batch(&ray_from, &ray_to, None).unwrap();
+    // num_threads used to be read from report_hit_number
+    for &num_threads in &[0, 1, 4] {
+        let hits = client
+            .ray_test_batch(
+                &ray_from,
+                &ray_to,
+                RayTestBatchOptions {
+                    num_threads: Some(num_threads),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(hits.len(), single_threaded.len());
+        for (hit, expected) in hits.iter().zip(single_threaded.iter()) {
+            let (hit, expected) = (hit.as_ref().unwrap(), expected.as_ref().unwrap());
+            assert_eq!(hit.body_id, expected.body_id);
+            float_compare(hit.hit_fraction, expected.hit_fraction, 1e-10);
+        }
+    }
+    for (from, hit) in ray_from.iter().zip(single_threaded.iter()) {
+        let hit = hit.as_ref().unwrap();
+        if from.x.abs() < 0.49 {
+            assert_eq!(hit.body_id, cube);
+            float_compare(hit.hit_position.z, 1., 1e-5);
+        } else if from.x.abs() > 0.51 {
+            assert_eq!(hit.body_id, plane);
+            float_compare(hit.hit_position.z, 0., 1e-5);
+        }
+    }
+}
+#[test]
+fn test_ray_test_batch_report_hit_number() {
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    let plane = client.load_urdf("plane.urdf", None).unwrap();
+    let cube = client
+        .load_urdf(
+            "cube.urdf",
+            UrdfOptions {
+                base_transform: Isometry3::translation(0., 0., 0.5),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    // the first half of the rays passes through the cube before it hits the plane
+    let ray_from: Vec<Vector3<f64>> = (0..10)
+        .map(|i| Vector3::new(-0.45 + 0.1 * i as f64, 0.1, 3.))
+        .chain((0..10).map(|i| Vector3::new(1. + 0.1 * i as f64, 0.1, 3.)))
+        .collect();
+    let ray_to: Vec<Vector3<f64>> = ray_from
+        .iter()
+        .map(|from| from - Vector3::new(0., 0., 4.))
+        .collect();
+    let single_hits = client.ray_test_batch(&ray_from, &ray_to, None).unwrap();
+    let nth_hits = |client: &mut PhysicsClient, report_hit_number| {
+        client
+            .ray_test_batch(
+                &ray_from,
+                &ray_to,
+                RayTestBatchOptions {
+                    report_hit_number: Some(report_hit_number),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+    };
+    let first_hits = nth_hits(&mut client, 0);
+    let second_hits = nth_hits(&mut client, 1);
+    let third_hits = nth_hits(&mut client, 2);
+    assert_eq!(first_hits.len(), ray_from.len());
+    assert_eq!(second_hits.len(), ray_from.len());
+    assert_eq!(third_hits.len(), ray_from.len());
+    for (i, (single_hit, first_hit)) in single_hits.iter().zip(first_hits.iter()).enumerate() {
+        let (single_hit, first_hit) = (single_hit.as_ref().unwrap(), first_hit.as_ref().unwrap());
+        assert_eq!(single_hit.body_id, first_hit.body_id);
+        float_compare(single_hit.hit_fraction, first_hit.hit_fraction, 1e-10);
+        if i < 10 {
+            assert_eq!(single_hit.body_id, cube);
+            let second_hit = second_hits[i].as_ref().unwrap();
+            assert_eq!(second_hit.body_id, plane);
+            assert!(second_hit.hit_fraction > single_hit.hit_fraction);
+            float_compare(second_hit.hit_position.z, 0., 1e-5);
+        } else {
+            assert_eq!(single_hit.body_id, plane);
+            assert!(second_hits[i].is_none());
+        }
+        assert!(third_hits[i].is_none());
+    }
+}
+#[test]
+fn test_lidar() {
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    let plane = client.load_urdf("plane.urdf", None).unwrap();
+    client
+        .load_urdf(
+            "cube.urdf",
+            UrdfOptions {
+                base_transform: Isometry3::translation(3., 0., 0.5),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let mount_pose = Isometry3::translation(0., 0., 0.5);
+
+    // planar scan: the beam at index 180 looks along the x-axis
+    let mut lidar = Lidar::new(
+        plane,
+        None,
+        LidarOptions {
+            mount_pose,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    float_compare(lidar.horizontal_angles()[180], 0., 1e-10);
+    let scan = lidar.scan(&mut client).unwrap();
+    assert_eq!(scan.ranges.len(), 360);
+    assert_eq!(scan.points.len(), 360);
+    float_compare(scan.range(0, 180).unwrap(), 2.5, 1e-5);
+    slice_compare(scan.points[180].unwrap().as_slice(), &[2.5, 0., 0.], 1e-5);
+    assert!(scan.range(0, 270).is_none());
+    assert!(scan.range(1, 0).is_none());
+    assert!(scan.range(0, 360).is_none());
+    assert_eq!(scan.point_cloud().ncols(), scan.num_hits());
+    let world_cloud = scan.world_point_cloud();
+    assert!(world_cloud
+        .column_iter()
+        .all(|point| (point.x - 2.5).abs() < 1e-5 && (point.z - 0.5).abs() < 1e-5));
+
+    // a 3D scan which needs more than one ray test batch
+    let options = LidarOptions {
+        mount_pose,
+        num_horizontal_beams: 5000,
+        vertical_angles: vec![-PI / 2.; 4],
+        range_noise_std_dev: 0.01,
+        seed: Some(42),
+        ..Default::default()
+    };
+    let mut lidar = Lidar::new(plane, None, options.clone()).unwrap();
+    let scan = lidar.scan(&mut client).unwrap();
+    assert_eq!(scan.num_vertical_beams, 4);
+    assert_eq!(scan.num_hits(), 20000);
+    assert!(scan
+        .ranges
+        .iter()
+        .all(|range| (range.unwrap() - 0.5).abs() < 0.1));
+    let mean = scan.ranges.iter().map(|range| range.unwrap()).sum::<f64>() / 20000.;
+    float_compare(mean, 0.5, 1e-3);
+    let same_seed = Lidar::new(plane, None, options)
+        .unwrap()
+        .scan(&mut client)
+        .unwrap();
+    assert_eq!(scan.ranges, same_seed.ranges);
+
+    let error = Lidar::new(
+        plane,
+        None,
+        LidarOptions {
+            min_range: 2.,
+            max_range: 1.,
+            ..Default::default()
+        },
+    )
+    .err()
+    .unwrap();
+    assert_eq!(error.kind(), ErrorKind::InvalidArgument);
+    assert!(Lidar::new(
+        plane,
+        None,
+        LidarOptions {
+            vertical_angles: vec![],
+            ..Default::default()
+        },
+    )
+    .is_err());
+}
+#[test]
+fn test_depth_point_cloud() {
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    let plane = client.load_urdf("plane.urdf", None).unwrap();
+    let cube = client
+        .load_urdf(
+            "cube.urdf",
+            UrdfOptions {
+                base_transform: Isometry3::translation(0., 0., 0.5),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    // the camera looks straight down from 3 m, so the cube covers the center of the image
+    let options = CameraImageOptions {
+        view_matrix: Some(PhysicsClient::compute_view_matrix(
+            [0., 0., 3.],
+            [0., 0., 0.],
+            [0., 1., 0.],
+        )),
+        projection_matrix: Some(PhysicsClient::compute_projection_matrix_fov(
+            60., 1., 0.1, 10.,
+        )),
+        flags: Some(RendererAuxFlags::SEGMENTATION_MASK_OBJECT_AND_LINKINDEX),
+        ..Default::default()
+    };
+    let images = client.get_camera_image(64, 64, options.clone()).unwrap();
+
+    let depth = images.metric_depth(&options).unwrap();
+    float32_compare(depth.get_pixel(32, 32)[0], 2., 1e-2);
+    float32_compare(depth.get_pixel(0, 0)[0], 3., 1e-2);
+
+    let organized = images
+        .organized_point_cloud(&options, PointCloudFrame::World)
+        .unwrap();
+    assert_eq!((organized.width, organized.height), (64, 64));
+    assert_eq!(organized.points.len(), 64 * 64);
+    let center = 32 * 64 + 32;
+    float_compare(organized.points[center].z, 1., 1e-2);
+    assert_eq!(organized.labels[center], Some((cube, None)));
+    assert_eq!(organized.labels[0], Some((plane, None)));
+    float_compare(organized.points[0].z, 0., 1e-2);
+
+    let camera = images
+        .point_cloud(&options, PointCloudFrame::Camera)
+        .unwrap();
+    assert_eq!(camera.height, 1);
+    assert_eq!(camera.width, camera.points.len());
+    assert_eq!(camera.colors.len(), camera.points.len());
+    assert_eq!(camera.labels.len(), camera.points.len());
+    assert!(camera
+        .points
+        .iter()
+        .all(|point| point.z > 1.9 && point.z < 3.1));
+    // the image y-axis points down, which is the world y-axis pointing up seen from above
+    let top = camera
+        .points
+        .iter()
+        .zip(
+            images
+                .point_cloud(&options, PointCloudFrame::World)
+                .unwrap()
+                .points,
+        )
+        .find(|(point, _)| point.y < -0.5)
+        .unwrap();
+    assert!(top.1.y > 0.);
+
+    let error = images
+        .point_cloud(&CameraImageOptions::default(), PointCloudFrame::World)
+        .err()
+        .unwrap();
+    assert_eq!(error.kind(), ErrorKind::InvalidArgument);
+}
+#[test]
+fn test_segmentation_mask() {
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    let plane = client.load_urdf("plane.urdf", None).unwrap();
+    let cube = client
+        .load_urdf(
+            "cube.urdf",
+            UrdfOptions {
+                base_transform: Isometry3::translation(0., 0., 0.5),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let options = CameraImageOptions {
+        view_matrix: Some(PhysicsClient::compute_view_matrix(
+            [0., 0., 3.],
+            [0., 0., 0.],
+            [0., 1., 0.],
+        )),
+        projection_matrix: Some(PhysicsClient::compute_projection_matrix_fov(
+            60., 1., 0.1, 10.,
+        )),
+        flags: Some(RendererAuxFlags::SEGMENTATION_MASK_OBJECT_AND_LINKINDEX),
+        ..Default::default()
+    };
+    let images = client.get_camera_image(64, 64, options.clone()).unwrap();
+    let mask = images.segmentation_mask(&options).unwrap();
+    assert_eq!(mask.labels.len(), 64 * 64);
+    assert_eq!(mask.label(32, 32), Some((cube, None)));
+    assert_eq!(mask.label(0, 0), Some((plane, None)));
+    assert_eq!(mask.bodies(), vec![plane, cube]);
+
+    let counts = mask.pixel_counts();
+    assert_eq!(counts[&plane] + counts[&cube], 64 * 64);
+    assert_eq!(mask.link_pixel_counts()[&(cube, None)], counts[&cube]);
+    let cube_mask = mask.body_mask(cube);
+    assert_eq!(
+        cube_mask.pixels().filter(|pixel| pixel[0] == 255).count(),
+        counts[&cube]
+    );
+    assert_eq!(mask.link_mask(cube, None), cube_mask);
+    assert_eq!(
+        mask.link_mask(cube, Some(0))
+            .pixels()
+            .filter(|pixel| pixel[0] > 0)
+            .count(),
+        0
+    );
+
+    let boxes = mask.bounding_boxes();
+    let plane_box = boxes[&plane];
+    assert_eq!((plane_box.width(), plane_box.height()), (64, 64));
+    let cube_box = boxes[&cube];
+    assert!(cube_box.x_min < 32 && cube_box.x_max > 32);
+    assert!(cube_box.y_min < 32 && cube_box.y_max > 32);
+    assert!(cube_box.width() > 24 && cube_box.width() < 32);
+    assert_eq!(mask.link_bounding_boxes()[&(cube, None)], cube_box);
+
+    // without the link index flag only the bodies are known
+    let options = CameraImageOptions {
+        flags: None,
+        ..options
+    };
+    let images = client.get_camera_image(64, 64, options.clone()).unwrap();
+    let mask = images.segmentation_mask(&options).unwrap();
+    assert_eq!(mask.label(32, 32), Some((cube, None)));
+    assert_eq!(mask.pixel_counts(), counts);
+}
+#[test]
+fn test_camera() {
+    let intrinsics = CameraIntrinsics::from_fov(60., 64, 48, 0.1, 10.);
+    let projection_matrix = intrinsics.projection_matrix();
+    slice_compare_f32(
+        projection_matrix.as_slice(),
+        PhysicsClient::compute_projection_matrix_fov(60., 64. / 48., 0.1, 10.).as_slice(),
+        1e-5,
+    );
+    let recovered = CameraIntrinsics::from_projection_matrix(&projection_matrix, 64, 48).unwrap();
+    float_compare(recovered.fx, intrinsics.fx, 1e-3);
+    float_compare(recovered.fy, intrinsics.fy, 1e-3);
+    float_compare(recovered.cx, 31.5, 1e-3);
+    float_compare(recovered.cy, 23.5, 1e-3);
+    float_compare(recovered.near, 0.1, 1e-4);
+    float_compare(recovered.far, 10., 1e-2);
+    let error = CameraIntrinsics::from_projection_matrix(&Matrix4::identity(), 64, 48)
+        .err()
+        .unwrap();
+    assert_eq!(error.kind(), ErrorKind::InvalidArgument);
+
+    let pose = Camera::look_at(
+        Vector3::new(0., 0., 3.),
+        Vector3::zeros(),
+        Vector3::new(0., 1., 0.),
+    );
+    let camera = Camera::new(intrinsics, pose);
+    slice_compare_f32(
+        camera.view_matrix().as_slice(),
+        PhysicsClient::compute_view_matrix([0., 0., 3.], [0., 0., 0.], [0., 1., 0.]).as_slice(),
+        1e-5,
+    );
+    let pixel = camera.project(Vector3::new(0., 0., 1.)).unwrap();
+    slice_compare(pixel.as_slice(), &[31.5, 23.5], 1e-10);
+    let point = Vector3::new(0.2, -0.1, 1.);
+    let pixel = camera.project(point).unwrap();
+    assert!(pixel.x > 31.5 && pixel.y > 23.5);
+    slice_compare(
+        camera.unproject(pixel, 2.).as_slice(),
+        point.as_slice(),
+        1e-10,
+    );
+    assert!(camera.project(Vector3::new(0., 0., 4.)).is_none());
+
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    client.load_urdf("plane.urdf", None).unwrap();
+    let cube = client
+        .load_urdf(
+            "cube.urdf",
+            UrdfOptions {
+                base_transform: Isometry3::translation(0., 0., 0.5),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    // the camera hovers above the cube and looks down
+    let offset = Isometry3::from_parts(
+        Translation3::new(0., 0., 2.5),
+        UnitQuaternion::from_axis_angle(&Vector3::x_axis(), PI),
+    );
+    let mut camera = Camera::mounted(intrinsics, cube, None, offset);
+    let images = camera.get_image(&mut client).unwrap();
+    slice_compare(
+        camera.pose.translation.vector.as_slice(),
+        &[0., 0., 3.],
+        1e-5,
+    );
+    let depth = images.metric_depth(&camera.image_options()).unwrap();
+    float32_compare(depth.get_pixel(32, 24)[0], 2., 1e-2);
+    // points in the camera frame of the point cloud are relative to the pose of the camera
+    let local = images
+        .point_cloud(&camera.image_options(), PointCloudFrame::Camera)
+        .unwrap();
+    let world = images
+        .point_cloud(&camera.image_options(), PointCloudFrame::World)
+        .unwrap();
+    for (local, world) in local.points.iter().zip(world.points.iter()) {
+        slice_compare(
+            (camera.pose * Point3::from(*local)).coords.as_slice(),
+            world.as_slice(),
+            1e-3,
+        );
+    }
+
+    client
+        .reset_base_transform(cube, Isometry3::translation(1., 0., 0.5))
+        .unwrap();
+    let pose = camera.update_pose(&mut client).unwrap();
+    slice_compare(pose.translation.vector.as_slice(), &[1., 0., 3.], 1e-5);
+}
+#[test]
+fn test_get_camera_image_into() {
+    let mut client = PhysicsClient::connect(Direct).unwrap();
+    client
+        .set_additional_search_path("../misfire-sys/bullet3/libbullet3/data")
+        .unwrap();
+    client.load_urdf("plane.urdf", None).unwrap();
+    let cube = client
+        .load_urdf(
+            "cube.urdf",
+            UrdfOptions {
+                base_transform: Isometry3::translation(0., 0., 0.5),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let options = CameraImageOptions {
+        view_matrix: Some(PhysicsClient::compute_view_matrix(
+            [0., 0., 3.],
+            [0., 0., 0.],
+            [0., 1., 0.],
+        )),
+        projection_matrix: Some(PhysicsClient::compute_projection_matrix_fov(
+            60., 1., 0.1, 10.,
+        )),
+        ..Default::default()
+    };
+    let reference = client.get_camera_image(32, 32, options.clone()).unwrap();
+
+    // only the selected channels are filled
+    let mut images = Images::default();
+    client
+        .get_camera_image_into(&mut images, 32, 32, ImageChannels::RGBA, options.clone())
+        .unwrap();
+    assert_eq!((images.width, images.height), (32, 32));
+    assert_eq!(images.rgba, reference.rgba);
+    assert_eq!(images.depth.len(), 0);
+    assert_eq!(images.segmentation.len(), 0);
+    // the helpers need the channels which were not rendered
+    for error in [
+        images.metric_depth(&options).err().unwrap(),
+        images.segmentation_mask(&options).err().unwrap(),
+        images
+            .point_cloud(&options, PointCloudFrame::World)
+            .err()
+            .unwrap(),
+    ] {
+        assert_eq!(error.kind(), ErrorKind::InvalidArgument);
+    }
+
+    client
+        .get_camera_image_into(
+            &mut images,
+            32,
+            32,
+            ImageChannels::DEPTH | ImageChannels::SEGMENTATION,
+            options.clone(),
+        )
+        .unwrap();
+    assert_eq!(images.depth, reference.depth);
+    assert_eq!(images.segmentation, reference.segmentation);
+    assert_eq!(images.segmentation.get_pixel(16, 16)[0], 1);
+    // the RGBA image of the previous call is cleared
+    assert_eq!(images.rgba.dimensions(), (0, 0));
+    let cloud = images
+        .point_cloud(&options, PointCloudFrame::World)
+        .unwrap();
+    assert!(cloud.colors.is_empty());
+    assert_eq!(cloud.labels.len(), cloud.points.len());
+    assert_eq!(
+        images.segmentation_mask(&options).unwrap(),
+        reference.segmentation_mask(&options).unwrap()
+    );
+
+    // rendering the same resolution again reuses the buffers
+    let depth_buffer = images.depth.as_ptr();
+    let rgba_buffer = images.rgba.as_ptr();
+    client
+        .reset_base_transform(cube, Isometry3::translation(0., 0., 1.))
+        .unwrap();
+    client
+        .get_camera_image_into(&mut images, 32, 32, ImageChannels::all(), options)
+        .unwrap();
+    assert_eq!(images.depth.as_ptr(), depth_buffer);
+    assert_eq!(images.rgba.as_ptr(), rgba_buffer);
+    assert_ne!(images.depth, reference.depth);
+}